@@ -181,6 +181,56 @@ async fn main() -> Result<()> {
         )
     }
 
+    if command_line_args.admin_addr.is_some() {
+        assert_eq!(command_line_args.admin_addr, config.admin_addr);
+    } else {
+        assert_eq!(file_config.admin_addr, config.admin_addr);
+    }
+
+    assert_eq!(
+        config.admin_allow_remote,
+        file_config.admin_allow_remote || command_line_args.admin_allow_remote
+    );
+
+    if command_line_args.storage_backend.is_some() {
+        assert_eq!(command_line_args.storage_backend, config.storage_backend);
+    } else {
+        assert_eq!(file_config.storage_backend, config.storage_backend);
+    }
+
+    if command_line_args.chunk_compression.is_some() {
+        assert_eq!(
+            command_line_args.chunk_compression,
+            config.chunk_compression
+        );
+    } else {
+        assert_eq!(file_config.chunk_compression, config.chunk_compression);
+    }
+
+    if command_line_args.chunk_copy_count.is_some() {
+        assert_eq!(command_line_args.chunk_copy_count, config.chunk_copy_count);
+    } else {
+        assert_eq!(file_config.chunk_copy_count, config.chunk_copy_count);
+    }
+
+    if command_line_args.register_copy_count.is_some() {
+        assert_eq!(
+            command_line_args.register_copy_count,
+            config.register_copy_count
+        );
+    } else {
+        assert_eq!(file_config.register_copy_count, config.register_copy_count);
+    }
+
+    if command_line_args.min_write_holders.is_some() {
+        assert_eq!(
+            command_line_args.min_write_holders,
+            config.min_write_holders
+        );
+    } else {
+        assert_eq!(file_config.min_write_holders, config.min_write_holders);
+    }
+
     clear_disk_config().await?;
 
     Ok(())
@@ -49,6 +49,7 @@ use tracing_subscriber::filter::EnvFilter;
 #[cfg(not(feature = "tokio-console"))]
 const MODULE_NAME: &str = "safe_network";
 const BOOTSTRAP_RETRY_TIME: u64 = 3; // in minutes
+const LEAVE_GRACE_PERIOD: u64 = 2; // in seconds
 
 fn main() -> Result<()> {
     color_eyre::install()?;
@@ -338,10 +339,41 @@ async fn run_node() -> Result<()> {
             });
     }
 
-    // This just keeps the node going as long as routing goes
-    while let Some(event) = event_stream.next().await {
-        trace!("Routing event! {:?}", event);
+    // This just keeps the node going as long as routing goes, or until we're asked to stop
+    let run = async {
+        while let Some(event) = event_stream.next().await {
+            trace!("Routing event! {:?}", event);
+        }
+    };
+    tokio::select! {
+        _ = run => {}
+        result = shutdown_signal() => {
+            result?;
+            info!("Shutdown requested, leaving the network");
+            if let Err(err) = node.leave().await {
+                error!("Failed to tell our Elders we're leaving: {:?}", err);
+            }
+            // Give the msg some time to make it to the Elders before the process exits
+            sleep(Duration::from_secs(LEAVE_GRACE_PERIOD)).await;
+        }
+    }
+
+    Ok(())
+}
+
+// Completes when the process is asked to terminate, i.e. on SIGTERM (unix only) or Ctrl-C
+async fn shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut sigterm = signal(SignalKind::terminate())?;
+        tokio::select! {
+            _ = sigterm.recv() => {}
+            result = tokio::signal::ctrl_c() => result?,
+        }
     }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
 
     Ok(())
 }
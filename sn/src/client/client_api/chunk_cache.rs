@@ -0,0 +1,423 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::Result;
+use crate::types::Chunk;
+
+use bytes::Bytes;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{fs, sync::RwLock, task};
+use xor_name::XorName;
+
+// Dir the cached chunks are kept in, within the client's root dir
+const CHUNK_CACHE_DIR_NAME: &str = "chunk_cache";
+// File the order the chunks were last used in is kept in, within the cache dir
+const INDEX_FILE_NAME: &str = "index";
+// Number of chunks read from the cache between each write of its index to disk. Chunks
+// read since the last write are only taken as the least recently used if the cache is reopened.
+const READS_PER_INDEX_WRITE: usize = 64;
+
+/// Number and size of the chunks held in a [`ChunkCache`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct ChunkCacheStats {
+    /// Number of chunks cached.
+    pub chunks: usize,
+    /// Bytes taken by the chunks cached.
+    pub size: u64,
+    /// Bytes the chunks cached are allowed to take, the least recently used being evicted beyond it.
+    pub max_size: u64,
+}
+
+/// Chunks retrieved from the network, kept on disk under the client's root dir so they
+/// don't need to be retrieved again by later clients, e.g. when the same content is read
+/// over and over by separate processes.
+///
+/// Chunks are stored by name, and verified against it when read back. The least recently
+/// used are evicted once the chunks cached take more than the size limit of the cache.
+#[derive(Clone, Debug)]
+pub struct ChunkCache {
+    dir: PathBuf,
+    max_size: u64,
+    index: Arc<RwLock<CacheIndex>>,
+}
+
+impl ChunkCache {
+    /// Opens the cache kept under `root_dir`, creating it if needed,
+    /// which is allowed to take `max_size` bytes.
+    pub async fn open(root_dir: &Path, max_size: u64) -> Result<Self> {
+        let dir = root_dir.join(CHUNK_CACHE_DIR_NAME);
+        fs::create_dir_all(&dir).await?;
+        let index = CacheIndex::read(&dir).await?;
+
+        Ok(Self {
+            dir,
+            max_size,
+            index: Arc::new(RwLock::new(index)),
+        })
+    }
+
+    /// Dir the chunks are cached in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Number and size of the chunks cached.
+    pub async fn stats(&self) -> ChunkCacheStats {
+        let index = self.index.read().await;
+        ChunkCacheStats {
+            chunks: index.entries.len(),
+            size: index.size,
+            max_size: self.max_size,
+        }
+    }
+
+    /// Returns the chunk with the given name, if it's cached and its content matches its name.
+    pub async fn get(&self, name: &XorName) -> Result<Option<Chunk>> {
+        let mut index = self.index.write().await;
+        if !index.entries.contains_key(name) {
+            return Ok(None);
+        }
+
+        let path = self.chunk_path(name);
+        let chunk = match fs::read(&path).await {
+            Ok(bytes) => Chunk::new(Bytes::from(bytes)),
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                // removed by someone else, e.g. another client evicting it
+                index.remove(name);
+                return Ok(None);
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        if chunk.name() != name {
+            warn!(
+                "Cached chunk {:?} doesn't match its name, removing it",
+                name
+            );
+            index.remove(name);
+            remove_file(&path).await?;
+            index.write(&self.dir).await?;
+            return Ok(None);
+        }
+
+        index.touch(name);
+        index.unwritten_reads += 1;
+        if index.unwritten_reads >= READS_PER_INDEX_WRITE {
+            index.write(&self.dir).await?;
+        }
+        Ok(Some(chunk))
+    }
+
+    /// Writes the order the chunks were last used in to disk, including that of the chunks
+    /// read since it was last written.
+    pub async fn flush(&self) -> Result<()> {
+        let mut index = self.index.write().await;
+        if index.unwritten_reads > 0 {
+            index.write(&self.dir).await?;
+        }
+        Ok(())
+    }
+
+    /// Caches the chunk, evicting the least recently used ones if that takes the cache over its size limit.
+    pub(crate) async fn insert(&self, chunk: &Chunk) -> Result<()> {
+        let size = chunk.value().len() as u64;
+        if size > self.max_size {
+            return Ok(());
+        }
+
+        let mut index = self.index.write().await;
+        let name = *chunk.name();
+        if !index.entries.contains_key(&name) {
+            write_file(&self.dir, self.chunk_path(&name), chunk.value().clone()).await?;
+            index.add(name, size);
+        } else {
+            index.touch(&name);
+        }
+
+        while index.size > self.max_size {
+            match index.least_recently_used() {
+                Some(evicted) => {
+                    trace!("Evicting chunk {:?} from the chunk cache", evicted);
+                    index.remove(&evicted);
+                    remove_file(&self.chunk_path(&evicted)).await?;
+                }
+                None => break,
+            }
+        }
+
+        index.write(&self.dir).await
+    }
+
+    /// Removes all the chunks cached.
+    pub async fn clear(&self) -> Result<()> {
+        let mut index = self.index.write().await;
+        for name in index.entries.keys() {
+            remove_file(&self.chunk_path(name)).await?;
+        }
+        *index = CacheIndex::default();
+        index.write(&self.dir).await
+    }
+
+    fn chunk_path(&self, name: &XorName) -> PathBuf {
+        self.dir.join(hex::encode(name))
+    }
+}
+
+// The chunks cached, along with when they were last used.
+#[derive(Debug, Default)]
+struct CacheIndex {
+    // size and last use of each chunk
+    entries: HashMap<XorName, (u64, u64)>,
+    // chunks by last use
+    by_use: BTreeMap<u64, XorName>,
+    next_use: u64,
+    size: u64,
+    // chunks read since the index was last written
+    unwritten_reads: usize,
+}
+
+impl CacheIndex {
+    // Reads the index of the cache in `dir`. Chunks found in the dir which are missing from it,
+    // e.g. if written by another client at the same time, are taken as the least recently used.
+    async fn read(dir: &Path) -> Result<Self> {
+        let mut on_disk = HashMap::new();
+        let mut files = fs::read_dir(dir).await?;
+        while let Some(entry) = files.next_entry().await? {
+            let name = match parse_name(&entry.file_name().to_string_lossy()) {
+                Some(name) => name,
+                None => continue,
+            };
+            let _ = on_disk.insert(name, entry.metadata().await?.len());
+        }
+
+        let by_use: Vec<XorName> = match fs::read(dir.join(INDEX_FILE_NAME)).await {
+            Ok(bytes) => bincode::deserialize(&bytes).unwrap_or_else(|error| {
+                warn!(
+                    "Could not read the chunk cache index, rebuilding it: {:?}",
+                    error
+                );
+                vec![]
+            }),
+            Err(error) if error.kind() == ErrorKind::NotFound => vec![],
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut index = Self::default();
+        let unindexed: Vec<_> = on_disk
+            .keys()
+            .filter(|name| !by_use.contains(name))
+            .copied()
+            .collect();
+        for name in unindexed.into_iter().chain(by_use) {
+            if let Some(size) = on_disk.get(&name) {
+                index.add(name, *size);
+            }
+        }
+
+        Ok(index)
+    }
+
+    // Writes the order the chunks were last used in, the least recent first.
+    async fn write(&mut self, dir: &Path) -> Result<()> {
+        let by_use: Vec<&XorName> = self.by_use.values().collect();
+        let bytes = bincode::serialize(&by_use)?;
+        write_file(dir, dir.join(INDEX_FILE_NAME), bytes).await?;
+        self.unwritten_reads = 0;
+        Ok(())
+    }
+
+    fn add(&mut self, name: XorName, size: u64) {
+        let last_use = self.next_use();
+        if let Some((old_size, old_use)) = self.entries.insert(name, (size, last_use)) {
+            let _ = self.by_use.remove(&old_use);
+            self.size -= old_size;
+        }
+        let _ = self.by_use.insert(last_use, name);
+        self.size += size;
+    }
+
+    fn touch(&mut self, name: &XorName) {
+        let last_use = self.next_use();
+        if let Some((_, old_use)) = self.entries.get_mut(name) {
+            let _ = self.by_use.remove(old_use);
+            *old_use = last_use;
+            let _ = self.by_use.insert(last_use, *name);
+        }
+    }
+
+    fn remove(&mut self, name: &XorName) {
+        if let Some((size, last_use)) = self.entries.remove(name) {
+            let _ = self.by_use.remove(&last_use);
+            self.size -= size;
+        }
+    }
+
+    fn least_recently_used(&self) -> Option<XorName> {
+        self.by_use.values().next().copied()
+    }
+
+    fn next_use(&mut self) -> u64 {
+        self.next_use += 1;
+        self.next_use
+    }
+}
+
+fn parse_name(file_name: &str) -> Option<XorName> {
+    let bytes = hex::decode(file_name).ok()?;
+    let mut name = XorName::default();
+    if bytes.len() != name.0.len() {
+        return None;
+    }
+    name.0.copy_from_slice(&bytes);
+    Some(name)
+}
+
+// Writes the file through a uniquely named temporary file in `dir`, which is then renamed to
+// `path`, so the file is never read halfway written, even with other clients writing it too.
+async fn write_file(
+    dir: &Path,
+    path: PathBuf,
+    contents: impl AsRef<[u8]> + Send + 'static,
+) -> Result<()> {
+    let dir = dir.to_path_buf();
+    task::spawn_blocking(move || -> std::io::Result<()> {
+        let mut tmp_file = tempfile::NamedTempFile::new_in(dir)?;
+        tmp_file.write_all(contents.as_ref())?;
+        let _ = tmp_file.persist(path).map_err(|error| error.error)?;
+        Ok(())
+    })
+    .await??;
+    Ok(())
+}
+
+async fn remove_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path).await {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChunkCache, ChunkCacheStats, INDEX_FILE_NAME, READS_PER_INDEX_WRITE};
+    use crate::types::{utils::random_bytes, Chunk};
+    use eyre::Result;
+    use futures::future::try_join_all;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn chunk_cache_evicts_least_recently_used() -> Result<()> {
+        let root_dir = tempfile::tempdir()?;
+        let chunks: Vec<_> = (0..3).map(|_| Chunk::new(random_bytes(100))).collect();
+        let cache = ChunkCache::open(root_dir.path(), 250).await?;
+
+        cache.insert(&chunks[0]).await?;
+        cache.insert(&chunks[1]).await?;
+        // the first chunk is now more recently used than the second one
+        assert_eq!(cache.get(chunks[0].name()).await?, Some(chunks[0].clone()));
+        cache.insert(&chunks[2]).await?;
+
+        assert_eq!(cache.get(chunks[1].name()).await?, None);
+        assert_eq!(
+            cache.stats().await,
+            ChunkCacheStats {
+                chunks: 2,
+                size: 200,
+                max_size: 250
+            }
+        );
+
+        // the cache outlives the client which filled it, keeping the order chunks were used in
+        let reopened = ChunkCache::open(root_dir.path(), 250).await?;
+        assert_eq!(reopened.stats().await, cache.stats().await);
+        reopened.insert(&chunks[1]).await?;
+        assert_eq!(reopened.get(chunks[0].name()).await?, None);
+        assert_eq!(
+            reopened.get(chunks[2].name()).await?,
+            Some(chunks[2].clone())
+        );
+
+        // chunks whose content doesn't match their name are discarded
+        std::fs::write(
+            reopened.dir().join(hex::encode(chunks[2].name())),
+            b"corrupted",
+        )?;
+        assert_eq!(reopened.get(chunks[2].name()).await?, None);
+
+        reopened.clear().await?;
+        assert_eq!(reopened.stats().await.chunks, 0);
+        assert_eq!(reopened.get(chunks[1].name()).await?, None);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn chunk_cache_index_is_written_once_per_batch_of_reads() -> Result<()> {
+        let root_dir = tempfile::tempdir()?;
+        let chunks: Vec<_> = (0..2).map(|_| Chunk::new(random_bytes(100))).collect();
+        let cache = ChunkCache::open(root_dir.path(), 1000).await?;
+        cache.insert(&chunks[0]).await?;
+        cache.insert(&chunks[1]).await?;
+        let index_path = cache.dir().join(INDEX_FILE_NAME);
+        let written = std::fs::read(&index_path)?;
+
+        // reading the first chunk makes it the most recently used, but only in memory...
+        assert_eq!(cache.get(chunks[0].name()).await?, Some(chunks[0].clone()));
+        assert_eq!(std::fs::read(&index_path)?, written);
+
+        // ...until it's flushed
+        cache.flush().await?;
+        let flushed = std::fs::read(&index_path)?;
+        assert_ne!(flushed, written);
+
+        // or enough chunks have been read since it was last written
+        for _ in 0..READS_PER_INDEX_WRITE - 1 {
+            let _ = cache.get(chunks[1].name()).await?;
+        }
+        assert_eq!(std::fs::read(&index_path)?, flushed);
+        let _ = cache.get(chunks[1].name()).await?;
+        assert_eq!(std::fs::read(&index_path)?, written);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn chunk_cache_shared_by_clients_writing_at_once() -> Result<()> {
+        let root_dir = tempfile::tempdir()?;
+        let caches = [
+            ChunkCache::open(root_dir.path(), 100_000).await?,
+            ChunkCache::open(root_dir.path(), 100_000).await?,
+        ];
+        let chunks: Vec<_> = (0..20).map(|_| Chunk::new(random_bytes(1000))).collect();
+
+        // both write the same chunks, and their index, at the same time
+        let _ = try_join_all(caches.iter().flat_map(|cache| {
+            chunks.iter().map(move |chunk| {
+                let cache = cache.clone();
+                let chunk = chunk.clone();
+                tokio::spawn(async move { cache.insert(&chunk).await })
+            })
+        }))
+        .await?
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let reopened = ChunkCache::open(root_dir.path(), 100_000).await?;
+        assert_eq!(reopened.stats().await.chunks, chunks.len());
+        for chunk in &chunks {
+            assert_eq!(reopened.get(chunk.name()).await?, Some(chunk.clone()));
+        }
+
+        Ok(())
+    }
+}
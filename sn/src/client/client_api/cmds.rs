@@ -25,14 +25,20 @@ impl Client {
     /// Cmds are not retried if the timeout is hit.
     #[instrument(skip(self), level = "debug")]
     pub async fn send_cmd_without_retry(&self, cmd: DataCmd) -> Result<(), Error> {
-        self.send_cmd_with_retry_count(cmd, 1.0).await
+        self.send_cmd_with_retry_count(cmd, 1.0, false).await
     }
 
     // Send a Cmd to the network and await a response.
-    // Cmds are automatically retried if an error is returned
+    // Cmds are automatically retried if an error is returned, which includes not getting
+    // enough acks for it if `require_acks` is set.
     // This function is a private helper.
     #[instrument(skip(self), level = "debug")]
-    async fn send_cmd_with_retry_count(&self, cmd: DataCmd, retry_count: f32) -> Result<(), Error> {
+    async fn send_cmd_with_retry_count(
+        &self,
+        cmd: DataCmd,
+        retry_count: f32,
+        require_acks: bool,
+    ) -> Result<(), Error> {
         let client_pk = self.public_key();
         let dst_name = cmd.dst_name(); // let msg = ServiceMsg::Cmd(cmd.clone());
 
@@ -64,13 +70,13 @@ impl Client {
         loop {
             debug!("Attempting {:?} (attempt #{})", debug_cmd, attempt);
 
+            let auth = ServiceAuth {
+                public_key: client_pk,
+                signature: signature.clone(),
+            };
             let res = self
-                .send_signed_cmd(
-                    dst_name,
-                    client_pk,
-                    serialised_cmd.clone(),
-                    signature.clone(),
-                )
+                .session
+                .send_cmd(dst_name, auth, serialised_cmd.clone(), require_acks)
                 .await;
 
             if let Ok(cmd_result) = res {
@@ -112,7 +118,7 @@ impl Client {
         };
 
         self.session
-            .send_cmd(dst_address, auth, serialised_cmd)
+            .send_cmd(dst_address, auth, serialised_cmd, false)
             .await
     }
 
@@ -121,6 +127,15 @@ impl Client {
     /// This function is a helper private to this module.
     #[instrument(skip_all, level = "debug", name = "client-api send cmd")]
     pub(crate) async fn send_cmd(&self, cmd: DataCmd) -> Result<(), Error> {
-        self.send_cmd_with_retry_count(cmd, MAX_RETRY_COUNT).await
+        self.send_cmd_with_retry_count(cmd, MAX_RETRY_COUNT, false)
+            .await
+    }
+
+    /// Same as [`Client::send_cmd`], but only succeeding once a majority of the Elders acked
+    /// the cmd, so its outcome can be relied on.
+    #[instrument(skip_all, level = "debug", name = "client-api send acked cmd")]
+    pub(crate) async fn send_acked_cmd(&self, cmd: DataCmd) -> Result<(), Error> {
+        self.send_cmd_with_retry_count(cmd, MAX_RETRY_COUNT, true)
+            .await
     }
 }
@@ -7,17 +7,20 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 mod pac_man;
+mod stream_encryptor;
 
-pub(crate) use pac_man::{encrypt_large, to_chunk, DataMapLevel};
+pub(crate) use pac_man::{encrypt_data, encrypt_large, pack, to_chunk, DataMapLevel};
+pub(crate) use stream_encryptor::StreamEncryptor;
 
 use crate::client::{Error, Result};
 
 use bytes::Bytes;
 use self_encryption::MIN_ENCRYPTABLE_BYTES;
 
-/// Data of size more than 0 bytes less than [`MIN_ENCRYPTABLE_BYTES`] bytes.
+/// Data of size less than [`MIN_ENCRYPTABLE_BYTES`] bytes, including empty data.
 ///
-/// A `Spot` cannot be self-encrypted, thus is encrypted using the client encryption keys instead.
+/// A `SmallFile` cannot be self-encrypted, thus is encrypted using the client encryption keys instead.
+/// Empty data is stored like any other, as a single chunk with no content when public.
 #[allow(missing_debug_implementations)]
 #[derive(Clone)]
 pub(crate) struct SmallFile {
@@ -35,12 +38,10 @@ pub(crate) struct LargeFile {
 }
 
 impl SmallFile {
-    /// Enforces size > 0 and size < [`MIN_ENCRYPTABLE_BYTES`] bytes.
+    /// Enforces size < [`MIN_ENCRYPTABLE_BYTES`] bytes.
     pub(crate) fn new(bytes: Bytes) -> Result<Self> {
         if bytes.len() >= MIN_ENCRYPTABLE_BYTES {
             Err(Error::TooLargeAsSmallFile)
-        } else if bytes.is_empty() {
-            Err(Error::EmptyFileProvided)
         } else {
             Ok(Self { bytes })
         }
@@ -0,0 +1,370 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Self-encryption of a file on disk, one chunk at a time.
+//!
+//! `self_encryption::encrypt` needs the whole content in memory. Here we instead read the file twice:
+//! a first pass computes the source hash of every chunk (which each chunk's key, iv and pad are derived from),
+//! and a second pass reads, encrypts and hands out the chunks one by one.
+//! The produced chunks and `DataMap` are identical to what `self_encryption::encrypt` produces for the same content.
+//!
+//! Each chunk is hashed again when read the second time, so a file changing in between fails the encryption
+//! rather than producing chunks which can't be decrypted. `self_encryption` doesn't expose the encryption of
+//! a single chunk, so that step is done here, but every chunk is decrypted back with `self_encryption::decrypt_range`
+//! before being handed out, so any divergence from the crate is caught rather than stored.
+
+use crate::client::{Error, Result};
+
+use aes::{
+    cipher::{block_padding::Pkcs7, BlockEncryptMut, KeyIvInit},
+    Aes128,
+};
+use brotli::enc::BrotliEncoderParams;
+use bytes::Bytes;
+use self_encryption::{
+    ChunkInfo, DataMap, EncryptedChunk, COMPRESSION_QUALITY, MAX_CHUNK_SIZE, MIN_CHUNK_SIZE,
+    MIN_ENCRYPTABLE_BYTES,
+};
+use std::{io::Cursor, path::Path};
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt, SeekFrom},
+    task,
+};
+use xor_name::{XorName, XOR_NAME_LEN};
+
+type Aes128CbcEnc = cbc::Encryptor<Aes128>;
+
+const KEY_SIZE: usize = 16;
+const IV_SIZE: usize = 16;
+const PAD_SIZE: usize = (XOR_NAME_LEN * 3) - KEY_SIZE - IV_SIZE;
+
+/// Reads a file chunk by chunk, self-encrypting each chunk as it is read.
+pub(crate) struct StreamEncryptor {
+    file: File,
+    file_size: usize,
+    src_hashes: Vec<XorName>,
+    // the source hashes in the form `self_encryption` decrypts from
+    src_map: DataMap,
+    chunk_infos: Vec<ChunkInfo>,
+    next_index: usize,
+}
+
+impl StreamEncryptor {
+    /// Opens the file at `path` and computes the source hashes of all its chunks.
+    /// Only one chunk is held in memory at any time.
+    pub(crate) async fn from_path(path: &Path) -> Result<Self> {
+        let mut file = File::open(path).await?;
+        let file_size = file.metadata().await?.len() as usize;
+        if MIN_ENCRYPTABLE_BYTES > file_size {
+            return Err(Error::TooSmallForSelfEncryption);
+        }
+
+        let num_chunks = get_num_chunks(file_size);
+        let mut src_hashes = Vec::with_capacity(num_chunks);
+        for index in 0..num_chunks {
+            let data = read_chunk(&mut file, file_size, index).await?;
+            src_hashes.push(XorName::from_content(&data));
+        }
+
+        // only the source hashes are needed to decrypt a chunk
+        let src_map = DataMap::new(
+            src_hashes
+                .iter()
+                .enumerate()
+                .map(|(index, src_hash)| ChunkInfo {
+                    index,
+                    dst_hash: XorName::default(),
+                    src_hash: *src_hash,
+                    src_size: get_chunk_size(file_size, index),
+                })
+                .collect(),
+        );
+
+        Ok(Self {
+            file,
+            file_size,
+            src_hashes,
+            src_map,
+            chunk_infos: Vec::with_capacity(num_chunks),
+            next_index: 0,
+        })
+    }
+
+    /// Size in bytes of the file being encrypted.
+    pub(crate) fn file_size(&self) -> usize {
+        self.file_size
+    }
+
+    /// Number of encrypted chunks the file content will be split into,
+    /// not counting the chunk(s) of the `DataMap` itself.
+    pub(crate) fn num_chunks(&self) -> usize {
+        self.src_hashes.len()
+    }
+
+    /// Number of bytes of the file held in the chunk at `index`.
+    pub(crate) fn chunk_size(&self, index: usize) -> usize {
+        get_chunk_size(self.file_size, index)
+    }
+
+    /// Reads and encrypts the next chunk of the file.
+    /// Returns `None` once all the chunks have been produced.
+    ///
+    /// Fails if the chunk read differs from the one first hashed, i.e. the file changed meanwhile.
+    pub(crate) async fn next_encrypted(&mut self) -> Result<Option<EncryptedChunk>> {
+        let index = self.next_index;
+        if index >= self.num_chunks() {
+            return Ok(None);
+        }
+
+        let data = read_chunk(&mut self.file, self.file_size, index).await?;
+        if XorName::from_content(&data) != self.src_hashes[index] {
+            return Err(Error::FileChangedWhileEncrypting { index });
+        }
+
+        let src_size = data.len();
+        let (pad, key, iv) = get_pad_key_and_iv(index, &self.src_hashes);
+        let src_map = self.src_map.clone();
+        let content = task::spawn_blocking(move || -> Result<Bytes> {
+            let content = encrypt_chunk(data.clone(), &pad, &key, &iv)?;
+            let chunk = EncryptedChunk {
+                index,
+                content: content.clone(),
+            };
+            if self_encryption::decrypt_range(&src_map, &[chunk], 0, src_size)? != data {
+                return Err(Error::SelfEncryption(self_encryption::Error::Encryption));
+            }
+            Ok(content)
+        })
+        .await??;
+
+        self.chunk_infos.push(ChunkInfo {
+            index,
+            dst_hash: XorName::from_content(&content),
+            src_hash: self.src_hashes[index],
+            src_size,
+        });
+        self.next_index += 1;
+
+        Ok(Some(EncryptedChunk { index, content }))
+    }
+
+    /// Returns the `DataMap` of the file, once all its chunks have been produced.
+    pub(crate) fn data_map(self) -> Result<DataMap> {
+        if self.chunk_infos.len() < self.num_chunks() {
+            return Err(Error::NotAllDataWasChunked {
+                expected: self.num_chunks(),
+                chunked: self.chunk_infos.len(),
+            });
+        }
+        Ok(DataMap::new(self.chunk_infos))
+    }
+}
+
+async fn read_chunk(file: &mut File, file_size: usize, index: usize) -> Result<Bytes> {
+    let (start, end) = get_start_end_positions(file_size, index);
+    let mut data = vec![0; end - start];
+    let _ = file.seek(SeekFrom::Start(start as u64)).await?;
+    let _ = file.read_exact(&mut data).await?;
+    Ok(Bytes::from(data))
+}
+
+fn encrypt_chunk(
+    content: Bytes,
+    pad: &[u8; PAD_SIZE],
+    key: &[u8; KEY_SIZE],
+    iv: &[u8; IV_SIZE],
+) -> Result<Bytes> {
+    let mut compressed = vec![];
+    let enc_params = BrotliEncoderParams {
+        quality: COMPRESSION_QUALITY,
+        ..Default::default()
+    };
+    let _size = brotli::BrotliCompress(
+        &mut Cursor::new(content.as_ref()),
+        &mut compressed,
+        &enc_params,
+    )
+    .map_err(|_| Error::SelfEncryption(self_encryption::Error::Compression))?;
+
+    let cipher = Aes128CbcEnc::new(key.into(), iv.into());
+    let encrypted = cipher.encrypt_padded_vec_mut::<Pkcs7>(&compressed);
+
+    Ok(encrypted
+        .iter()
+        .zip(pad.iter().cycle())
+        .map(|(&a, &b)| a ^ b)
+        .collect())
+}
+
+// The chunk layout and key derivation below mirror those of `self_encryption`,
+// which are not exposed by the crate. Chunks encrypted with them are checked
+// against the crate's own decryption in `next_encrypted`.
+
+fn get_pad_key_and_iv(
+    chunk_index: usize,
+    chunk_hashes: &[XorName],
+) -> ([u8; PAD_SIZE], [u8; KEY_SIZE], [u8; IV_SIZE]) {
+    let (n_1, n_2) = match chunk_index {
+        0 => (chunk_hashes.len() - 1, chunk_hashes.len() - 2),
+        1 => (0, chunk_hashes.len() - 1),
+        n => (n - 1, n - 2),
+    };
+    let src_hash = &chunk_hashes[chunk_index];
+    let n_1_src_hash = &chunk_hashes[n_1];
+    let n_2_src_hash = &chunk_hashes[n_2];
+
+    let mut pad = [0u8; PAD_SIZE];
+    let mut key = [0u8; KEY_SIZE];
+    let mut iv = [0u8; IV_SIZE];
+
+    for (pad_el, element) in pad
+        .iter_mut()
+        .zip(src_hash.iter().chain(n_2_src_hash.iter()))
+    {
+        *pad_el = *element;
+    }
+
+    for (key_el, element) in key.iter_mut().chain(iv.iter_mut()).zip(n_1_src_hash.iter()) {
+        *key_el = *element;
+    }
+
+    (pad, key, iv)
+}
+
+// Returns the number of chunks according to file size.
+fn get_num_chunks(file_size: usize) -> usize {
+    if file_size < MIN_ENCRYPTABLE_BYTES {
+        return 0;
+    }
+    if file_size < 3 * MAX_CHUNK_SIZE {
+        return 3;
+    }
+    if file_size.is_multiple_of(MAX_CHUNK_SIZE) {
+        file_size / MAX_CHUNK_SIZE
+    } else {
+        (file_size / MAX_CHUNK_SIZE) + 1
+    }
+}
+
+// Returns the size of a chunk according to file size.
+fn get_chunk_size(file_size: usize, chunk_index: usize) -> usize {
+    if file_size < MIN_ENCRYPTABLE_BYTES {
+        return 0;
+    }
+    if file_size < 3 * MAX_CHUNK_SIZE {
+        if chunk_index < 2 {
+            return file_size / 3;
+        } else {
+            return file_size - (2 * (file_size / 3));
+        }
+    }
+    let total_chunks = get_num_chunks(file_size);
+    if chunk_index < total_chunks - 2 {
+        return MAX_CHUNK_SIZE;
+    }
+    let remainder = file_size % MAX_CHUNK_SIZE;
+    let penultimate = (total_chunks - 2) == chunk_index;
+    if remainder == 0 {
+        return MAX_CHUNK_SIZE;
+    }
+    if remainder < MIN_CHUNK_SIZE {
+        if penultimate {
+            MAX_CHUNK_SIZE - MIN_CHUNK_SIZE
+        } else {
+            MIN_CHUNK_SIZE + remainder
+        }
+    } else if penultimate {
+        MAX_CHUNK_SIZE
+    } else {
+        remainder
+    }
+}
+
+// Returns the [start, end) half-open byte range of a chunk.
+fn get_start_end_positions(file_size: usize, chunk_index: usize) -> (usize, usize) {
+    let total_chunks = get_num_chunks(file_size);
+    if total_chunks == 0 {
+        return (0, 0);
+    }
+    let first_chunk_size = get_chunk_size(file_size, 0);
+    let start = if (total_chunks - 1) == chunk_index {
+        first_chunk_size * (chunk_index - 1) + get_chunk_size(file_size, chunk_index - 1)
+    } else {
+        first_chunk_size * chunk_index
+    };
+    (start, start + get_chunk_size(file_size, chunk_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamEncryptor;
+    use crate::{client::Error, types::utils::random_bytes};
+
+    use eyre::Result;
+    use self_encryption::{MAX_CHUNK_SIZE, MIN_CHUNK_SIZE, MIN_ENCRYPTABLE_BYTES};
+    use std::io::Write;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stream_encryption_matches_in_memory_encryption() -> Result<()> {
+        let sizes = [
+            MIN_ENCRYPTABLE_BYTES,
+            3 * MAX_CHUNK_SIZE - 1,
+            3 * MAX_CHUNK_SIZE,
+            4 * MAX_CHUNK_SIZE + MIN_CHUNK_SIZE / 2,
+            3 * MAX_CHUNK_SIZE + 3 * MIN_CHUNK_SIZE,
+        ];
+
+        for size in sizes {
+            let bytes = random_bytes(size);
+            let mut file = tempfile::NamedTempFile::new()?;
+            file.write_all(&bytes)?;
+
+            let (expected_map, expected_chunks) = self_encryption::encrypt(bytes)?;
+
+            let mut encryptor = StreamEncryptor::from_path(file.path()).await?;
+            assert_eq!(encryptor.file_size(), size);
+            assert_eq!(encryptor.num_chunks(), expected_chunks.len());
+
+            while let Some(chunk) = encryptor.next_encrypted().await? {
+                let expected = expected_chunks
+                    .iter()
+                    .find(|c| c.index == chunk.index)
+                    .ok_or_else(|| eyre::eyre!("Unexpected chunk index {}", chunk.index))?;
+                assert_eq!(expected.content, chunk.content);
+            }
+
+            assert_eq!(expected_map.infos(), encryptor.data_map()?.infos());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stream_encryption_fails_if_the_file_changes() -> Result<()> {
+        let mut file = tempfile::NamedTempFile::new()?;
+        file.write_all(&random_bytes(3 * MAX_CHUNK_SIZE))?;
+
+        let mut encryptor = StreamEncryptor::from_path(file.path()).await?;
+        assert!(encryptor.next_encrypted().await?.is_some());
+
+        // same size, different content
+        std::fs::write(file.path(), random_bytes(3 * MAX_CHUNK_SIZE))?;
+
+        match encryptor.next_encrypted().await {
+            Err(Error::FileChangedWhileEncrypting { index }) => assert_eq!(index, 1),
+            Err(error) => return Err(error.into()),
+            Ok(chunk) => eyre::bail!(
+                "Expected the changed file to fail, got chunk {:?}",
+                chunk.map(|c| c.index)
+            ),
+        }
+
+        Ok(())
+    }
+}
@@ -7,24 +7,50 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::{
-    data::{encrypt_large, to_chunk, LargeFile, SmallFile},
-    Client,
+    data::{encrypt_data, encrypt_large, pack, to_chunk, LargeFile, SmallFile, StreamEncryptor},
+    progress::{Progress, Transfer},
+    Client, FileReader, UploadManifest,
 };
 use crate::{
-    client::{client_api::data::DataMapLevel, utils::encryption, Error, Result},
+    client::{
+        client_api::data::DataMapLevel,
+        utils::{encryption, MAX_ENCRYPTION_OVERHEAD},
+        Error, Result,
+    },
     messaging::data::{DataCmd, DataQuery, QueryResponse},
-    types::{BytesAddress, Chunk, ChunkAddress, Encryption, PublicKey, Scope},
+    types::{BytesAddress, Chunk, ChunkAddress, Encryption, Keypair, Scope},
 };
 
+use backoff::{backoff::Backoff, ExponentialBackoff};
 use bincode::deserialize;
 use bytes::Bytes;
-use futures::future::join_all;
+use futures::{
+    future::join_all,
+    stream::{FuturesUnordered, StreamExt},
+};
 use itertools::Itertools;
 use self_encryption::{self, ChunkInfo, DataMap, EncryptedChunk};
-use tokio::task;
+use std::{collections::BTreeMap, path::Path};
+use tokio::{
+    fs,
+    io::{AsyncRead, AsyncWriteExt},
+    task::{self, JoinHandle},
+    time::{sleep, Duration},
+};
 use tracing::trace;
 use xor_name::XorName;
 
+// Maximum number of chunks being sent to the network at any time when uploading.
+// This bounds the memory used by uploads from a path or stream to about this many times
+// `self_encryption::MAX_CHUNK_SIZE`.
+const MAX_CONCURRENT_CHUNK_UPLOADS: usize = 10;
+
+// Number of times storing a chunk is attempted before it's left for the upload to be resumed.
+const MAX_CHUNK_STORE_ATTEMPTS: usize = 5;
+
+// Number of chunks acked between each write of an upload's manifest to disk.
+const CHUNKS_PER_CHECKPOINT: usize = 20;
+
 struct HeadChunk {
     chunk: Chunk,
     address: BytesAddress,
@@ -44,10 +70,28 @@ impl Client {
             })
             .await
         {
-            self.read_all(data_map).await
+            let transfer = self
+                .progress
+                .start(Progress::DownloadStarted {
+                    address,
+                    bytes: data_map.file_size() as u64,
+                })
+                .await;
+            let bytes = self.read_all(data_map, &transfer).await?;
+            // the use of the chunks read from the disk cache is recorded once for all of them
+            if let Some(disk_cache) = &self.disk_chunk_cache {
+                if let Err(error) = disk_cache.flush().await {
+                    warn!("Failed to write the index of the disk cache: {:?}", error);
+                }
+            }
+            transfer.report(Progress::DownloadCompleted);
+            Ok(bytes)
         } else {
             // if an error occurs, we assume it's a SmallFile
-            self.get_bytes(chunk, address.scope())
+            let bytes = self.get_bytes(chunk, address.scope())?;
+            let transfer = self.start_small_download(address, &bytes).await;
+            transfer.report(Progress::DownloadCompleted);
+            Ok(bytes)
         }
     }
 
@@ -106,6 +150,37 @@ impl Client {
         Ok(bytes)
     }
 
+    /// Returns a reader over the content stored at the given address, which fetches and decrypts
+    /// the chunks lazily, as the content is read.
+    /// The number of chunks fetched ahead of the one being read can be set with [`FileReader::with_prefetch`].
+    #[instrument(skip(self), level = "debug")]
+    pub async fn read_stream(&self, address: BytesAddress) -> Result<FileReader> {
+        let chunk = self.get_chunk(address.name()).await?;
+
+        // first try to deserialize a LargeFile, if it works, we read it as it's needed
+        if let Ok(data_map) = self
+            .unpack_head_chunk(HeadChunk {
+                chunk: chunk.clone(),
+                address,
+            })
+            .await
+        {
+            let transfer = self
+                .progress
+                .start(Progress::DownloadStarted {
+                    address,
+                    bytes: data_map.file_size() as u64,
+                })
+                .await;
+            Ok(FileReader::new(self.clone(), data_map, transfer))
+        } else {
+            // if an error occurs, we assume it's a SmallFile
+            let bytes = self.get_bytes(chunk, address.scope())?;
+            let transfer = self.start_small_download(address, &bytes).await;
+            Ok(FileReader::from_bytes(self.clone(), bytes, transfer))
+        }
+    }
+
     #[instrument(skip(self), level = "trace")]
     pub(crate) async fn get_chunk(&self, name: &XorName) -> Result<Chunk> {
         // first check it's not already in our Chunks' cache
@@ -119,6 +194,22 @@ impl Client {
             return Ok(chunk.clone());
         }
 
+        // then whether a previous client cached it on disk
+        if let Some(disk_cache) = &self.disk_chunk_cache {
+            match disk_cache.get(name).await {
+                Ok(Some(chunk)) => {
+                    trace!("Chunk retrieved from disk cache: {:?}", name);
+                    let _ = self.chunks_cache.write().await.insert(chunk.clone());
+                    return Ok(chunk);
+                }
+                Ok(None) => {}
+                Err(error) => warn!(
+                    "Failed to read chunk {:?} from disk cache: {:?}",
+                    name, error
+                ),
+            }
+        }
+
         let res = self
             .send_query(DataQuery::GetChunk(ChunkAddress(*name)))
             .await?;
@@ -132,6 +223,11 @@ impl Client {
         }?;
 
         let _ = self.chunks_cache.write().await.insert(chunk.clone());
+        if let Some(disk_cache) = &self.disk_chunk_cache {
+            if let Err(error) = disk_cache.insert(&chunk).await {
+                warn!("Failed to cache chunk {:?} on disk: {:?}", name, error);
+            }
+        }
 
         Ok(chunk)
     }
@@ -140,10 +236,10 @@ impl Client {
     #[instrument(skip_all, level = "trace")]
     pub fn chunk_bytes(&self, bytes: Bytes, scope: Scope) -> Result<(BytesAddress, Vec<Chunk>)> {
         if let Ok(file) = LargeFile::new(bytes.clone()) {
-            Self::encrypt_large(file, scope, self.public_key())
+            Self::encrypt_large(file, scope, &self.keypair())
         } else {
             let file = SmallFile::new(bytes)?;
-            let (address, chunk) = Self::package_small(file, scope, self.public_key())?;
+            let (address, chunk) = Self::package_small(file, scope, &self.keypair())?;
             Ok((address, vec![chunk]))
         }
     }
@@ -154,9 +250,9 @@ impl Client {
     fn encrypt_large(
         file: LargeFile,
         scope: Scope,
-        public_key: PublicKey,
+        keypair: &Keypair,
     ) -> Result<(BytesAddress, Vec<Chunk>)> {
-        let owner = encryption(scope, public_key);
+        let owner = encryption(scope, keypair);
         encrypt_large(file.bytes(), owner.as_ref())
     }
 
@@ -166,11 +262,11 @@ impl Client {
     fn package_small(
         file: SmallFile,
         scope: Scope,
-        public_key: PublicKey,
+        keypair: &Keypair,
     ) -> Result<(BytesAddress, Chunk)> {
-        let encryption = encryption(scope, public_key);
+        let encryption = encryption(scope, keypair);
         let chunk = to_chunk(file.bytes(), encryption.as_ref())?;
-        if chunk.value().len() >= self_encryption::MIN_ENCRYPTABLE_BYTES {
+        if chunk.value().len() >= self_encryption::MIN_ENCRYPTABLE_BYTES + MAX_ENCRYPTION_OVERHEAD {
             return Err(Error::SmallFilePaddingNeeded);
         }
         let name = *chunk.name();
@@ -211,18 +307,111 @@ impl Client {
         Ok((address, bytes))
     }
 
+    /// Writes the content of the file at `path` to the network in the form of immutable chunks,
+    /// without first reading the whole file into memory.
+    /// The chunks are self-encrypted and sent as they are read from disk, with a bounded number
+    /// of them in flight at any time.
+    #[instrument(skip(self), level = "debug")]
+    ///
+    /// The chunks stored are checkpointed in an [`UploadManifest`] under the client's root dir,
+    /// so the upload can be resumed with [`Client::resume_upload`] if it's interrupted.
+    pub async fn upload_from_path(&self, path: &Path, scope: Scope) -> Result<BytesAddress> {
+        let manifest = UploadManifest::new(path, scope).await?;
+        manifest.write(&self.root_dir).await?;
+        let upload = self.start_upload(manifest).await;
+        Self::encrypt_from_path(path, scope, &self.keypair(), Some(upload)).await
+    }
+
+    /// Returns the manifest of the upload of the file at `path` with the given scope,
+    /// if it was interrupted before completing.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn pending_upload(
+        &self,
+        path: &Path,
+        scope: Scope,
+    ) -> Result<Option<UploadManifest>> {
+        UploadManifest::read(&self.root_dir, path, scope).await
+    }
+
+    /// Resumes an interrupted upload from its manifest, as obtained with [`Client::pending_upload`].
+    /// The file is self-encrypted again, but only the chunks not yet acked are sent to the network.
+    #[instrument(skip(self, manifest), level = "debug")]
+    pub async fn resume_upload(&self, manifest: UploadManifest) -> Result<BytesAddress> {
+        manifest.check_source().await?;
+        let path = manifest.path().to_path_buf();
+        let scope = manifest.scope();
+        debug!(
+            "Resuming upload of {:?}, {} chunks already stored",
+            path,
+            manifest.acked_count()
+        );
+
+        let upload = self.start_upload(manifest).await;
+        Self::encrypt_from_path(&path, scope, &self.keypair(), Some(upload)).await
+    }
+
+    /// Writes all the data read from `reader` to the network in the form of immutable chunks.
+    /// Since self-encryption needs to know the full content before the first chunk can be encrypted,
+    /// the data is first spooled to a temporary file, which is then uploaded as per [`Client::upload_from_path`].
+    #[instrument(skip_all, level = "debug")]
+    pub async fn upload_stream<R>(&self, mut reader: R, scope: Scope) -> Result<BytesAddress>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let spool = tempfile::NamedTempFile::new()?;
+        let mut file = fs::File::from_std(spool.reopen()?);
+        let size = tokio::io::copy(&mut reader, &mut file).await?;
+        file.flush().await?;
+        trace!("Spooled {} bytes to {:?} for upload", size, spool.path());
+
+        self.upload_from_path(spool.path(), scope).await
+    }
+
+    /// Calculates the address the content of the file at `path` would be stored at,
+    /// without storing anything to the network, nor reading the whole file into memory.
+    /// As the address of private content depends on its owner, a random one is used for it.
+    #[instrument(level = "debug")]
+    pub async fn calculate_address_from_path(path: &Path, scope: Scope) -> Result<BytesAddress> {
+        // we use just a random keypair as the owner
+        let keypair = Keypair::new_ed25519(&mut rand::rngs::OsRng);
+        Self::encrypt_from_path(path, scope, &keypair, None).await
+    }
+
     /// Calculates a LargeFile's/SmallFile's address from self encrypted chunks,
     /// without storing them onto the network.
+    /// As the address of private content depends on its owner, a random one is used for it.
     #[instrument(skip(bytes), level = "debug")]
     pub fn calculate_address(bytes: Bytes, scope: Scope) -> Result<BytesAddress> {
-        // we use just a random BLS public key as the owner
-        let public_key = PublicKey::Bls(bls::SecretKey::random().public_key());
+        // we use just a random keypair as the owner
+        let keypair = Keypair::new_ed25519(&mut rand::rngs::OsRng);
+        Self::address_for(bytes, scope, &keypair)
+    }
+
+    /// Same as [`Client::calculate_address_from_path`], but with this client as the owner,
+    /// thus obtaining the address private content would be stored at by this client.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn calculate_own_address_from_path(
+        &self,
+        path: &Path,
+        scope: Scope,
+    ) -> Result<BytesAddress> {
+        Self::encrypt_from_path(path, scope, &self.keypair(), None).await
+    }
+
+    /// Same as [`Client::calculate_address`], but with this client as the owner,
+    /// thus obtaining the address private content would be stored at by this client.
+    #[instrument(skip(self, bytes), level = "debug")]
+    pub fn calculate_own_address(&self, bytes: Bytes, scope: Scope) -> Result<BytesAddress> {
+        Self::address_for(bytes, scope, &self.keypair())
+    }
+
+    fn address_for(bytes: Bytes, scope: Scope, keypair: &Keypair) -> Result<BytesAddress> {
         if let Ok(file) = LargeFile::new(bytes.clone()) {
-            let (head_address, _all_chunks) = Self::encrypt_large(file, scope, public_key)?;
+            let (head_address, _all_chunks) = Self::encrypt_large(file, scope, keypair)?;
             Ok(head_address)
         } else {
             let file = SmallFile::new(bytes)?;
-            let (address, _chunk) = Self::package_small(file, scope, public_key)?;
+            let (address, _chunk) = Self::package_small(file, scope, keypair)?;
             Ok(address)
         }
     }
@@ -231,33 +420,52 @@ impl Client {
     /// form of immutable self encrypted chunks, without any batching.
     #[instrument(skip_all, level = "trace")]
     async fn upload_large(&self, large: LargeFile, scope: Scope) -> Result<BytesAddress> {
-        let (head_address, all_chunks) = Self::encrypt_large(large, scope, self.public_key())?;
-
-        let tasks = all_chunks.into_iter().map(|chunk| {
-            let writer = self.clone();
-            task::spawn(async move { writer.send_cmd(DataCmd::StoreChunk(chunk)).await })
-        });
+        let bytes = large.bytes();
+        let transfer = self
+            .progress
+            .start(Progress::UploadStarted {
+                path: None,
+                bytes: bytes.len() as u64,
+            })
+            .await;
 
-        let respones = join_all(tasks)
-            .await
+        // we keep the size of the content in each chunk, to report the progress of the upload
+        let (data_map, encrypted_chunks) = encrypt_data(bytes)?;
+        let content_sizes: BTreeMap<_, _> = data_map
+            .infos()
             .into_iter()
-            .flatten() // swallows errors
-            .collect_vec();
-
-        for res in respones {
-            // fail with any issue here
-            let _ = res?;
+            .map(|info| (info.dst_hash, info.src_size))
+            .collect();
+        let owner = encryption(scope, &self.keypair());
+        let (head_address, all_chunks) = pack(data_map, encrypted_chunks, owner.as_ref())?;
+
+        let mut upload = Upload::new(self, None, transfer);
+        for chunk in all_chunks {
+            let content_size = content_sizes.get(chunk.name()).copied().unwrap_or(0);
+            upload.send(chunk, content_size).await?;
         }
-
-        Ok(head_address)
+        upload.finish(head_address).await
     }
 
     /// Directly writes a [`SmallFile`] to the network in the
     /// form of a single chunk, without any batching.
     #[instrument(skip_all, level = "trace")]
     async fn upload_small(&self, small: SmallFile, scope: Scope) -> Result<BytesAddress> {
-        let (address, chunk) = Self::package_small(small, scope, self.public_key())?;
-        self.send_cmd(DataCmd::StoreChunk(chunk)).await?;
+        let bytes = small.bytes().len();
+        let transfer = self
+            .progress
+            .start(Progress::UploadStarted {
+                path: None,
+                bytes: bytes as u64,
+            })
+            .await;
+
+        let (address, chunk) = Self::package_small(small, scope, &self.keypair())?;
+        transfer.report(Progress::ChunkEncrypted { bytes });
+        transfer.report(Progress::ChunkSent { bytes });
+        self.store_chunk(chunk).await?;
+        transfer.report(Progress::ChunkAcked { bytes });
+        transfer.report(Progress::UploadCompleted { address });
         Ok(address)
     }
 
@@ -265,10 +473,125 @@ impl Client {
     // ---------- Private helpers -----------------
     // --------------------------------------------
 
+    // Starts an upload from a path, reporting its progress.
+    async fn start_upload(&self, manifest: UploadManifest) -> Upload<'_> {
+        let transfer = self
+            .progress
+            .start(Progress::UploadStarted {
+                path: Some(manifest.path().to_path_buf()),
+                bytes: manifest.file_size(),
+            })
+            .await;
+        Upload::new(self, Some(manifest), transfer)
+    }
+
+    // Starts reporting the download of content which was stored in a single chunk, thus already fetched.
+    async fn start_small_download(&self, address: BytesAddress, bytes: &Bytes) -> Transfer {
+        let transfer = self
+            .progress
+            .start(Progress::DownloadStarted {
+                address,
+                bytes: bytes.len() as u64,
+            })
+            .await;
+        transfer.report(Progress::ChunkFetched { bytes: bytes.len() });
+        transfer
+    }
+
+    // Self-encrypts the file at `path`, chunk by chunk, returning its address.
+    // If an upload is provided, each chunk is sent to the network as soon as it's been produced.
+    async fn encrypt_from_path(
+        path: &Path,
+        scope: Scope,
+        keypair: &Keypair,
+        mut upload: Option<Upload<'_>>,
+    ) -> Result<BytesAddress> {
+        let file_size = fs::metadata(path).await?.len() as usize;
+        if file_size < self_encryption::MIN_ENCRYPTABLE_BYTES {
+            // a SmallFile fits in a single chunk, so we can simply read it
+            let file = SmallFile::new(Bytes::from(fs::read(path).await?))?;
+            let (address, chunk) = Self::package_small(file, scope, keypair)?;
+            if let Some(mut upload) = upload {
+                upload.send(chunk, file_size).await?;
+                return upload.finish(address).await;
+            }
+            return Ok(address);
+        }
+
+        let mut encryptor = StreamEncryptor::from_path(path).await?;
+        trace!(
+            "Self-encrypting {} bytes from {:?} into {} chunks",
+            encryptor.file_size(),
+            path,
+            encryptor.num_chunks()
+        );
+
+        while let Some(encrypted_chunk) = encryptor.next_encrypted().await? {
+            if let Some(upload) = &mut upload {
+                // no need to encrypt what is self-encrypted
+                let content_size = encryptor.chunk_size(encrypted_chunk.index);
+                upload
+                    .send(Chunk::new(encrypted_chunk.content), content_size)
+                    .await?;
+            }
+        }
+
+        let owner = encryption(scope, keypair);
+        let (address, data_map_chunks) = pack(encryptor.data_map()?, vec![], owner.as_ref())?;
+
+        match upload {
+            Some(mut upload) => {
+                for chunk in data_map_chunks {
+                    upload.send(chunk, 0).await?;
+                }
+                upload.finish(address).await
+            }
+            None => Ok(address),
+        }
+    }
+
+    // Sends the chunk to the network to be stored, succeeding only once its storing was acked.
+    async fn store_chunk(&self, chunk: Chunk) -> Result<()> {
+        self.send_acked_cmd(DataCmd::StoreChunk(chunk)).await
+    }
+
+    // Stores the chunk, retrying with backoff if it fails, up to `MAX_CHUNK_STORE_ATTEMPTS` times.
+    async fn store_chunk_with_retry(&self, chunk: Chunk) -> Result<()> {
+        let mut backoff = ExponentialBackoff {
+            initial_interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(30),
+            max_elapsed_time: None,
+            ..Default::default()
+        };
+
+        // this seems needed for custom settings to take effect
+        backoff.reset();
+
+        let mut attempt = 1;
+        loop {
+            match self.store_chunk(chunk.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(error) if attempt < MAX_CHUNK_STORE_ATTEMPTS => {
+                    let delay = backoff.next_backoff().unwrap_or(backoff.max_interval);
+                    debug!(
+                        "Failed to store chunk {:?} (attempt #{}), retrying in {:?}: {:?}",
+                        chunk.name(),
+                        attempt,
+                        delay,
+                        error
+                    );
+                    sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
     // Gets and decrypts chunks from the network using nothing else but the data map,
     // then returns the raw data.
-    async fn read_all(&self, data_map: DataMap) -> Result<Bytes> {
-        let encrypted_chunks = Self::try_get_chunks(self, data_map.infos()).await?;
+    async fn read_all(&self, data_map: DataMap, transfer: &Transfer) -> Result<Bytes> {
+        let encrypted_chunks = Self::try_get_chunks(self, data_map.infos(), transfer).await?;
         let bytes = self_encryption::decrypt_full_set(&data_map, &encrypted_chunks)?;
         Ok(bytes)
     }
@@ -287,6 +610,7 @@ impl Client {
                 .clone()
                 .map(|i| all_infos[i].clone())
                 .collect_vec(),
+            &Transfer::untracked(),
         )
         .await?;
 
@@ -300,17 +624,24 @@ impl Client {
     async fn try_get_chunks(
         client: &Client,
         chunks_info: Vec<ChunkInfo>,
+        transfer: &Transfer,
     ) -> Result<Vec<EncryptedChunk>> {
         let expected_count = chunks_info.len();
 
         let tasks = chunks_info.into_iter().map(|chunk_info| {
             let client = client.clone();
+            let transfer = transfer.clone();
             task::spawn(async move {
                 match client.get_chunk(&chunk_info.dst_hash).await {
-                    Ok(chunk) => Ok(EncryptedChunk {
-                        index: chunk_info.index,
-                        content: chunk.value().clone(),
-                    }),
+                    Ok(chunk) => {
+                        transfer.report(Progress::ChunkFetched {
+                            bytes: chunk_info.src_size,
+                        });
+                        Ok(EncryptedChunk {
+                            index: chunk_info.index,
+                            content: chunk.value().clone(),
+                        })
+                    }
                     Err(err) => {
                         warn!(
                             "Reading chunk {} from network, resulted in error {:?}.",
@@ -356,7 +687,7 @@ impl Client {
                     return Ok(data_map);
                 }
                 DataMapLevel::Additional(data_map) => {
-                    let serialized_chunk = self.read_all(data_map).await?;
+                    let serialized_chunk = self.read_all(data_map, &Transfer::untracked()).await?;
                     chunk = deserialize(&serialized_chunk)?;
                 }
             }
@@ -370,30 +701,172 @@ impl Client {
         if matches!(scope, Scope::Public) {
             Ok(chunk.value().clone())
         } else {
-            let owner = encryption(scope, self.public_key()).ok_or(Error::NoEncryptionObject)?;
+            let owner = encryption(scope, &self.keypair()).ok_or(Error::NoEncryptionObject)?;
             Ok(owner.decrypt(chunk.value().clone())?)
         }
     }
 }
 
+// Chunks being sent to the network, never more than `MAX_CONCURRENT_CHUNK_UPLOADS` at a time.
+// If there's a manifest, the chunks acked are checkpointed in it, and those already acked
+// when resuming the upload are skipped.
+// Chunks are sent along with the size of the content they hold, to report the upload's progress.
+struct Upload<'a> {
+    client: &'a Client,
+    manifest: Option<UploadManifest>,
+    transfer: Transfer,
+    in_flight: FuturesUnordered<JoinHandle<(XorName, usize, Result<()>)>>,
+    acked_since_checkpoint: usize,
+    failed: usize,
+    last_error: Option<Error>,
+}
+
+impl<'a> Upload<'a> {
+    fn new(client: &'a Client, manifest: Option<UploadManifest>, transfer: Transfer) -> Self {
+        Self {
+            client,
+            manifest,
+            transfer,
+            in_flight: FuturesUnordered::new(),
+            acked_since_checkpoint: 0,
+            failed: 0,
+            last_error: None,
+        }
+    }
+
+    // Sends the chunk unless it was already acked, once there's room for it among those in flight.
+    async fn send(&mut self, chunk: Chunk, content_size: usize) -> Result<()> {
+        let name = *chunk.name();
+        self.transfer.report(Progress::ChunkEncrypted {
+            bytes: content_size,
+        });
+        if let Some(manifest) = &mut self.manifest {
+            if manifest.is_acked(&name) {
+                trace!("Chunk {:?} already stored, not sending it again", name);
+                self.transfer.report(Progress::ChunkAcked {
+                    bytes: content_size,
+                });
+                return Ok(());
+            }
+            manifest.add(name);
+        }
+
+        while self.in_flight.len() >= MAX_CONCURRENT_CHUNK_UPLOADS {
+            self.next_ack().await?;
+        }
+
+        let client = self.client.clone();
+        self.in_flight.push(task::spawn(async move {
+            let result = client.store_chunk_with_retry(chunk).await;
+            (name, content_size, result)
+        }));
+        self.transfer.report(Progress::ChunkSent {
+            bytes: content_size,
+        });
+
+        Ok(())
+    }
+
+    // Waits for the next chunk in flight to be stored, or to have failed to.
+    async fn next_ack(&mut self) -> Result<()> {
+        let (name, content_size, result) = match self.in_flight.next().await {
+            Some(joined) => joined?,
+            None => return Ok(()),
+        };
+
+        match result {
+            Ok(()) => {
+                self.transfer.report(Progress::ChunkAcked {
+                    bytes: content_size,
+                });
+                if let Some(manifest) = &mut self.manifest {
+                    manifest.ack(name);
+                    self.acked_since_checkpoint += 1;
+                    if self.acked_since_checkpoint >= CHUNKS_PER_CHECKPOINT {
+                        self.checkpoint().await?;
+                    }
+                }
+            }
+            Err(error) => {
+                warn!("Failed to store chunk {:?}: {:?}", name, error);
+                self.failed += 1;
+                self.last_error = Some(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn checkpoint(&mut self) -> Result<()> {
+        if let Some(manifest) = &self.manifest {
+            manifest.write(&self.client.root_dir).await?;
+        }
+        self.acked_since_checkpoint = 0;
+        Ok(())
+    }
+
+    // Waits for all chunks in flight, failing if any of them couldn't be stored. The manifest,
+    // if any, is then left behind for the upload to be resumed, and removed otherwise.
+    async fn finish(mut self, address: BytesAddress) -> Result<BytesAddress> {
+        while !self.in_flight.is_empty() {
+            self.next_ack().await?;
+        }
+
+        let manifest = match self.manifest.as_mut() {
+            Some(manifest) => manifest,
+            None => {
+                return match self.last_error.take() {
+                    Some(error) => Err(error),
+                    None => {
+                        self.transfer.report(Progress::UploadCompleted { address });
+                        Ok(address)
+                    }
+                }
+            }
+        };
+        manifest.set_address(address);
+
+        if self.failed > 0 {
+            let path = manifest.path().to_path_buf();
+            self.checkpoint().await?;
+            return Err(Error::UploadIncomplete {
+                path,
+                failed: self.failed,
+            });
+        }
+
+        manifest.remove(&self.client.root_dir).await?;
+        self.transfer.report(Progress::UploadCompleted { address });
+        Ok(address)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::client::utils::test_utils::create_test_client_with;
     use crate::client::{
-        client_api::file_apis::LargeFile,
+        client_api::{
+            data::StreamEncryptor,
+            file_apis::{LargeFile, SmallFile},
+            UploadManifest,
+        },
         utils::test_utils::{create_test_client, init_test_logger},
-        Client,
+        Client, Error,
     };
     use crate::types::log_markers::LogMarker;
-    use crate::types::{utils::random_bytes, BytesAddress, Keypair, Scope};
+    use crate::types::{utils::random_bytes, BytesAddress, Chunk, Keypair, Scope};
 
     use bytes::Bytes;
-    use eyre::Result;
+    use eyre::{eyre, Result};
     use futures::future::join_all;
     use rand::rngs::OsRng;
-    use std::time::Duration;
-    use tokio::time::Instant;
+    use std::{io::Write, time::Duration};
+    use tokio::{
+        io::{AsyncReadExt, AsyncSeekExt, SeekFrom},
+        time::Instant,
+    };
     use tracing::{instrument::Instrumented, Instrument};
+    use xor_name::XorName;
 
     const LARGE_FILE_SIZE_MIN: usize = self_encryption::MIN_ENCRYPTABLE_BYTES;
 
@@ -405,13 +878,13 @@ mod tests {
 
         use crate::client::client_api::data::encrypt_large;
         use crate::client::utils::encryption;
-        let owner = encryption(Scope::Private, keypair.public_key());
+        let owner = encryption(Scope::Private, &keypair);
         let (first_address, mut first_chunks) = encrypt_large(file.clone(), owner.as_ref())?;
 
         first_chunks.sort();
 
         for _ in 0..100 {
-            let owner = encryption(Scope::Private, keypair.public_key());
+            let owner = encryption(Scope::Private, &keypair);
             let (head_address, mut all_chunks) = encrypt_large(file.clone(), owner.as_ref())?;
             assert_eq!(first_address, head_address);
             all_chunks.sort();
@@ -421,6 +894,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn empty_content_is_a_single_chunk() -> Result<()> {
+        init_test_logger();
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+
+        for scope in [Scope::Public, Scope::Private] {
+            let file = SmallFile::new(Bytes::new())?;
+            let (address, chunk) = Client::package_small(file, scope, &keypair)?;
+            assert_eq!(address.scope(), scope);
+            assert_eq!(address.name(), chunk.name());
+            assert_eq!(Client::address_for(Bytes::new(), scope, &keypair)?, address);
+        }
+
+        // all empty public content is stored at the same, well-known, address
+        assert_eq!(
+            Client::calculate_address(Bytes::new(), Scope::Public)?,
+            BytesAddress::Public(*Chunk::new(Bytes::new()).name())
+        );
+
+        Ok(())
+    }
+
     // Test storing and reading min sized LargeFile.
     #[tokio::test(flavor = "multi_thread")]
     async fn store_and_read_3kb() -> Result<()> {
@@ -533,6 +1028,34 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn read_stream_and_seek() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("read_stream_and_seek").entered();
+        let client = create_test_client().await?;
+
+        for size in [LARGE_FILE_SIZE_MIN / 3, 5 * 1024 * 1024 + 512] {
+            let bytes = random_bytes(size);
+            let address = client.upload(bytes.clone(), Scope::Public).await?;
+
+            // read it all through the stream, with no prefetching
+            let mut reader = client.read_stream(address).await?.with_prefetch(0);
+            assert_eq!(reader.file_size(), size as u64);
+            let mut read_data = vec![];
+            let _ = reader.read_to_end(&mut read_data).await?;
+            compare(bytes.clone(), Bytes::from(read_data))?;
+
+            // then seek back and read from halfway through
+            let pos = size / 2;
+            let _ = reader.seek(SeekFrom::Start(pos as u64)).await?;
+            let mut read_data = vec![];
+            let _ = reader.read_to_end(&mut read_data).await?;
+            compare(bytes.slice(pos..), Bytes::from(read_data))?;
+        }
+
+        Ok(())
+    }
+
     // Test storing and reading 5mb file. Try and read from many clients and ensure we do not overwelm nodes.
     #[tokio::test(flavor = "multi_thread")]
     async fn store_and_read_5mb_from_many_clients() -> Result<()> {
@@ -668,6 +1191,88 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resume_interrupted_upload() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("resume_interrupted_upload").entered();
+        let client = create_test_client().await?;
+
+        let bytes = random_bytes(3 * LARGE_FILE_SIZE_MIN);
+        let mut file = tempfile::NamedTempFile::new()?;
+        file.write_all(&bytes)?;
+
+        for scope in [Scope::Public, Scope::Private] {
+            // the upload was interrupted once half the chunks of the content had been stored,
+            // the rest of them being left pending in its manifest
+            let mut encryptor = StreamEncryptor::from_path(file.path()).await?;
+            let mut chunks = vec![];
+            while let Some(encrypted_chunk) = encryptor.next_encrypted().await? {
+                chunks.push(Chunk::new(encrypted_chunk.content));
+            }
+            let mut manifest = UploadManifest::new(file.path(), scope).await?;
+            for (index, chunk) in chunks.into_iter().enumerate() {
+                manifest.add(*chunk.name());
+                if index % 2 == 0 {
+                    client.store_chunk(chunk.clone()).await?;
+                    manifest.ack(*chunk.name());
+                }
+            }
+            let acked = manifest.acked_count();
+            manifest.write(&client.root_dir).await?;
+
+            let manifest = client
+                .pending_upload(file.path(), scope)
+                .await?
+                .ok_or_else(|| eyre!("no pending upload of {:?}", scope))?;
+            assert_eq!(manifest.acked_count(), acked);
+
+            // resuming it stores the rest, and then the upload is no longer pending
+            let address = client.resume_upload(manifest).await?;
+            assert_eq!(
+                address,
+                client
+                    .calculate_own_address_from_path(file.path(), scope)
+                    .await?
+            );
+            assert!(client.pending_upload(file.path(), scope).await?.is_none());
+            compare(bytes.clone(), client.read_bytes(address).await?)?;
+        }
+
+        // an upload can't be resumed once its file changed
+        let mut manifest = UploadManifest::new(file.path(), Scope::Public).await?;
+        manifest.add(XorName::random());
+        manifest.write(&client.root_dir).await?;
+        file.write_all(b"more content")?;
+        let manifest = client
+            .pending_upload(file.path(), Scope::Public)
+            .await?
+            .ok_or_else(|| eyre!("no pending upload"))?;
+        assert!(matches!(
+            client.resume_upload(manifest).await,
+            Err(Error::UploadSourceChanged(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn store_and_read_empty() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("store_and_read_empty").entered();
+        let client = create_test_client().await?;
+        store_and_read(&client, 0, Scope::Public).await?;
+        store_and_read(&client, 0, Scope::Private).await?;
+
+        // an empty stream is read to its end straight away
+        let address = client.upload(Bytes::new(), Scope::Public).await?;
+        let mut reader = client.read_stream(address).await?;
+        assert_eq!(reader.file_size(), 0);
+        let mut read_data = vec![];
+        assert_eq!(reader.read_to_end(&mut read_data).await?, 0);
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn store_and_read_1kb() -> Result<()> {
         init_test_logger();
@@ -786,8 +1391,8 @@ mod tests {
         // random bytes of requested size
         let bytes = random_bytes(size);
 
-        // we'll also test we can calculate address offline using `calculate_address` API
-        let expected_address = Client::calculate_address(bytes.clone(), scope)?;
+        // we'll also test we can calculate address offline using `calculate_own_address` API
+        let expected_address = client.calculate_own_address(bytes.clone(), scope)?;
 
         // we use upload_and_verify since it uploads and also confirms it was uploaded
         let (address, read_data) = client.upload_and_verify(bytes.clone(), scope).await?;
@@ -0,0 +1,269 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{
+    progress::{Progress, Transfer},
+    Client,
+};
+use crate::client::Result;
+
+use bytes::Bytes;
+use self_encryption::{ChunkInfo, DataMap, EncryptedChunk};
+use std::{
+    cmp,
+    collections::BTreeMap,
+    future::Future,
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncSeek, ReadBuf, SeekFrom},
+    task::{self, JoinHandle},
+};
+
+/// Default number of chunks fetched ahead of the one currently being read.
+pub const DEFAULT_PREFETCH_CHUNKS: usize = 4;
+
+/// Reads the content stored at a [`BytesAddress`](crate::types::BytesAddress) as it is needed.
+///
+/// Chunks are fetched and decrypted lazily, as the reader advances through the content,
+/// with a configurable number of the chunks that follow the current one being prefetched.
+/// Only the current chunk and the prefetched ones are kept in memory.
+#[derive(Debug)]
+pub struct FileReader {
+    client: Client,
+    // `None` when all the content fitted in a single chunk, which is then the current one.
+    data_map: Option<Arc<DataMap>>,
+    chunk_infos: Vec<ChunkInfo>,
+    // Position of the first byte of each chunk within the content.
+    chunk_offsets: Vec<u64>,
+    file_size: u64,
+    position: u64,
+    prefetch: usize,
+    current: Option<(usize, Bytes)>,
+    pending: BTreeMap<usize, JoinHandle<Result<Bytes>>>,
+    // Reports the chunks fetched, and the end of the content being reached.
+    transfer: Transfer,
+    completed: bool,
+}
+
+impl FileReader {
+    /// A reader over content spread across the chunks of the given `DataMap`.
+    pub(crate) fn new(client: Client, data_map: DataMap, transfer: Transfer) -> Self {
+        let chunk_infos = data_map.infos();
+        let mut chunk_offsets = Vec::with_capacity(chunk_infos.len());
+        let mut file_size = 0;
+        for info in &chunk_infos {
+            chunk_offsets.push(file_size);
+            file_size += info.src_size as u64;
+        }
+
+        Self {
+            client,
+            data_map: Some(Arc::new(data_map)),
+            chunk_infos,
+            chunk_offsets,
+            file_size,
+            position: 0,
+            prefetch: DEFAULT_PREFETCH_CHUNKS,
+            current: None,
+            pending: BTreeMap::new(),
+            transfer,
+            completed: false,
+        }
+    }
+
+    /// A reader over content which was stored in a single chunk, thus already retrieved.
+    pub(crate) fn from_bytes(client: Client, bytes: Bytes, transfer: Transfer) -> Self {
+        // there is nothing to read of empty content, which is thus already downloaded
+        let completed = bytes.is_empty();
+        if completed {
+            transfer.report(Progress::DownloadCompleted);
+        }
+
+        Self {
+            client,
+            data_map: None,
+            chunk_infos: vec![],
+            chunk_offsets: vec![0],
+            file_size: bytes.len() as u64,
+            position: 0,
+            prefetch: 0,
+            current: Some((0, bytes)),
+            pending: BTreeMap::new(),
+            transfer,
+            completed,
+        }
+    }
+
+    /// Sets the number of chunks to fetch ahead of the one currently being read.
+    /// Each of them takes up to `self_encryption::MAX_CHUNK_SIZE` bytes of memory.
+    pub fn with_prefetch(mut self, chunks: usize) -> Self {
+        self.prefetch = chunks;
+        self
+    }
+
+    /// Size in bytes of the content being read.
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    // Index of the chunk containing the byte at `position`, which must be within the content.
+    fn chunk_index(&self, position: u64) -> usize {
+        match self.chunk_offsets.binary_search(&position) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        }
+    }
+
+    // Makes sure the chunk at `index`, and the ones to be prefetched after it, are being fetched,
+    // and cancels fetching any chunk outside of that window.
+    fn schedule_fetches(&mut self, index: usize, data_map: &Arc<DataMap>) {
+        let last = cmp::min(index + self.prefetch, self.chunk_offsets.len() - 1);
+        self.pending.retain(|i, handle| {
+            let keep = (index..=last).contains(i);
+            if !keep {
+                handle.abort();
+            }
+            keep
+        });
+
+        for info in &self.chunk_infos[index..=last] {
+            if self.pending.contains_key(&info.index) {
+                continue;
+            }
+            let client = self.client.clone();
+            let data_map = data_map.clone();
+            let handle = task::spawn(fetch_and_decrypt(client, data_map, info.clone()));
+            let _ = self.pending.insert(info.index, handle);
+        }
+    }
+}
+
+impl Drop for FileReader {
+    fn drop(&mut self) {
+        for handle in self.pending.values() {
+            handle.abort();
+        }
+    }
+}
+
+impl AsyncRead for FileReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.position >= this.file_size || buf.remaining() == 0 {
+                return Poll::Ready(Ok(()));
+            }
+
+            let index = this.chunk_index(this.position);
+            if let Some((current_index, bytes)) = &this.current {
+                if *current_index == index {
+                    let start = (this.position - this.chunk_offsets[index]) as usize;
+                    if start >= bytes.len() {
+                        return Poll::Ready(Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            format!("Chunk {} is shorter than expected", index),
+                        )));
+                    }
+                    let len = cmp::min(buf.remaining(), bytes.len() - start);
+                    buf.put_slice(&bytes[start..start + len]);
+                    this.position += len as u64;
+                    if this.position >= this.file_size && !this.completed {
+                        this.completed = true;
+                        this.transfer.report(Progress::DownloadCompleted);
+                    }
+                    return Poll::Ready(Ok(()));
+                }
+            }
+
+            // content without a DataMap is a single chunk, which is always the current one
+            let data_map = match &this.data_map {
+                Some(data_map) => data_map.clone(),
+                None => return Poll::Ready(Err(io_error("No DataMap to fetch chunks with"))),
+            };
+            this.schedule_fetches(index, &data_map);
+
+            let handle = match this.pending.get_mut(&index) {
+                Some(handle) => handle,
+                None => return Poll::Ready(Err(io_error("Chunk was not scheduled for fetching"))),
+            };
+            match Pin::new(handle).poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    let _ = this.pending.remove(&index);
+                    let bytes = result.map_err(io_error)?.map_err(io_error)?;
+                    this.transfer
+                        .report(Progress::ChunkFetched { bytes: bytes.len() });
+                    this.current = Some((index, bytes));
+                }
+            }
+        }
+    }
+}
+
+impl AsyncSeek for FileReader {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let this = self.get_mut();
+        let new_position = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => checked_add_signed(this.file_size, offset),
+            SeekFrom::Current(offset) => checked_add_signed(this.position, offset),
+        };
+
+        match new_position {
+            Some(new_position) => {
+                this.position = new_position;
+                Ok(())
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(self.position))
+    }
+}
+
+async fn fetch_and_decrypt(
+    client: Client,
+    data_map: Arc<DataMap>,
+    chunk_info: ChunkInfo,
+) -> Result<Bytes> {
+    let chunk = client.get_chunk(&chunk_info.dst_hash).await?;
+    let encrypted_chunk = EncryptedChunk {
+        index: chunk_info.index,
+        content: chunk.value().clone(),
+    };
+    let bytes = self_encryption::decrypt_range(&data_map, &[encrypted_chunk], 0, usize::MAX)?;
+    Ok(bytes)
+}
+
+fn checked_add_signed(base: u64, offset: i64) -> Option<u64> {
+    if offset >= 0 {
+        base.checked_add(offset as u64)
+    } else {
+        base.checked_sub(offset.unsigned_abs())
+    }
+}
+
+fn io_error<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::other(error)
+}
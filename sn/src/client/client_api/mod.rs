@@ -6,13 +6,22 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod chunk_cache;
 mod cmds;
 mod data;
 mod file_apis;
+mod file_reader;
+mod progress;
 mod queries;
 mod register_apis;
+mod rewards;
+mod upload_manifest;
 
+pub use chunk_cache::{ChunkCache, ChunkCacheStats};
+pub use file_reader::{FileReader, DEFAULT_PREFETCH_CHUNKS};
+pub use progress::{Progress, ProgressEvent, TransferId};
 pub use register_apis::RegisterWriteAheadLog;
+pub use upload_manifest::{ChunkState, UploadManifest};
 
 use crate::client::{connections::Session, errors::Error, ClientConfig};
 use crate::messaging::{
@@ -23,13 +32,14 @@ use crate::types::{
     prefix_map::NetworkPrefixMap, utils::read_prefix_map_from_disk, Chunk, Keypair, Peer,
     PublicKey, RegisterAddress,
 };
+use progress::ProgressReporter;
 
 use bytes::Bytes;
 use itertools::Itertools;
 use rand::rngs::OsRng;
-use std::{collections::BTreeSet, net::SocketAddr, sync::Arc};
+use std::{collections::BTreeSet, net::SocketAddr, path::PathBuf, sync::Arc};
 use tokio::{
-    sync::{mpsc::Receiver, RwLock},
+    sync::{broadcast, mpsc::Receiver, RwLock},
     time::Duration,
 };
 use tracing::{debug, info};
@@ -56,6 +66,11 @@ pub struct Client {
     pub(crate) query_timeout: Duration,
     pub(crate) cmd_timeout: Duration,
     chunks_cache: Arc<RwLock<ChunksCache>>,
+    // Chunks cached on disk, if enabled, which outlive the client.
+    disk_chunk_cache: Option<ChunkCache>,
+    // Dir the manifests of uploads from a path are kept in, to resume them if interrupted.
+    root_dir: PathBuf,
+    progress: ProgressReporter,
 }
 
 /// Easily manage connections to/from The Safe Network with the client and its APIs.
@@ -160,6 +175,11 @@ impl Client {
             prefix_map.clone(),
         )?;
 
+        let disk_chunk_cache = match config.chunk_cache_size {
+            Some(max_size) => Some(ChunkCache::open(&config.root_dir, max_size).await?),
+            None => None,
+        };
+
         let client = Self {
             keypair,
             session,
@@ -167,6 +187,9 @@ impl Client {
             query_timeout: config.query_timeout,
             cmd_timeout: config.cmd_timeout,
             chunks_cache: Arc::new(RwLock::new(ChunksCache::default())),
+            disk_chunk_cache,
+            root_dir: config.root_dir,
+            progress: ProgressReporter::default(),
         };
 
         // TODO: The message being sent below is a temporary solution to fetch network info for
@@ -277,6 +300,18 @@ impl Client {
     pub fn public_key(&self) -> PublicKey {
         self.keypair().public_key()
     }
+
+    /// Returns the cache the chunks retrieved are kept in on disk, if enabled in the client's config.
+    pub fn chunk_cache(&self) -> Option<&ChunkCache> {
+        self.disk_chunk_cache.as_ref()
+    }
+
+    /// Returns a receiver of the progress of the uploads and downloads made with this client
+    /// and its clones, from now on. Every receiver obtained gets all the events, unless it falls
+    /// too far behind, in which case it misses the oldest ones.
+    pub async fn progress_events(&self) -> broadcast::Receiver<ProgressEvent> {
+        self.progress.subscribe().await
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,250 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::types::BytesAddress;
+
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+// Number of events kept for each receiver. A receiver falling further behind misses the oldest ones.
+const PROGRESS_EVENTS_CAPACITY: usize = 1024;
+
+/// Identifies an upload or download among those reported in [`ProgressEvent`]s.
+pub type TransferId = u64;
+
+/// Progress of an upload or download, as reported to the receivers obtained with
+/// [`Client::progress_events`](crate::client::Client::progress_events).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgressEvent {
+    /// The upload or download the event belongs to.
+    pub transfer: TransferId,
+    /// What happened in it.
+    pub progress: Progress,
+}
+
+/// Steps of an upload or download.
+///
+/// The `bytes` of chunk events are those of the file's content held in the chunk, so they add up
+/// to the size reported when the transfer started. Chunks holding the `DataMap` report none.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Progress {
+    /// An upload started, of the file at `path` if uploading from a path.
+    UploadStarted {
+        /// Path of the file being uploaded.
+        path: Option<PathBuf>,
+        /// Size of the content being uploaded.
+        bytes: u64,
+    },
+    /// A chunk was produced from the content being uploaded.
+    ChunkEncrypted {
+        /// Bytes of content in the chunk.
+        bytes: usize,
+    },
+    /// A chunk was sent to the network.
+    ChunkSent {
+        /// Bytes of content in the chunk.
+        bytes: usize,
+    },
+    /// The network acked storing a chunk, or it was already stored when resuming the upload.
+    ChunkAcked {
+        /// Bytes of content in the chunk.
+        bytes: usize,
+    },
+    /// All the chunks of the upload were stored.
+    UploadCompleted {
+        /// Address the content was stored at.
+        address: BytesAddress,
+    },
+    /// A download of the content at `address` started.
+    DownloadStarted {
+        /// Address of the content being downloaded.
+        address: BytesAddress,
+        /// Size of the content being downloaded.
+        bytes: u64,
+    },
+    /// A chunk of the content being downloaded was fetched.
+    ChunkFetched {
+        /// Bytes of content in the chunk.
+        bytes: usize,
+    },
+    /// All the content of the download was fetched.
+    DownloadCompleted,
+}
+
+// Hands out the ids of transfers, and broadcasts their events to the receivers, if any.
+#[derive(Clone, Debug)]
+pub(crate) struct ProgressReporter {
+    sender: Sender<ProgressEvent>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Default for ProgressReporter {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(PROGRESS_EVENTS_CAPACITY);
+        Self {
+            sender,
+            next_id: Arc::default(),
+        }
+    }
+}
+
+impl ProgressReporter {
+    // Returns a new receiver of the events of the transfers started from now on.
+    pub(crate) async fn subscribe(&self) -> Receiver<ProgressEvent> {
+        self.sender.subscribe()
+    }
+
+    // Starts reporting the progress of a new transfer, if anyone is receiving the events.
+    pub(crate) async fn start(&self, progress: Progress) -> Transfer {
+        let sender = (self.sender.receiver_count() > 0).then(|| self.sender.clone());
+        let transfer = Transfer {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            sender,
+        };
+        transfer.report(progress);
+        transfer
+    }
+}
+
+// Reports the progress of a single transfer.
+#[derive(Clone, Debug)]
+pub(crate) struct Transfer {
+    id: TransferId,
+    sender: Option<Sender<ProgressEvent>>,
+}
+
+impl Transfer {
+    // A transfer which reports nothing, for those whose progress isn't tracked.
+    pub(crate) fn untracked() -> Self {
+        Self {
+            id: TransferId::MAX,
+            sender: None,
+        }
+    }
+
+    pub(crate) fn report(&self, progress: Progress) {
+        if let Some(sender) = &self.sender {
+            // the receivers may have been dropped, in which case nobody is interested in the events
+            let _ = sender.send(ProgressEvent {
+                transfer: self.id,
+                progress,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Progress, ProgressEvent, ProgressReporter, PROGRESS_EVENTS_CAPACITY};
+    use crate::types::BytesAddress;
+    use eyre::Result;
+    use tokio::sync::broadcast::error::TryRecvError;
+    use xor_name::XorName;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn events_are_reported_per_transfer() -> Result<()> {
+        let reporter = ProgressReporter::default();
+
+        // nothing is reported before subscribing
+        let unreported = reporter
+            .start(Progress::UploadStarted {
+                path: None,
+                bytes: 10,
+            })
+            .await;
+        let mut receiver = reporter.subscribe().await;
+        unreported.report(Progress::ChunkAcked { bytes: 10 });
+
+        let upload = reporter
+            .start(Progress::UploadStarted {
+                path: None,
+                bytes: 10,
+            })
+            .await;
+        let address = BytesAddress::Public(XorName::random());
+        let download = reporter
+            .start(Progress::DownloadStarted { address, bytes: 5 })
+            .await;
+        upload.report(Progress::ChunkAcked { bytes: 10 });
+        download.report(Progress::ChunkFetched { bytes: 5 });
+        upload.report(Progress::UploadCompleted { address });
+
+        let events: Vec<_> = std::iter::from_fn(|| receiver.try_recv().ok()).collect();
+        assert_ne!(upload.id, download.id);
+        assert_eq!(
+            events,
+            vec![
+                ProgressEvent {
+                    transfer: upload.id,
+                    progress: Progress::UploadStarted {
+                        path: None,
+                        bytes: 10
+                    },
+                },
+                ProgressEvent {
+                    transfer: download.id,
+                    progress: Progress::DownloadStarted { address, bytes: 5 },
+                },
+                ProgressEvent {
+                    transfer: upload.id,
+                    progress: Progress::ChunkAcked { bytes: 10 },
+                },
+                ProgressEvent {
+                    transfer: download.id,
+                    progress: Progress::ChunkFetched { bytes: 5 },
+                },
+                ProgressEvent {
+                    transfer: upload.id,
+                    progress: Progress::UploadCompleted { address },
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn every_receiver_gets_the_events_it_keeps_up_with() -> Result<()> {
+        let reporter = ProgressReporter::default();
+        let mut first = reporter.subscribe().await;
+        let mut second = reporter.subscribe().await;
+
+        let upload = reporter
+            .start(Progress::UploadStarted {
+                path: None,
+                bytes: 10,
+            })
+            .await;
+        for receiver in [&mut first, &mut second] {
+            assert_eq!(
+                receiver.try_recv().ok().map(|event| event.progress),
+                Some(Progress::UploadStarted {
+                    path: None,
+                    bytes: 10
+                })
+            );
+        }
+
+        // a receiver which falls too far behind misses the oldest events, rather than
+        // having them pile up
+        for _ in 0..PROGRESS_EVENTS_CAPACITY + 1 {
+            upload.report(Progress::ChunkAcked { bytes: 10 });
+        }
+        assert!(matches!(first.try_recv(), Err(TryRecvError::Lagged(1))));
+        let received = std::iter::from_fn(|| first.try_recv().ok()).count();
+        assert_eq!(received, PROGRESS_EVENTS_CAPACITY);
+
+        Ok(())
+    }
+}
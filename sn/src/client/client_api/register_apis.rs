@@ -11,7 +11,8 @@ use super::Client;
 use crate::client::Error;
 use crate::messaging::data::{
     CreateRegister, DataCmd, DataQuery, DeleteRegister, EditRegister, QueryResponse, RegisterCmd,
-    RegisterQuery, SignedRegisterCreate, SignedRegisterDelete, SignedRegisterEdit,
+    RegisterQuery, SetRegisterPolicy, SignedRegisterCreate, SignedRegisterDelete,
+    SignedRegisterEdit, SignedRegisterSetPolicy,
 };
 use crate::types::{
     register::{Entry, EntryHash, Permissions, Policy, Register, User},
@@ -152,6 +153,42 @@ impl Client {
         Ok((hash, batch))
     }
 
+    /// Set the Policy of a Register
+    ///
+    /// Returns a write ahead log (WAL) of register operations, note that the changes are not uploaded to the
+    /// network until the WAL is published with `publish_register_ops`
+    ///
+    /// Only the owner of the Register can set its Policy, and the new Policy must keep
+    /// the same owner and be of the same kind (public or private) as the current one.
+    ///
+    /// The Register is fetched to version the new Policy after its current one.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn set_register_policy(
+        &self,
+        address: Address,
+        policy: Policy,
+    ) -> Result<RegisterWriteAheadLog, Error> {
+        let version = self.get_register(address).await?.policy_version() + 1;
+        let op = SetRegisterPolicy {
+            address,
+            policy,
+            version,
+        };
+        let signature = self.keypair.sign(&bincode::serialize(&op)?);
+
+        let update = SignedRegisterSetPolicy {
+            op,
+            auth: crate::messaging::ServiceAuth {
+                public_key: self.keypair.public_key(),
+                signature,
+            },
+        };
+
+        let cmd = DataCmd::Register(RegisterCmd::SetPolicy(update));
+
+        Ok(vec![cmd])
+    }
+
     //----------------------
     // Get Register
     //---------------------
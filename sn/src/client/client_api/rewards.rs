@@ -0,0 +1,46 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::Error;
+use crate::messaging::{
+    data::{RewardLedger, RewardQuery, ServiceMsg},
+    ServiceAuth, WireMsg,
+};
+
+impl Client {
+    /// Retrieves the reward ledgers kept by the Elders of every section we know about,
+    /// accounting for the work done by their Adults and the rewards credited to their wallets.
+    /// Each ledger is only returned once a majority of the Elders of its section agree on it.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_reward_ledgers(&self) -> Result<Vec<RewardLedger>, Error> {
+        let mut ledgers = vec![];
+        for prefix in self.session.known_prefixes() {
+            let query = RewardQuery::GetLedger(prefix.name());
+            ledgers.push(self.send_reward_query(query).await?);
+        }
+
+        Ok(ledgers)
+    }
+
+    // Sends a reward query to the Elders, awaiting a ledger a majority of them agree on.
+    async fn send_reward_query(&self, query: RewardQuery) -> Result<RewardLedger, Error> {
+        let payload = WireMsg::serialize_msg_payload(&ServiceMsg::Rewards(query.clone()))?;
+        let auth = ServiceAuth {
+            public_key: self.public_key(),
+            signature: self.keypair.sign(&payload),
+        };
+
+        tokio::time::timeout(
+            self.query_timeout,
+            self.session.send_reward_query(query, auth, payload),
+        )
+        .await
+        .map_err(|_| Error::QueryTimedOut)?
+    }
+}
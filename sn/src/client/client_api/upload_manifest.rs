@@ -0,0 +1,241 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{Error, Result};
+use crate::types::{BytesAddress, Scope};
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tokio::fs;
+use xor_name::XorName;
+
+// Dir the manifests of uploads are kept in, within the client's root dir
+const UPLOADS_DIR_NAME: &str = "uploads";
+
+/// State of a chunk of an upload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ChunkState {
+    /// The chunk was produced, but its storing hasn't been acked yet.
+    Pending,
+    /// The network acked storing the chunk.
+    Acked,
+}
+
+/// Record of the chunks of a file being uploaded and whether they've been stored, from which
+/// an interrupted upload can be resumed with [`Client::resume_upload`](crate::client::Client::resume_upload).
+///
+/// It's written under the client's root dir as the upload progresses, and removed once it completes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UploadManifest {
+    path: PathBuf,
+    scope: Scope,
+    file_size: u64,
+    modified: Option<SystemTime>,
+    address: Option<BytesAddress>,
+    chunks: BTreeMap<XorName, ChunkState>,
+}
+
+impl UploadManifest {
+    // Starts the manifest of a new upload of the file at `path`.
+    pub(crate) async fn new(path: &Path, scope: Scope) -> Result<Self> {
+        let path = fs::canonicalize(path).await?;
+        let metadata = fs::metadata(&path).await?;
+        Ok(Self {
+            path,
+            scope,
+            file_size: metadata.len(),
+            modified: metadata.modified().ok(),
+            address: None,
+            chunks: BTreeMap::new(),
+        })
+    }
+
+    // Reads the manifest of the upload of the file at `path`, if it was interrupted.
+    pub(crate) async fn read(root_dir: &Path, path: &Path, scope: Scope) -> Result<Option<Self>> {
+        let path = fs::canonicalize(path).await?;
+        let location = Self::location(root_dir, &path, scope);
+        if !location.is_file() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(location).await?;
+        Ok(Some(bincode::deserialize(&bytes)?))
+    }
+
+    /// Path of the file being uploaded.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Scope the file is being uploaded with.
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+
+    /// Size of the file being uploaded.
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    /// Address of the file, known once all its chunks have been produced.
+    pub fn address(&self) -> Option<BytesAddress> {
+        self.address
+    }
+
+    /// State of each chunk produced so far.
+    pub fn chunks(&self) -> &BTreeMap<XorName, ChunkState> {
+        &self.chunks
+    }
+
+    /// Number of chunks whose storing has been acked.
+    pub fn acked_count(&self) -> usize {
+        self.chunks
+            .values()
+            .filter(|state| **state == ChunkState::Acked)
+            .count()
+    }
+
+    pub(crate) fn is_acked(&self, name: &XorName) -> bool {
+        self.chunks.get(name) == Some(&ChunkState::Acked)
+    }
+
+    pub(crate) fn add(&mut self, name: XorName) {
+        let _ = self.chunks.entry(name).or_insert(ChunkState::Pending);
+    }
+
+    pub(crate) fn ack(&mut self, name: XorName) {
+        let _ = self.chunks.insert(name, ChunkState::Acked);
+    }
+
+    pub(crate) fn set_address(&mut self, address: BytesAddress) {
+        self.address = Some(address);
+    }
+
+    // Makes sure the file hasn't changed since the manifest was started, as the chunks already
+    // stored would otherwise not be those of its current content.
+    pub(crate) async fn check_source(&self) -> Result<()> {
+        let metadata = fs::metadata(&self.path).await?;
+        if metadata.len() != self.file_size || metadata.modified().ok() != self.modified {
+            return Err(Error::UploadSourceChanged(self.path.clone()));
+        }
+        Ok(())
+    }
+
+    // Writes the manifest under the client's root dir.
+    pub(crate) async fn write(&self, root_dir: &Path) -> Result<()> {
+        let location = Self::location(root_dir, &self.path, self.scope);
+        if let Some(dir) = location.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        let bytes = bincode::serialize(self)?;
+
+        // Write to a temporary file first, so a previous checkpoint is not lost if we stop halfway.
+        let tmp_location = location.with_extension("tmp");
+        fs::write(&tmp_location, bytes).await?;
+        fs::rename(tmp_location, location).await?;
+
+        Ok(())
+    }
+
+    // Removes the manifest from under the client's root dir, once the upload has completed.
+    pub(crate) async fn remove(&self, root_dir: &Path) -> Result<()> {
+        let location = Self::location(root_dir, &self.path, self.scope);
+        match fs::remove_file(location).await {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    // Where the manifest of the upload of the file at `path` is kept, named after the path and scope.
+    // The path must be canonical, so the same file is found however it's referred to.
+    fn location(root_dir: &Path, path: &Path, scope: Scope) -> PathBuf {
+        let mut key = path.to_string_lossy().as_bytes().to_vec();
+        key.push(scope as u8);
+        root_dir
+            .join(UPLOADS_DIR_NAME)
+            .join(hex::encode(XorName::from_content(&key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChunkState, UploadManifest};
+    use crate::types::{BytesAddress, Scope};
+    use eyre::Result;
+    use std::io::Write;
+    use xor_name::XorName;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn manifest_checkpoints_acked_chunks() -> Result<()> {
+        let root_dir = tempfile::tempdir()?;
+        let mut file = tempfile::NamedTempFile::new()?;
+        file.write_all(b"some content to upload")?;
+
+        let mut manifest = UploadManifest::new(file.path(), Scope::Public).await?;
+        let acked = XorName::random();
+        let pending = XorName::random();
+        manifest.add(acked);
+        manifest.add(pending);
+        manifest.ack(acked);
+        manifest.write(root_dir.path()).await?;
+
+        // a manifest is kept per path and scope
+        assert!(
+            UploadManifest::read(root_dir.path(), file.path(), Scope::Private)
+                .await?
+                .is_none()
+        );
+        let read = UploadManifest::read(root_dir.path(), file.path(), Scope::Public)
+            .await?
+            .ok_or_else(|| eyre::eyre!("manifest not found"))?;
+        assert!(read.is_acked(&acked));
+        assert_eq!(read.chunks().get(&pending), Some(&ChunkState::Pending));
+        assert_eq!(read.acked_count(), 1);
+        read.check_source().await?;
+
+        // adding an acked chunk again, as it's produced on resume, doesn't reset its state
+        manifest.add(acked);
+        assert!(manifest.is_acked(&acked));
+
+        manifest.set_address(BytesAddress::Public(XorName::random()));
+        manifest.remove(root_dir.path()).await?;
+        assert!(
+            UploadManifest::read(root_dir.path(), file.path(), Scope::Public)
+                .await?
+                .is_none()
+        );
+
+        // the manifest is found however the file is referred to
+        manifest.write(root_dir.path()).await?;
+        let dir = file
+            .path()
+            .parent()
+            .ok_or_else(|| eyre::eyre!("temp file without a parent dir"))?;
+        let file_name = file
+            .path()
+            .file_name()
+            .ok_or_else(|| eyre::eyre!("temp file without a name"))?;
+        let indirect_path = dir.join(".").join(file_name);
+        assert!(
+            UploadManifest::read(root_dir.path(), &indirect_path, Scope::Public)
+                .await?
+                .is_some()
+        );
+        manifest.remove(root_dir.path()).await?;
+
+        // the file changing invalidates the manifest
+        file.write_all(b" and more")?;
+        assert!(read.check_source().await.is_err());
+
+        Ok(())
+    }
+}
@@ -32,6 +32,7 @@ const DEFAULT_ROOT_DIR_NAME: &str = "root_dir";
 const SN_QUERY_TIMEOUT: &str = "SN_QUERY_TIMEOUT";
 const SN_CMD_TIMEOUT: &str = "SN_CMD_TIMEOUT";
 const SN_AE_WAIT: &str = "SN_AE_WAIT";
+const SN_CHUNK_CACHE_SIZE: &str = "SN_CHUNK_CACHE_SIZE";
 
 /// Configuration for sn_client.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -50,6 +51,10 @@ pub struct ClientConfig {
     pub cmd_timeout: Duration,
     /// The amount of time to wait after a cmd is sent for AE flows to complete.
     pub cmd_ack_wait: Duration,
+    /// Bytes the chunks retrieved can take when cached on disk under `root_dir`,
+    /// so they are not retrieved from the network again. They're not cached on disk if not set.
+    #[serde(default)]
+    pub chunk_cache_size: Option<u64>,
 }
 
 impl ClientConfig {
@@ -63,6 +68,9 @@ impl ClientConfig {
     /// port).
     ///
     /// If `query_timeout` is not specified, [`DEFAULT_OPERATION_TIMEOUT`] will be used.
+    ///
+    /// Chunks are cached on disk if a size limit is set with the `SN_CHUNK_CACHE_SIZE` env var,
+    /// see [`chunk_cache_size_from_env`].
     pub async fn new(
         root_dir: Option<&Path>,
         local_addr: Option<SocketAddr>,
@@ -140,6 +148,8 @@ impl ClientConfig {
             Err(_) => cmd_ack_wait,
         };
 
+        let chunk_cache_size = chunk_cache_size_from_env();
+
         info!(
             "Client set to use a query timeout of {:?}, and AE await post-put for {:?}",
             query_timeout, cmd_ack_wait
@@ -152,10 +162,28 @@ impl ClientConfig {
             query_timeout,
             cmd_timeout,
             cmd_ack_wait,
+            chunk_cache_size,
+        }
+    }
+}
+
+/// Size limit in bytes of the on-disk chunk cache, as set with the `SN_CHUNK_CACHE_SIZE` env var.
+pub fn chunk_cache_size_from_env() -> Option<u64> {
+    let size = std::env::var(SN_CHUNK_CACHE_SIZE).ok()?;
+    match size.parse() {
+        Ok(size) => Some(size),
+        Err(error) => {
+            warn!("There was an error parsing {} env var value: '{}'. Chunks won't be cached on disk: {:?}", SN_CHUNK_CACHE_SIZE, size, error);
+            None
         }
     }
 }
 
+/// Root directory the client keeps its state in when none is set in its config.
+pub fn default_root_dir() -> PathBuf {
+    default_dir()
+}
+
 async fn read_config_file(filepath: &Path) -> Result<QuicP2pConfig, Error> {
     debug!("Reading config file '{}' ...", filepath.display());
     let mut file = File::open(filepath).await?;
@@ -275,6 +303,9 @@ mod tests {
             query_timeout: expected_query_timeout,
             cmd_timeout: expected_cmd_timeout,
             cmd_ack_wait: expected_cmd_ack_wait,
+            chunk_cache_size: std::env::var(SN_CHUNK_CACHE_SIZE)
+                .ok()
+                .and_then(|v| v.parse().ok()),
         };
         assert_eq!(format!("{:?}", config), format!("{:?}", expected_config));
         assert_eq!(serialize(&config)?, serialize(&expected_config)?);
@@ -8,7 +8,6 @@
 
 use super::Session;
 
-use crate::at_least_one_correct_elder;
 use crate::client::{
     connections::{
         messaging::{send_msg, NUM_OF_ELDERS_SUBSET_FOR_QUERIES},
@@ -23,6 +22,7 @@ use crate::messaging::{
 };
 use crate::node::SectionAuthorityProvider;
 use crate::types::{log_markers::LogMarker, utils::compare_and_write_prefix_map_to_disk, Peer};
+use crate::{at_least_one_correct_elder, elder_count};
 
 use bytes::Bytes;
 use itertools::Itertools;
@@ -424,6 +424,7 @@ impl Session {
         let (target_count, dst_address_of_bounced_msg) = match service_msg.clone() {
             ServiceMsg::Cmd(cmd) => (at_least_one_correct_elder(), cmd.dst_name()),
             ServiceMsg::Query(query) => (NUM_OF_ELDERS_SUBSET_FOR_QUERIES, query.dst_name()),
+            ServiceMsg::Rewards(query) => (elder_count(), query.dst_name()),
             _ => {
                 warn!(
                     "Invalid bounced msg {:?} received in AE response: {:?}. Msg is of invalid type",
@@ -10,7 +10,7 @@ use super::{QueryResult, Session};
 
 use crate::client::{connections::CmdResponse, Error, Result};
 use crate::messaging::{
-    data::{CmdError, DataQuery, QueryResponse},
+    data::{CmdError, DataQuery, QueryResponse, RewardLedger, RewardQuery},
     DstLocation, MsgId, MsgKind, ServiceAuth, WireMsg,
 };
 use crate::types::{prefix_map::NetworkPrefixMap, Peer, PeerLinks, PublicKey, SendToOneError};
@@ -29,7 +29,7 @@ use tokio::{
     task::JoinHandle,
 };
 use tracing::{debug, error, trace, warn};
-use xor_name::XorName;
+use xor_name::{Prefix, XorName};
 
 // Number of Elders subset to send queries to
 pub(crate) const NUM_OF_ELDERS_SUBSET_FOR_QUERIES: usize = 3;
@@ -73,12 +73,15 @@ impl Session {
         Ok(session)
     }
 
+    /// Sends a cmd to the Elders of the destination section, waiting for a majority of them
+    /// to ack it. If `require_acks` is set, not receiving enough acks in time is an error.
     #[instrument(skip(self, auth, payload), level = "debug", name = "session send cmd")]
     pub(crate) async fn send_cmd(
         &self,
         dst_address: XorName,
         auth: ServiceAuth,
         payload: Bytes,
+        require_acks: bool,
     ) -> Result<()> {
         let endpoint = self.endpoint.clone();
         // TODO: Consider other approach: Keep a session per section!
@@ -164,6 +167,14 @@ impl Session {
                     "Terminated with insufficient CmdAcks for {:?}, {:?} / {:?} acks received",
                     msg_id, received_ack, expected_acks
                 );
+                if require_acks {
+                    let _ = self.pending_cmds.remove(&msg_id);
+                    return Err(Error::InsufficientAcks {
+                        msg_id,
+                        received: received_ack,
+                        required: expected_acks,
+                    });
+                }
                 break;
             }
             trace!(
@@ -330,6 +341,112 @@ impl Session {
         }
     }
 
+    #[instrument(skip_all, level = "debug")]
+    /// Send a reward query to all the Elders of the section closest to its destination,
+    /// returning the ledger once a majority of them responded with ledgers agreeing
+    /// with each other, which fails if every Elder responded without that happening.
+    pub(crate) async fn send_reward_query(
+        &self,
+        query: RewardQuery,
+        auth: ServiceAuth,
+        payload: Bytes,
+    ) -> Result<RewardLedger> {
+        let dst = query.dst_name();
+        let op_id = query
+            .operation_id()
+            .map_err(|_| Error::UnknownOperationId)?;
+
+        // every Elder keeps the ledger, so we reach all of them
+        let (section_pk, elders) = match self.network.closest_or_opposite(&dst, None) {
+            Some(sap) => (sap.section_key(), sap.elders_vec()),
+            None => return Err(Error::NoNetworkKnowledge),
+        };
+        let elders_len = elders.len();
+        let msg_id = MsgId::new();
+
+        debug!(
+            "Sending reward query message {:?}, msg_id: {:?}, to the {} Elders of section {:?}",
+            query, msg_id, elders_len, section_pk
+        );
+
+        let (sender, mut receiver) = channel::<QueryResponse>(elders_len.max(1));
+        if let Some(mut entry) = self.pending_queries.get_mut(&op_id) {
+            entry.value_mut().push((msg_id, sender));
+        } else {
+            let _nonexistant_entry = self.pending_queries.insert(op_id, vec![(msg_id, sender)]);
+        }
+
+        let dst_location = DstLocation::Section {
+            name: dst,
+            section_pk,
+        };
+        let msg_kind = MsgKind::ServiceMsg(auth);
+        let wire_msg = WireMsg::new_msg(msg_id, payload, msg_kind, dst_location)?;
+
+        send_msg(self.clone(), elders, wire_msg, msg_id).await?;
+
+        let required = std::cmp::max(1, elders_len * 2 / 3);
+        // the distinct ledgers received, along with the number of Elders agreeing on each
+        let mut ledgers: Vec<(RewardLedger, usize)> = vec![];
+        let mut error = None;
+        let mut received = 0;
+        let result = loop {
+            let response = match receiver.recv().await {
+                Some(response) => response,
+                None => break Err(error.unwrap_or(Error::NoResponse)),
+            };
+            received += 1;
+            match response {
+                QueryResponse::GetRewardLedger((Ok(ledger), _)) => {
+                    match ledgers
+                        .iter_mut()
+                        .find(|(agreed, _)| agreed.agrees_with(&ledger))
+                    {
+                        Some((_, agreeing)) => *agreeing += 1,
+                        None => ledgers.push((ledger, 1)),
+                    }
+                }
+                QueryResponse::GetRewardLedger((Err(source), op_id)) => {
+                    debug!("Reward QueryResponse error received: {:?}", source);
+                    error = Some(Error::ErrorMsg { source, op_id });
+                }
+                other => error = Some(Error::UnexpectedQueryResponse(other)),
+            }
+
+            if let Some((ledger, _)) = ledgers.iter().find(|(_, agreeing)| *agreeing >= required) {
+                break Ok(ledger.clone());
+            }
+            if received >= elders_len {
+                let agreeing = ledgers.iter().map(|(_, agreeing)| *agreeing).max();
+                break Err(match (agreeing, error) {
+                    (None, Some(error)) => error,
+                    (agreeing, _) => Error::InsufficientAgreement {
+                        agreeing: agreeing.unwrap_or_default(),
+                        required,
+                    },
+                });
+            }
+        };
+
+        // Remove the response sender
+        if let Some(mut entry) = self.pending_queries.get_mut(&op_id) {
+            let listeners_for_op = entry.value_mut();
+            if let Some(index) = listeners_for_op
+                .iter()
+                .position(|(id, _sender)| *id == msg_id)
+            {
+                let _old_listener = listeners_for_op.swap_remove(index);
+            }
+        }
+
+        result
+    }
+
+    /// Prefixes of all the sections we know about.
+    pub(crate) fn known_prefixes(&self) -> Vec<Prefix> {
+        self.network.all().iter().map(|sap| sap.prefix()).collect()
+    }
+
     #[instrument(skip_all, level = "debug")]
     pub(crate) async fn make_contact_with_nodes(
         &self,
@@ -15,6 +15,7 @@ use crate::types::Error as DtError;
 use bls::PublicKey;
 use std::io;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use thiserror::Error;
 
 /// Specialisation of `std::Result` for Client.
@@ -71,9 +72,6 @@ pub enum Error {
     /// Peer connection retrieval failed
     #[error("Error with Peer's connection: {0:?}")]
     PeerConnection(SocketAddr),
-    /// Cannot store empty file..
-    #[error("Cannot store empty file.")]
-    EmptyFileProvided,
     /// Not enough bytes for self-encryption.
     #[error("Not enough bytes for self-encryption. Try storing it as a SmallFile.")]
     TooSmallForSelfEncryption,
@@ -135,6 +133,9 @@ pub enum Error {
     /// Io error.
     #[error(transparent)]
     IoError(#[from] io::Error),
+    /// A spawned task could not be completed.
+    #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
     /// Endpoint setup error.
     #[error(transparent)]
     EndpointSetup(#[from] qp2p::ClientEndpointError),
@@ -166,6 +167,45 @@ pub enum Error {
         /// Number of Chunks generated
         chunked: usize,
     },
+    /// The content of a file changed while it was being self-encrypted, so the chunks read
+    /// so far don't match those it was first hashed as.
+    #[error("File content changed while it was being encrypted, chunk {index} differs.")]
+    FileChangedWhileEncrypting {
+        /// Index of the chunk which differs
+        index: usize,
+    },
+    /// Some chunks of an upload could not be stored, even after retrying them.
+    /// The upload can be resumed from its manifest, without resending the chunks already stored.
+    #[error(
+        "Upload of {path:?} incomplete, {failed} chunks could not be stored. It can be resumed."
+    )]
+    UploadIncomplete {
+        /// Path of the file being uploaded
+        path: PathBuf,
+        /// Number of chunks which could not be stored
+        failed: usize,
+    },
+    /// The file being uploaded changed since the upload was interrupted, so it can't be resumed.
+    #[error("File {0:?} changed since its upload was interrupted, it needs to be uploaded anew.")]
+    UploadSourceChanged(PathBuf),
+    /// Not enough Elders acked a cmd which had to be confirmed, so it may not have been carried out.
+    #[error("Only {received} of the {required} acks needed were received for cmd {msg_id:?}")]
+    InsufficientAcks {
+        /// Id of the cmd
+        msg_id: MsgId,
+        /// Number of acks received
+        received: usize,
+        /// Number of acks needed
+        required: usize,
+    },
+    /// Not enough Elders responded with the same data for it to be trusted.
+    #[error("Only {agreeing} of the {required} Elders needed agreed on the response")]
+    InsufficientAgreement {
+        /// Largest number of Elders which agreed on a response
+        agreeing: usize,
+        /// Number of Elders needed to agree
+        required: usize,
+    },
 }
 
 impl From<(CmdError, MsgId)> for Error {
@@ -30,8 +30,14 @@ mod connections;
 mod errors;
 
 // Export public API.
-pub use client_api::{Client, RegisterWriteAheadLog};
-pub use config_handler::{ClientConfig, DEFAULT_ACK_WAIT, DEFAULT_OPERATION_TIMEOUT};
+pub use client_api::{
+    ChunkCache, ChunkCacheStats, ChunkState, Client, FileReader, Progress, ProgressEvent,
+    RegisterWriteAheadLog, TransferId, UploadManifest, DEFAULT_PREFETCH_CHUNKS,
+};
+pub use config_handler::{
+    chunk_cache_size_from_env, default_root_dir, ClientConfig, DEFAULT_ACK_WAIT,
+    DEFAULT_OPERATION_TIMEOUT,
+};
 pub use errors::ErrorMsg;
 pub use errors::{Error, Result};
 pub use qp2p::Config as QuicP2pConfig;
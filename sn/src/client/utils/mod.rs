@@ -10,38 +10,130 @@
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
-use crate::types::{Encryption, PublicKey, Result as TypesResult, Scope};
+use crate::types::{
+    Encryption, Error as TypesError, Keypair, PublicKey, Result as TypesResult, Scope,
+};
+use aes::{
+    cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit},
+    Aes128,
+};
 use bytes::Bytes;
 use rand::{self, distributions::Alphanumeric, rngs::OsRng, Rng};
+use tiny_keccak::{Hasher, Sha3};
 
-struct DummyEncryption {
+type Aes128CbcEnc = cbc::Encryptor<Aes128>;
+type Aes128CbcDec = cbc::Decryptor<Aes128>;
+
+const KEY_SIZE: usize = 16;
+const IV_SIZE: usize = 16;
+// Prefixes private data encrypted by `OwnerEncryption`, followed by the version of its format.
+const ENCRYPTION_MAGIC: &[u8] = b"SNOE";
+// Format of the private data encrypted by `OwnerEncryption`: the IV followed by the AES-128-CBC
+// ciphertext with PKCS7 padding.
+const ENCRYPTION_FORMAT_VERSION: u8 = 1;
+const HEADER_SIZE: usize = ENCRYPTION_MAGIC.len() + 1;
+/// Maximum number of bytes the encryption of private data adds to it,
+/// i.e. the header, the IV and a block of padding.
+pub(crate) const MAX_ENCRYPTION_OVERHEAD: usize = HEADER_SIZE + IV_SIZE + 16;
+// Domain separation for the key derived from the owner's secret key
+const KEY_DERIVATION_SALT: &[u8] = b"safe_network private data";
+
+/// Encrypts private data with a symmetric key derived from its owner's secret key.
+///
+/// The IV is derived from the key and the plain content, so the same content encrypted
+/// by the same owner always produces the same ciphertext, and thus the same chunk address.
+/// It also lets the owner check, upon decryption, that the content was encrypted with its key.
+///
+/// Data which doesn't start with the header wasn't encrypted by an owner, and fails to decrypt
+/// rather than being returned as if it were the plain content.
+struct OwnerEncryption {
     public_key: PublicKey,
+    key: [u8; KEY_SIZE],
 }
 
-impl DummyEncryption {
-    pub(crate) fn new(public_key: PublicKey) -> Self {
-        Self { public_key }
+impl OwnerEncryption {
+    fn new(keypair: &Keypair) -> Self {
+        let secret = match keypair {
+            Keypair::Ed25519(pair) => pair.secret.to_bytes().to_vec(),
+            Keypair::BlsShare(share) => share.secret.to_bytes().to_vec(),
+        };
+        let mut key = [0; KEY_SIZE];
+        key.copy_from_slice(&sha3_256(&[KEY_DERIVATION_SALT, &secret])[..KEY_SIZE]);
+
+        Self {
+            public_key: keypair.public_key(),
+            key,
+        }
+    }
+
+    fn iv_for(&self, content: &[u8]) -> [u8; IV_SIZE] {
+        let mut iv = [0; IV_SIZE];
+        iv.copy_from_slice(&sha3_256(&[&self.key, content])[..IV_SIZE]);
+        iv
+    }
+
+    fn decrypt_v1(&self, encrypted: &[u8]) -> TypesResult<Bytes> {
+        if encrypted.len() < IV_SIZE {
+            return Err(TypesError::FailedToDecrypt);
+        }
+        let (iv, ciphertext) = encrypted.split_at(IV_SIZE);
+        let cipher = Aes128CbcDec::new(&self.key.into(), iv.into());
+        let data = cipher
+            .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+            .map_err(|_| TypesError::FailedToDecrypt)?;
+
+        // a different key would have derived a different IV
+        if self.iv_for(&data) != iv {
+            return Err(TypesError::FailedToDecrypt);
+        }
+
+        Ok(Bytes::from(data))
     }
 }
 
-impl Encryption for DummyEncryption {
+impl Encryption for OwnerEncryption {
     fn public_key(&self) -> &PublicKey {
         &self.public_key
     }
+
     fn encrypt(&self, data: Bytes) -> TypesResult<Bytes> {
-        Ok(data)
+        let iv = self.iv_for(&data);
+        let cipher = Aes128CbcEnc::new(&self.key.into(), &iv.into());
+        let mut encrypted = ENCRYPTION_MAGIC.to_vec();
+        encrypted.push(ENCRYPTION_FORMAT_VERSION);
+        encrypted.extend(iv);
+        encrypted.extend(cipher.encrypt_padded_vec_mut::<Pkcs7>(&data));
+        Ok(Bytes::from(encrypted))
     }
+
     fn decrypt(&self, encrypted_data: Bytes) -> TypesResult<Bytes> {
-        Ok(encrypted_data)
+        let versioned = encrypted_data
+            .strip_prefix(ENCRYPTION_MAGIC)
+            .ok_or(TypesError::FailedToDecrypt)?;
+        match versioned.split_first() {
+            Some((&ENCRYPTION_FORMAT_VERSION, encrypted)) => self.decrypt_v1(encrypted),
+            _ => Err(TypesError::FailedToDecrypt),
+        }
     }
 }
 
-///
-pub fn encryption(scope: Scope, public_key: PublicKey) -> Option<impl Encryption> {
+/// Returns the encryption to apply to data of the given scope, owned by the given keypair:
+/// none for public data, and one with a key derived from the owner's secret key for private data.
+pub fn encryption(scope: Scope, keypair: &Keypair) -> Option<impl Encryption> {
     match scope {
         Scope::Public => None,
-        Scope::Private => Some(DummyEncryption::new(public_key)),
+        Scope::Private => Some(OwnerEncryption::new(keypair)),
+    }
+}
+
+fn sha3_256(inputs: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha3::v256();
+    for input in inputs {
+        hasher.update(input);
     }
+    let mut hash = [0; 32];
+    hasher.finalize(&mut hash);
+    hash
 }
 
 /// Generates a `String` from `length` random UTF-8 `char`s.  Note that the NULL character will be
@@ -126,4 +218,52 @@ mod tests {
         assert_eq!(vec1.len(), SIZE);
         assert_eq!(vec2.len(), SIZE);
     }
+
+    #[test]
+    fn private_data_is_only_decrypted_by_its_owner() -> TypesResult<()> {
+        let data = random_bytes(SIZE * 100);
+        let owner = Keypair::new_ed25519(&mut OsRng);
+        let encryption = OwnerEncryption::new(&owner);
+
+        let encrypted = encryption.encrypt(data.clone())?;
+        assert_ne!(encrypted, data);
+        // encryption is deterministic for a given owner
+        assert_eq!(encryption.encrypt(data.clone())?, encrypted);
+        assert_eq!(encryption.decrypt(encrypted.clone())?, data);
+
+        let other = OwnerEncryption::new(&Keypair::new_ed25519(&mut OsRng));
+        assert_ne!(other.encrypt(data)?, encrypted);
+        assert!(matches!(
+            other.decrypt(encrypted),
+            Err(TypesError::FailedToDecrypt)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn private_data_without_the_header_is_not_decrypted() -> TypesResult<()> {
+        let data = random_bytes(SIZE * 100);
+        let encryption = OwnerEncryption::new(&Keypair::new_ed25519(&mut OsRng));
+
+        // data which was never encrypted isn't taken for the plain content
+        assert!(matches!(
+            encryption.decrypt(data.clone()),
+            Err(TypesError::FailedToDecrypt)
+        ));
+
+        // whereas encrypted data is in a versioned format
+        let encrypted = encryption.encrypt(data)?;
+        assert!(encrypted.starts_with(ENCRYPTION_MAGIC));
+        assert_eq!(encrypted[ENCRYPTION_MAGIC.len()], ENCRYPTION_FORMAT_VERSION);
+
+        let mut unknown_version = encrypted.to_vec();
+        unknown_version[ENCRYPTION_MAGIC.len()] = ENCRYPTION_FORMAT_VERSION + 1;
+        assert!(matches!(
+            encryption.decrypt(Bytes::from(unknown_version)),
+            Err(TypesError::FailedToDecrypt)
+        ));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,152 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::StorageBackend;
+use crate::dbs::{Error, Result};
+
+use async_trait::async_trait;
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use tokio::{fs, io::AsyncWriteExt};
+use walkdir::WalkDir;
+
+const BIT_TREE_DEPTH: usize = 20;
+
+/// Stores each value in its own file, within a prefix tree of directories
+/// made from the leading bits of its key.
+#[derive(Debug)]
+pub(super) struct FileTreeBackend {
+    bit_tree_depth: usize,
+    root: PathBuf,
+}
+
+impl FileTreeBackend {
+    pub(super) fn new(root: PathBuf) -> Self {
+        Self {
+            bit_tree_depth: BIT_TREE_DEPTH,
+            root,
+        }
+    }
+
+    // Helper that returns the prefix tree path of depth `bit_count` for a given key
+    // Example:
+    // - with a key with starting bits `010001110110....`
+    // - and a bit_count of `6`
+    // returns the path `ROOT/0/1/0/0/0/1`
+    // If the provided bit count is larger than `self.bit_tree_depth`, uses `self.bit_tree_depth`
+    // to stay within the prefix tree path
+    fn prefix_tree_path(&self, key: &[u8], bit_count: usize) -> PathBuf {
+        let prefix_dir_path: PathBuf = key
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1))
+            .take(std::cmp::min(bit_count, self.bit_tree_depth))
+            .map(|bit| bit.to_string())
+            .collect();
+
+        self.root.join(prefix_dir_path)
+    }
+
+    fn key_to_filepath(&self, key: &[u8]) -> PathBuf {
+        self.prefix_tree_path(key, self.bit_tree_depth)
+            .join(hex::encode(key))
+    }
+
+    fn filepath_to_key(path: &Path) -> Result<Vec<u8>> {
+        let filename = path
+            .file_name()
+            .ok_or(Error::NoFilename)?
+            .to_str()
+            .ok_or(Error::InvalidFilename)?;
+        hex::decode(filename).map_err(|_| Error::InvalidFilename)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for FileTreeBackend {
+    async fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.key_to_filepath(key)).await {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        let filepath = self.key_to_filepath(key);
+        if let Some(dirs) = filepath.parent() {
+            fs::create_dir_all(dirs).await?;
+        }
+
+        let mut file = fs::File::create(filepath).await?;
+        file.write_all(value).await?;
+
+        Ok(())
+    }
+
+    async fn insert_if_absent(&self, key: &[u8], value: &[u8]) -> Result<bool> {
+        let filepath = self.key_to_filepath(key);
+        if let Some(dirs) = filepath.parent() {
+            fs::create_dir_all(dirs).await?;
+        }
+
+        // creating the file fails if it exists already
+        let mut file = match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(filepath)
+            .await
+        {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => return Ok(false),
+            Err(error) => return Err(error.into()),
+        };
+        file.write_all(value).await?;
+
+        Ok(true)
+    }
+
+    async fn delete(&self, key: &[u8]) -> Result<()> {
+        match fs::remove_file(self.key_to_filepath(key)).await {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn list(&self, prefix: &[u8]) -> Result<Vec<Vec<u8>>> {
+        // only the subtree the prefix leads to needs to be walked
+        let dir = self.prefix_tree_path(prefix, 8 * prefix.len());
+        let mut keys = WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|e| match e {
+                Ok(direntry) => Some(direntry),
+                Err(err) => {
+                    if err.io_error().map(|e| e.kind()) != Some(ErrorKind::NotFound) {
+                        warn!("FileTreeBackend: failed to process file entry: {}", err);
+                    }
+                    None
+                }
+            })
+            .filter(|e| e.file_type().is_file())
+            .map(|e| Self::filepath_to_key(e.path()))
+            .collect::<Result<Vec<_>>>()?;
+
+        keys.retain(|key| key.starts_with(prefix));
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn size(&self, key: &[u8]) -> Result<Option<u64>> {
+        match fs::metadata(self.key_to_filepath(key)).await {
+            Ok(meta) => Ok(Some(meta.len())),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
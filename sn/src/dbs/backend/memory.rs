@@ -0,0 +1,68 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::StorageBackend;
+use crate::dbs::Result;
+
+use async_trait::async_trait;
+use std::collections::{btree_map::Entry, BTreeMap};
+use tokio::sync::RwLock;
+
+/// Keeps the values in memory, so they are lost when dropped.
+#[derive(Debug, Default)]
+pub(super) struct MemoryBackend {
+    map: RwLock<BTreeMap<Vec<u8>, Vec<u8>>>,
+}
+
+#[async_trait]
+impl StorageBackend for MemoryBackend {
+    async fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.map.read().await.get(key).cloned())
+    }
+
+    async fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        let _prev = self.map.write().await.insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    async fn insert_if_absent(&self, key: &[u8], value: &[u8]) -> Result<bool> {
+        match self.map.write().await.entry(key.to_vec()) {
+            Entry::Occupied(_) => Ok(false),
+            Entry::Vacant(entry) => {
+                let _ = entry.insert(value.to_vec());
+                Ok(true)
+            }
+        }
+    }
+
+    async fn delete(&self, key: &[u8]) -> Result<()> {
+        let _prev = self.map.write().await.remove(key);
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &[u8]) -> Result<Vec<Vec<u8>>> {
+        Ok(self
+            .map
+            .read()
+            .await
+            .range(prefix.to_vec()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+
+    async fn size(&self, key: &[u8]) -> Result<Option<u64>> {
+        Ok(self
+            .map
+            .read()
+            .await
+            .get(key)
+            .map(|value| value.len() as u64))
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod file_tree;
+mod memory;
+mod redb_db;
+mod sled_db;
+
+use super::Result;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// Key-value storage the node's data stores are kept in.
+#[async_trait]
+pub(crate) trait StorageBackend: Debug + Send + Sync {
+    /// Returns the value stored at the key, if any.
+    async fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Stores the value at the key, replacing any previous one.
+    async fn put(&self, key: &[u8], value: &[u8]) -> Result<()>;
+
+    /// Stores the value at the key only if there's none yet, as a single atomic operation.
+    /// Returns whether it was stored.
+    async fn insert_if_absent(&self, key: &[u8], value: &[u8]) -> Result<bool>;
+
+    /// Removes the value stored at the key. Does nothing if there's none.
+    async fn delete(&self, key: &[u8]) -> Result<()>;
+
+    /// Returns all the keys starting with the prefix, in ascending order.
+    async fn list(&self, prefix: &[u8]) -> Result<Vec<Vec<u8>>>;
+
+    /// Returns the size in bytes of the value stored at the key, if any.
+    async fn size(&self, key: &[u8]) -> Result<Option<u64>>;
+}
+
+/// The kinds of storage backend a node can keep its data in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageBackendKind {
+    /// A tree of directories on disk, with a file per value.
+    FileTree,
+    /// A `sled` database.
+    Sled,
+    /// A single file embedded database (`redb`).
+    Redb,
+    /// Kept in memory only, and lost when the node stops. Meant for tests.
+    Memory,
+}
+
+impl StorageBackendKind {
+    /// Opens the backend named `name` within the `root` dir, creating it if it doesn't exist.
+    pub(crate) async fn open(self, root: &Path, name: &str) -> Result<Arc<dyn StorageBackend>> {
+        Ok(match (self, self.path(root, name)) {
+            (Self::FileTree, Some(path)) => Arc::new(file_tree::FileTreeBackend::new(path)),
+            (Self::Sled, Some(path)) => Arc::new(sled_db::SledBackend::open(&path).await?),
+            (Self::Redb, Some(path)) => Arc::new(redb_db::RedbBackend::open(&path)?),
+            _ => Arc::new(memory::MemoryBackend::default()),
+        })
+    }
+
+    /// Whether the backend named `name` was already created within the `root` dir.
+    pub(crate) fn exists(self, root: &Path, name: &str) -> bool {
+        match self.path(root, name) {
+            Some(path) => path.exists(),
+            None => false,
+        }
+    }
+
+    // Where on disk the backend is kept, if it is
+    fn path(self, root: &Path, name: &str) -> Option<PathBuf> {
+        match self {
+            Self::FileTree => Some(root.join(name)),
+            Self::Sled => Some(root.join("db").join(name)),
+            Self::Redb => Some(root.join(format!("{}.redb", name))),
+            Self::Memory => None,
+        }
+    }
+}
+
+impl FromStr for StorageBackendKind {
+    type Err = String;
+
+    fn from_str(str: &str) -> std::result::Result<Self, String> {
+        match str {
+            "file-tree" => Ok(Self::FileTree),
+            "sled" => Ok(Self::Sled),
+            "redb" => Ok(Self::Redb),
+            "memory" => Ok(Self::Memory),
+            other => Err(format!(
+                "Storage backend '{}' not supported. Supported values are file-tree, sled, redb and memory",
+                other
+            )),
+        }
+    }
+}
+
+impl Display for StorageBackendKind {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Self::FileTree => "file-tree",
+            Self::Sled => "sled",
+            Self::Redb => "redb",
+            Self::Memory => "memory",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StorageBackendKind;
+    use crate::node::Result;
+    use tempfile::tempdir;
+
+    const ALL_KINDS: [StorageBackendKind; 4] = [
+        StorageBackendKind::FileTree,
+        StorageBackendKind::Sled,
+        StorageBackendKind::Redb,
+        StorageBackendKind::Memory,
+    ];
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn put_get_and_delete() -> Result<()> {
+        for kind in ALL_KINDS {
+            let root = tempdir()?;
+            let backend = kind.open(root.path(), "test").await?;
+
+            assert_eq!(backend.get(b"key").await?, None, "{}", kind);
+            assert_eq!(backend.size(b"key").await?, None, "{}", kind);
+
+            backend.put(b"key", b"value").await?;
+            assert_eq!(
+                backend.get(b"key").await?,
+                Some(b"value".to_vec()),
+                "{}",
+                kind
+            );
+            assert_eq!(backend.size(b"key").await?, Some(5), "{}", kind);
+
+            backend.put(b"key", b"other value").await?;
+            assert_eq!(
+                backend.get(b"key").await?,
+                Some(b"other value".to_vec()),
+                "{}",
+                kind
+            );
+
+            backend.delete(b"key").await?;
+            assert_eq!(backend.get(b"key").await?, None, "{}", kind);
+            // deleting a missing key is not an error
+            backend.delete(b"key").await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn insert_only_if_absent() -> Result<()> {
+        for kind in ALL_KINDS {
+            let root = tempdir()?;
+            let backend = kind.open(root.path(), "test").await?;
+
+            assert!(
+                backend.insert_if_absent(b"key", b"value").await?,
+                "{}",
+                kind
+            );
+            assert!(
+                !backend.insert_if_absent(b"key", b"other value").await?,
+                "{}",
+                kind
+            );
+            assert_eq!(
+                backend.get(b"key").await?,
+                Some(b"value".to_vec()),
+                "{}",
+                kind
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn list_by_prefix() -> Result<()> {
+        for kind in ALL_KINDS {
+            let root = tempdir()?;
+            let backend = kind.open(root.path(), "test").await?;
+
+            for key in [&[1, 2, 3][..], &[1, 2], &[1, 3], &[2, 0]] {
+                backend.put(key, b"value").await?;
+            }
+
+            assert_eq!(
+                backend.list(&[1, 2]).await?,
+                vec![vec![1, 2], vec![1, 2, 3]],
+                "{}",
+                kind
+            );
+            assert_eq!(backend.list(&[1]).await?.len(), 3, "{}", kind);
+            assert_eq!(backend.list(&[]).await?.len(), 4, "{}", kind);
+            assert!(backend.list(&[3]).await?.is_empty(), "{}", kind);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn values_persist_across_reopening() -> Result<()> {
+        for kind in ALL_KINDS {
+            if kind == StorageBackendKind::Memory {
+                continue;
+            }
+            let root = tempdir()?;
+            {
+                let backend = kind.open(root.path(), "test").await?;
+                backend.put(b"key", b"value").await?;
+            }
+
+            let backend = kind.open(root.path(), "test").await?;
+            assert_eq!(
+                backend.get(b"key").await?,
+                Some(b"value".to_vec()),
+                "{}",
+                kind
+            );
+        }
+
+        Ok(())
+    }
+}
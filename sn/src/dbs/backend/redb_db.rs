@@ -0,0 +1,108 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::StorageBackend;
+use crate::dbs::Result;
+
+use async_trait::async_trait;
+use redb::{Database, ReadableTable, TableDefinition};
+use std::{fmt, path::Path};
+
+const TABLE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("data");
+
+/// Stores the values in a single file `redb` database.
+pub(super) struct RedbBackend {
+    db: Database,
+}
+
+impl fmt::Debug for RedbBackend {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "RedbBackend")
+    }
+}
+
+impl RedbBackend {
+    pub(super) fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let db = Database::create(path).map_err(redb::Error::from)?;
+
+        // create the table upfront, so reads never find it missing
+        let txn = db.begin_write().map_err(redb::Error::from)?;
+        let _ = txn.open_table(TABLE).map_err(redb::Error::from)?;
+        txn.commit().map_err(redb::Error::from)?;
+
+        Ok(Self { db })
+    }
+
+    fn write(&self, op: impl FnOnce(&mut redb::Table<&[u8], &[u8]>) -> Result<()>) -> Result<()> {
+        let txn = self.db.begin_write().map_err(redb::Error::from)?;
+        {
+            let mut table = txn.open_table(TABLE).map_err(redb::Error::from)?;
+            op(&mut table)?;
+        }
+        txn.commit().map_err(redb::Error::from)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StorageBackend for RedbBackend {
+    async fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let txn = self.db.begin_read().map_err(redb::Error::from)?;
+        let table = txn.open_table(TABLE).map_err(redb::Error::from)?;
+        let value = table.get(key)?.map(|value| value.value().to_vec());
+        Ok(value)
+    }
+
+    async fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.write(|table| {
+            let _prev = table.insert(key, value)?;
+            Ok(())
+        })
+    }
+
+    async fn insert_if_absent(&self, key: &[u8], value: &[u8]) -> Result<bool> {
+        let mut inserted = false;
+        self.write(|table| {
+            if table.get(key)?.is_none() {
+                let _prev = table.insert(key, value)?;
+                inserted = true;
+            }
+            Ok(())
+        })?;
+        Ok(inserted)
+    }
+
+    async fn delete(&self, key: &[u8]) -> Result<()> {
+        self.write(|table| {
+            let _prev = table.remove(key)?;
+            Ok(())
+        })
+    }
+
+    async fn list(&self, prefix: &[u8]) -> Result<Vec<Vec<u8>>> {
+        let txn = self.db.begin_read().map_err(redb::Error::from)?;
+        let table = txn.open_table(TABLE).map_err(redb::Error::from)?;
+        let mut keys = vec![];
+        for entry in table.range(prefix..)? {
+            let (key, _) = entry?;
+            let key = key.value();
+            if !key.starts_with(prefix) {
+                break;
+            }
+            keys.push(key.to_vec());
+        }
+        Ok(keys)
+    }
+
+    async fn size(&self, key: &[u8]) -> Result<Option<u64>> {
+        Ok(self.get(key).await?.map(|value| value.len() as u64))
+    }
+}
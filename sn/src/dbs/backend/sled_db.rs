@@ -0,0 +1,120 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::StorageBackend;
+use crate::dbs::{Error, Result, SLED_FLUSH_TIME_MS};
+
+use async_trait::async_trait;
+use sled::{Batch, Db};
+use std::{io::ErrorKind, path::Path, time::Duration};
+
+/// Stores the values in a `sled` database.
+#[derive(Debug)]
+pub(super) struct SledBackend {
+    db: Db,
+}
+
+// sled's lock on its files can outlive the db being dropped for a little while,
+// so reopening a db right away is retried a few times
+const OPEN_ATTEMPTS: usize = 10;
+const OPEN_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+impl SledBackend {
+    pub(super) async fn open(path: &Path) -> Result<Self> {
+        let mut attempt = 1;
+        loop {
+            match sled::Config::default()
+                .path(path)
+                .flush_every_ms(SLED_FLUSH_TIME_MS)
+                .open()
+            {
+                Ok(db) => {
+                    let imported = import_legacy_logs(&db)?;
+                    if imported > 0 {
+                        info!("Imported {} event logs from their former layout", imported);
+                    }
+                    return Ok(Self { db });
+                }
+                // sled fails with an `Other` io error when its files are locked
+                Err(sled::Error::Io(error))
+                    if attempt < OPEN_ATTEMPTS && error.kind() == ErrorKind::Other =>
+                {
+                    attempt += 1;
+                    tokio::time::sleep(OPEN_RETRY_DELAY).await;
+                }
+                Err(error) => return Err(Error::from(error)),
+            }
+        }
+    }
+}
+
+// Event logs used to be kept in a tree each, named after the log's id and keyed by the decimal
+// index of each event. They're moved into the default tree, keyed the way `EventStore` keys
+// them, i.e. by the log's id followed by the big-endian index of each event.
+// A log is only dropped once all its events are moved, so an interrupted import can be run again.
+fn import_legacy_logs(db: &Db) -> Result<usize> {
+    let mut imported = 0;
+    for name in db.tree_names() {
+        if name == db.name() {
+            continue;
+        }
+
+        let tree = db.open_tree(&name)?;
+        let mut batch = Batch::default();
+        for entry in tree.iter() {
+            let (index, event) = entry?;
+            let index: u64 = std::str::from_utf8(&index)
+                .ok()
+                .and_then(|index| index.parse().ok())
+                .ok_or_else(|| Error::CouldNotParseDbKey(index.to_vec()))?;
+            let mut key = name.to_vec();
+            key.extend(index.to_be_bytes());
+            batch.insert(key, event);
+        }
+        db.apply_batch(batch)?;
+        let _ = db.drop_tree(&name)?;
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+#[async_trait]
+impl StorageBackend for SledBackend {
+    async fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.db.get(key)?.map(|value| value.to_vec()))
+    }
+
+    async fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        let _prev = self.db.insert(key, value)?;
+        Ok(())
+    }
+
+    async fn insert_if_absent(&self, key: &[u8], value: &[u8]) -> Result<bool> {
+        Ok(self
+            .db
+            .compare_and_swap(key, None::<&[u8]>, Some(value))?
+            .is_ok())
+    }
+
+    async fn delete(&self, key: &[u8]) -> Result<()> {
+        let _prev = self.db.remove(key)?;
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &[u8]) -> Result<Vec<Vec<u8>>> {
+        self.db
+            .scan_prefix(prefix)
+            .keys()
+            .map(|key| Ok(key?.to_vec()))
+            .collect()
+    }
+
+    async fn size(&self, key: &[u8]) -> Result<Option<u64>> {
+        Ok(self.db.get(key)?.map(|value| value.len() as u64))
+    }
+}
@@ -6,81 +6,102 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{Error, Result};
+use super::{decode, encode, Compression, Error, Result, StorageBackend, StorageBackendKind};
 
 use crate::types::{Chunk, ChunkAddress};
 use crate::UsedSpace;
 
 use bytes::Bytes;
-use std::path::{Path, PathBuf};
-use tokio::io::AsyncWriteExt;
+use std::{io::ErrorKind, path::Path, sync::Arc};
+use tokio::fs;
 use walkdir::WalkDir;
-use xor_name::{Prefix, XorName};
+use xor_name::{XorName, XOR_NAME_LEN};
 
-const BIT_TREE_DEPTH: usize = 20;
 const CHUNK_DB_DIR: &str = "chunkdb";
 
-/// A disk store for chunks
+// The version of the on-disk format of the chunks, kept in a file beside them.
+// Stores without it are from before chunks were encoded, and hold them raw.
+const CHUNK_DB_VERSION: &str = "1";
+const CHUNK_DB_VERSION_FILE: &str = "chunkdb.version";
+
+/// A store for chunks, keyed by their name in the given storage backend
 #[derive(Clone)]
 pub(crate) struct ChunkStore {
-    bit_tree_depth: usize,
-    chunk_store_path: PathBuf,
+    backend: Arc<dyn StorageBackend>,
+    compression: Compression,
     used_space: UsedSpace,
 }
 
 impl ChunkStore {
-    /// Creates a new `ChunkStore` at location `root/CHUNK_DB_DIR`
+    /// Creates a new `ChunkStore` at location `root/CHUNK_DB_DIR`, using a file per chunk
+    /// unless another backend is given, and writing chunks uncompressed unless told otherwise
     ///
-    /// If the location specified already contains a ChunkStore, it is simply used
+    /// If the location specified already contains a ChunkStore, it is simply used, after
+    /// migrating it to the current on-disk format if needed
     ///
     /// Used space of the dir is tracked
-    pub(crate) fn new<P: AsRef<Path>>(root: P, used_space: UsedSpace) -> Result<Self> {
-        let chunk_store_path = root.as_ref().join(CHUNK_DB_DIR);
-
-        Ok(ChunkStore {
-            bit_tree_depth: BIT_TREE_DEPTH,
-            chunk_store_path,
+    pub(crate) async fn new<P: AsRef<Path>>(
+        root: P,
+        used_space: UsedSpace,
+        backend: Option<StorageBackendKind>,
+        compression: Option<Compression>,
+    ) -> Result<Self> {
+        let root = root.as_ref();
+        let kind = backend.unwrap_or(StorageBackendKind::FileTree);
+        let existed = kind.exists(root, CHUNK_DB_DIR);
+
+        let store = ChunkStore {
+            backend: kind.open(root, CHUNK_DB_DIR).await?,
+            compression: compression.unwrap_or(Compression::None),
             used_space,
-        })
-    }
-
-    // ---------------------- helper methods ----------------------
+        };
 
-    // Helper that returns the prefix tree path of depth `bit_count` for a given xorname
-    // Example:
-    // - with a xorname with starting bits `010001110110....`
-    // - and a bit_count of `6`
-    // returns the path `CHUNK_STORE_PATH/0/1/0/0/0/1`
-    // If the provided bit count is larger than `self.bit_tree_depth`, uses `self.bit_tree_depth`
-    // to stay within the prefix tree path
-    fn prefix_tree_path(&self, xorname: XorName, bit_count: usize) -> PathBuf {
-        let bin = format!("{:b}", xorname);
-        let prefix_dir_path: PathBuf = bin
-            .chars()
-            .take(std::cmp::min(bit_count, self.bit_tree_depth))
-            .map(|c| format!("{}", c))
-            .collect();
+        // nothing is kept on disk then
+        if kind == StorageBackendKind::Memory {
+            return Ok(store);
+        }
 
-        let mut path = self.chunk_store_path.clone();
-        path.push(prefix_dir_path);
-        path
-    }
+        let version_file = root.join(CHUNK_DB_VERSION_FILE);
+        match fs::read_to_string(&version_file).await {
+            Ok(version) if version == CHUNK_DB_VERSION => {}
+            Ok(version) => return Err(Error::UnsupportedStoreVersion(version)),
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                if existed && kind == StorageBackendKind::FileTree {
+                    let renamed = rename_legacy_chunk_files(&root.join(CHUNK_DB_DIR)).await?;
+                    info!("Renamed {} chunk files to the current naming", renamed);
+                }
+                if existed {
+                    let migrated = store.migrate().await?;
+                    info!("Migrated {} chunks to the current on-disk format", migrated);
+                }
+                fs::create_dir_all(root).await?;
+                fs::write(&version_file, CHUNK_DB_VERSION).await?;
+            }
+            Err(error) => return Err(error.into()),
+        }
 
-    fn address_to_filepath(&self, addr: &ChunkAddress) -> Result<PathBuf> {
-        let xorname = *addr.name();
-        let filename = addr.encode_to_zbase32()?;
-        let mut path = self.prefix_tree_path(xorname, self.bit_tree_depth);
-        path.push(filename);
-        Ok(path)
+        Ok(store)
     }
 
-    fn filepath_to_address(&self, path: &str) -> Result<ChunkAddress> {
-        let filename = Path::new(path)
-            .file_name()
-            .ok_or(Error::NoFilename)?
-            .to_str()
-            .ok_or(Error::InvalidFilename)?;
-        Ok(ChunkAddress::decode_from_zbase32(filename)?)
+    // Rewrites the chunks written raw, from before chunks were encoded, in the current format.
+    // Chunks are recognised as raw by their content hashing to their name, so a migration
+    // which was interrupted can be run again.
+    async fn migrate(&self) -> Result<usize> {
+        let mut migrated = 0;
+        for key in self.backend.list(&[]).await? {
+            let value = match self.backend.get(&key).await? {
+                Some(value) => value,
+                None => continue,
+            };
+            if XorName::from_content(&value).0[..] != key[..] {
+                continue;
+            }
+            self.backend
+                .put(&key, &encode(&value, self.compression)?)
+                .await?;
+            migrated += 1;
+        }
+        Ok(migrated)
     }
 
     // ---------------------- api methods ----------------------
@@ -91,89 +112,78 @@ impl ChunkStore {
 
     pub(crate) async fn write_chunk(&self, data: &Chunk) -> Result<ChunkAddress> {
         let addr = data.address();
-        let filepath = self.address_to_filepath(addr)?;
-        if let Some(dirs) = filepath.parent() {
-            tokio::fs::create_dir_all(dirs).await?;
-        }
-
-        let mut file = tokio::fs::File::create(filepath).await?;
-        file.write_all(data.value()).await?;
+        let encoded = encode(data.value(), self.compression)?;
+        self.backend.put(&addr.name().0, &encoded).await?;
 
-        self.used_space.increase(data.value().len());
+        self.used_space.increase(encoded.len());
 
         Ok(*addr)
     }
 
-    #[allow(dead_code)]
     pub(crate) async fn delete_chunk(&self, addr: &ChunkAddress) -> Result<()> {
-        let filepath = self.address_to_filepath(addr)?;
-        let meta = tokio::fs::metadata(filepath.clone()).await?;
-        tokio::fs::remove_file(filepath).await?;
-        self.used_space.decrease(meta.len() as usize);
+        let key = addr.name().0;
+        let size = self
+            .backend
+            .size(&key)
+            .await?
+            .ok_or(Error::ChunkNotFound(*addr.name()))?;
+        self.backend.delete(&key).await?;
+        self.used_space.decrease(size as usize);
         Ok(())
     }
 
     pub(crate) async fn read_chunk(&self, addr: &ChunkAddress) -> Result<Chunk> {
-        let file_path = self.address_to_filepath(addr)?;
-        let bytes = Bytes::from(tokio::fs::read(file_path).await?);
-        let chunk = Chunk::new(bytes);
-        Ok(chunk)
+        let encoded = self
+            .backend
+            .get(&addr.name().0)
+            .await?
+            .ok_or(Error::ChunkNotFound(*addr.name()))?;
+        Ok(Chunk::new(Bytes::from(decode(&encoded)?)))
     }
 
-    pub(crate) fn chunk_file_exists(&self, addr: &ChunkAddress) -> Result<bool> {
-        let filepath = self.address_to_filepath(addr)?;
-        Ok(filepath.exists())
+    pub(crate) async fn chunk_exists(&self, addr: &ChunkAddress) -> Result<bool> {
+        Ok(self.backend.size(&addr.name().0).await?.is_some())
     }
 
-    pub(crate) fn list_all_files(&self) -> Result<Vec<String>> {
-        list_files_in(&self.chunk_store_path)
-    }
-
-    pub(crate) fn list_all_chunk_addresses(&self) -> Result<Vec<ChunkAddress>> {
-        let all_files = self.list_all_files()?;
-        let all_addrs = all_files
-            .iter()
-            .map(|filepath| self.filepath_to_address(filepath))
-            .collect();
-        all_addrs
-    }
-
-    #[allow(unused)]
-    /// quickly find chunks related or not to a section, might be useful when adults change sections
-    /// not used yet
-    pub(crate) fn list_files_without_prefix(&self, prefix: Prefix) -> Result<Vec<String>> {
-        let all_files = self.list_all_files()?;
-        let prefix_path = self.prefix_tree_path(prefix.name(), prefix.bit_count());
-        let outside_prefix = all_files
+    pub(crate) async fn list_all_chunk_addresses(&self) -> Result<Vec<ChunkAddress>> {
+        self.backend
+            .list(&[])
+            .await?
             .into_iter()
-            .filter(|p| !Path::new(&p).starts_with(&prefix_path.as_path()))
-            .collect();
-        Ok(outside_prefix)
-    }
-
-    #[allow(unused)]
-    /// quickly find chunks related or not to a section, might be useful when adults change sections
-    /// not used yet
-    pub(crate) fn list_files_with_prefix(&self, prefix: Prefix) -> Result<Vec<String>> {
-        let prefix_path = self.prefix_tree_path(prefix.name(), prefix.bit_count());
-        list_files_in(prefix_path.as_path())
+            .map(|key| {
+                // we expect xornames as keys
+                if key.len() != XOR_NAME_LEN {
+                    return Err(Error::CouldNotParseDbKey(key));
+                }
+                let mut name = XorName::default();
+                name.0.copy_from_slice(&key);
+                Ok(ChunkAddress(name))
+            })
+            .collect()
     }
 }
 
-fn list_files_in(path: &Path) -> Result<Vec<String>> {
-    let files = WalkDir::new(path)
+// Renames the chunk files named after the z-base-32 encoding of their address, as they were
+// before chunks were kept in a storage backend, after the hex of their name instead.
+// Both namings are kept within the same tree of directories.
+async fn rename_legacy_chunk_files(dir: &Path) -> Result<usize> {
+    let mut renamed = 0;
+    for entry in WalkDir::new(dir)
         .into_iter()
-        .filter_map(|e| match e {
-            Ok(direntry) => Some(direntry),
-            Err(err) => {
-                warn!("ChunkStore: failed to process file entry: {}", err);
-                None
-            }
-        })
-        .filter(|e| e.file_type().is_file())
-        .map(|e| e.path().display().to_string())
-        .collect();
-    Ok(files)
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+    {
+        let path = entry.path();
+        let filename = match path.file_name().and_then(|name| name.to_str()) {
+            Some(filename) if hex::decode(filename).is_err() => filename,
+            _ => continue,
+        };
+        if let Ok(addr) = ChunkAddress::decode_from_zbase32(filename) {
+            fs::rename(path, path.with_file_name(hex::encode(addr.name()))).await?;
+            renamed += 1;
+        }
+    }
+    Ok(renamed)
 }
 
 #[cfg(test)]
@@ -183,18 +193,20 @@ mod tests {
     use super::*;
     use futures::future::join_all;
     use rayon::prelude::*;
+    use std::path::PathBuf;
     use tempfile::tempdir;
 
-    fn init_chunk_disk_store() -> ChunkStore {
+    async fn init_chunk_disk_store() -> ChunkStore {
         let root = tempdir().expect("Failed to create temporary directory for chunk disk store");
-        ChunkStore::new(root.path(), UsedSpace::new(usize::MAX))
+        ChunkStore::new(root.path(), UsedSpace::new(usize::MAX), None, None)
+            .await
             .expect("Failed to create chunk disk store")
     }
 
     #[tokio::test]
     #[ignore]
     async fn test_write_read_chunk() {
-        let store = init_chunk_disk_store();
+        let store = init_chunk_disk_store().await;
         // test that a range of different chunks return the written chunk
         for _ in 0..10 {
             let chunk = Chunk::new(random_bytes(100));
@@ -215,7 +227,7 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_write_read_async_multiple_chunks() {
-        let store = init_chunk_disk_store();
+        let store = init_chunk_disk_store().await;
         let size = 100;
         let chunks: Vec<Chunk> = std::iter::repeat_with(|| Chunk::new(random_bytes(size)))
             .take(7)
@@ -225,7 +237,7 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_write_read_async_multiple_identical_chunks() {
-        let store = init_chunk_disk_store();
+        let store = init_chunk_disk_store().await;
         let chunks: Vec<Chunk> = std::iter::repeat(Chunk::new(Bytes::from("test_concurrent")))
             .take(7)
             .collect();
@@ -247,4 +259,108 @@ mod tests {
             .par_iter()
             .all(|c| read_chunks.iter().any(|r| r.value() == c.value())))
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_compressed_chunks_read_back_whatever_the_compression() -> Result<()> {
+        let root = tempdir()?;
+        let used_space = UsedSpace::new(1024 * 1024);
+        let compressible = Chunk::new(Bytes::from(vec![7; 100 * 1024]));
+
+        let store = ChunkStore::new(
+            root.path(),
+            used_space.clone(),
+            None,
+            Some(Compression::Zstd),
+        )
+        .await?;
+        let addr = store.write_chunk(&compressible).await?;
+        // space is accounted for with the compressed size
+        assert!(used_space.ratio() < 0.01);
+        assert_eq!(store.read_chunk(&addr).await?, compressible);
+
+        // chunks keep being readable after switching compression
+        let store = ChunkStore::new(root.path(), used_space, None, Some(Compression::Lz4)).await?;
+        assert_eq!(store.read_chunk(&addr).await?, compressible);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_chunks_of_former_layout_are_listed() -> Result<()> {
+        let root = tempdir()?;
+        let chunks: Vec<Chunk> = std::iter::repeat_with(|| Chunk::new(random_bytes(100)))
+            .take(5)
+            .collect();
+        for chunk in &chunks {
+            write_former_layout(root.path(), chunk).await?;
+        }
+
+        let store = ChunkStore::new(root.path(), UsedSpace::new(usize::MAX), None, None).await?;
+        let mut addresses = store.list_all_chunk_addresses().await?;
+        addresses.sort();
+        let mut expected: Vec<_> = chunks.iter().map(|chunk| *chunk.address()).collect();
+        expected.sort();
+        assert_eq!(addresses, expected);
+
+        Ok(())
+    }
+
+    // writes the chunk as it was stored before the storage backends, in a file named after
+    // the z-base-32 encoding of its address, within a dir per bit of the leading 20 bits of its name
+    async fn write_former_layout(root: &Path, chunk: &Chunk) -> Result<()> {
+        let dirs: PathBuf = format!("{:b}", chunk.name())
+            .chars()
+            .take(20)
+            .map(String::from)
+            .collect();
+        let dir = root.join(CHUNK_DB_DIR).join(dirs);
+        fs::create_dir_all(&dir).await?;
+        fs::write(
+            dir.join(chunk.address().encode_to_zbase32()?),
+            chunk.value(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_raw_chunk_store_is_migrated() -> Result<()> {
+        for kind in [StorageBackendKind::FileTree, StorageBackendKind::Sled] {
+            let root = tempdir()?;
+            let chunks: Vec<Chunk> = std::iter::repeat_with(|| Chunk::new(random_bytes(100)))
+                .take(5)
+                .collect();
+
+            // a store from before chunks were encoded, which for a tree of files is also
+            // from before the storage backends, so is laid out as it was then
+            if kind == StorageBackendKind::FileTree {
+                for chunk in &chunks {
+                    write_former_layout(root.path(), chunk).await?;
+                }
+            } else {
+                let backend = kind.open(root.path(), CHUNK_DB_DIR).await?;
+                for chunk in &chunks {
+                    backend.put(&chunk.name().0, chunk.value()).await?;
+                }
+            }
+
+            let store =
+                ChunkStore::new(root.path(), UsedSpace::new(usize::MAX), Some(kind), None).await?;
+            for chunk in &chunks {
+                assert_eq!(&store.read_chunk(chunk.address()).await?, chunk, "{}", kind);
+            }
+            // migrating again rewrites nothing
+            assert_eq!(store.migrate().await?, 0, "{}", kind);
+            drop(store);
+
+            // the store isn't migrated again once reopened
+            let store =
+                ChunkStore::new(root.path(), UsedSpace::new(usize::MAX), Some(kind), None).await?;
+            for chunk in &chunks {
+                assert_eq!(&store.read_chunk(chunk.address()).await?, chunk, "{}", kind);
+            }
+        }
+
+        Ok(())
+    }
 }
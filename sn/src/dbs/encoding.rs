@@ -8,6 +8,92 @@
 
 use super::{Error, Result};
 use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+// The leading byte of every value written to disk, telling how the rest of it is encoded.
+const FORMAT_RAW: u8 = 0;
+const FORMAT_ZSTD: u8 = 1;
+const FORMAT_LZ4: u8 = 2;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// The compression values are written to disk with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compression {
+    /// Written as they are.
+    None,
+    /// Compressed with `zstd`, slower but compressing the most.
+    Zstd,
+    /// Compressed with `lz4`, faster but compressing less.
+    Lz4,
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(str: &str) -> std::result::Result<Self, String> {
+        match str {
+            "none" => Ok(Self::None),
+            "zstd" => Ok(Self::Zstd),
+            "lz4" => Ok(Self::Lz4),
+            other => Err(format!(
+                "Compression '{}' not supported. Supported values are none, zstd and lz4",
+                other
+            )),
+        }
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Self::None => "none",
+            Self::Zstd => "zstd",
+            Self::Lz4 => "lz4",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// Encodes the value for writing to disk, prefixed with its format byte.
+/// The value is kept as it is when compressing doesn't make it smaller, as with encrypted content.
+pub(crate) fn encode(value: &[u8], compression: Compression) -> Result<Vec<u8>> {
+    let compressed = match compression {
+        Compression::None => None,
+        Compression::Zstd => Some((FORMAT_ZSTD, zstd::bulk::compress(value, ZSTD_LEVEL)?)),
+        Compression::Lz4 => Some((FORMAT_LZ4, lz4_flex::compress_prepend_size(value))),
+    };
+
+    let mut encoded = Vec::with_capacity(value.len() + 1);
+    match compressed {
+        Some((format, body)) if body.len() < value.len() => {
+            encoded.push(format);
+            encoded.extend_from_slice(&body);
+        }
+        _ => {
+            encoded.push(FORMAT_RAW);
+            encoded.extend_from_slice(value);
+        }
+    }
+    Ok(encoded)
+}
+
+/// Decodes a value read from disk, as written by `encode`.
+pub(crate) fn decode(encoded: &[u8]) -> Result<Vec<u8>> {
+    match encoded.split_first() {
+        Some((&FORMAT_RAW, body)) => Ok(body.to_vec()),
+        Some((&FORMAT_ZSTD, body)) => {
+            zstd::stream::decode_all(body).map_err(|err| Error::Decompression(err.to_string()))
+        }
+        Some((&FORMAT_LZ4, body)) => lz4_flex::decompress_size_prepended(body)
+            .map_err(|err| Error::Decompression(err.to_string())),
+        Some((format, _)) => Err(Error::UnknownEncodingFormat(*format)),
+        None => Err(Error::Decompression("Empty value".to_string())),
+    }
+}
 
 /// Wrapper for raw bincode::serialise.
 pub(crate) fn serialise<T: Serialize>(data: &T) -> Result<Vec<u8>> {
@@ -21,3 +107,40 @@ where
 {
     bincode::deserialize(bytes).map_err(|err| Error::Deserialize(err.as_ref().to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, Compression};
+    use crate::dbs::Result;
+    use crate::types::utils::random_bytes;
+
+    #[test]
+    fn encoding_roundtrips() -> Result<()> {
+        let compressible = vec![7; 10 * 1024];
+        let incompressible = random_bytes(10 * 1024).to_vec();
+
+        for compression in [Compression::None, Compression::Zstd, Compression::Lz4] {
+            for value in [&compressible, &incompressible, &vec![]] {
+                let encoded = encode(value, compression)?;
+                assert_eq!(&decode(&encoded)?, value, "{}", compression);
+                // never grows by more than the format byte
+                assert!(encoded.len() <= value.len() + 1, "{}", compression);
+            }
+
+            let encoded = encode(&compressible, compression)?;
+            if compression == Compression::None {
+                assert_eq!(encoded.len(), compressible.len() + 1);
+            } else {
+                assert!(encoded.len() < compressible.len() / 10, "{}", compression);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn decoding_rejects_unknown_formats() {
+        assert!(decode(&[]).is_err());
+        assert!(decode(&[42, 1, 2, 3]).is_err());
+    }
+}
@@ -62,6 +62,9 @@ pub enum Error {
     /// Data owner provided is invalid.
     #[error("Provided PublicKey could not validate signature {0:?}")]
     InvalidSignature(PublicKey),
+    /// A replicated Register snapshot doesn't match the signed ops shipped with it
+    #[error("Register snapshot doesn't match its signed ops: {0}")]
+    InvalidRegisterSnapshot(String),
     /// Serialization error
     #[error("Serialization error: {0}")]
     Serialize(String),
@@ -86,6 +89,9 @@ pub enum Error {
     /// Sled error.
     #[error("Sled error:: {0}")]
     Sled(#[from] sled::Error),
+    /// Redb error.
+    #[error("Redb error:: {0}")]
+    Redb(#[from] redb::Error),
     /// There were Error(s) while batching for Sled operations.
     #[error("Errors found when batching for Sled")]
     SledBatching,
@@ -98,6 +104,21 @@ pub enum Error {
     /// No filename found
     #[error("Path contains no file name")]
     NoFilename,
+    /// A value read from disk was written in a format we don't know.
+    #[error("Unknown encoding format: {0}")]
+    UnknownEncodingFormat(u8),
+    /// A value read from disk could not be decompressed.
+    #[error("Decompression error: {0}")]
+    Decompression(String),
+    /// A store was written in a newer on-disk format than we support.
+    #[error("Unsupported store version: {0}")]
+    UnsupportedStoreVersion(String),
+}
+
+impl From<redb::StorageError> for Error {
+    fn from(error: redb::StorageError) -> Self {
+        Self::Redb(error.into())
+    }
 }
 
 /// Convert db error to messaging error message for sending over the network.
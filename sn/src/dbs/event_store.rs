@@ -6,16 +6,21 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{deserialise, serialise, Error, Result};
+use super::{deserialise, serialise, Error, Result, StorageBackend};
 use serde::{de::DeserializeOwned, Serialize};
-use sled::{Db, Tree};
-use std::{fmt::Debug, marker::PhantomData};
+use std::{fmt::Debug, marker::PhantomData, ops::Range, sync::Arc};
+use tokio::sync::Mutex;
 use xor_name::XorName;
 
-/// Disk storage for events and similar.
+/// Storage for events and similar, kept in a backend under keys made of
+/// the store's id followed by the big-endian index of each event.
 #[derive(Clone, Debug)]
 pub(crate) struct EventStore<TEvent: Debug + Serialize + DeserializeOwned> {
-    tree: Tree,
+    id: XorName,
+    backend: Arc<dyn StorageBackend>,
+    // the indexes of the events stored, read from the backend on first use, and kept
+    // up to date from then on, so they don't need to be listed again
+    indexes: Arc<Mutex<Option<Range<u64>>>>,
     _phantom: PhantomData<TEvent>,
 }
 
@@ -23,71 +28,145 @@ impl<'a, TEvent: Debug + Serialize + DeserializeOwned> EventStore<TEvent>
 where
     TEvent: 'a,
 {
-    pub(crate) fn new(id: &XorName, db: Db) -> Result<Self> {
-        let tree = db.open_tree(id)?;
-        Ok(Self {
-            tree,
+    pub(crate) fn new(id: &XorName, backend: Arc<dyn StorageBackend>) -> Self {
+        Self {
+            id: *id,
+            backend,
+            indexes: Arc::new(Mutex::new(None)),
             _phantom: PhantomData::default(),
-        })
+        }
     }
 
-    /// Get all events stored in db
-    pub(crate) fn get_all(&self) -> Result<Vec<TEvent>> {
-        let iter = self.tree.iter();
+    /// Get the events stored at or after the given index
+    pub(crate) async fn get_from(&self, index: u64) -> Result<Vec<TEvent>> {
+        let mut indexes = self.indexes.lock().await;
+        let stored = self.stored_indexes(&mut indexes).await?;
 
         let mut events = vec![];
-        for (_, res) in iter.enumerate() {
-            let (key, val) = res?;
-            let db_key = String::from_utf8(key.to_vec())
-                .map_err(|_| Error::CouldNotParseDbKey(key.to_vec()))?;
-
-            let value: TEvent = deserialise(&val)?;
-            events.push((db_key, value))
+        for index in index.max(stored.start)..stored.end {
+            let key = self.key(index);
+            let val = self
+                .backend
+                .get(&key)
+                .await?
+                .ok_or_else(|| Error::NoSuchValue(hex::encode(&key)))?;
+            events.push(deserialise(&val)?);
         }
 
-        events.sort_by(|(key_a, _), (key_b, _)| key_a.partial_cmp(key_b).unwrap());
-
-        let events: Vec<TEvent> = events.into_iter().map(|(_, val)| val).collect();
-
         Ok(events)
     }
 
-    /// append a new entry
-    pub(crate) fn append(&self, event: TEvent) -> Result<()> {
-        let key = &self.tree.len().to_string();
-        if self.tree.get(key)?.is_some() {
+    /// append a new entry, returning its index
+    pub(crate) async fn append(&self, event: TEvent) -> Result<u64> {
+        let mut indexes = self.indexes.lock().await;
+        let stored = self.stored_indexes(&mut indexes).await?;
+
+        let index = stored.end;
+        let event = serialise(&event)?;
+        if !self
+            .backend
+            .insert_if_absent(&self.key(index), &event)
+            .await?
+        {
             return Err(Error::DataExists);
         }
 
-        let event = serialise(&event)?;
-        let _old_entry = self.tree.insert(key, event).map_err(Error::Sled)?;
+        *indexes = Some(stored.start..index + 1);
+        Ok(index)
+    }
+
+    /// The index the next event will be appended at
+    pub(crate) async fn next_index(&self) -> Result<u64> {
+        let mut indexes = self.indexes.lock().await;
+        Ok(self.stored_indexes(&mut indexes).await?.end)
+    }
 
+    /// Number of events stored
+    pub(crate) async fn len(&self) -> Result<usize> {
+        let mut indexes = self.indexes.lock().await;
+        let stored = self.stored_indexes(&mut indexes).await?;
+        Ok((stored.end - stored.start) as usize)
+    }
+
+    /// Remove all the events stored
+    pub(crate) async fn clear(&self) -> Result<()> {
+        let mut indexes = self.indexes.lock().await;
+        for index in self.stored_indexes(&mut indexes).await? {
+            self.backend.delete(&self.key(index)).await?;
+        }
+        *indexes = Some(0..0);
         Ok(())
     }
+
+    /// Remove the events stored before the given index, returning how many were removed.
+    /// The latest event is always kept, so that the indexes carry on from it.
+    pub(crate) async fn truncate(&self, index: u64) -> Result<usize> {
+        let mut indexes = self.indexes.lock().await;
+        let stored = self.stored_indexes(&mut indexes).await?;
+
+        let first_kept = index.min(stored.end.saturating_sub(1)).max(stored.start);
+        for index in stored.start..first_kept {
+            // removed in order, so an interrupted truncation leaves a contiguous log behind
+            self.backend.delete(&self.key(index)).await?;
+            *indexes = Some(index + 1..stored.end);
+        }
+
+        Ok((first_kept - stored.start) as usize)
+    }
+
+    // the indexes of the events stored, which are listed from the backend the first time only
+    async fn stored_indexes(&self, indexes: &mut Option<Range<u64>>) -> Result<Range<u64>> {
+        if let Some(stored) = indexes {
+            return Ok(stored.clone());
+        }
+
+        // keys are listed in ascending order, i.e. in the order the events were appended
+        let keys = self.backend.list(&self.id).await?;
+        let stored = match (keys.first(), keys.last()) {
+            (Some(first), Some(last)) => Self::index_of(first)?..Self::index_of(last)? + 1,
+            _ => 0..0,
+        };
+        *indexes = Some(stored.clone());
+        Ok(stored)
+    }
+
+    fn key(&self, index: u64) -> Vec<u8> {
+        let mut key = self.id.0.to_vec();
+        key.extend(index.to_be_bytes());
+        key
+    }
+
+    fn index_of(key: &[u8]) -> Result<u64> {
+        let mut index = [0; 8];
+        let len = index.len();
+        if key.len() < len {
+            return Err(Error::CouldNotParseDbKey(key.to_vec()));
+        }
+        index.copy_from_slice(&key[key.len() - len..]);
+        Ok(u64::from_be_bytes(index))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::EventStore;
-    use crate::node::{Error, Result};
+    use crate::dbs::StorageBackendKind;
+    use crate::node::Result;
     use crate::types::Token;
-    use std::path::Path;
     use tempfile::tempdir;
 
     #[tokio::test]
     async fn history() -> Result<()> {
         let id = xor_name::XorName::random();
         let tmp_dir = tempdir()?;
-        let db_dir = tmp_dir.path().join(Path::new(&"Token"));
-        let db = sled::open(db_dir).map_err(|error| {
-            trace!("Sled Error: {:?}", error);
-            Error::Sled(error)
-        })?;
-        let store = EventStore::<Token>::new(&id, db)?;
+        let backend = StorageBackendKind::Sled
+            .open(tmp_dir.path(), "Token")
+            .await?;
+        let store = EventStore::<Token>::new(&id, backend);
 
-        store.append(Token::from_nano(10))?;
+        let _ = store.append(Token::from_nano(10)).await?;
 
-        let events = store.get_all()?;
+        let events = store.get_from(0).await?;
         assert_eq!(events.len(), 1);
 
         match events.get(0) {
@@ -97,4 +176,38 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn truncated_history_carries_on() -> Result<()> {
+        let id = xor_name::XorName::random();
+        let backend = StorageBackendKind::Memory
+            .open(tempdir()?.path(), "Token")
+            .await?;
+        let store = EventStore::<Token>::new(&id, backend.clone());
+
+        for nanos in 0..5 {
+            assert_eq!(store.append(Token::from_nano(nanos)).await?, nanos);
+        }
+
+        assert_eq!(store.truncate(3).await?, 3);
+        let events = store.get_from(0).await?;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].as_nano(), 3);
+
+        // the latest event is kept, so the indexes carry on from it
+        assert_eq!(store.truncate(10).await?, 1);
+        assert_eq!(store.len().await?, 1);
+        assert_eq!(store.append(Token::from_nano(5)).await?, 5);
+
+        let events = store.get_from(5).await?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_nano(), 5);
+
+        // the events removed are gone from the backend, not just skipped
+        assert_eq!(backend.list(&id).await?.len(), 2);
+        let reopened = EventStore::<Token>::new(&id, backend);
+        assert_eq!(reopened.next_index().await?, 6);
+
+        Ok(())
+    }
 }
@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod backend;
 mod chunk_store;
 mod encoding;
 mod errors;
@@ -13,8 +14,11 @@ mod event_store;
 mod lru_cache;
 mod used_space;
 
+pub(crate) use backend::StorageBackend;
+pub use backend::StorageBackendKind;
 pub(crate) use chunk_store::ChunkStore;
-pub(crate) use encoding::{deserialise, serialise};
+pub use encoding::Compression;
+pub(crate) use encoding::{decode, deserialise, encode, serialise};
 pub(crate) use errors::{convert_to_error_msg, Error, Result};
 pub(crate) use event_store::EventStore;
 pub(crate) use lru_cache::LruCache;
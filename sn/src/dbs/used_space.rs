@@ -10,7 +10,7 @@ use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc,
 };
-use tracing::info;
+use tracing::debug;
 
 #[derive(Clone, Debug)]
 /// Tracking used space
@@ -46,9 +46,9 @@ impl UsedSpace {
         let used = self.used_space.load(Ordering::Relaxed);
         let max_capacity = self.max_capacity;
         let used_space_ratio = used as f64 / max_capacity as f64;
-        info!("Used space: {:?}", used);
-        info!("Max capacity: {:?}", max_capacity);
-        info!("Used space ratio: {:?}", used_space_ratio);
+        debug!("Used space: {:?}", used);
+        debug!("Max capacity: {:?}", max_capacity);
+        debug!("Used space ratio: {:?}", used_space_ratio);
         used_space_ratio
     }
 }
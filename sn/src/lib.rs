@@ -48,7 +48,7 @@ mod dbs;
 /// Helpers for analysis of testnet logs
 mod testnet_grep;
 
-pub use dbs::UsedSpace;
+pub use dbs::{Compression, StorageBackendKind, UsedSpace};
 
 pub mod messaging;
 pub mod node;
@@ -106,7 +106,8 @@ pub(crate) fn elder_count() -> usize {
     }
 }
 
-/// Get the expected chunk copy count for our network.
+/// Get the default data copy count of the storage policy, used by the genesis node when it's
+/// not configured with one.
 /// Defaults to DEFAULT_DATA_COPY_COUNT, but can be overridden by the env var SN_DATA_COPY_COUNT.
 pub(crate) fn data_copy_count() -> usize {
     // if we have an env var for this, lets override
@@ -6,20 +6,22 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::RegisterCmd;
+use super::{RegisterCmd, RewardLedger, SignedRegisterSetPolicy};
 use crate::{
     messaging::SectionAuth,
-    types::{Error, RegisterAddress as Address, Result},
+    types::{register::Register, Error, RegisterAddress as Address, Result},
 };
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use xor_name::XorName;
+use xor_name::{Prefix, XorName};
 
 /// Metadata (register and chunk holders) replication.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MetadataExchange {
     /// Adult storage levels.
     pub adult_levels: BTreeMap<XorName, StorageLevel>,
+    /// The rewards accounted for the Adults, handed over to new Elders.
+    pub reward_ledger: RewardLedger,
 }
 
 /// Data to be exchanged between Register stores.
@@ -34,10 +36,30 @@ pub struct ReplicatedRegisterLog {
     /// section sig over address.id()
     /// This is a duplicated entry as it should exist in first cmd
     pub section_auth: SectionAuth,
-    ///
+    /// The latest snapshot of the register, if one was taken.
+    pub snapshot: Option<RegisterSnapshot>,
+    /// The ops applied to the register, starting with its creation, followed by the ops not
+    /// covered by the snapshot when there's one, or by its full history otherwise.
     pub op_log: Vec<RegisterCmd>,
 }
 
+/// The state of a register once the ops before it were dropped from its log.
+///
+/// Its policy is verified against the signed op which set it, or the create op when it was never
+/// set, while its entries, which are not signed by their authors, are taken as they are.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterSnapshot {
+    /// The register as it was when the snapshot was taken.
+    pub register: Register,
+    /// The signed op which set the policy of the register, unless it's the one it was created with.
+    pub policy_op: Option<SignedRegisterSetPolicy>,
+}
+
+/// Digests of the addresses of the data an Adult holds for another one, keyed by the prefix
+/// the addresses of each digest fall within.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataDigest(pub BTreeMap<Prefix, [u8; 32]>);
+
 /// The degree to which storage has been used.
 /// Expressed in values between 0-10, where each unit represents 10-percentage points.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
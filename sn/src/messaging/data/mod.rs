@@ -13,16 +13,22 @@ mod data_exchange;
 mod errors;
 mod query;
 mod register;
+mod rewards;
 
 pub use self::{
     cmd::DataCmd,
-    data_exchange::{MetadataExchange, RegisterStoreExport, ReplicatedRegisterLog, StorageLevel},
+    data_exchange::{
+        DataDigest, MetadataExchange, RegisterSnapshot, RegisterStoreExport, ReplicatedRegisterLog,
+        StorageLevel,
+    },
     errors::{Error, Result},
     query::DataQuery,
     register::{
         CreateRegister, DeleteRegister, EditRegister, ExtendRegister, RegisterCmd, RegisterQuery,
-        SignedRegisterCreate, SignedRegisterDelete, SignedRegisterEdit, SignedRegisterExtend,
+        SetRegisterPolicy, SignedRegisterCreate, SignedRegisterDelete, SignedRegisterEdit,
+        SignedRegisterExtend, SignedRegisterSetPolicy,
     },
+    rewards::{NodePayout, NodeRewards, RewardLedger, RewardPayout, RewardQuery},
 };
 
 use crate::types::{
@@ -36,6 +42,7 @@ use crate::{
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeSet, convert::TryFrom};
+use strum_macros::IntoStaticStr;
 use tiny_keccak::{Hasher, Sha3};
 use xor_name::XorName;
 
@@ -71,7 +78,7 @@ pub struct ServiceError {
 /// Network service messages that clients or nodes send in order to use the services,
 /// communicate and carry out the tasks.
 #[allow(clippy::large_enum_variant)]
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, IntoStaticStr)]
 pub enum ServiceMsg {
     /// Messages that lead to mutation.
     ///
@@ -85,6 +92,12 @@ pub enum ServiceMsg {
     /// reply.
     /// [`QueryResponse`]: Self::QueryResponse
     Query(DataQuery),
+    /// A query about the rewards accounted for by a section.
+    ///
+    /// It is handled by the section's Elders, each of which should eventually reply with a
+    /// corresponding [`QueryResponse`], or an error.
+    /// [`QueryResponse`]: Self::QueryResponse
+    Rewards(RewardQuery),
     /// The response to a query, containing the query result.
     QueryResponse {
         /// The result of the query.
@@ -121,6 +134,7 @@ impl ServiceMsg {
         match self {
             Self::Cmd(cmd) => Some(cmd.dst_name()),
             Self::Query(query) => Some(query.dst_name()),
+            Self::Rewards(query) => Some(query.dst_name()),
             _ => None,
         }
     }
@@ -162,6 +176,11 @@ pub enum QueryResponse {
     /// Response to [`RegisterQuery::GetUserPermissions`].
     GetRegisterUserPermissions((Result<Permissions>, OperationId)),
     //
+    // ===== Rewards =====
+    //
+    /// Response to [`RewardQuery::GetLedger`].
+    GetRewardLedger((Result<RewardLedger>, OperationId)),
+    //
     // ===== Other =====
     //
     /// Failed to create id generation
@@ -180,6 +199,7 @@ impl QueryResponse {
             ReadRegister((result, _op_id)) => result.is_ok(),
             GetRegisterPolicy((result, _op_id)) => result.is_ok(),
             GetRegisterUserPermissions((result, _op_id)) => result.is_ok(),
+            GetRewardLedger((result, _op_id)) => result.is_ok(),
             FailedToCreateOperationId => false,
         }
     }
@@ -217,6 +237,7 @@ impl QueryResponse {
                 Ok(_) => false,
                 Err(error) => matches!(*error, ErrorMsg::DataNotFound(_)),
             },
+            GetRewardLedger(_) => false,
             FailedToCreateOperationId => false,
         }
     }
@@ -251,7 +272,8 @@ impl QueryResponse {
             | GetRegisterOwner((_, operation_id))
             | ReadRegister((_, operation_id))
             | GetRegisterPolicy((_, operation_id))
-            | GetRegisterUserPermissions((_, operation_id)) => Ok(*operation_id),
+            | GetRegisterUserPermissions((_, operation_id))
+            | GetRewardLedger((_, operation_id)) => Ok(*operation_id),
             FailedToCreateOperationId => Err(Error::NoOperationId),
         }
     }
@@ -303,6 +325,7 @@ try_from!(User, GetRegisterOwner);
 try_from!(BTreeSet<(EntryHash, Entry)>, ReadRegister);
 try_from!(Policy, GetRegisterPolicy);
 try_from!(Permissions, GetRegisterUserPermissions);
+try_from!(RewardLedger, GetRewardLedger);
 
 #[cfg(test)]
 mod tests {
@@ -96,6 +96,8 @@ pub enum RegisterCmd {
         /// verifying that it was paid for.
         section_auth: SectionAuth,
     },
+    /// Replace the [`Policy`] of the [`Register`].
+    SetPolicy(SignedRegisterSetPolicy),
 }
 
 ///
@@ -173,6 +175,21 @@ pub struct ExtendRegister {
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct DeleteRegister(pub Address);
 
+/// Replacement of the [`Policy`] of a [`Register`].
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SetRegisterPolicy {
+    /// The address of the [`Register`] to set the policy of.
+    pub address: Address,
+    /// The new policy of the [`Register`].
+    ///
+    /// It must keep the same owner, and be of the same kind (public or private), as the current one.
+    pub policy: Policy,
+    /// The version of the new policy, which must be newer than that of the current one.
+    ///
+    /// Replicas order policies by version, so an old policy can't be replayed over a newer one.
+    pub version: u64,
+}
+
 ///
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct EditRegister {
@@ -215,6 +232,19 @@ pub struct SignedRegisterEdit {
     pub auth: crate::messaging::ServiceAuth,
 }
 
+/// A signed cmd to replace the policy of a [`Register`].
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SignedRegisterSetPolicy {
+    /// Set the policy of a [`Register`].
+    ///
+    /// Only the current owner of the register can set its policy.
+    pub op: SetRegisterPolicy,
+    /// A signature carrying authority to perform the operation.
+    ///
+    /// This will be verified against the register's owner.
+    pub auth: crate::messaging::ServiceAuth,
+}
+
 /// A [`Register`] write operation.
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SignedRegisterDelete {
@@ -257,6 +287,13 @@ impl SignedRegisterExtend {
     }
 }
 
+impl SignedRegisterSetPolicy {
+    /// Returns the dst address of the register.
+    pub fn dst_address(&self) -> &Address {
+        &self.op.address
+    }
+}
+
 impl RegisterQuery {
     /// Creates a Response containing an error, with the Response variant corresponding to the
     /// Request variant.
@@ -353,6 +390,7 @@ impl RegisterCmd {
             Self::Edit(cmd) => *cmd.dst_address(),
             Self::Delete(cmd) => *cmd.dst_address(),
             Self::Extend { cmd, .. } => *cmd.dst_address(),
+            Self::SetPolicy(cmd) => *cmd.dst_address(),
         }
     }
 
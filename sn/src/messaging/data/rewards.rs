@@ -0,0 +1,123 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{Error, OperationId, Result, StorageLevel};
+use crate::types::{PublicKey, Token};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tiny_keccak::{Hasher, Sha3};
+use xor_name::{Prefix, XorName};
+
+/// Reward queries, sent by clients to the Elders of a section.
+#[derive(Eq, PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub enum RewardQuery {
+    /// Ask for the rewards accounted for by the section closest to the given name.
+    ///
+    /// This should eventually lead to a [`GetRewardLedger`] response.
+    ///
+    /// [`GetRewardLedger`]: super::QueryResponse::GetRewardLedger
+    GetLedger(XorName),
+}
+
+impl RewardQuery {
+    /// Returns the xorname the query is to be sent to.
+    pub fn dst_name(&self) -> XorName {
+        match self {
+            Self::GetLedger(name) => *name,
+        }
+    }
+
+    /// Retrieves the operation identifier for this query, used to match responses.
+    pub fn operation_id(&self) -> Result<OperationId> {
+        let bytes = crate::types::utils::encode(&self).map_err(|_| Error::NoOperationId)?;
+        let mut hasher = Sha3::v256();
+        let mut output = [0; 32];
+        hasher.update(bytes.as_bytes());
+        hasher.finalize(&mut output);
+        Ok(output)
+    }
+}
+
+/// The rewards accounted for by the Elders of a section, for the work done by its Adults.
+#[derive(Eq, PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct RewardLedger {
+    /// Prefix of the section.
+    pub prefix: Prefix,
+    /// Number of the last payout agreed by the Elders of the section.
+    pub epoch: u64,
+    /// Work done and rewards earned by each of the Adults of the section.
+    pub nodes: BTreeMap<XorName, NodeRewards>,
+    /// Total amount credited to each wallet by the section.
+    pub credits: BTreeMap<PublicKey, Token>,
+}
+
+impl RewardLedger {
+    /// Total amount credited by the section to the given wallet.
+    pub fn credited(&self, wallet: &PublicKey) -> Token {
+        self.credits
+            .get(wallet)
+            .copied()
+            .unwrap_or_else(Token::zero)
+    }
+
+    /// Whether both ledgers account for the same payouts agreed by the Elders of the section,
+    /// i.e. they only differ in the work each Elder observed since the last one.
+    pub fn agrees_with(&self, other: &Self) -> bool {
+        let earnings = |ledger: &Self| -> BTreeMap<XorName, (Token, Token)> {
+            ledger
+                .nodes
+                .iter()
+                .filter(|(_, node)| node.earned != Token::zero() || node.unpaid != Token::zero())
+                .map(|(name, node)| (*name, (node.earned, node.unpaid)))
+                .collect()
+        };
+
+        self.prefix == other.prefix
+            && self.epoch == other.epoch
+            && self.credits == other.credits
+            && earnings(self) == earnings(other)
+    }
+}
+
+/// Work done by an Adult since the last payout, and the rewards it has earned.
+#[derive(Eq, PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct NodeRewards {
+    /// Key of the wallet the node registered when joining, if it is known to the Elders.
+    pub wallet: Option<PublicKey>,
+    /// Number of queries the Elder which responded observed the node serve since the last payout.
+    pub served: u64,
+    /// Storage level last reported by the node.
+    pub storage_level: StorageLevel,
+    /// Total rewards earned by the node.
+    pub earned: Token,
+    /// Rewards earned which couldn't be credited yet, as the node's wallet is not known.
+    pub unpaid: Token,
+}
+
+/// Rewards to be paid out for the work done by the Adults of a section since
+/// the previous payout. It's proposed by one of the Elders and only applied
+/// to their ledgers once they've agreed on it.
+#[derive(Eq, PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct RewardPayout {
+    /// Number of the payout, one more than the last payout agreed.
+    pub epoch: u64,
+    /// Work each of the Adults is to be rewarded for.
+    pub nodes: BTreeMap<XorName, NodePayout>,
+}
+
+/// Work an Adult is to be rewarded for in a payout.
+#[derive(Eq, PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct NodePayout {
+    /// Key of the wallet the node registered when joining, if known to the proposer.
+    pub wallet: Option<PublicKey>,
+    /// Storage level reported by the node.
+    pub storage_level: StorageLevel,
+    /// Number of queries served by the node since the previous payout.
+    pub served: u64,
+}
@@ -58,6 +58,14 @@ pub enum MsgType {
 }
 
 impl MsgType {
+    /// The kind of the message, i.e. the name of its `SystemMsg` or `ServiceMsg` variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MsgType::System { msg, .. } => msg.into(),
+            MsgType::Service { msg, .. } => msg.into(),
+        }
+    }
+
     /// The priority of the message, when handled by lower level comms.
     pub fn priority(&self) -> i32 {
         match self {
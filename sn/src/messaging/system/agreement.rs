@@ -7,7 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::{KeyedSig, NodeState};
-use crate::messaging::SectionAuthorityProvider;
+use crate::messaging::{data::RewardPayout, SectionAuthorityProvider};
 use ed25519_dalek::{PublicKey, Signature};
 use hex_fmt::HexFmt;
 use serde::{Deserialize, Serialize};
@@ -106,4 +106,6 @@ pub enum Proposal {
     NewElders(SectionAuth<SectionAuthorityProvider>),
     /// Proposal to change whether new nodes are allowed to join our section.
     JoinsAllowed(bool),
+    /// Proposal to pay out the rewards earned by the Adults of our section.
+    RewardPayout(RewardPayout),
 }
@@ -6,8 +6,9 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{agreement::SectionAuth, KeyedSig, NodeState, SectionPeers, SigShare};
+use super::{agreement::SectionAuth, KeyedSig, NodeState, SectionPeers, SigShare, StoragePolicy};
 use crate::messaging::SectionAuthorityProvider;
+use crate::types::PublicKey;
 use bls::PublicKey as BlsPublicKey;
 use ed25519_dalek::Signature;
 use secured_linked_list::SecuredLinkedList;
@@ -23,6 +24,10 @@ pub struct JoinRequest {
     pub resource_proof_response: Option<ResourceProofResponse>,
     /// Aggregated approval from the Elders
     pub aggregated: Option<SectionAuth<NodeState>>,
+    /// Key of the wallet to be credited with the rewards the node earns.
+    pub reward_key: PublicKey,
+    /// Storage policy the node was configured with, if any, which has to match the network's.
+    pub storage_policy: Option<StoragePolicy>,
 }
 
 /// Joining peer's proof of resolvement of given resource proofing challenge.
@@ -97,6 +102,8 @@ pub enum JoinResponse {
         node_state: SectionAuth<NodeState>,
         /// Full verifiable section chain
         section_chain: SecuredLinkedList,
+        /// Storage policy of the network, signed with the genesis key
+        storage_policy: SectionAuth<StoragePolicy>,
     },
     /// Join was rejected
     Rejected(JoinRejectionReason),
@@ -109,4 +116,6 @@ pub enum JoinRejectionReason {
     JoinsDisallowed,
     /// The requesting node is not externally reachable
     NodeNotReachable(SocketAddr),
+    /// The requesting node was configured with a storage policy other than the network's
+    StoragePolicyMismatch(StoragePolicy),
 }
@@ -12,6 +12,7 @@ mod join_as_relocated;
 mod node_msgs;
 mod node_state;
 mod signed;
+mod storage_policy;
 
 pub use agreement::{DkgFailureSig, DkgFailureSigSet, DkgSessionId, Proposal, SectionAuth};
 pub use join::{JoinRejectionReason, JoinRequest, JoinResponse, ResourceProofResponse};
@@ -19,6 +20,7 @@ pub use join_as_relocated::{JoinAsRelocatedRequest, JoinAsRelocatedResponse};
 pub use node_msgs::{NodeCmd, NodeEvent, NodeQuery, NodeQueryResponse};
 pub use node_state::{MembershipState, NodeState, RelocateDetails};
 pub use signed::{KeyedSig, SigShare};
+pub use storage_policy::StoragePolicy;
 
 /// List of peers of a section
 pub type SectionPeers = BTreeSet<SectionAuth<NodeState>>;
@@ -30,6 +32,7 @@ use secured_linked_list::SecuredLinkedList;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, BTreeSet},
+    fmt::{self, Debug, Formatter},
     net::SocketAddr,
 };
 use xor_name::{Prefix, XorName};
@@ -37,7 +40,7 @@ use xor_name::{Prefix, XorName};
 use super::authority::SectionAuth as SectionAuthProof;
 use super::AuthorityProof;
 
-#[derive(Clone, PartialEq, Serialize, Deserialize, custom_debug::Debug)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 #[allow(clippy::large_enum_variant)]
 /// Message sent over the among nodes
 pub enum SystemMsg {
@@ -52,7 +55,6 @@ pub enum SystemMsg {
         /// Sender's section chain truncated from the dst section key found in the `bounced_msg`.
         proof_chain: SecuredLinkedList,
         /// Message bounced due to outdated destination section information.
-        #[debug(skip)]
         bounced_msg: Bytes,
     },
     /// Message sent to a peer when a message needs to be sent to a different
@@ -67,7 +69,6 @@ pub enum SystemMsg {
         /// Section chain (from genesis key) for the closest section.
         section_chain: SecuredLinkedList,
         /// Message bounced that shall be resent by the peer.
-        #[debug(skip)]
         bounced_msg: Bytes,
     },
     /// Message to update a section when they bounced a message as untrusted back at us.
@@ -202,6 +203,201 @@ pub enum SystemMsg {
     },
 }
 
+impl Debug for SystemMsg {
+    // the bounced msgs are left out, as they're just bytes
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AntiEntropyRetry {
+                section_auth,
+                section_signed,
+                proof_chain,
+                ..
+            } => f
+                .debug_struct("AntiEntropyRetry")
+                .field("section_auth", section_auth)
+                .field("section_signed", section_signed)
+                .field("proof_chain", proof_chain)
+                .finish(),
+            Self::AntiEntropyRedirect {
+                section_auth,
+                section_signed,
+                section_chain,
+                ..
+            } => f
+                .debug_struct("AntiEntropyRedirect")
+                .field("section_auth", section_auth)
+                .field("section_signed", section_signed)
+                .field("section_chain", section_chain)
+                .finish(),
+            Self::AntiEntropyUpdate {
+                section_auth,
+                section_signed,
+                proof_chain,
+                members,
+            } => f
+                .debug_struct("AntiEntropyUpdate")
+                .field("section_auth", section_auth)
+                .field("section_signed", section_signed)
+                .field("proof_chain", proof_chain)
+                .field("members", members)
+                .finish(),
+            Self::AntiEntropyProbe(inner) => {
+                f.debug_tuple("AntiEntropyProbe").field(inner).finish()
+            }
+            Self::BackPressure(inner) => f.debug_tuple("BackPressure").field(inner).finish(),
+            Self::Relocate(inner) => f.debug_tuple("Relocate").field(inner).finish(),
+            Self::JoinRequest(inner) => f.debug_tuple("JoinRequest").field(inner).finish(),
+            Self::JoinResponse(inner) => f.debug_tuple("JoinResponse").field(inner).finish(),
+            Self::JoinAsRelocatedRequest(inner) => f
+                .debug_tuple("JoinAsRelocatedRequest")
+                .field(inner)
+                .finish(),
+            Self::JoinAsRelocatedResponse(inner) => f
+                .debug_tuple("JoinAsRelocatedResponse")
+                .field(inner)
+                .finish(),
+            Self::DkgStart {
+                session_id,
+                prefix,
+                elders,
+            } => f
+                .debug_struct("DkgStart")
+                .field("session_id", session_id)
+                .field("prefix", prefix)
+                .field("elders", elders)
+                .finish(),
+            Self::DkgSessionUnknown {
+                session_id,
+                message,
+            } => f
+                .debug_struct("DkgSessionUnknown")
+                .field("session_id", session_id)
+                .field("message", message)
+                .finish(),
+            Self::DkgSessionInfo {
+                session_id,
+                prefix,
+                elders,
+                section_auth,
+                message_cache,
+                message,
+            } => f
+                .debug_struct("DkgSessionInfo")
+                .field("session_id", session_id)
+                .field("prefix", prefix)
+                .field("elders", elders)
+                .field("section_auth", section_auth)
+                .field("message_cache", message_cache)
+                .field("message", message)
+                .finish(),
+            Self::DkgMessage {
+                session_id,
+                message,
+            } => f
+                .debug_struct("DkgMessage")
+                .field("session_id", session_id)
+                .field("message", message)
+                .finish(),
+            Self::DkgNotReady {
+                session_id,
+                message,
+            } => f
+                .debug_struct("DkgNotReady")
+                .field("session_id", session_id)
+                .field("message", message)
+                .finish(),
+            Self::DkgRetry {
+                message_history,
+                session_id,
+                message,
+            } => f
+                .debug_struct("DkgRetry")
+                .field("message_history", message_history)
+                .field("session_id", session_id)
+                .field("message", message)
+                .finish(),
+            Self::DkgFailureObservation {
+                session_id,
+                sig,
+                failed_participants,
+            } => f
+                .debug_struct("DkgFailureObservation")
+                .field("session_id", session_id)
+                .field("sig", sig)
+                .field("failed_participants", failed_participants)
+                .finish(),
+            Self::DkgFailureAgreement(inner) => {
+                f.debug_tuple("DkgFailureAgreement").field(inner).finish()
+            }
+            Self::Propose {
+                proposal,
+                sig_share,
+            } => f
+                .debug_struct("Propose")
+                .field("proposal", proposal)
+                .field("sig_share", sig_share)
+                .finish(),
+            Self::StartConnectivityTest(inner) => {
+                f.debug_tuple("StartConnectivityTest").field(inner).finish()
+            }
+            Self::NodeCmd(inner) => f.debug_tuple("NodeCmd").field(inner).finish(),
+            Self::NodeQuery(inner) => f.debug_tuple("NodeQuery").field(inner).finish(),
+            Self::NodeEvent(inner) => f.debug_tuple("NodeEvent").field(inner).finish(),
+            Self::NodeQueryResponse {
+                response,
+                correlation_id,
+                user,
+            } => f
+                .debug_struct("NodeQueryResponse")
+                .field("response", response)
+                .field("correlation_id", correlation_id)
+                .field("user", user)
+                .finish(),
+            Self::NodeMsgError {
+                error,
+                correlation_id,
+            } => f
+                .debug_struct("NodeMsgError")
+                .field("error", error)
+                .field("correlation_id", correlation_id)
+                .finish(),
+        }
+    }
+}
+
+impl From<&SystemMsg> for &'static str {
+    /// The name of the kind of msg, as counted by the node metrics
+    fn from(msg: &SystemMsg) -> Self {
+        match msg {
+            SystemMsg::AntiEntropyRetry { .. } => "AntiEntropyRetry",
+            SystemMsg::AntiEntropyRedirect { .. } => "AntiEntropyRedirect",
+            SystemMsg::AntiEntropyUpdate { .. } => "AntiEntropyUpdate",
+            SystemMsg::AntiEntropyProbe { .. } => "AntiEntropyProbe",
+            SystemMsg::BackPressure { .. } => "BackPressure",
+            SystemMsg::Relocate { .. } => "Relocate",
+            SystemMsg::JoinRequest { .. } => "JoinRequest",
+            SystemMsg::JoinResponse { .. } => "JoinResponse",
+            SystemMsg::JoinAsRelocatedRequest { .. } => "JoinAsRelocatedRequest",
+            SystemMsg::JoinAsRelocatedResponse { .. } => "JoinAsRelocatedResponse",
+            SystemMsg::DkgStart { .. } => "DkgStart",
+            SystemMsg::DkgSessionUnknown { .. } => "DkgSessionUnknown",
+            SystemMsg::DkgSessionInfo { .. } => "DkgSessionInfo",
+            SystemMsg::DkgMessage { .. } => "DkgMessage",
+            SystemMsg::DkgNotReady { .. } => "DkgNotReady",
+            SystemMsg::DkgRetry { .. } => "DkgRetry",
+            SystemMsg::DkgFailureObservation { .. } => "DkgFailureObservation",
+            SystemMsg::DkgFailureAgreement { .. } => "DkgFailureAgreement",
+            SystemMsg::Propose { .. } => "Propose",
+            SystemMsg::StartConnectivityTest { .. } => "StartConnectivityTest",
+            SystemMsg::NodeCmd { .. } => "NodeCmd",
+            SystemMsg::NodeQuery { .. } => "NodeQuery",
+            SystemMsg::NodeEvent { .. } => "NodeEvent",
+            SystemMsg::NodeQueryResponse { .. } => "NodeQueryResponse",
+            SystemMsg::NodeMsgError { .. } => "NodeMsgError",
+        }
+    }
+}
+
 /// Load report to be sent over the wire.
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LoadReport {
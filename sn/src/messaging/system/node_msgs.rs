@@ -8,7 +8,8 @@
 
 use crate::messaging::{
     data::{
-        DataCmd, DataQuery, MetadataExchange, OperationId, QueryResponse, Result, StorageLevel,
+        DataCmd, DataDigest, DataQuery, MetadataExchange, OperationId, QueryResponse, Result,
+        StorageLevel,
     },
     EndUser, MsgId, ServiceAuth,
 };
@@ -18,8 +19,8 @@ use crate::types::{
 };
 
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
-use xor_name::XorName;
+use std::collections::{BTreeMap, BTreeSet};
+use xor_name::{Prefix, XorName};
 
 /// cmd message sent among nodes
 #[allow(clippy::large_enum_variant)]
@@ -45,16 +46,44 @@ pub enum NodeCmd {
     },
     /// Tells an Adult to store a replica of the data
     ReplicateData(Vec<ReplicatedData>),
+    /// Tells an Adult to store the data of a client's write, acking it with `NodeEvent::DataStored`
+    StoreData {
+        /// The data to store
+        data: ReplicatedData,
+        /// The id of the client's msg the data was written with
+        correlation_id: MsgId,
+    },
     /// Tells an Adult to fetch and replicate data from the sender
     SendReplicateDataAddress(Vec<ReplicatedDataAddress>),
     /// Fetch the given replicated data we are missing
     FetchReplicateData(Vec<ReplicatedDataAddress>),
+    /// Tells an Adult the digests of the data the sender holds for it, so it can find out
+    /// which prefixes it's missing data within
+    SyncDataDigest(DataDigest),
+    /// Asks the Adult which sent us a digest for the digests, or the addresses, of the data
+    /// it holds for us within the given prefixes, which our data differs in
+    RequestDataDigest(Vec<Prefix>),
+    /// Tells the Elders the sending Adult found its replicas of the data corrupt and removed them
+    ReportCorruptData(Vec<ReplicatedDataAddress>),
+    /// Tells an Adult which reported corrupt data to fetch fresh replicas of it from another holder
+    FetchReplicateDataFrom {
+        /// The Adult holding the data
+        holder: XorName,
+        /// The data to fetch
+        data_addresses: Vec<ReplicatedDataAddress>,
+    },
+    /// Tells an Adult to drop the extra replicas it was given of data which is no longer hot,
+    /// unless it's one of the data's regular holders by now
+    DropExtraReplicas(Vec<ReplicatedDataAddress>),
     /// Sent to all promoted nodes (also sibling if any) after
     /// a completed transition to a new constellation.
     ReceiveMetadata {
         /// Metadata
         metadata: MetadataExchange,
     },
+    /// Tells the Elders that the sending node is leaving the network, so its data
+    /// can be replicated to other Adults, and it's taken out of the section right away.
+    Leave,
 }
 
 /// Event message sent among nodes
@@ -72,6 +101,23 @@ pub enum NodeEvent {
     },
     /// Inform Adults of a possible deviant node
     DeviantsDetected(BTreeSet<XorName>),
+    /// Inform Adults a node is leaving the section, so the data they held along with it
+    /// is replicated to the remaining Adults
+    NodeLeaving(XorName),
+    /// Sent by an Adult which stored the data of a client's write, so the Elder
+    /// acks the write to the client once enough Adults have
+    DataStored {
+        /// The id of the client's msg the data was written with
+        correlation_id: MsgId,
+    },
+    /// Sent by an Elder to the one proposing the reward payouts, with the
+    /// queries it observed each Adult serve since the last payout
+    QueriesServed {
+        /// Number of the payout the queries are to be rewarded with
+        epoch: u64,
+        /// Number of queries observed per Adult
+        served: BTreeMap<XorName, u64>,
+    },
 }
 
 /// Query originating at a node
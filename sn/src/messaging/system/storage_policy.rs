@@ -0,0 +1,51 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::types::ReplicatedDataAddress;
+use serde::{Deserialize, Serialize};
+
+/// How the network stores data, agreed at genesis and signed with the genesis key.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+pub struct StoragePolicy {
+    /// Number of Adults each chunk is replicated to.
+    pub chunk_copy_count: usize,
+    /// Number of Adults each register is replicated to.
+    pub register_copy_count: usize,
+    /// Minimum number of Adults a write has to be sent to before it's acked to the client.
+    pub min_write_holders: usize,
+}
+
+impl StoragePolicy {
+    /// Number of Adults the data at the given address is replicated to.
+    pub fn copy_count(&self, address: &ReplicatedDataAddress) -> usize {
+        match address {
+            ReplicatedDataAddress::Chunk(_) => self.chunk_copy_count,
+            ReplicatedDataAddress::Register(_) => self.register_copy_count,
+        }
+    }
+
+    /// Whether every kind of data is replicated to at least one Adult, and a write can reach
+    /// `min_write_holders` Adults whatever kind of data it's of.
+    pub fn is_valid(&self) -> bool {
+        self.chunk_copy_count > 0
+            && self.register_copy_count > 0
+            && self.min_write_holders > 0
+            && self.min_write_holders <= self.chunk_copy_count.min(self.register_copy_count)
+    }
+}
+
+impl Default for StoragePolicy {
+    fn default() -> Self {
+        let copy_count = crate::data_copy_count();
+        Self {
+            chunk_copy_count: copy_count,
+            register_copy_count: copy_count,
+            min_write_holders: copy_count,
+        }
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Optional local HTTP endpoint for monitoring a node. It serves the node's status as JSON
+//! at `/status`, and its metrics in Prometheus text format at `/metrics`.
+
+use super::{NodeApi, Result};
+use crate::messaging::system::{CpuLoad, LoadReport};
+
+use hyper::{
+    header::{HeaderValue, CONTENT_TYPE},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    fmt::{self, Write},
+    net::SocketAddr,
+};
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Snapshot of a node's state and msg counters, as served by the admin endpoint.
+#[derive(Clone, Debug, Serialize)]
+pub struct NodeMetrics {
+    /// Hex encoded name of the node.
+    pub name: String,
+    /// Age of the node.
+    pub age: u8,
+    /// Prefix of the node's section, as a binary string.
+    pub prefix: String,
+    /// Whether the node is an Elder.
+    pub is_elder: bool,
+    /// Elders of the node's section.
+    pub elders: Vec<String>,
+    /// Adults of the node's section.
+    pub adults: Vec<String>,
+    /// Length of the section chain known to the node.
+    pub section_chain_len: usize,
+    /// Ratio of the used space to the max capacity of the node's storage.
+    pub used_space_ratio: f64,
+    /// Latest cpu load evaluated by the node, which is reported back to callers when bad.
+    pub load: Option<LoadReport>,
+    /// Number of nodes the node is regulating its comms with, as per their load reports.
+    pub regulated_peers: usize,
+    /// Total number of msgs received.
+    pub msgs_received: usize,
+    /// Total number of msgs sent.
+    pub msgs_sent: usize,
+    /// Number of msgs received of each kind.
+    pub msg_kinds_received: BTreeMap<&'static str, usize>,
+    /// Number of msgs sent of each kind.
+    pub msg_kinds_sent: BTreeMap<&'static str, usize>,
+}
+
+impl NodeMetrics {
+    /// Renders the metrics in Prometheus text format.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        // writing to a String never fails
+        let _ = self.write_prometheus(&mut out);
+        out
+    }
+
+    fn write_prometheus(&self, out: &mut String) -> fmt::Result {
+        header(
+            out,
+            "sn_node_info",
+            "gauge",
+            "Name and section prefix of the node.",
+        )?;
+        writeln!(
+            out,
+            "sn_node_info{{name=\"{}\",prefix=\"{}\"}} 1",
+            self.name, self.prefix
+        )?;
+
+        header(out, "sn_node_age", "gauge", "Age of the node.")?;
+        writeln!(out, "sn_node_age {}", self.age)?;
+
+        header(
+            out,
+            "sn_node_is_elder",
+            "gauge",
+            "Whether the node is an Elder.",
+        )?;
+        writeln!(out, "sn_node_is_elder {}", self.is_elder as u8)?;
+
+        header(
+            out,
+            "sn_node_section_elders",
+            "gauge",
+            "Number of Elders in the section.",
+        )?;
+        writeln!(out, "sn_node_section_elders {}", self.elders.len())?;
+
+        header(
+            out,
+            "sn_node_section_adults",
+            "gauge",
+            "Number of Adults in the section.",
+        )?;
+        writeln!(out, "sn_node_section_adults {}", self.adults.len())?;
+
+        header(
+            out,
+            "sn_node_section_chain_length",
+            "gauge",
+            "Length of the known section chain.",
+        )?;
+        writeln!(
+            out,
+            "sn_node_section_chain_length {}",
+            self.section_chain_len
+        )?;
+
+        header(
+            out,
+            "sn_node_used_space_ratio",
+            "gauge",
+            "Ratio of the used space to the max capacity.",
+        )?;
+        writeln!(out, "sn_node_used_space_ratio {}", self.used_space_ratio)?;
+
+        if let Some(load) = &self.load {
+            header(
+                out,
+                "sn_node_cpu_load",
+                "gauge",
+                "Latest evaluated cpu load, per term and level.",
+            )?;
+            for (term, cpu_load) in [
+                ("short", &load.short_term),
+                ("mid", &load.mid_term),
+                ("long", &load.long_term),
+            ] {
+                for (level, set) in cpu_load_levels(cpu_load) {
+                    writeln!(
+                        out,
+                        "sn_node_cpu_load{{term=\"{}\",level=\"{}\"}} {}",
+                        term, level, set as u8
+                    )?;
+                }
+            }
+        }
+
+        header(
+            out,
+            "sn_node_regulated_peers",
+            "gauge",
+            "Number of nodes comms are regulated with, as per their load reports.",
+        )?;
+        writeln!(out, "sn_node_regulated_peers {}", self.regulated_peers)?;
+
+        header(
+            out,
+            "sn_node_msgs_received_total",
+            "counter",
+            "Total number of msgs received.",
+        )?;
+        writeln!(out, "sn_node_msgs_received_total {}", self.msgs_received)?;
+
+        header(
+            out,
+            "sn_node_msgs_sent_total",
+            "counter",
+            "Total number of msgs sent.",
+        )?;
+        writeln!(out, "sn_node_msgs_sent_total {}", self.msgs_sent)?;
+
+        header(
+            out,
+            "sn_node_msg_kinds_received_total",
+            "counter",
+            "Number of msgs received of each kind.",
+        )?;
+        for (kind, count) in &self.msg_kinds_received {
+            writeln!(
+                out,
+                "sn_node_msg_kinds_received_total{{kind=\"{}\"}} {}",
+                kind, count
+            )?;
+        }
+
+        header(
+            out,
+            "sn_node_msg_kinds_sent_total",
+            "counter",
+            "Number of msgs sent of each kind.",
+        )?;
+        for (kind, count) in &self.msg_kinds_sent {
+            writeln!(
+                out,
+                "sn_node_msg_kinds_sent_total{{kind=\"{}\"}} {}",
+                kind, count
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) -> fmt::Result {
+    writeln!(out, "# HELP {} {}", name, help)?;
+    writeln!(out, "# TYPE {} {}", name, kind)
+}
+
+fn cpu_load_levels(load: &CpuLoad) -> [(&'static str, bool); 5] {
+    [
+        ("low", load.low),
+        ("moderate", load.moderate),
+        ("high", load.high),
+        ("very_high", load.very_high),
+        ("critical", load.critical),
+    ]
+}
+
+/// Serves the admin endpoint of the node at the given address,
+/// for as long as the process runs.
+pub(super) fn run_admin_server(node: NodeApi, addr: SocketAddr) -> Result<()> {
+    let server = Server::try_bind(&addr)?;
+    let make_service = make_service_fn(move |_conn| {
+        let node = node.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle_request(node.clone(), req))) }
+    });
+
+    info!("Admin endpoint listening at http://{}", addr);
+    let _handle = tokio::spawn(async move {
+        if let Err(err) = server.serve(make_service).await {
+            error!("Admin endpoint at {} stopped: {:?}", addr, err);
+        }
+    });
+
+    Ok(())
+}
+
+async fn handle_request(
+    node: NodeApi,
+    req: Request<Body>,
+) -> std::result::Result<Response<Body>, Infallible> {
+    let response = match (req.method(), req.uri().path()) {
+        (&Method::GET, "/status") => match serde_json::to_string(&node.metrics().await) {
+            Ok(json) => response(StatusCode::OK, JSON_CONTENT_TYPE, json),
+            Err(err) => response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "text/plain",
+                err.to_string(),
+            ),
+        },
+        (&Method::GET, "/metrics") => response(
+            StatusCode::OK,
+            PROMETHEUS_CONTENT_TYPE,
+            node.metrics().await.to_prometheus(),
+        ),
+        _ => response(StatusCode::NOT_FOUND, "text/plain", "Not found".to_string()),
+    };
+
+    Ok(response)
+}
+
+fn response(status: StatusCode, content_type: &'static str, body: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    let _prev = response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NodeMetrics;
+    use crate::messaging::system::{CpuLoad, LoadReport};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn prometheus_rendering_of_node_metrics() {
+        let cpu_load = CpuLoad {
+            low: false,
+            moderate: true,
+            high: true,
+            very_high: false,
+            critical: false,
+        };
+        let metrics = NodeMetrics {
+            name: "abcd".to_string(),
+            age: 7,
+            prefix: "01".to_string(),
+            is_elder: true,
+            elders: vec!["elder".to_string(); 3],
+            adults: vec!["adult".to_string(); 2],
+            section_chain_len: 4,
+            used_space_ratio: 0.25,
+            load: Some(LoadReport {
+                short_term: cpu_load,
+                mid_term: cpu_load,
+                long_term: cpu_load,
+            }),
+            regulated_peers: 1,
+            msgs_received: 12,
+            msgs_sent: 9,
+            msg_kinds_received: BTreeMap::from([("Cmd", 10), ("NodeCmd", 2)]),
+            msg_kinds_sent: BTreeMap::from([("CmdAck", 9)]),
+        };
+
+        let text = metrics.to_prometheus();
+        for line in [
+            "# TYPE sn_node_age gauge",
+            "sn_node_info{name=\"abcd\",prefix=\"01\"} 1",
+            "sn_node_age 7",
+            "sn_node_is_elder 1",
+            "sn_node_section_elders 3",
+            "sn_node_section_adults 2",
+            "sn_node_section_chain_length 4",
+            "sn_node_used_space_ratio 0.25",
+            "sn_node_cpu_load{term=\"mid\",level=\"high\"} 1",
+            "sn_node_cpu_load{term=\"long\",level=\"critical\"} 0",
+            "sn_node_regulated_peers 1",
+            "# TYPE sn_node_msgs_received_total counter",
+            "sn_node_msgs_received_total 12",
+            "sn_node_msgs_sent_total 9",
+            "sn_node_msg_kinds_received_total{kind=\"Cmd\"} 10",
+            "sn_node_msg_kinds_received_total{kind=\"NodeCmd\"} 2",
+            "sn_node_msg_kinds_sent_total{kind=\"CmdAck\"} 9",
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing '{}' in:\n{}",
+                line,
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn cpu_load_is_not_rendered_when_not_evaluated() {
+        let metrics = NodeMetrics {
+            name: "abcd".to_string(),
+            age: 5,
+            prefix: "".to_string(),
+            is_elder: false,
+            elders: vec![],
+            adults: vec![],
+            section_chain_len: 1,
+            used_space_ratio: 0.0,
+            load: None,
+            regulated_peers: 0,
+            msgs_received: 0,
+            msgs_sent: 0,
+            msg_kinds_received: BTreeMap::new(),
+            msg_kinds_sent: BTreeMap::new(),
+        };
+
+        assert!(!metrics.to_prometheus().contains("sn_node_cpu_load"));
+    }
+}
@@ -24,6 +24,10 @@ use tracing::Instrument;
 
 const PROBE_INTERVAL: Duration = Duration::from_secs(30);
 const LINK_CLEANUP_INTERVAL: Duration = Duration::from_secs(15);
+const REWARD_PAYOUT_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const NETWORK_KNOWLEDGE_WRITE_INTERVAL: Duration = Duration::from_secs(60);
+const CHUNK_SCRUB_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const COLD_REPLICAS_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
 
 // A command/subcommand id e.g. "963111461", "963111461.0"
 type CmdId = String;
@@ -160,9 +164,114 @@ impl Dispatcher {
         });
     }
 
-    pub(super) async fn write_prefixmap_to_disk(self: Arc<Self>) {
-        info!("Writing our PrefixMap to disk");
-        self.clone().node.write_prefix_map().await
+    pub(super) async fn start_rewards_payout(self: Arc<Self>) {
+        info!("Starting to propose reward payouts");
+        let _handle = tokio::spawn(async move {
+            let dispatcher = self.clone();
+            let mut interval = time::interval(REWARD_PAYOUT_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            // the first tick completes immediately, whereas rewards are earned over an interval
+            let _instant = interval.tick().await;
+
+            loop {
+                let _instant = interval.tick().await;
+
+                // Only Elders account for the rewards of the section
+                let node = &dispatcher.node;
+                if node.is_elder().await {
+                    match node.propose_reward_payout().await {
+                        Ok(cmds) => {
+                            for cmd in cmds {
+                                if let Err(e) = dispatcher
+                                    .clone()
+                                    .enqueue_and_handle_next_cmd_and_offshoots(cmd, None)
+                                    .await
+                                {
+                                    error!("Error proposing a reward payout: {:?}", e);
+                                }
+                            }
+                        }
+                        Err(error) => error!("Problem proposing a reward payout: {:?}", error),
+                    }
+                }
+            }
+        });
+    }
+
+    pub(super) async fn start_writing_network_knowledge(self: Arc<Self>) {
+        info!("Starting to write our network knowledge to disk");
+        let _handle = tokio::spawn(async move {
+            let mut interval = time::interval(NETWORK_KNOWLEDGE_WRITE_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+            loop {
+                let _instant = interval.tick().await;
+                self.node.write_network_knowledge().await;
+            }
+        });
+    }
+
+    pub(super) async fn start_scrubbing_chunks(self: Arc<Self>) {
+        info!("Starting to scrub stored chunks");
+        let _handle = tokio::spawn(async move {
+            let dispatcher = self.clone();
+            let mut interval = time::interval(CHUNK_SCRUB_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            // the first tick completes immediately, let the node settle in its section first
+            let _instant = interval.tick().await;
+
+            loop {
+                let _instant = interval.tick().await;
+
+                let cmds = match dispatcher.node.scrub_chunks().await {
+                    Ok(cmds) => cmds,
+                    Err(error) => {
+                        error!("Problem scrubbing chunks: {:?}", error);
+                        continue;
+                    }
+                };
+                for cmd in cmds {
+                    if let Err(e) = dispatcher
+                        .clone()
+                        .enqueue_and_handle_next_cmd_and_offshoots(cmd, None)
+                        .await
+                    {
+                        error!("Error reporting corrupt chunks: {:?}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    pub(super) async fn start_dropping_cold_replicas(self: Arc<Self>) {
+        info!("Starting to drop extra replicas of chunks no longer hot");
+        let _handle = tokio::spawn(async move {
+            let dispatcher = self.clone();
+            let mut interval = time::interval(COLD_REPLICAS_CHECK_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+            loop {
+                let _instant = interval.tick().await;
+
+                // Only Elders track the extra replicas of hot chunks
+                let cmds = match dispatcher.node.drop_cold_extra_replicas().await {
+                    Ok(cmds) => cmds,
+                    Err(error) => {
+                        error!("Problem dropping cold extra replicas: {:?}", error);
+                        continue;
+                    }
+                };
+                for cmd in cmds {
+                    if let Err(e) = dispatcher
+                        .clone()
+                        .enqueue_and_handle_next_cmd_and_offshoots(cmd, None)
+                        .await
+                    {
+                        error!("Error sending drop of extra replicas: {:?}", e);
+                    }
+                }
+            }
+        });
     }
 
     /// Handles a single cmd.
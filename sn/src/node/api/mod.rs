@@ -22,15 +22,20 @@ use self::{
     event_stream::EventStream,
 };
 
-use crate::messaging::{system::SystemMsg, DstLocation, WireMsg};
+use crate::messaging::{
+    data::RewardLedger,
+    system::{NodeCmd, SystemMsg},
+    DstLocation, WireMsg,
+};
 use crate::node::{
+    admin::{run_admin_server, NodeMetrics},
     cfg::keypair_storage::{get_reward_pk, store_network_keypair, store_new_reward_keypair},
     core::{join_network, Comm, ConnectionEvent, Node},
     ed25519,
     error::{Error, Result},
     logging::{log_ctx::LogCtx, run_system_logger},
     messages::WireMsgUtils,
-    network_knowledge::SectionAuthorityProvider,
+    network_knowledge::{NetworkKnowledge, SectionAuthorityProvider},
     Config, NodeInfo, Peer, MIN_ADULT_AGE,
 };
 use crate::types::{log_markers::LogMarker, PublicKey as TypesPublicKey};
@@ -58,6 +63,7 @@ use xor_name::{Prefix, XorName};
 /// `Node` or as a part of a section or group location. Their `src` argument indicates that
 /// role, and can be `crate::messaging::SrcLocation::Node` or `crate::messaging::SrcLocation::Section`.
 #[allow(missing_debug_implementations)]
+#[derive(Clone)]
 pub struct NodeApi {
     dispatcher: Arc<Dispatcher>,
 }
@@ -71,25 +77,35 @@ impl NodeApi {
 
     /// Initialize a new node.
     pub async fn new(config: &Config, joining_timeout: Duration) -> Result<(Self, EventStream)> {
+        let admin_addr = config.admin_addr()?;
         let root_dir_buf = config.root_dir()?;
         let root_dir = root_dir_buf.as_path();
         tokio::fs::create_dir_all(root_dir).await?;
 
-        let _reward_key = match get_reward_pk(root_dir).await? {
-            Some(public_key) => TypesPublicKey::Ed25519(public_key),
-            None => {
-                let mut rng = OsRng;
-                let keypair = ed25519_dalek::Keypair::generate(&mut rng);
-                store_new_reward_keypair(root_dir, &keypair).await?;
-                TypesPublicKey::Ed25519(keypair.public)
-            }
+        // Rewards are credited to the configured wallet, falling back to this node's own reward key.
+        let reward_key = match config.wallet_id() {
+            Some(wallet_id) => TypesPublicKey::ed25519_from_hex(wallet_id)
+                .or_else(|_| TypesPublicKey::bls_from_hex(wallet_id))
+                .map_err(|error| {
+                    Error::Configuration(format!("Invalid wallet id {}: {:?}", wallet_id, error))
+                })?,
+            None => match get_reward_pk(root_dir).await? {
+                Some(public_key) => TypesPublicKey::Ed25519(public_key),
+                None => {
+                    let mut rng = OsRng;
+                    let keypair = ed25519_dalek::Keypair::generate(&mut rng);
+                    store_new_reward_keypair(root_dir, &keypair).await?;
+                    TypesPublicKey::Ed25519(keypair.public)
+                }
+            },
         };
+        info!("Rewards are to be credited to {:?}", reward_key);
 
         let used_space = UsedSpace::new(config.max_capacity());
 
         let (api, network_events) = tokio::time::timeout(
             joining_timeout,
-            Self::start_node(config, used_space, root_dir),
+            Self::start_node(config, used_space, root_dir, reward_key),
         )
         .await
         .map_err(|_| Error::JoinTimeout)??;
@@ -116,6 +132,10 @@ impl NodeApi {
 
         run_system_logger(LogCtx::new(api.dispatcher.clone()), config.resource_logs).await;
 
+        if let Some(admin_addr) = admin_addr {
+            run_admin_server(api.clone(), admin_addr)?;
+        }
+
         Ok((api, network_events))
     }
 
@@ -128,6 +148,7 @@ impl NodeApi {
         config: &Config,
         used_space: UsedSpace,
         root_storage_dir: &Path,
+        reward_key: TypesPublicKey,
     ) -> Result<(Self, EventStream)> {
         let (event_tx, event_rx) = mpsc::channel(EVENT_CHANNEL_SIZE);
         let (connection_event_tx, mut connection_event_rx) = mpsc::channel(1);
@@ -153,7 +174,9 @@ impl NodeApi {
                 connection_event_tx,
             )
             .await?;
-            let info = NodeInfo::new(keypair, comm.our_connection_info());
+            let info = NodeInfo::new(keypair, comm.our_connection_info())
+                .with_reward_key(reward_key)
+                .with_storage_policy(config.storage_policy()?);
 
             let genesis_sk_set = bls::SecretKeySet::random(0, &mut rand::thread_rng());
             let node = Node::first_node(
@@ -162,6 +185,8 @@ impl NodeApi {
                 event_tx,
                 used_space.clone(),
                 root_storage_dir.to_path_buf(),
+                config.storage_backend(),
+                config.chunk_compression(),
                 genesis_sk_set,
             )
             .await?;
@@ -208,14 +233,35 @@ impl NodeApi {
             let node_name = ed25519::name(&keypair.public);
             info!("{} Bootstrapping a new node.", node_name);
 
+            // Network knowledge written to disk by a previous run of this node, if any.
+            let known_network =
+                match NetworkKnowledge::read_from_disk(root_storage_dir, genesis_key).await {
+                    Ok(known_network) => known_network,
+                    Err(error) => {
+                        warn!("Ignoring network knowledge found on disk: {:?}", error);
+                        None
+                    }
+                };
+
+            // Any peer known from a previous run can be bootstrapped from, after the hard-coded contacts.
+            let mut bootstrap_contacts = config.hard_coded_contacts.iter().copied().collect_vec();
+            if let Some(known_network) = &known_network {
+                let known_contacts = known_network.known_contacts().await;
+                info!(
+                    "{} Restored network knowledge from disk, with {} known contacts",
+                    node_name,
+                    known_contacts.len()
+                );
+                bootstrap_contacts.extend(
+                    known_contacts
+                        .into_iter()
+                        .filter(|addr| !config.hard_coded_contacts.contains(addr)),
+                );
+            }
+
             let (comm, bootstrap_addr) = Comm::bootstrap(
                 local_addr,
-                config
-                    .hard_coded_contacts
-                    .iter()
-                    .copied()
-                    .collect_vec()
-                    .as_slice(),
+                bootstrap_contacts.as_slice(),
                 config.network_config().clone(),
                 connection_event_tx,
             )
@@ -229,13 +275,16 @@ impl NodeApi {
                 genesis_key
             );
 
-            let joining_node = NodeInfo::new(keypair, comm.our_connection_info());
+            let joining_node = NodeInfo::new(keypair, comm.our_connection_info())
+                .with_reward_key(reward_key)
+                .with_storage_policy(config.storage_policy()?);
             let (info, network_knowledge) = join_network(
                 joining_node,
                 &comm,
                 &mut connection_event_rx,
                 bootstrap_addr,
                 genesis_key,
+                known_network.map(|network| network.prefix_map().clone()),
             )
             .await?;
 
@@ -247,11 +296,26 @@ impl NodeApi {
                 event_tx,
                 used_space.clone(),
                 root_storage_dir.to_path_buf(),
+                config.storage_backend(),
+                config.chunk_compression(),
             )
             .await?;
             info!("{} Joined the network!", node.info.read().await.name());
             info!("Our AGE: {}", node.info.read().await.age());
 
+            // Data kept from a previous run is only held on to if it belongs to our new section.
+            match node.remove_data_outside_section().await {
+                Ok(0) => {}
+                Ok(removed) => info!(
+                    "Removed {} data entries not belonging to our section from local store",
+                    removed
+                ),
+                Err(error) => error!(
+                    "Problem removing data not belonging to our section: {:?}",
+                    error
+                ),
+            }
+
             node
         };
 
@@ -266,7 +330,10 @@ impl NodeApi {
 
         dispatcher.clone().start_network_probing().await;
         dispatcher.clone().start_cleaning_peer_links().await;
-        dispatcher.clone().write_prefixmap_to_disk().await;
+        dispatcher.clone().start_rewards_payout().await;
+        dispatcher.clone().start_writing_network_knowledge().await;
+        dispatcher.clone().start_scrubbing_chunks().await;
+        dispatcher.clone().start_dropping_cold_replicas().await;
 
         let api = Self { dispatcher };
 
@@ -323,6 +390,45 @@ impl NodeApi {
         self.dispatcher.node.network_knowledge().adults().await
     }
 
+    /// Returns the rewards accounted for the Adults of our section.
+    /// Only Elders keep a reward ledger.
+    pub async fn reward_ledger(&self) -> Result<RewardLedger> {
+        self.dispatcher.node.reward_ledger().await
+    }
+
+    /// Returns a snapshot of this node's state and msg counters,
+    /// as served by the admin endpoint.
+    pub async fn metrics(&self) -> NodeMetrics {
+        let node = &self.dispatcher.node;
+        let msg_stats = node.comm.msg_stats();
+        NodeMetrics {
+            name: hex::encode(self.name().await),
+            age: self.age().await,
+            prefix: format!("{:b}", self.our_prefix().await),
+            is_elder: self.is_elder().await,
+            elders: self
+                .our_elders()
+                .await
+                .iter()
+                .map(Peer::to_string)
+                .collect(),
+            adults: self
+                .our_adults()
+                .await
+                .iter()
+                .map(Peer::to_string)
+                .collect(),
+            section_chain_len: self.section_chain().await.len(),
+            used_space_ratio: node.used_space_ratio(),
+            load: node.comm.our_load().await,
+            regulated_peers: node.comm.regulated_peers().await,
+            msgs_received: msg_stats.incoming_total,
+            msgs_sent: msg_stats.outgoing_total,
+            msg_kinds_received: msg_stats.incoming,
+            msg_kinds_sent: msg_stats.outgoing,
+        }
+    }
+
     /// Returns the info about the section matching the name.
     pub async fn matching_section(&self, name: &XorName) -> Result<SectionAuthorityProvider> {
         self.dispatcher.node.matching_section(name).await
@@ -362,6 +468,36 @@ impl NodeApi {
         Ok(())
     }
 
+    /// Tells our Elders we're leaving the network, so they replicate our data to other Adults
+    /// and take us out of the section, rather than waiting for us to be found unresponsive.
+    /// It returns once the msg has been sent, after which the node can be stopped.
+    pub async fn leave(&self) -> Result<()> {
+        let dst = DstLocation::Section {
+            name: self.name().await,
+            section_pk: *self.section_chain().await.last_key(),
+        };
+        let wire_msg = self
+            .sign_single_src_msg(SystemMsg::NodeCmd(NodeCmd::Leave), dst)
+            .await?;
+
+        info!("Sending Leave msg to our Elders: {:?}", wire_msg.msg_id());
+
+        // The resulting cmds are processed here rather than enqueued,
+        // so the msg is sent by the time we return.
+        let mut cmds: Vec<_> = self
+            .dispatcher
+            .node
+            .send_msg_to_nodes(wire_msg)
+            .await?
+            .into_iter()
+            .collect();
+        while let Some(cmd) = cmds.pop() {
+            cmds.extend(self.dispatcher.process_cmd(cmd, "leave").await?);
+        }
+
+        Ok(())
+    }
+
     /// Returns the current BLS public key set if this node has one, or
     /// `Error::MissingSecretKeyShare` otherwise.
     pub async fn public_key_set(&self) -> Result<bls::PublicKeySet> {
@@ -10,11 +10,13 @@
 
 use super::{Cmd, Comm, Dispatcher};
 
-use crate::dbs::UsedSpace;
+use crate::dbs::{StorageBackendKind, UsedSpace};
 use crate::messaging::{
+    data::ServiceMsg,
     system::{
-        JoinAsRelocatedRequest, JoinRequest, JoinResponse, KeyedSig, MembershipState,
-        NodeState as NodeStateMsg, RelocateDetails, ResourceProofResponse, SectionAuth, SystemMsg,
+        JoinAsRelocatedRequest, JoinRequest, JoinResponse, KeyedSig, MembershipState, NodeCmd,
+        NodeEvent, NodeState as NodeStateMsg, RelocateDetails, ResourceProofResponse, SectionAuth,
+        StoragePolicy, SystemMsg,
     },
     AuthorityProof, DstLocation, MsgId, MsgKind, MsgType, NodeAuth,
     SectionAuth as MsgKindSectionAuth, WireMsg,
@@ -34,7 +36,9 @@ use crate::node::{
     recommended_section_size, supermajority, Error, Event, NodeInfo, Result as RoutingResult,
     FIRST_SECTION_MAX_AGE, FIRST_SECTION_MIN_AGE, MIN_ADULT_AGE,
 };
-use crate::types::{Keypair, Peer, PublicKey};
+use crate::types::{
+    utils::random_bytes, Chunk, Keypair, Peer, PublicKey, ReplicatedData, ReplicatedDataAddress,
+};
 use crate::{elder_count, init_test_logger};
 
 use assert_matches::assert_matches;
@@ -80,6 +84,8 @@ async fn receive_join_request_without_resource_proof_response() -> Result<()> {
         mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
     let dispatcher = Dispatcher::new(node);
@@ -100,6 +106,8 @@ async fn receive_join_request_without_resource_proof_response() -> Result<()> {
             section_key,
             resource_proof_response: None,
             aggregated: None,
+            reward_key: new_node.reward_key,
+            storage_policy: None,
         })),
         section_key,
     )?;
@@ -161,6 +169,8 @@ async fn receive_join_request_with_resource_proof_response() -> Result<()> {
         mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
     let dispatcher = Dispatcher::new(node);
@@ -197,6 +207,8 @@ async fn receive_join_request_with_resource_proof_response() -> Result<()> {
                 nonce_signature,
             }),
             aggregated: Some(auth.clone()),
+            reward_key: new_node.reward_key,
+            storage_policy: None,
         })),
         section_key,
     )?;
@@ -251,6 +263,8 @@ async fn receive_join_request_from_relocated_node() -> Result<()> {
         mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
     let dispatcher = Dispatcher::new(node);
@@ -341,6 +355,8 @@ async fn handle_agreement_on_online() -> Result<()> {
         event_tx,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
     let dispatcher = Dispatcher::new(node);
@@ -374,6 +390,8 @@ async fn handle_agreement_on_online_of_elder_candidate() -> Result<()> {
     let signed_sap = section_signed(sk_set.secret_key(), section_auth.clone())?;
 
     let section = NetworkKnowledge::new(*chain.root_key(), chain, signed_sap, None)?;
+    let storage_policy = section_signed(sk_set.secret_key(), StoragePolicy::default())?;
+    section.set_storage_policy(storage_policy).await?;
     let mut expected_new_elders = BTreeSet::new();
 
     for peer in section_auth.elders() {
@@ -402,6 +420,8 @@ async fn handle_agreement_on_online_of_elder_candidate() -> Result<()> {
         mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
     let dispatcher = Dispatcher::new(node);
@@ -558,6 +578,8 @@ async fn handle_agreement_on_online_of_rejoined_node(phase: NetworkPhase, age: u
         event_tx,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
     let dispatcher = Dispatcher::new(node);
@@ -627,6 +649,8 @@ async fn handle_agreement_on_offline_of_non_elder() -> Result<()> {
         event_tx,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
     let dispatcher = Dispatcher::new(node);
@@ -683,6 +707,8 @@ async fn handle_agreement_on_offline_of_elder() -> Result<()> {
         event_tx,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
     let dispatcher = Dispatcher::new(node);
@@ -754,6 +780,489 @@ async fn handle_agreement_on_offline_of_elder() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn handle_leave_msg_of_non_elder() -> Result<()> {
+    init_test_logger();
+    let _span = tracing::info_span!("handle_leave_msg_of_non_elder").entered();
+
+    let (section_auth, mut nodes, sk_set) = create_section_auth();
+    let (section, section_key_share) = create_section(&sk_set, &section_auth).await?;
+    let section_key = section.section_key().await;
+
+    let leaving_node = gen_info(MIN_ADULT_AGE, None);
+    let node_state = NodeState::joined(leaving_node.peer(), None);
+    let node_state = section_signed(sk_set.secret_key(), node_state)?;
+    assert!(section.update_member(node_state).await);
+
+    let remaining_adult = gen_info(MIN_ADULT_AGE, None);
+    let node_state = NodeState::joined(remaining_adult.peer(), None);
+    let node_state = section_signed(sk_set.secret_key(), node_state)?;
+    assert!(section.update_member(node_state).await);
+
+    let node = nodes.remove(0);
+    let (max_capacity, root_storage_dir) = create_test_max_capacity_and_root_storage()?;
+    let node = Node::new(
+        create_comm().await?,
+        node,
+        section,
+        Some(section_key_share),
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+        UsedSpace::new(max_capacity),
+        root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
+    )
+    .await?;
+    let dispatcher = Dispatcher::new(node);
+
+    let wire_msg = WireMsg::single_src(
+        &leaving_node,
+        DstLocation::Section {
+            name: leaving_node.name(),
+            section_pk: section_key,
+        },
+        SystemMsg::NodeCmd(NodeCmd::Leave),
+        section_key,
+    )?;
+
+    let cmds = dispatcher
+        .process_cmd(
+            Cmd::HandleMsg {
+                sender: leaving_node.peer(),
+                wire_msg,
+                original_bytes: None,
+            },
+            "cmd-id",
+        )
+        .await?;
+
+    // The leaving node is proposed `Left` right away, and the remaining Adults are told to
+    // replicate the data they held along with it.
+    let mut offline_left_sent = false;
+    let mut replication_sent = false;
+    for cmd in cmds {
+        let wire_msg = match cmd {
+            Cmd::SendMsg { wire_msg, .. } => wire_msg,
+            Cmd::SignOutgoingSystemMsg {
+                msg: SystemMsg::NodeEvent(NodeEvent::NodeLeaving(name)),
+                dst: DstLocation::Node { name: dst, .. },
+            } => {
+                assert_eq!(name, leaving_node.name());
+                assert_eq!(dst, remaining_adult.name());
+                replication_sent = true;
+                continue;
+            }
+            _ => continue,
+        };
+
+        if let Ok(MsgType::System {
+            msg:
+                SystemMsg::Propose {
+                    proposal: crate::messaging::system::Proposal::Offline(node_state),
+                    ..
+                },
+            ..
+        }) = wire_msg.into_msg()
+        {
+            assert_eq!(node_state.name, leaving_node.name());
+            assert_eq!(node_state.state, MembershipState::Left);
+            offline_left_sent = true;
+        }
+    }
+
+    assert!(offline_left_sent);
+    assert!(replication_sent);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn drop_extra_replicas_only_when_sent_by_elders() -> Result<()> {
+    init_test_logger();
+    let _span = tracing::info_span!("drop_extra_replicas_only_when_sent_by_elders").entered();
+
+    let (section_auth, nodes, sk_set) = create_section_auth();
+    let (section, _) = create_section(&sk_set, &section_auth).await?;
+    let section_key = section.section_key().await;
+
+    let info = gen_info(MIN_ADULT_AGE, None);
+    let other_adults: Vec<_> = iter::repeat_with(|| gen_info(MIN_ADULT_AGE, None))
+        .take(10)
+        .collect();
+    for adult in iter::once(&info).chain(&other_adults) {
+        let node_state = NodeState::joined(adult.peer(), None);
+        let node_state = section_signed(sk_set.secret_key(), node_state)?;
+        assert!(section.update_member(node_state).await);
+    }
+
+    let (max_capacity, root_storage_dir) = create_test_max_capacity_and_root_storage()?;
+    let node = Node::new(
+        create_comm().await?,
+        info.clone(),
+        section,
+        None,
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+        UsedSpace::new(max_capacity),
+        root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
+    )
+    .await?;
+
+    // an extra replica, of a chunk we're not a regular holder of
+    let adults = node
+        .network_knowledge()
+        .adults()
+        .await
+        .iter()
+        .map(|peer| peer.name())
+        .collect();
+    let chunk = loop {
+        let chunk = Chunk::new(random_bytes(100));
+        let address = ReplicatedDataAddress::Chunk(*chunk.address());
+        if !node
+            .compute_holders(&address, &adults)
+            .await?
+            .contains(&info.name())
+        {
+            break chunk;
+        }
+    };
+    let address = ReplicatedDataAddress::Chunk(*chunk.address());
+    let _ = node
+        .data_storage
+        .store(&ReplicatedData::Chunk(chunk))
+        .await?;
+    let dispatcher = Dispatcher::new(node);
+
+    let drop_extra_replicas = |sender: &NodeInfo| {
+        WireMsg::single_src(
+            sender,
+            DstLocation::Node {
+                name: info.name(),
+                section_pk: section_key,
+            },
+            SystemMsg::NodeCmd(NodeCmd::DropExtraReplicas(vec![address])),
+            section_key,
+        )
+    };
+
+    // another Adult can't have us drop it
+    let _ = dispatcher
+        .process_cmd(
+            Cmd::HandleMsg {
+                sender: other_adults[0].peer(),
+                wire_msg: drop_extra_replicas(&other_adults[0])?,
+                original_bytes: None,
+            },
+            "cmd-id",
+        )
+        .await?;
+    assert!(dispatcher
+        .node
+        .data_storage
+        .get_from_local_store(&address)
+        .await
+        .is_ok());
+
+    // but our Elders can
+    let _ = dispatcher
+        .process_cmd(
+            Cmd::HandleMsg {
+                sender: nodes[0].peer(),
+                wire_msg: drop_extra_replicas(&nodes[0])?,
+                original_bytes: None,
+            },
+            "cmd-id",
+        )
+        .await?;
+    assert!(dispatcher
+        .node
+        .data_storage
+        .get_from_local_store(&address)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replicate_data_of_leaving_node_only_when_told_by_elders() -> Result<()> {
+    init_test_logger();
+    let _span =
+        tracing::info_span!("replicate_data_of_leaving_node_only_when_told_by_elders").entered();
+
+    let (section_auth, nodes, sk_set) = create_section_auth();
+    let infos: Vec<_> = iter::repeat_with(|| gen_info(MIN_ADULT_AGE, None))
+        .take(10)
+        .collect();
+    let info = &infos[0];
+    let node = adult_node(&sk_set, &section_auth, info, &infos).await?;
+    let section_key = node.network_knowledge().section_key().await;
+
+    // a chunk we hold along with the node which is leaving
+    let adults = infos.iter().map(|info| info.name()).collect();
+    let (chunk, leaving) = loop {
+        let chunk = Chunk::new(random_bytes(100));
+        let address = ReplicatedDataAddress::Chunk(*chunk.address());
+        let holders = node.compute_holders(&address, &adults).await?;
+        if holders.contains(&info.name()) {
+            let leaving = holders
+                .into_iter()
+                .find(|holder| *holder != info.name())
+                .ok_or_else(|| eyre!("the chunk should have other holders"))?;
+            break (chunk, leaving);
+        }
+    };
+    let _ = node
+        .data_storage
+        .store(&ReplicatedData::Chunk(chunk))
+        .await?;
+    let dispatcher = Dispatcher::new(node);
+
+    let node_leaving = |sender: &NodeInfo| {
+        WireMsg::single_src(
+            sender,
+            DstLocation::Node {
+                name: info.name(),
+                section_pk: section_key,
+            },
+            SystemMsg::NodeEvent(NodeEvent::NodeLeaving(leaving)),
+            section_key,
+        )
+    };
+
+    // another Adult can't have us replicate it
+    let other_adult = infos
+        .iter()
+        .find(|adult| adult.name() != info.name() && adult.name() != leaving)
+        .ok_or_else(|| eyre!("there should be another Adult"))?;
+    let cmds = dispatcher
+        .process_cmd(
+            Cmd::HandleMsg {
+                sender: other_adult.peer(),
+                wire_msg: node_leaving(other_adult)?,
+                original_bytes: None,
+            },
+            "cmd-id",
+        )
+        .await?;
+    assert!(cmds.is_empty());
+
+    // but our Elders can
+    let cmds = dispatcher
+        .process_cmd(
+            Cmd::HandleMsg {
+                sender: nodes[0].peer(),
+                wire_msg: node_leaving(&nodes[0])?,
+                original_bytes: None,
+            },
+            "cmd-id",
+        )
+        .await?;
+    assert!(!cmds.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn client_write_is_acked_once_enough_adults_stored_it() -> Result<()> {
+    init_test_logger();
+    let _span = tracing::info_span!("client_write_is_acked_once_enough_adults_stored_it").entered();
+
+    let (section_auth, mut nodes, sk_set) = create_section_auth();
+    let (section, section_key_share) = create_section(&sk_set, &section_auth).await?;
+    for _ in 0..10 {
+        let adult = gen_info(MIN_ADULT_AGE, None);
+        let node_state = NodeState::joined(adult.peer(), None);
+        let node_state = section_signed(sk_set.secret_key(), node_state)?;
+        assert!(section.update_member(node_state).await);
+    }
+
+    let (max_capacity, root_storage_dir) = create_test_max_capacity_and_root_storage()?;
+    let node = Node::new(
+        create_comm().await?,
+        nodes.remove(0),
+        section,
+        Some(section_key_share),
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+        UsedSpace::new(max_capacity),
+        root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
+    )
+    .await?;
+
+    // the data is sent to its holders, without the client being acked yet
+    let origin = create_peer(MIN_ADULT_AGE);
+    let msg_id = MsgId::new();
+    let data = ReplicatedData::Chunk(Chunk::new(random_bytes(100)));
+    let mut holders = vec![];
+    for cmd in node
+        .store_data_of_client(data, msg_id, origin.clone())
+        .await?
+    {
+        let (recipients, wire_msg) = match cmd {
+            Cmd::SendMsgDeliveryGroup {
+                recipients,
+                wire_msg,
+                ..
+            } => (recipients, wire_msg),
+            _ => continue,
+        };
+        assert_matches!(
+            wire_msg.into_msg(),
+            Ok(MsgType::System {
+                msg: SystemMsg::NodeCmd(NodeCmd::StoreData { correlation_id, .. }),
+                ..
+            }) => assert_eq!(correlation_id, msg_id)
+        );
+        holders.extend(recipients.into_iter().map(|peer| peer.name()));
+    }
+
+    let min_write_holders = StoragePolicy::default().min_write_holders;
+    assert!(holders.len() >= min_write_holders);
+
+    // it's only acked once enough of them stored it
+    for holder in &holders[..min_write_holders - 1] {
+        assert!(node.handle_data_stored(msg_id, *holder).await?.is_empty());
+    }
+    let cmds = node
+        .handle_data_stored(msg_id, holders[min_write_holders - 1])
+        .await?;
+    assert_matches!(
+        &cmds[..],
+        [Cmd::SendMsg { recipients, wire_msg }] => {
+            assert_eq!(recipients, &vec![origin]);
+            assert_matches!(
+                wire_msg.clone().into_msg(),
+                Ok(MsgType::Service {
+                    msg: ServiceMsg::CmdAck { correlation_id },
+                    ..
+                }) => assert_eq!(correlation_id, msg_id)
+            );
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn data_digests_only_cover_the_data_both_holders_should_hold() -> Result<()> {
+    init_test_logger();
+    let _span =
+        tracing::info_span!("data_digests_only_cover_the_data_both_holders_should_hold").entered();
+
+    let (section_auth, _, sk_set) = create_section_auth();
+    let infos: Vec<_> = iter::repeat_with(|| gen_info(MIN_ADULT_AGE, None))
+        .take(8)
+        .collect();
+    let sender = adult_node(&sk_set, &section_auth, &infos[0], &infos).await?;
+    let receiver = adult_node(&sk_set, &section_auth, &infos[1], &infos).await?;
+    let adults: BTreeSet<_> = infos.iter().map(|info| info.name()).collect();
+
+    // more data both should hold than fits in a single message of addresses, so it's digested
+    let mut shared = vec![];
+    while shared.len() <= 100 {
+        let chunk = Chunk::new(random_bytes(100));
+        let address = ReplicatedDataAddress::Chunk(*chunk.address());
+        let holders = sender.compute_holders(&address, &adults).await?;
+        if holders.contains(&infos[0].name()) && holders.contains(&infos[1].name()) {
+            for node in [&sender, &receiver] {
+                let _ = node
+                    .data_storage
+                    .store(&ReplicatedData::Chunk(chunk.clone()))
+                    .await?;
+            }
+            shared.push(address);
+        }
+    }
+
+    // and data only the receiver should hold
+    let mut receivers_only = 0;
+    while receivers_only < 100 {
+        let chunk = Chunk::new(random_bytes(100));
+        let address = ReplicatedDataAddress::Chunk(*chunk.address());
+        let holders = receiver.compute_holders(&address, &adults).await?;
+        if holders.contains(&infos[1].name()) && !holders.contains(&infos[0].name()) {
+            let _ = receiver
+                .data_storage
+                .store(&ReplicatedData::Chunk(chunk))
+                .await?;
+            receivers_only += 1;
+        }
+    }
+
+    let digest = |cmds: Vec<Cmd>| {
+        cmds.into_iter()
+            .find_map(|cmd| match cmd {
+                Cmd::SignOutgoingSystemMsg {
+                    msg: SystemMsg::NodeCmd(NodeCmd::SyncDataDigest(digest)),
+                    ..
+                } => Some(digest),
+                _ => None,
+            })
+            .ok_or_else(|| eyre!("no data digest sent"))
+    };
+
+    // data only one of them should hold doesn't make their digests differ
+    let cmds = sender
+        .sync_data_with(infos[1].name(), vec![Prefix::default()], &adults)
+        .await?;
+    let cmds = receiver
+        .handle_data_digest(infos[0].name(), digest(cmds)?)
+        .await?;
+    assert!(cmds.is_empty());
+
+    // but missing data they should both hold does
+    receiver.data_storage.remove(&shared[0]).await?;
+    let cmds = sender
+        .sync_data_with(infos[1].name(), vec![Prefix::default()], &adults)
+        .await?;
+    let cmds = receiver
+        .handle_data_digest(infos[0].name(), digest(cmds)?)
+        .await?;
+    assert_matches!(
+        cmds.as_slice(),
+        [Cmd::SignOutgoingSystemMsg {
+            msg: SystemMsg::NodeCmd(NodeCmd::RequestDataDigest(prefixes)),
+            ..
+        }] if prefixes.iter().any(|prefix| prefix.matches(shared[0].name()))
+    );
+
+    Ok(())
+}
+
+// creates an Adult of a section whose Adults are `adults`
+async fn adult_node(
+    sk_set: &SecretKeySet,
+    section_auth: &SectionAuthorityProvider,
+    info: &NodeInfo,
+    adults: &[NodeInfo],
+) -> Result<Node> {
+    let (section, _) = create_section(sk_set, section_auth).await?;
+    for adult in adults {
+        let node_state = NodeState::joined(adult.peer(), None);
+        let node_state = section_signed(sk_set.secret_key(), node_state)?;
+        assert!(section.update_member(node_state).await);
+    }
+
+    let (max_capacity, root_storage_dir) = create_test_max_capacity_and_root_storage()?;
+    let node = Node::new(
+        create_comm().await?,
+        info.clone(),
+        section,
+        None,
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+        UsedSpace::new(max_capacity),
+        root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
+    )
+    .await?;
+    Ok(node)
+}
+
 #[derive(PartialEq)]
 enum UntrustedMessageSource {
     Peer,
@@ -793,6 +1302,8 @@ async fn ae_msg_from_the_future_is_handled() -> Result<()> {
         event_tx,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
 
@@ -906,6 +1417,8 @@ async fn untrusted_ae_msg_errors() -> Result<()> {
         event_tx,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
 
@@ -990,6 +1503,8 @@ async fn relocation(relocated_peer_role: RelocatedPeerRole) -> Result<()> {
         mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
     let dispatcher = Dispatcher::new(node);
@@ -1067,6 +1582,8 @@ async fn message_to_self(dst: MessageDst) -> Result<()> {
         event_tx,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
         genesis_sk_set,
     )
     .await?;
@@ -1183,6 +1700,8 @@ async fn handle_elders_update() -> Result<()> {
         event_tx,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
 
@@ -1299,6 +1818,8 @@ async fn handle_demote_during_split() -> Result<()> {
         event_tx,
         UsedSpace::new(max_capacity),
         root_storage_dir,
+        Some(StorageBackendKind::Memory),
+        None,
     )
     .await?;
 
@@ -1430,6 +1951,8 @@ async fn create_section(
 
     let section =
         NetworkKnowledge::new(*section_chain.root_key(), section_chain, signed_sap, None)?;
+    let storage_policy = section_signed(sk_set.secret_key(), StoragePolicy::default())?;
+    section.set_storage_policy(storage_policy).await?;
 
     for peer in section_auth.elders() {
         let node_state = NodeState::joined(peer.clone(), None);
@@ -6,7 +6,9 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::messaging::system::StoragePolicy;
 use crate::node::{Error, NetworkConfig, Result};
+use crate::{Compression, StorageBackendKind};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeSet,
@@ -33,7 +35,7 @@ const DEFAULT_MAX_CAPACITY: usize = 2 * 1024 * 1024 * 1024;
 #[structopt(global_settings = &[structopt::clap::AppSettings::ColoredHelp])]
 pub struct Config {
     /// The address to be credited when this node farms SafeCoin.
-    /// A hex formatted BLS public key.
+    /// A hex formatted Ed25519 or BLS public key.
     #[structopt(short, long, parse(try_from_str))]
     pub wallet_id: Option<String>,
     /// Upper limit in bytes for allowed network storage on this node.
@@ -80,6 +82,43 @@ pub struct Config {
     /// print node resourse usage to stdout
     #[structopt(long)]
     pub resource_logs: bool,
+    /// Local address to serve the node's admin endpoint at, e.g. `127.0.0.1:9100`.
+    ///
+    /// When set, the node's status is served as JSON at `/status`, and its metrics in Prometheus
+    /// text format at `/metrics`. Disabled by default.
+    #[structopt(long)]
+    pub admin_addr: Option<SocketAddr>,
+    /// Allow the admin endpoint to be served at an address other than a loopback one.
+    ///
+    /// The admin endpoint is not authenticated, so anyone who can reach its address can read
+    /// the node's status and metrics. It's thus refused at any other address unless this is set.
+    #[structopt(long)]
+    pub admin_allow_remote: bool,
+    /// Storage backend to keep the node's data in: file-tree, sled, redb or memory.
+    ///
+    /// If not set, chunks are stored in a file each (file-tree) and Registers in sled dbs.
+    /// The memory backend loses all data when the node stops, and is meant for tests.
+    #[structopt(long)]
+    pub storage_backend: Option<StorageBackendKind>,
+    /// Compression to write chunks to disk with: none, zstd or lz4. Defaults to none.
+    ///
+    /// Chunks which don't get smaller, e.g. encrypted ones, are always written uncompressed.
+    /// Chunks already on disk are read back whatever compression they were written with.
+    /// Only chunks are compressed: Register ops, being small, are always written as they are.
+    #[structopt(long)]
+    pub chunk_compression: Option<Compression>,
+    /// Number of Adults each chunk is replicated to.
+    ///
+    /// This and the other storage policy options are agreed by the whole network when the first
+    /// node starts it. Any other node setting them is refused at join if they differ.
+    #[structopt(long)]
+    pub chunk_copy_count: Option<usize>,
+    /// Number of Adults each register is replicated to.
+    #[structopt(long)]
+    pub register_copy_count: Option<usize>,
+    /// Minimum number of Adults a write has to be sent to before it's acked to the client.
+    #[structopt(long)]
+    pub min_write_holders: Option<usize>,
     /// Delete all data from a previous node running on the same PC
     #[structopt(long)]
     pub clear_data: bool,
@@ -231,6 +270,31 @@ impl Config {
         self.json_logs = config.json_logs;
         self.resource_logs = config.resource_logs;
 
+        if let Some(admin_addr) = config.admin_addr {
+            self.admin_addr = Some(admin_addr);
+        }
+        self.admin_allow_remote = config.admin_allow_remote || self.admin_allow_remote;
+
+        if let Some(storage_backend) = config.storage_backend {
+            self.storage_backend = Some(storage_backend);
+        }
+
+        if let Some(chunk_compression) = config.chunk_compression {
+            self.chunk_compression = Some(chunk_compression);
+        }
+
+        if let Some(chunk_copy_count) = config.chunk_copy_count {
+            self.chunk_copy_count = Some(chunk_copy_count);
+        }
+
+        if let Some(register_copy_count) = config.register_copy_count {
+            self.register_copy_count = Some(register_copy_count);
+        }
+
+        if let Some(min_write_holders) = config.min_write_holders {
+            self.min_write_holders = Some(min_write_holders);
+        }
+
         if config.verbose > 0 {
             self.verbose = config.verbose;
         }
@@ -296,6 +360,61 @@ impl Config {
         self.wallet_id.as_ref()
     }
 
+    /// Local address to serve the node's admin endpoint at, if enabled.
+    /// It must be a loopback address, unless serving it at any other was allowed.
+    pub fn admin_addr(&self) -> Result<Option<SocketAddr>> {
+        match self.admin_addr {
+            Some(addr) if !addr.ip().is_loopback() && !self.admin_allow_remote => {
+                Err(Error::Configuration(format!(
+                    "The admin endpoint can't be served at {}, as it's not a loopback address. \
+                    Set admin-allow-remote to serve it at such an address anyway",
+                    addr
+                )))
+            }
+            admin_addr => Ok(admin_addr),
+        }
+    }
+
+    /// Storage backend to keep the node's data in, if not the default one of each store.
+    pub fn storage_backend(&self) -> Option<StorageBackendKind> {
+        self.storage_backend
+    }
+
+    /// Compression to write chunks to disk with, if any.
+    pub fn chunk_compression(&self) -> Option<Compression> {
+        self.chunk_compression
+    }
+
+    /// Storage policy this node was configured with, if any of its options were set.
+    /// Options not set take their default values. Returns error if the policy is not valid,
+    /// i.e. a count is zero or `min_write_holders` exceeds a copy count.
+    pub fn storage_policy(&self) -> Result<Option<StoragePolicy>> {
+        if self.chunk_copy_count.is_none()
+            && self.register_copy_count.is_none()
+            && self.min_write_holders.is_none()
+        {
+            return Ok(None);
+        }
+
+        let default = StoragePolicy::default();
+        let storage_policy = StoragePolicy {
+            chunk_copy_count: self.chunk_copy_count.unwrap_or(default.chunk_copy_count),
+            register_copy_count: self
+                .register_copy_count
+                .unwrap_or(default.register_copy_count),
+            min_write_holders: self.min_write_holders.unwrap_or(default.min_write_holders),
+        };
+        if !storage_policy.is_valid() {
+            return Err(Error::Configuration(format!(
+                "Invalid storage policy {:?}: copy counts and min-write-holders must be \
+                non-zero, and min-write-holders can't exceed either copy count",
+                storage_policy
+            )));
+        }
+
+        Ok(Some(storage_policy))
+    }
+
     /// Is this the first node in a section?
     pub fn is_first(&self) -> bool {
         self.first
@@ -520,7 +639,46 @@ fn smoke() {
     // NOTE: IF this value is being changed due to a change in the config,
     // the change in config also be handled in Config::merge()
     // and in examples/config_handling.rs
-    let expected_size = 472;
+    let expected_size = 528;
 
     assert_eq!(std::mem::size_of::<Config>(), expected_size);
 }
+
+#[test]
+fn admin_addr_must_be_loopback_unless_remote_allowed() {
+    let mut config = Config {
+        admin_addr: Some(SocketAddr::from(([127, 0, 0, 1], 9100))),
+        ..Default::default()
+    };
+    assert!(config.admin_addr().is_ok());
+
+    config.admin_addr = Some(SocketAddr::from(([10, 0, 0, 1], 9100)));
+    assert!(matches!(config.admin_addr(), Err(Error::Configuration(_))));
+
+    config.admin_allow_remote = true;
+    assert!(matches!(config.admin_addr(), Ok(Some(_))));
+}
+
+#[test]
+fn storage_policy_must_be_valid() {
+    let mut config = Config::default();
+    assert!(matches!(config.storage_policy(), Ok(None)));
+
+    config.chunk_copy_count = Some(6);
+    config.register_copy_count = Some(3);
+    config.min_write_holders = Some(3);
+    assert!(matches!(config.storage_policy(), Ok(Some(_))));
+
+    config.min_write_holders = Some(4);
+    assert!(matches!(
+        config.storage_policy(),
+        Err(Error::Configuration(_))
+    ));
+
+    config.min_write_holders = Some(3);
+    config.chunk_copy_count = Some(0);
+    assert!(matches!(
+        config.storage_policy(),
+        Err(Error::Configuration(_))
+    ));
+}
@@ -19,7 +19,7 @@ use crate::node::{
     Event, NodeInfo,
 };
 use crate::types::{log_markers::LogMarker, Peer};
-use crate::UsedSpace;
+use crate::{Compression, StorageBackendKind, UsedSpace};
 
 use secured_linked_list::SecuredLinkedList;
 use std::{collections::BTreeSet, net::SocketAddr, path::PathBuf};
@@ -28,19 +28,24 @@ use xor_name::XorName;
 
 impl Node {
     // Creates `Core` for the first node in the network
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn first_node(
         comm: Comm,
         mut node: NodeInfo,
         event_tx: mpsc::Sender<Event>,
         used_space: UsedSpace,
         root_storage_dir: PathBuf,
+        storage_backend: Option<StorageBackendKind>,
+        chunk_compression: Option<Compression>,
         genesis_sk_set: bls::SecretKeySet,
     ) -> Result<Self> {
         // make sure the Node has the correct local addr as Comm
         node.addr = comm.our_connection_info();
 
+        // the genesis node sets the storage policy for the whole network
+        let storage_policy = node.storage_policy.unwrap_or_default();
         let (section, section_key_share) =
-            NetworkKnowledge::first_node(node.peer(), genesis_sk_set).await?;
+            NetworkKnowledge::first_node(node.peer(), genesis_sk_set, storage_policy).await?;
         Self::new(
             comm,
             node,
@@ -49,6 +54,8 @@ impl Node {
             event_tx,
             used_space,
             root_storage_dir,
+            storage_backend,
+            chunk_compression,
         )
         .await
     }
@@ -89,6 +96,11 @@ impl Node {
         !self.is_elder().await
     }
 
+    /// Ratio of the used space to the max capacity of our storage
+    pub(crate) fn used_space_ratio(&self) -> f64 {
+        self.data_storage.used_space_ratio()
+    }
+
     /// Returns connection info of this node.
     pub(crate) fn our_connection_info(&self) -> SocketAddr {
         self.comm.our_connection_info()
@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{read_prefix_map_from_disk, UsedRecipientSaps};
+use super::UsedRecipientSaps;
 use crate::messaging::{
     signature_aggregator::{Error as AggregatorError, SignatureAggregator},
     system::{
@@ -37,7 +37,8 @@ use xor_name::Prefix;
 // arbitrarily long. No join in a non splitting section should fail to get signature shares in anything like a few minutes
 const JOIN_SHARE_EXPIRATION_DURATION: Duration = Duration::from_secs(900);
 
-/// Join the network as new node.
+/// Join the network as new node. The prefix map known from a previous run of the node,
+/// if any, is used to target the section to join directly.
 ///
 /// NOTE: It's not guaranteed this function ever returns. This can happen due to messages being
 /// lost in transit or other reasons. It's the responsibility of the caller to handle this case,
@@ -48,13 +49,14 @@ pub(crate) async fn join_network(
     incoming_conns: &mut mpsc::Receiver<ConnectionEvent>,
     bootstrap_addr: SocketAddr,
     genesis_key: BlsPublicKey,
+    known_prefix_map: Option<NetworkPrefixMap>,
 ) -> Result<(NodeInfo, NetworkKnowledge)> {
     let (send_tx, send_rx) = mpsc::channel(1);
 
     let span = trace_span!("bootstrap");
 
-    // Read prefix map from cache if available
-    let prefix_map = read_prefix_map_from_disk(genesis_key).await?;
+    // Otherwise start from a fresh one, it's updated as we're redirected to our section
+    let prefix_map = known_prefix_map.unwrap_or_else(|| NetworkPrefixMap::new(genesis_key));
 
     let state = Join::new(node, send_tx, incoming_conns, prefix_map);
 
@@ -149,6 +151,8 @@ impl<'a> Join<'a> {
             section_key,
             resource_proof_response: None,
             aggregated: None,
+            reward_key: self.node.reward_key,
+            storage_policy: self.node.storage_policy,
         };
 
         self.send_join_requests(join_request.clone(), &recipients, section_key, false)
@@ -171,11 +175,19 @@ impl<'a> Join<'a> {
                     error!("Network is set to not taking any new joining node, try join later.");
                     return Err(Error::TryJoinLater);
                 }
+                JoinResponse::Rejected(JoinRejectionReason::StoragePolicyMismatch(policy)) => {
+                    error!(
+                        "Node cannot join the network since its storage policy {:?} differs from the network's: {:?}",
+                        self.node.storage_policy, policy
+                    );
+                    return Err(Error::StoragePolicyMismatch(policy));
+                }
                 JoinResponse::Approval {
                     section_auth,
                     genesis_key,
                     section_chain,
                     node_state,
+                    storage_policy,
                 } => {
                     info!("{}", LogMarker::ReceivedJoinApproval);
                     if node_state.name != self.node.name() {
@@ -205,6 +217,19 @@ impl<'a> Join<'a> {
                         section_auth,
                         Some(self.prefix_map),
                     )?;
+                    network_knowledge
+                        .set_storage_policy(storage_policy.clone())
+                        .await?;
+
+                    if let Some(ours) = self.node.storage_policy {
+                        if ours != storage_policy.value {
+                            error!(
+                                "Node cannot join the network since its storage policy {:?} differs from the network's: {:?}",
+                                ours, storage_policy.value
+                            );
+                            return Err(Error::StoragePolicyMismatch(storage_policy.value));
+                        }
+                    }
 
                     return Ok((self.node, network_knowledge));
                 }
@@ -272,6 +297,8 @@ impl<'a> Join<'a> {
                                 section_key,
                                 resource_proof_response: None,
                                 aggregated: Some(auth),
+                                reward_key: self.node.reward_key,
+                                storage_policy: self.node.storage_policy,
                             };
                             let name = self.node.name();
                             let recipients: Vec<Peer> = if let Some(signed_sap) =
@@ -301,6 +328,8 @@ impl<'a> Join<'a> {
                                     section_key,
                                     resource_proof_response: None,
                                     aggregated: None,
+                                    reward_key: self.node.reward_key,
+                                    storage_policy: self.node.storage_policy,
                                 };
 
                                 self.send_join_requests(
@@ -389,7 +418,9 @@ impl<'a> Join<'a> {
                         let new_name = ed25519::name(&new_keypair.public);
 
                         info!("Setting Node name to {} (age {})", new_name, expected_age);
-                        self.node = NodeInfo::new(new_keypair, self.node.addr);
+                        self.node = NodeInfo::new(new_keypair, self.node.addr)
+                            .with_reward_key(self.node.reward_key)
+                            .with_storage_policy(self.node.storage_policy);
                     } else if !is_new_sap {
                         debug!("Ignoring JoinResponse::Retry with same SAP as we previously sent to: {:?}", section_auth);
                         continue;
@@ -407,6 +438,8 @@ impl<'a> Join<'a> {
                         section_key,
                         resource_proof_response: None,
                         aggregated: None,
+                        reward_key: self.node.reward_key,
+                        storage_policy: self.node.storage_policy,
                     };
 
                     let new_recipients = section_auth.elders_vec();
@@ -463,6 +496,8 @@ impl<'a> Join<'a> {
                         section_key,
                         resource_proof_response: None,
                         aggregated: None,
+                        reward_key: self.node.reward_key,
+                        storage_policy: self.node.storage_policy,
                     };
 
                     self.send_join_requests(join_request, &new_recipients, section_key, true)
@@ -489,6 +524,8 @@ impl<'a> Join<'a> {
                             nonce_signature,
                         }),
                         aggregated: None,
+                        reward_key: self.node.reward_key,
+                        storage_policy: self.node.storage_policy,
                     };
                     let recipients = &[sender];
                     self.send_join_requests(join_request, recipients, section_key, false)
@@ -613,7 +650,9 @@ async fn send_messages(mut rx: mpsc::Receiver<(WireMsg, Vec<Peer>)>, comm: &Comm
 mod tests {
     use super::*;
 
-    use crate::messaging::SectionAuthorityProvider as SectionAuthorityProviderMsg;
+    use crate::messaging::{
+        system::StoragePolicy, SectionAuthorityProvider as SectionAuthorityProviderMsg,
+    };
     use crate::node::{
         dkg::test_utils::*,
         messages::WireMsgUtils,
@@ -726,6 +765,7 @@ mod tests {
                     section_auth: section_auth.clone().into_authed_msg(),
                     node_state: node_state.into_authed_msg(),
                     section_chain: proof_chain,
+                    storage_policy: section_signed(sk, StoragePolicy::default())?,
                 })),
                 &bootstrap_node,
                 section_auth.section_key(),
@@ -739,6 +779,7 @@ mod tests {
 
         assert_eq!(section.authority_provider().await, section_auth);
         assert_eq!(section.section_key().await, section_key);
+        assert_eq!(section.storage_policy().await?, StoragePolicy::default());
         assert_eq!(node.age(), node_age);
 
         Ok(())
@@ -988,6 +1029,69 @@ mod tests {
         test_result
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_storage_policy_mismatch_response() -> Result<()> {
+        let (send_tx, mut send_rx) = mpsc::channel(1);
+        let (recv_tx, mut recv_rx) = mpsc::channel(1);
+
+        let (section_auth, mut nodes, sk_set) =
+            gen_section_authority_provider(Prefix::default(), elder_count());
+        let bootstrap_node = nodes.remove(0);
+
+        let network_policy = StoragePolicy::default();
+        let our_policy = StoragePolicy {
+            chunk_copy_count: network_policy.chunk_copy_count + 1,
+            ..network_policy
+        };
+        let node = NodeInfo::new(
+            ed25519::gen_keypair(&Prefix::default().range_inclusive(), MIN_ADULT_AGE),
+            gen_addr(),
+        )
+        .with_storage_policy(Some(our_policy));
+
+        let section_key = sk_set.secret_key().public_key();
+        let state = Join::new(
+            node,
+            send_tx,
+            &mut recv_rx,
+            NetworkPrefixMap::new(section_key),
+        );
+
+        let bootstrap_task = state.run(bootstrap_node.addr);
+        let test_task = async {
+            let (wire_msg, _) = send_rx
+                .recv()
+                .await
+                .ok_or_else(|| eyre!("JoinRequest was not received"))?;
+
+            assert_matches!(wire_msg.into_msg(), Ok(MsgType::System { msg, .. }) =>
+            assert_matches!(msg, SystemMsg::JoinRequest(request) => {
+                assert_eq!(request.storage_policy, Some(our_policy));
+            }));
+
+            send_response(
+                &recv_tx,
+                SystemMsg::JoinResponse(Box::new(JoinResponse::Rejected(
+                    JoinRejectionReason::StoragePolicyMismatch(network_policy),
+                ))),
+                &bootstrap_node,
+                section_auth.section_key(),
+            )?;
+
+            Ok(())
+        };
+
+        let (join_result, test_result) = future::join(bootstrap_task, test_task).await;
+
+        if let Err(RoutingError::StoragePolicyMismatch(policy)) = join_result {
+            assert_eq!(policy, network_policy);
+        } else {
+            return Err(eyre!("Not getting an expected storage policy rejection."));
+        }
+
+        test_result
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn join_invalid_retry_prefix_response() -> Result<()> {
         init_test_logger();
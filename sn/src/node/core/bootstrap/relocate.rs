@@ -205,7 +205,8 @@ impl JoiningAsRelocated {
         let signature_over_new_name = ed25519::sign(&new_name.0, &self.old_keypair);
 
         info!("Changing name to {}", new_name);
-        self.node = NodeInfo::new(new_keypair, self.node.addr);
+        self.node =
+            NodeInfo::new(new_keypair, self.node.addr).with_reward_key(self.node.reward_key);
 
         signature_over_new_name
     }
@@ -98,6 +98,21 @@ impl BackPressure {
             .insert(addr, (Instant::now(), cfg));
     }
 
+    /// The latest load we've evaluated, if any caller has been checked yet.
+    pub(crate) async fn our_load(&self) -> Option<LoadReport> {
+        self.our_reports
+            .read()
+            .await
+            .values()
+            .max_by_key(|(at, _)| *at)
+            .map(|(_, load)| *load)
+    }
+
+    /// Number of nodes we are regulating our comms with.
+    pub(crate) async fn regulated_peers(&self) -> usize {
+        self.reports.read().await.len()
+    }
+
     /// Sent to nodes calling us, if we are strained
     pub(crate) async fn load_report(&self, caller: &SocketAddr) -> Option<LoadReport> {
         let now = Instant::now();
@@ -11,6 +11,7 @@ mod msg_count;
 
 use back_pressure::BackPressure;
 use msg_count::MsgCount;
+pub(crate) use msg_count::MsgStats;
 
 use crate::messaging::{system::LoadReport, MsgId, WireMsg};
 use crate::node::error::{Error, Result};
@@ -170,14 +171,19 @@ impl Comm {
 
         let bytes = wire_msg.serialize()?;
         // TODO: rework priority so this we dont need to deserialise payload to determine priority.
-        let priority = wire_msg.into_msg()?.priority();
+        let msg = wire_msg.into_msg()?;
+        let priority = msg.priority();
 
         let (_, result) = self
             .send_to_one(recipient, wire_msg.msg_id(), priority, bytes)
             .await;
 
         match result {
-            Ok(_) => self.msg_count.increase_outgoing(addr), // count outgoing msgs..
+            Ok(_) => {
+                // count outgoing msgs..
+                self.msg_count.increase_outgoing(addr);
+                self.msg_count.increase_outgoing_kind(msg.kind());
+            }
             Err(err) => {
                 error!(
                     "Sending message (msg_id: {:?}) to {:?} (name {:?}) failed with {:?}",
@@ -267,7 +273,9 @@ impl Comm {
         }
 
         let msg_bytes = wire_msg.serialize().map_err(Error::Messaging)?;
-        let priority = wire_msg.clone().into_msg()?.priority();
+        let msg = wire_msg.clone().into_msg()?;
+        let priority = msg.priority();
+        let kind = msg.kind();
 
         // Run all the sends concurrently (using `FuturesUnordered`). If any of them fails, pick
         // the next recipient and try to send to them. Proceed until the needed number of sends
@@ -287,6 +295,7 @@ impl Comm {
                     successes += 1;
                     // count outgoing msgs..
                     self.msg_count.increase_outgoing(recipient.addr());
+                    self.msg_count.increase_outgoing_kind(kind);
                 }
                 Err(error) if error.is_local_close() => {
                     // The connection was closed by us which means
@@ -398,6 +407,31 @@ impl Comm {
         self.back_pressure.load_report(caller).await
     }
 
+    /// Counts an incoming msg of the given kind, once it's been deserialised.
+    pub(crate) fn count_incoming_kind(&self, kind: &'static str) {
+        self.msg_count.increase_incoming_kind(kind)
+    }
+
+    /// Snapshot of the msg counters, for the node's metrics.
+    pub(crate) fn msg_stats(&self) -> MsgStats {
+        MsgStats {
+            incoming_total: self.msg_count.incoming().total,
+            outgoing_total: self.msg_count.outgoing().total,
+            incoming: self.msg_count.incoming_kinds(),
+            outgoing: self.msg_count.outgoing_kinds(),
+        }
+    }
+
+    /// The latest cpu load we've evaluated for callers, if any.
+    pub(crate) async fn our_load(&self) -> Option<LoadReport> {
+        self.back_pressure.our_load().await
+    }
+
+    /// Number of peers our comms are being regulated with, as per their load reports.
+    pub(crate) async fn regulated_peers(&self) -> usize {
+        self.back_pressure.regulated_peers().await
+    }
+
     pub(crate) fn print_stats(&self) {
         let incoming = self.msg_count.incoming();
         let outgoing = self.msg_count.outgoing();
@@ -7,14 +7,17 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use dashmap::DashMap;
-use std::{net::SocketAddr, sync::Arc};
+use std::{collections::BTreeMap, net::SocketAddr, sync::Arc};
 
 type MsgRegistry = DashMap<SocketAddr, usize>;
+type MsgKindRegistry = DashMap<&'static str, usize>;
 
 #[derive(Clone, Debug)]
 pub(super) struct MsgCount {
     incoming: Arc<MsgRegistry>,
     outgoing: Arc<MsgRegistry>,
+    incoming_kinds: Arc<MsgKindRegistry>,
+    outgoing_kinds: Arc<MsgKindRegistry>,
 }
 
 #[derive(Debug)]
@@ -26,14 +29,42 @@ pub(super) struct MsgNumbers {
     pub(super) min_node: Option<(SocketAddr, usize)>,
 }
 
+/// Snapshot of the msg counters: totals, and number of msgs of each kind.
+#[derive(Debug)]
+pub(crate) struct MsgStats {
+    pub(crate) incoming_total: usize,
+    pub(crate) outgoing_total: usize,
+    pub(crate) incoming: BTreeMap<&'static str, usize>,
+    pub(crate) outgoing: BTreeMap<&'static str, usize>,
+}
+
 impl MsgCount {
     pub(super) fn new() -> Self {
         Self {
             incoming: Arc::new(MsgRegistry::new()),
             outgoing: Arc::new(MsgRegistry::new()),
+            incoming_kinds: Arc::new(MsgKindRegistry::new()),
+            outgoing_kinds: Arc::new(MsgKindRegistry::new()),
         }
     }
 
+    /// Number of incoming msgs of each kind
+    pub(super) fn incoming_kinds(&self) -> BTreeMap<&'static str, usize> {
+        Self::get_kinds(&self.incoming_kinds)
+    }
+
+    /// Number of outgoing msgs of each kind
+    pub(super) fn outgoing_kinds(&self) -> BTreeMap<&'static str, usize> {
+        Self::get_kinds(&self.outgoing_kinds)
+    }
+
+    fn get_kinds(registry: &MsgKindRegistry) -> BTreeMap<&'static str, usize> {
+        registry
+            .iter()
+            .map(|pair| (*pair.key(), *pair.value()))
+            .collect()
+    }
+
     /// Numbers for incoming msgs
     pub(super) fn incoming(&self) -> MsgNumbers {
         Self::get(&self.incoming)
@@ -80,4 +111,12 @@ impl MsgCount {
     pub(super) fn increase_outgoing(&self, recipient: SocketAddr) {
         *self.outgoing.entry(recipient).or_insert(0) += 1;
     }
+
+    pub(super) fn increase_incoming_kind(&self, kind: &'static str) {
+        *self.incoming_kinds.entry(kind).or_insert(0) += 1;
+    }
+
+    pub(super) fn increase_outgoing_kind(&self, kind: &'static str) {
+        *self.outgoing_kinds.entry(kind).or_insert(0) += 1;
+    }
 }
@@ -6,11 +6,16 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::messaging::{
+    system::{NodeEvent, SystemMsg},
+    DstLocation,
+};
 use crate::node::{
     api::cmds::Cmd,
     core::{Node, Proposal},
     Result,
 };
+use crate::types::log_markers::LogMarker;
 use std::{collections::BTreeSet, iter, net::SocketAddr};
 use xor_name::XorName;
 
@@ -34,6 +39,42 @@ impl Node {
         Ok(cmds)
     }
 
+    /// Handles a member of our section telling us it's leaving the network. It's proposed `Left`
+    /// straight away, instead of waiting for it to be found unresponsive, and the remaining Adults
+    /// are told to replicate the data they held along with it, as we don't hold any ourselves.
+    pub(crate) async fn handle_leaving_node(&self, name: XorName) -> Result<Vec<Cmd>> {
+        if name == self.info.read().await.name() {
+            // We're the one leaving, nothing to do.
+            return Ok(vec![]);
+        }
+        if !self.network_knowledge.is_section_member(&name).await {
+            debug!("Ignoring Leave msg from {}, which is not a member", name);
+            return Ok(vec![]);
+        }
+
+        info!("{} {}", LogMarker::LeaveReceivedAtElder, name);
+
+        let section_pk = self.network_knowledge.section_key().await;
+        let mut cmds: Vec<_> = self
+            .network_knowledge
+            .adults()
+            .await
+            .iter()
+            .map(|peer| peer.name())
+            .filter(|adult| *adult != name)
+            .map(|adult| Cmd::SignOutgoingSystemMsg {
+                msg: SystemMsg::NodeEvent(NodeEvent::NodeLeaving(name)),
+                dst: DstLocation::Node {
+                    name: adult,
+                    section_pk,
+                },
+            })
+            .collect();
+        cmds.extend(self.propose_offline(name).await?);
+
+        Ok(cmds)
+    }
+
     pub(crate) async fn propose_offline(&self, name: XorName) -> Result<Vec<Cmd>> {
         self.cast_offline_proposals(&iter::once(name).collect())
             .await
@@ -9,5 +9,7 @@
 mod records;
 mod storage;
 
-pub(crate) use self::records::{Capacity, Liveness, MIN_LEVEL_WHEN_FULL};
+pub(crate) use self::records::{
+    Capacity, HotChunks, Liveness, PendingWrites, Rewards, MIN_LEVEL_WHEN_FULL,
+};
 pub(crate) use self::storage::DataStorage;
@@ -0,0 +1,199 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::types::{Cache, Chunk};
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+use xor_name::XorName;
+
+/// Number of queries a chunk gets within a `QUERY_RATE_WINDOW` for it to be hot.
+pub(crate) const HOT_CHUNK_QUERY_THRESHOLD: usize = 20;
+/// Number of replicas a hot chunk gets on top of its regular ones.
+pub(crate) const EXTRA_REPLICAS_OF_HOT_CHUNKS: usize = 2;
+
+// The window the query rate of a chunk is measured over.
+const QUERY_RATE_WINDOW: Duration = Duration::from_secs(60);
+// Max number of chunks whose query rate is tracked, dropping the least recently queried first.
+const MAX_TRACKED_CHUNKS: usize = 10_000;
+// Max number of hot chunks kept in memory, dropping the oldest first.
+const HOT_CHUNK_CACHE_CAPACITY: usize = 100;
+// How long a hot chunk is served from memory before it's fetched from the Adults again.
+const HOT_CHUNK_CACHE_DURATION: Duration = Duration::from_secs(5 * 60);
+// How long the extra replicas of a chunk are kept after it was last hot.
+const EXTRA_REPLICAS_DURATION: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Clone, Copy, Debug)]
+struct QueryRate {
+    window_start: Instant,
+    count: usize,
+}
+
+#[derive(Debug)]
+struct ExtraReplicas {
+    holders: BTreeSet<XorName>,
+    last_hot: Instant,
+}
+
+/// A util for tracking how often the chunks
+/// in the section are queried, keeping the
+/// hot ones in memory, and keeping track of
+/// the extra replicas they were given.
+#[derive(Clone)]
+pub(crate) struct HotChunks {
+    query_rates: Arc<Cache<XorName, QueryRate>>,
+    cache: Arc<Cache<XorName, Chunk>>,
+    extra_replicas: Arc<RwLock<BTreeMap<XorName, ExtraReplicas>>>,
+}
+
+impl Default for HotChunks {
+    fn default() -> Self {
+        Self {
+            query_rates: Arc::new(Cache::with_capacity(MAX_TRACKED_CHUNKS)),
+            cache: Arc::new(Cache::with_expiry_duration_and_capacity(
+                HOT_CHUNK_CACHE_DURATION,
+                HOT_CHUNK_CACHE_CAPACITY,
+            )),
+            extra_replicas: Arc::new(RwLock::new(BTreeMap::new())),
+        }
+    }
+}
+
+impl HotChunks {
+    /// Records a query for the chunk, returning whether the chunk is hot.
+    pub(super) async fn record_query(&self, name: XorName) -> bool {
+        let rate = match self.query_rates.get(&name).await {
+            Some(rate) if rate.window_start.elapsed() < QUERY_RATE_WINDOW => QueryRate {
+                count: rate.count.saturating_add(1),
+                ..rate
+            },
+            _ => QueryRate {
+                window_start: Instant::now(),
+                count: 1,
+            },
+        };
+        let _prev = self.query_rates.set(name, rate, None).await;
+
+        let hot = rate.count >= HOT_CHUNK_QUERY_THRESHOLD;
+        if hot {
+            if let Some(extra) = self.extra_replicas.write().await.get_mut(&name) {
+                extra.last_hot = Instant::now();
+            }
+        }
+        hot
+    }
+
+    /// Whether the chunk is currently queried at a hot rate.
+    pub(super) async fn is_hot(&self, name: &XorName) -> bool {
+        match self.query_rates.get(name).await {
+            Some(rate) => {
+                rate.window_start.elapsed() < QUERY_RATE_WINDOW
+                    && rate.count >= HOT_CHUNK_QUERY_THRESHOLD
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the chunk if it's kept in memory.
+    pub(super) async fn get(&self, name: &XorName) -> Option<Chunk> {
+        self.cache.get(name).await
+    }
+
+    /// Keeps the chunk in memory.
+    pub(super) async fn keep(&self, chunk: Chunk) {
+        let _prev = self.cache.set(*chunk.name(), chunk, None).await;
+    }
+
+    /// The Adults holding extra replicas of the chunk, if it was given any.
+    pub(super) async fn extra_holders(&self, name: &XorName) -> Option<BTreeSet<XorName>> {
+        self.extra_replicas
+            .read()
+            .await
+            .get(name)
+            .map(|extra| extra.holders.clone())
+    }
+
+    /// Records the Adults the extra replicas of the chunk were given to.
+    pub(super) async fn set_extra_holders(&self, name: XorName, holders: BTreeSet<XorName>) {
+        let _prev = self.extra_replicas.write().await.insert(
+            name,
+            ExtraReplicas {
+                holders,
+                last_hot: Instant::now(),
+            },
+        );
+    }
+
+    /// Stops tracking the extra replicas of the chunks which haven't been hot for a while,
+    /// returning their holders.
+    pub(super) async fn take_cold_extra_replicas(&self) -> BTreeMap<XorName, BTreeSet<XorName>> {
+        let mut extra_replicas = self.extra_replicas.write().await;
+        let cold: Vec<XorName> = extra_replicas
+            .iter()
+            .filter(|(_, extra)| extra.last_hot.elapsed() >= EXTRA_REPLICAS_DURATION)
+            .map(|(name, _)| *name)
+            .collect();
+
+        cold.into_iter()
+            .filter_map(|name| {
+                extra_replicas
+                    .remove(&name)
+                    .map(|extra| (name, extra.holders))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HotChunks, HOT_CHUNK_QUERY_THRESHOLD};
+    use crate::types::{utils::random_bytes, Chunk};
+    use xor_name::XorName;
+
+    #[tokio::test]
+    async fn chunk_gets_hot_once_queried_often() {
+        let hot_chunks = HotChunks::default();
+        let name = XorName::random();
+
+        for _ in 1..HOT_CHUNK_QUERY_THRESHOLD {
+            assert!(!hot_chunks.record_query(name).await);
+        }
+        assert!(!hot_chunks.is_hot(&name).await);
+
+        assert!(hot_chunks.record_query(name).await);
+        assert!(hot_chunks.is_hot(&name).await);
+        assert!(!hot_chunks.is_hot(&XorName::random()).await);
+    }
+
+    #[tokio::test]
+    async fn hot_chunks_are_kept_in_memory() {
+        let hot_chunks = HotChunks::default();
+        let chunk = Chunk::new(random_bytes(100));
+
+        assert_eq!(hot_chunks.get(chunk.name()).await, None);
+        hot_chunks.keep(chunk.clone()).await;
+        assert_eq!(hot_chunks.get(chunk.name()).await, Some(chunk));
+    }
+
+    #[tokio::test]
+    async fn extra_replicas_are_kept_while_hot() {
+        let hot_chunks = HotChunks::default();
+        let name = XorName::random();
+        let holders = [XorName::random(), XorName::random()].into_iter().collect();
+
+        hot_chunks.set_extra_holders(name, holders).await;
+        assert!(hot_chunks.extra_holders(&name).await.is_some());
+        // just replicated, so not cold yet
+        assert!(hot_chunks.take_cold_extra_replicas().await.is_empty());
+        assert!(hot_chunks.extra_holders(&name).await.is_some());
+    }
+}
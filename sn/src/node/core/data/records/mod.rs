@@ -7,29 +7,46 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 mod capacity;
+mod hot_chunks;
 mod liveness_tracking;
+mod pending_writes;
+mod rewards;
 
 pub(crate) use self::capacity::{Capacity, MIN_LEVEL_WHEN_FULL};
+pub(crate) use self::hot_chunks::HotChunks;
 pub(crate) use self::liveness_tracking::Liveness;
+pub(crate) use self::pending_writes::PendingWrites;
+pub(crate) use self::rewards::Rewards;
+
+use self::hot_chunks::EXTRA_REPLICAS_OF_HOT_CHUNKS;
 
 use crate::{
-    data_copy_count,
     messaging::{
-        data::{CmdError, DataQuery, MetadataExchange, StorageLevel},
-        system::{NodeCmd, NodeQuery, SystemMsg},
+        data::{
+            CmdError, DataQuery, Error as ErrorMsg, MetadataExchange, QueryResponse, RewardLedger,
+            RewardPayout, StorageLevel,
+        },
+        system::{NodeCmd, NodeEvent, NodeQuery, SystemMsg},
         AuthorityProof, DstLocation, EndUser, MsgId, ServiceAuth, WireMsg,
     },
     node::{
-        core::{Cmd, Node, Prefix, MAX_WAITING_PEERS_PER_QUERY},
+        core::{Cmd, Node, Prefix, Proposal, MAX_WAITING_PEERS_PER_QUERY},
         error::convert_to_error_msg,
         messages::WireMsgUtils,
         Error, Result,
     },
-    types::{log_markers::LogMarker, Peer, PublicKey, ReplicatedData, ReplicatedDataAddress},
+    types::{
+        log_markers::LogMarker, Chunk, ChunkAddress, Peer, PublicKey, ReplicatedData,
+        ReplicatedDataAddress,
+    },
 };
 
 use itertools::Itertools;
-use std::{cmp::Ordering, collections::BTreeSet};
+use rand::seq::IteratorRandom;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+};
 use tracing::info;
 use xor_name::XorName;
 
@@ -38,7 +55,9 @@ impl Node {
     pub(crate) async fn replicate_data(&self, data: ReplicatedData) -> Result<Vec<Cmd>> {
         trace!("{:?}: {:?}", LogMarker::DataStoreReceivedAtElder, data);
         if self.is_elder().await {
-            let targets = self.get_adults_who_should_store_data(data.name()).await;
+            let targets = self
+                .get_adults_who_should_store_data(&data.address())
+                .await?;
 
             info!(
                 "Replicating data {:?} to holders {:?}",
@@ -53,6 +72,119 @@ impl Node {
         }
     }
 
+    /// Sends the data of a client's write to the Adults which should store it, and waits for
+    /// the minimum number of holders of the storage policy to ack storing it before acking the
+    /// write to the client.
+    pub(crate) async fn store_data_of_client(
+        &self,
+        data: ReplicatedData,
+        msg_id: MsgId,
+        origin: Peer,
+    ) -> Result<Vec<Cmd>> {
+        trace!("{:?}: {:?}", LogMarker::DataStoreReceivedAtElder, data);
+        let targets = self
+            .get_adults_who_should_store_data(&data.address())
+            .await?;
+        let min_write_holders = self
+            .network_knowledge()
+            .storage_policy()
+            .await?
+            .min_write_holders;
+        if targets.len() < min_write_holders {
+            error!("InsufficientAdults for storing data reliably");
+            let error = CmdError::Data(ErrorMsg::InsufficientAdults {
+                prefix: self.network_knowledge().prefix().await,
+                expected: u8::try_from(min_write_holders).unwrap_or(u8::MAX),
+                found: u8::try_from(targets.len()).unwrap_or(u8::MAX),
+            });
+            return self.send_cmd_error_response(error, origin, msg_id).await;
+        }
+
+        info!(
+            "Storing data {:?} of write {:?} at holders {:?}",
+            data.name(),
+            msg_id,
+            &targets,
+        );
+        self.pending_writes
+            .add(msg_id, origin, targets.clone(), min_write_holders)
+            .await;
+        let msg = SystemMsg::NodeCmd(NodeCmd::StoreData {
+            data,
+            correlation_id: msg_id,
+        });
+        self.send_node_msg_to_nodes(msg, targets).await
+    }
+
+    /// Handles an Adult acking it stored the data of a client's write,
+    /// acking the write to the client once enough of them have.
+    pub(crate) async fn handle_data_stored(
+        &self,
+        correlation_id: MsgId,
+        adult: XorName,
+    ) -> Result<Vec<Cmd>> {
+        match self.pending_writes.ack(correlation_id, adult).await {
+            Some(origin) => {
+                trace!(
+                    "Enough Adults stored the data of write {:?}",
+                    correlation_id
+                );
+                self.send_cmd_ack(origin, correlation_id).await
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    // Tells the Adult which removed its corrupt replicas of the data where to fetch fresh ones from,
+    // i.e. the closest other Adult holding each of them
+    pub(crate) async fn handle_corrupt_data_report(
+        &self,
+        reporter: XorName,
+        data_addresses: Vec<ReplicatedDataAddress>,
+    ) -> Result<Vec<Cmd>> {
+        trace!(
+            "{:?} from {:?}: {:?}",
+            LogMarker::CorruptDataReportReceivedAtElder,
+            reporter,
+            data_addresses
+        );
+
+        let mut fetch_list: BTreeMap<XorName, Vec<ReplicatedDataAddress>> = BTreeMap::new();
+        for address in data_addresses {
+            let holder = self
+                .get_adults_holding_data(&address)
+                .await?
+                .into_iter()
+                .filter(|holder| *holder != reporter)
+                .sorted_by(|lhs, rhs| address.name().cmp_distance(lhs, rhs))
+                .next();
+            match holder {
+                Some(holder) => fetch_list.entry(holder).or_default().push(address),
+                None => warn!(
+                    "No other holder of corrupt data {:?} to fetch it from",
+                    address
+                ),
+            }
+        }
+
+        let section_pk = self.network_knowledge().section_key().await;
+        let cmds = fetch_list
+            .into_iter()
+            .map(|(holder, data_addresses)| Cmd::SignOutgoingSystemMsg {
+                msg: SystemMsg::NodeCmd(NodeCmd::FetchReplicateDataFrom {
+                    holder,
+                    data_addresses,
+                }),
+                dst: DstLocation::Node {
+                    name: reporter,
+                    section_pk,
+                },
+            })
+            .collect();
+
+        Ok(cmds)
+    }
+
     pub(crate) async fn read_data_from_adults(
         &self,
         query: DataQuery,
@@ -69,7 +201,29 @@ impl Node {
             operation_id
         );
 
-        let targets = self.get_adults_holding_data(address.name()).await;
+        let mut targets = self.get_adults_holding_data(&address).await?;
+
+        if let DataQuery::GetChunk(chunk_address) = &query {
+            if self.hot_chunks.record_query(*chunk_address.name()).await {
+                if let Some(chunk) = self.hot_chunks.get(chunk_address.name()).await {
+                    trace!("{:?} {:?}", LogMarker::HotChunkServedAtElder, chunk_address);
+                    let response = QueryResponse::GetChunk(Ok(chunk));
+                    return self.send_query_response(response, origin, msg_id).await;
+                }
+                // spread the queries of the hot chunk over its extra replicas as well
+                if let Some(extra_holders) =
+                    self.hot_chunks.extra_holders(chunk_address.name()).await
+                {
+                    let count = targets.len();
+                    targets = targets
+                        .union(&extra_holders)
+                        .copied()
+                        .choose_multiple(&mut rand::thread_rng(), count)
+                        .into_iter()
+                        .collect();
+                }
+            }
+        }
 
         if targets.is_empty() {
             let error =
@@ -129,23 +283,124 @@ impl Node {
         self.send_node_msg_to_nodes(msg, targets).await
     }
 
+    /// Keeps the chunk an Adult returned in memory if it's hot, giving it extra replicas the
+    /// first time it is.
+    pub(crate) async fn record_chunk_served(&self, chunk: &Chunk) -> Result<Vec<Cmd>> {
+        if !self.hot_chunks.is_hot(chunk.name()).await {
+            return Ok(vec![]);
+        }
+        self.hot_chunks.keep(chunk.clone()).await;
+
+        if self.hot_chunks.extra_holders(chunk.name()).await.is_some() {
+            return Ok(vec![]);
+        }
+
+        // the closest Adults to the chunk after its regular holders
+        let name = *chunk.name();
+        let holders = self
+            .get_adults_who_should_store_data(&ReplicatedDataAddress::Chunk(*chunk.address()))
+            .await?;
+        let full_adults = self.full_adults().await;
+        let extra_holders: BTreeSet<XorName> = self
+            .network_knowledge()
+            .adults()
+            .await
+            .iter()
+            .map(|peer| peer.name())
+            .filter(|adult| !holders.contains(adult) && !full_adults.contains(adult))
+            .sorted_by(|lhs, rhs| name.cmp_distance(lhs, rhs))
+            .take(EXTRA_REPLICAS_OF_HOT_CHUNKS)
+            .collect();
+
+        if extra_holders.is_empty() {
+            return Ok(vec![]);
+        }
+
+        info!(
+            "{:?} {:?} to {:?}",
+            LogMarker::HotChunkReplicated,
+            name,
+            extra_holders
+        );
+        self.hot_chunks
+            .set_extra_holders(name, extra_holders.clone())
+            .await;
+
+        let msg = SystemMsg::NodeCmd(NodeCmd::ReplicateData(vec![ReplicatedData::Chunk(
+            chunk.clone(),
+        )]));
+        self.send_node_msg_to_nodes(msg, extra_holders).await
+    }
+
+    /// Tells the Adults holding extra replicas of chunks which are no longer hot to drop them.
+    pub(crate) async fn drop_cold_extra_replicas(&self) -> Result<Vec<Cmd>> {
+        if self.is_not_elder().await {
+            return Ok(vec![]);
+        }
+
+        let mut drop_list: BTreeMap<XorName, Vec<ReplicatedDataAddress>> = BTreeMap::new();
+        for (name, extra_holders) in self.hot_chunks.take_cold_extra_replicas().await {
+            // churn may have made some of them regular holders since
+            let holders = self
+                .get_adults_holding_data(&ReplicatedDataAddress::Chunk(ChunkAddress(name)))
+                .await?;
+            for holder in extra_holders.difference(&holders) {
+                drop_list
+                    .entry(*holder)
+                    .or_default()
+                    .push(ReplicatedDataAddress::Chunk(ChunkAddress(name)));
+            }
+        }
+
+        let section_pk = self.network_knowledge().section_key().await;
+        let cmds = drop_list
+            .into_iter()
+            .map(|(holder, data_addresses)| Cmd::SignOutgoingSystemMsg {
+                msg: SystemMsg::NodeCmd(NodeCmd::DropExtraReplicas(data_addresses)),
+                dst: DstLocation::Node {
+                    name: holder,
+                    section_pk,
+                },
+            })
+            .collect();
+
+        Ok(cmds)
+    }
+
     pub(crate) async fn get_metadata_of(&self, prefix: &Prefix) -> MetadataExchange {
         // Load tracked adult_levels
         let adult_levels = self.capacity.levels_matching(*prefix).await;
-        MetadataExchange { adult_levels }
+        let mut reward_ledger = self.rewards.ledger(*prefix, &adult_levels).await;
+        reward_ledger.nodes.retain(|name, _| prefix.matches(name));
+        MetadataExchange {
+            adult_levels,
+            reward_ledger,
+        }
     }
 
-    pub(crate) async fn set_adult_levels(&self, adult_levels: MetadataExchange) {
-        let MetadataExchange { adult_levels } = adult_levels;
+    pub(crate) async fn set_adult_levels(&self, adult_levels: BTreeMap<XorName, StorageLevel>) {
         self.capacity.set_adult_levels(adult_levels).await
     }
 
+    /// Takes on the reward ledger handed over by another of our Elders.
+    pub(crate) async fn sync_reward_ledger(&self, ledger: RewardLedger) {
+        if ledger.prefix != self.network_knowledge().prefix().await {
+            return;
+        }
+        if let Err(error) = self.rewards.sync(ledger).await {
+            error!("Error writing our reward ledger to disk: {:?}", error);
+        }
+    }
+
     /// Registered holders not present in provided list of members
     /// will be removed from adult_storage_info and no longer tracked for liveness.
     pub(crate) async fn liveness_retain_only(&self, members: BTreeSet<XorName>) -> Result<()> {
         // full adults
         self.capacity.retain_members_only(&members).await;
 
+        // stop accounting rewards for absent holders
+        self.rewards.retain_members_only(&members).await?;
+
         // stop tracking liveness of absent holders
         self.liveness.retain_members_only(members);
 
@@ -177,25 +432,155 @@ impl Node {
         changed
     }
 
+    /// Records the wallet to be credited with the rewards of the given node.
+    pub(crate) async fn record_reward_wallet(&self, node: XorName, wallet: PublicKey) {
+        info!("Crediting rewards of {node} to wallet {wallet:?}");
+        if let Err(error) = self.rewards.set_wallet(node, wallet).await {
+            error!("Error writing our reward ledger to disk: {:?}", error);
+        }
+    }
+
+    /// Records a query successfully served by the given Adult.
+    pub(crate) async fn record_query_served(&self, adult: XorName) {
+        self.rewards.record_served(adult).await
+    }
+
+    /// The Elder proposing the reward payouts of our section, i.e. the one with the lowest name.
+    async fn reward_payout_proposer(&self) -> Option<XorName> {
+        self.network_knowledge
+            .authority_provider()
+            .await
+            .names()
+            .into_iter()
+            .next()
+    }
+
+    /// Reports the queries we observed our Adults serve since the last payout to the Elder
+    /// proposing payouts, which proposes the next one once a supermajority of us reported them.
+    /// The other Elders sign it too if it matches their records, and it's only applied once agreed.
+    pub(crate) async fn propose_reward_payout(&self) -> Result<Vec<Cmd>> {
+        if !self.is_elder().await {
+            return Err(Error::InvalidState);
+        }
+        let proposer = self
+            .reward_payout_proposer()
+            .await
+            .ok_or(Error::InvalidState)?;
+        let our_name = self.info.read().await.name();
+
+        let (epoch, served) = self.rewards.observed().await;
+        if proposer == our_name {
+            return self.handle_queries_served(our_name, epoch, served).await;
+        }
+
+        let msg = SystemMsg::NodeEvent(NodeEvent::QueriesServed { epoch, served });
+        self.send_node_msg_to_nodes(msg, BTreeSet::from([proposer]))
+            .await
+    }
+
+    /// Records the queries served reported by one of our Elders, if we are the Elder proposing
+    /// payouts, proposing the next payout once a supermajority of our Elders reported them.
+    pub(crate) async fn handle_queries_served(
+        &self,
+        elder: XorName,
+        epoch: u64,
+        served: BTreeMap<XorName, u64>,
+    ) -> Result<Vec<Cmd>> {
+        let our_name = self.info.read().await.name();
+        if !self.is_elder().await || self.reward_payout_proposer().await != Some(our_name) {
+            return Ok(vec![]);
+        }
+
+        let elders = self.network_knowledge.authority_provider().await.names();
+        let served = match self.rewards.add_report(elder, epoch, served, &elders).await {
+            Some(served) => served,
+            None => return Ok(vec![]),
+        };
+
+        let levels = self.capacity.levels().await;
+        let payout = self.rewards.next_payout(&levels, &served).await;
+        info!("Proposing reward payout {}", payout.epoch);
+        self.propose(Proposal::RewardPayout(payout)).await
+    }
+
+    /// Signs a reward payout proposed by another Elder, if it was proposed by
+    /// the Elder proposing payouts and it matches our records.
+    pub(crate) async fn sign_reward_payout(
+        &self,
+        payout: &RewardPayout,
+        proposer: &Peer,
+    ) -> Result<Vec<Cmd>> {
+        let our_name = self.info.read().await.name();
+        if !self.is_elder().await
+            || proposer.name() == our_name
+            || self.reward_payout_proposer().await != Some(proposer.name())
+        {
+            return Ok(vec![]);
+        }
+
+        let levels = self.capacity.levels().await;
+        if !self.rewards.is_valid_payout(payout, &levels).await {
+            warn!(
+                "Not signing reward payout {} from {}, as it doesn't match our records",
+                payout.epoch, proposer
+            );
+            return Ok(vec![]);
+        }
+
+        self.propose(Proposal::RewardPayout(payout.clone())).await
+    }
+
+    /// Applies a reward payout agreed by our Elders to our ledger.
+    pub(crate) async fn handle_reward_payout_agreement(&self, payout: RewardPayout) {
+        match self.rewards.apply_payout(&payout).await {
+            Ok(total) => info!("Paid out {} in rewards to our Adults", total),
+            Err(error) => error!("Problem paying out rewards: {:?}", error),
+        }
+    }
+
+    /// The rewards accounted for our Adults.
+    pub(crate) async fn reward_ledger(&self) -> Result<RewardLedger> {
+        if !self.is_elder().await {
+            return Err(Error::InvalidState);
+        }
+        let prefix = self.network_knowledge().prefix().await;
+        let levels = self.capacity.levels().await;
+        Ok(self.rewards.ledger(prefix, &levels).await)
+    }
+
     pub(crate) async fn full_adults(&self) -> BTreeSet<XorName> {
         self.capacity.full_adults().await
     }
 
-    pub(crate) fn compute_holders(
+    pub(crate) async fn compute_holders(
         &self,
         addr: &ReplicatedDataAddress,
         adult_list: &BTreeSet<XorName>,
-    ) -> BTreeSet<XorName> {
-        adult_list
+    ) -> Result<BTreeSet<XorName>> {
+        let copy_count = self
+            .network_knowledge()
+            .storage_policy()
+            .await?
+            .copy_count(addr);
+        Ok(adult_list
             .iter()
             .sorted_by(|lhs, rhs| addr.name().cmp_distance(lhs, rhs))
-            .take(data_copy_count())
+            .take(copy_count)
             .cloned()
-            .collect()
+            .collect())
     }
 
-    // Used to fetch the list of holders for given data name.
-    async fn get_adults_holding_data(&self, target: &XorName) -> BTreeSet<XorName> {
+    // Used to fetch the list of holders for given data address.
+    async fn get_adults_holding_data(
+        &self,
+        address: &ReplicatedDataAddress,
+    ) -> Result<BTreeSet<XorName>> {
+        let target = address.name();
+        let copy_count = self
+            .network_knowledge()
+            .storage_policy()
+            .await?
+            .copy_count(address);
         let full_adults = self.full_adults().await;
         // TODO: reuse our_adults_sorted_by_distance_to API when core is merged into upper layer
         let adults = self.network_knowledge().adults().await;
@@ -206,7 +591,7 @@ impl Node {
             .into_iter()
             .sorted_by(|lhs, rhs| target.cmp_distance(lhs, rhs))
             .filter(|peer| !full_adults.contains(peer))
-            .take(data_copy_count())
+            .take(copy_count)
             .collect::<BTreeSet<_>>();
 
         trace!(
@@ -235,11 +620,21 @@ impl Node {
         };
 
         candidates.extend(close_full_adults);
-        candidates
+        Ok(candidates)
     }
 
-    // Used to fetch the list of holders for given name of data.
-    async fn get_adults_who_should_store_data(&self, target: XorName) -> BTreeSet<XorName> {
+    // Used to fetch the list of holders for given address of data, as many as the storage
+    // policy replicates its kind of data to.
+    async fn get_adults_who_should_store_data(
+        &self,
+        address: &ReplicatedDataAddress,
+    ) -> Result<BTreeSet<XorName>> {
+        let target = address.name();
+        let copy_count = self
+            .network_knowledge()
+            .storage_policy()
+            .await?
+            .copy_count(address);
         let full_adults = self.full_adults().await;
         // TODO: reuse our_adults_sorted_by_distance_to API when core is merged into upper layer
         let adults = self.network_knowledge().adults().await;
@@ -252,7 +647,7 @@ impl Node {
             .into_iter()
             .sorted_by(|lhs, rhs| target.cmp_distance(lhs, rhs))
             .filter(|peer| !full_adults.contains(peer))
-            .take(data_copy_count())
+            .take(copy_count)
             .collect::<BTreeSet<_>>();
 
         trace!(
@@ -262,7 +657,7 @@ impl Node {
                full_adults
            );
 
-        candidates
+        Ok(candidates)
     }
 
     // Takes a message for specified targets, and builds internal send cmds
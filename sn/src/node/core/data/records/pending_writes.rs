@@ -0,0 +1,116 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{messaging::MsgId, types::Peer};
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+use xor_name::XorName;
+
+// How long we wait for the Adults to ack storing the data of a client's write,
+// longer than clients wait for the acks of their cmds by default.
+const WRITE_ACK_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+
+#[derive(Debug)]
+struct PendingWrite {
+    origin: Peer,
+    holders: BTreeSet<XorName>,
+    required: usize,
+    stored_by: BTreeSet<XorName>,
+    since: Instant,
+}
+
+/// The writes of clients whose data we sent the Adults to store, which
+/// are only acked to the client once enough of them acked storing it.
+#[derive(Clone, Default)]
+pub(crate) struct PendingWrites {
+    writes: Arc<RwLock<BTreeMap<MsgId, PendingWrite>>>,
+}
+
+impl PendingWrites {
+    /// Waits for `required` of the `holders` to ack storing the data of the write.
+    pub(super) async fn add(
+        &self,
+        msg_id: MsgId,
+        origin: Peer,
+        holders: BTreeSet<XorName>,
+        required: usize,
+    ) {
+        let mut writes = self.writes.write().await;
+        writes.retain(|_, write| write.since.elapsed() < WRITE_ACK_TIMEOUT);
+        let _prev = writes.insert(
+            msg_id,
+            PendingWrite {
+                origin,
+                holders,
+                required,
+                stored_by: BTreeSet::new(),
+                since: Instant::now(),
+            },
+        );
+    }
+
+    /// Records that the Adult stored the data of the write, returning the client
+    /// to ack the write to once enough of its holders have.
+    pub(super) async fn ack(&self, msg_id: MsgId, adult: XorName) -> Option<Peer> {
+        let mut writes = self.writes.write().await;
+        let write = writes.get_mut(&msg_id)?;
+        if !write.holders.contains(&adult) {
+            warn!(
+                "Ignoring ack of write {:?} from {}, which isn't one of its holders",
+                msg_id, adult
+            );
+            return None;
+        }
+
+        let _ = write.stored_by.insert(adult);
+        if write.stored_by.len() < write.required {
+            return None;
+        }
+
+        writes.remove(&msg_id).map(|write| write.origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PendingWrites;
+    use crate::{messaging::MsgId, types::Peer};
+
+    use std::net::{Ipv4Addr, SocketAddr};
+    use xor_name::XorName;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn write_is_acked_once_enough_holders_stored_it() {
+        let pending = PendingWrites::default();
+        let origin = Peer::new(
+            XorName::random(),
+            SocketAddr::from((Ipv4Addr::LOCALHOST, 12000)),
+        );
+        let holders: Vec<_> = (0..4).map(|_| XorName::random()).collect();
+        let msg_id = MsgId::new();
+        pending
+            .add(msg_id, origin.clone(), holders.iter().copied().collect(), 3)
+            .await;
+
+        // acks of others than its holders, or repeated ones, don't count
+        assert!(pending.ack(msg_id, XorName::random()).await.is_none());
+        assert!(pending.ack(msg_id, holders[0]).await.is_none());
+        assert!(pending.ack(msg_id, holders[0]).await.is_none());
+        assert!(pending.ack(msg_id, holders[1]).await.is_none());
+
+        assert_eq!(pending.ack(msg_id, holders[2]).await, Some(origin));
+
+        // the client is only acked once
+        assert!(pending.ack(msg_id, holders[3]).await.is_none());
+    }
+}
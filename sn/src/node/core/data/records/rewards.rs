@@ -0,0 +1,616 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{
+    messaging::data::{NodePayout, NodeRewards, RewardLedger, RewardPayout, StorageLevel},
+    node::{supermajority, Prefix, Result, XorName},
+    types::{PublicKey, Token},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{fs, sync::RwLock};
+
+/// Reward earned by an Adult at each payout, per storage level it has reported
+/// (counting the lowest level as one, so that available space is rewarded too).
+pub(crate) const STORAGE_REWARD_PER_LEVEL: Token = Token::from_nano(100);
+/// Reward earned by an Adult per query it successfully served since the last payout.
+pub(crate) const SERVING_REWARD: Token = Token::from_nano(10);
+
+// Filename for storing the reward ledger, within the node's root dir
+const REWARD_LEDGER_FILENAME: &str = "reward_ledger";
+
+/// A util for accounting the work done by
+/// the Adults in the section, and for paying
+/// out the rewards they earn to their wallets.
+///
+/// Work is recorded locally by each Elder, whereas
+/// payouts are only applied once the Elders agree on them.
+/// Each Elder reports the queries it observed being served to the one
+/// proposing the payouts, and a node is only rewarded for the ones
+/// a supermajority of the Elders observed.
+#[derive(Clone)]
+pub(crate) struct Rewards {
+    ledger: Arc<RwLock<Ledger>>,
+    reports: Arc<RwLock<Reports>>,
+    path: PathBuf,
+}
+
+// The queries served reported by each Elder for the next payout, kept by the proposer only.
+#[derive(Default)]
+struct Reports {
+    epoch: u64,
+    served: BTreeMap<XorName, BTreeMap<XorName, u64>>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Ledger {
+    epoch: u64,
+    accounts: BTreeMap<XorName, Account>,
+    credits: BTreeMap<PublicKey, Token>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Account {
+    wallet: Option<PublicKey>,
+    served: u64,
+    earned: Token,
+    unpaid: Token,
+}
+
+impl Rewards {
+    /// Restores the ledger previously written to the node's root dir, if there is one.
+    pub(crate) async fn new(root_dir: &Path) -> Result<Self> {
+        let path = root_dir.join(REWARD_LEDGER_FILENAME);
+        let ledger = if path.is_file() {
+            bincode::deserialize(&fs::read(&path).await?)?
+        } else {
+            // the ledger is written there from now on
+            fs::create_dir_all(root_dir).await?;
+            Ledger::default()
+        };
+
+        Ok(Self {
+            ledger: Arc::new(RwLock::new(ledger)),
+            reports: Arc::new(RwLock::new(Reports::default())),
+            path,
+        })
+    }
+
+    /// Sets the wallet to be credited with the rewards of the node.
+    /// Anything the node earned before its wallet was known is credited
+    /// with the first payout carrying it.
+    pub(super) async fn set_wallet(&self, node: XorName, wallet: PublicKey) -> Result<()> {
+        let mut ledger = self.ledger.write().await;
+        ledger.accounts.entry(node).or_default().wallet = Some(wallet);
+        self.write(&ledger).await
+    }
+
+    /// Records a query successfully served by the node.
+    pub(super) async fn record_served(&self, node: XorName) {
+        let mut ledger = self.ledger.write().await;
+        let account = ledger.accounts.entry(node).or_default();
+        account.served = account.served.saturating_add(1);
+    }
+
+    /// The number of the next payout, and the queries we observed each node serve since the last one.
+    pub(super) async fn observed(&self) -> (u64, BTreeMap<XorName, u64>) {
+        let ledger = self.ledger.read().await;
+        let served = ledger
+            .accounts
+            .iter()
+            .filter(|(_, account)| account.served > 0)
+            .map(|(node, account)| (*node, account.served))
+            .collect();
+        (ledger.epoch + 1, served)
+    }
+
+    /// Records the queries served the Elder observed for the next payout. Once a supermajority
+    /// of the given Elders reported them, returns the number each node is to be rewarded for,
+    /// i.e. the most queries a supermajority of the Elders observed it serve.
+    pub(super) async fn add_report(
+        &self,
+        elder: XorName,
+        epoch: u64,
+        served: BTreeMap<XorName, u64>,
+        elders: &BTreeSet<XorName>,
+    ) -> Option<BTreeMap<XorName, u64>> {
+        if !elders.contains(&elder) || epoch != self.ledger.read().await.epoch + 1 {
+            return None;
+        }
+
+        let mut reports = self.reports.write().await;
+        if reports.epoch != epoch {
+            *reports = Reports {
+                epoch,
+                served: BTreeMap::new(),
+            };
+        }
+        let _prev = reports.served.insert(elder, served);
+
+        let threshold = supermajority(elders.len());
+        let reported: Vec<_> = reports
+            .served
+            .iter()
+            .filter(|(elder, _)| elders.contains(elder))
+            .map(|(_, served)| served)
+            .collect();
+        if reported.len() < threshold {
+            return None;
+        }
+
+        let nodes: BTreeSet<_> = reported.iter().flat_map(|served| served.keys()).collect();
+        let agreed = nodes
+            .into_iter()
+            .map(|node| {
+                let mut counts: Vec<_> = reported
+                    .iter()
+                    .map(|served| served.get(node).copied().unwrap_or_default())
+                    .collect();
+                counts.sort_unstable_by(|lhs, rhs| rhs.cmp(lhs));
+                (*node, counts[threshold - 1])
+            })
+            .collect();
+        reports.served.clear();
+
+        Some(agreed)
+    }
+
+    /// The payout following the last one agreed, rewarding the nodes for the storage
+    /// they provide at the given levels, and for the given number of queries they served since.
+    pub(super) async fn next_payout(
+        &self,
+        levels: &BTreeMap<XorName, StorageLevel>,
+        served: &BTreeMap<XorName, u64>,
+    ) -> RewardPayout {
+        let ledger = self.ledger.read().await;
+        let nodes = levels
+            .iter()
+            .map(|(node, level)| {
+                let account = ledger.accounts.get(node);
+                let payout = NodePayout {
+                    wallet: account.and_then(|account| account.wallet),
+                    storage_level: *level,
+                    served: served.get(node).copied().unwrap_or_default(),
+                };
+                (*node, payout)
+            })
+            .collect();
+
+        RewardPayout {
+            epoch: ledger.epoch + 1,
+            nodes,
+        }
+    }
+
+    /// Whether a payout proposed by another Elder is consistent with our records:
+    /// it must follow the last payout we applied, only reward nodes we know of at no
+    /// more than the storage level they reported to us, for no more queries than we
+    /// observed them serve, and credit the wallets we know.
+    pub(super) async fn is_valid_payout(
+        &self,
+        payout: &RewardPayout,
+        levels: &BTreeMap<XorName, StorageLevel>,
+    ) -> bool {
+        let ledger = self.ledger.read().await;
+        if payout.epoch <= ledger.epoch {
+            return false;
+        }
+
+        payout.nodes.iter().all(|(node, node_payout)| {
+            let known_level = match levels.get(node) {
+                Some(level) => level,
+                None => return false,
+            };
+            let account = ledger.accounts.get(node);
+            let known_wallet = account.and_then(|account| account.wallet);
+            let observed = account.map(|account| account.served).unwrap_or_default();
+
+            node_payout.storage_level <= *known_level
+                && node_payout.served <= observed
+                && (known_wallet.is_none() || known_wallet == node_payout.wallet)
+        })
+    }
+
+    /// Applies a payout agreed by the Elders, unless a later one was already applied.
+    /// Returns the total amount earned by the nodes.
+    pub(super) async fn apply_payout(&self, payout: &RewardPayout) -> Result<Token> {
+        let mut ledger = self.ledger.write().await;
+        if payout.epoch <= ledger.epoch {
+            return Ok(Token::zero());
+        }
+
+        let mut total = Token::zero();
+        for (node, node_payout) in &payout.nodes {
+            let account = ledger.accounts.entry(*node).or_default();
+            let reward = reward(node_payout.storage_level, node_payout.served);
+            // the queries we observed since the Elders reported them are left for the next payout
+            account.served = account.served.saturating_sub(node_payout.served);
+            account.earned = add(account.earned, reward);
+            total = add(total, reward);
+
+            let wallet = node_payout.wallet.or(account.wallet);
+            account.wallet = wallet;
+            match wallet {
+                Some(wallet) => {
+                    let unpaid = std::mem::replace(&mut account.unpaid, Token::zero());
+                    ledger.credit(wallet, add(unpaid, reward));
+                }
+                None => account.unpaid = add(account.unpaid, reward),
+            }
+        }
+        ledger.epoch = payout.epoch;
+
+        self.write(&ledger).await?;
+        Ok(total)
+    }
+
+    /// The ledger of the section with the given prefix, including the storage levels of its nodes.
+    pub(super) async fn ledger(
+        &self,
+        prefix: Prefix,
+        levels: &BTreeMap<XorName, StorageLevel>,
+    ) -> RewardLedger {
+        let ledger = self.ledger.read().await;
+        let nodes = ledger
+            .accounts
+            .iter()
+            .map(|(name, account)| {
+                let rewards = NodeRewards {
+                    wallet: account.wallet,
+                    served: account.served,
+                    storage_level: levels.get(name).copied().unwrap_or_else(StorageLevel::zero),
+                    earned: account.earned,
+                    unpaid: account.unpaid,
+                };
+                (*name, rewards)
+            })
+            .collect();
+
+        RewardLedger {
+            prefix,
+            epoch: ledger.epoch,
+            nodes,
+            credits: ledger.credits.clone(),
+        }
+    }
+
+    /// Takes on the ledger another of our Elders handed over, if it applied payouts we missed.
+    /// The queries we observed are then no longer accounted for, as we can't tell which
+    /// of them those payouts covered. Otherwise only the wallets we didn't know are taken.
+    pub(super) async fn sync(&self, other: RewardLedger) -> Result<()> {
+        let mut ledger = self.ledger.write().await;
+        if other.epoch < ledger.epoch {
+            return Ok(());
+        }
+
+        if other.epoch > ledger.epoch {
+            let known_wallets: BTreeMap<_, _> = ledger
+                .accounts
+                .iter()
+                .filter_map(|(node, account)| Some((*node, account.wallet?)))
+                .collect();
+            ledger.epoch = other.epoch;
+            ledger.credits = other.credits;
+            ledger.accounts = other
+                .nodes
+                .into_iter()
+                .map(|(node, rewards)| {
+                    let account = Account {
+                        wallet: rewards.wallet,
+                        served: 0,
+                        earned: rewards.earned,
+                        unpaid: rewards.unpaid,
+                    };
+                    (node, account)
+                })
+                .collect();
+            for (node, wallet) in known_wallets {
+                let account = ledger.accounts.entry(node).or_default();
+                account.wallet = account.wallet.or(Some(wallet));
+            }
+        } else {
+            for (node, rewards) in other.nodes {
+                if let Some(wallet) = rewards.wallet {
+                    let account = ledger.accounts.entry(node).or_default();
+                    account.wallet = account.wallet.or(Some(wallet));
+                }
+            }
+        }
+
+        self.write(&ledger).await
+    }
+
+    /// Nodes not present in provided list of members will no longer be accounted for.
+    /// Whatever was credited to their wallets remains in the ledger.
+    pub(super) async fn retain_members_only(&self, members: &BTreeSet<XorName>) -> Result<()> {
+        let mut ledger = self.ledger.write().await;
+        ledger.accounts.retain(|name, _| members.contains(name));
+        self.write(&ledger).await
+    }
+
+    // Writes the ledger to a temporary file first, so a previous copy is not lost if we stop halfway.
+    async fn write(&self, ledger: &Ledger) -> Result<()> {
+        let bytes = bincode::serialize(ledger)?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, bytes).await?;
+        fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+impl Ledger {
+    fn credit(&mut self, wallet: PublicKey, amount: Token) {
+        if amount == Token::zero() {
+            return;
+        }
+        let credited = self.credits.entry(wallet).or_insert_with(Token::zero);
+        *credited = add(*credited, amount);
+    }
+}
+
+fn reward(level: StorageLevel, served: u64) -> Token {
+    let storage = STORAGE_REWARD_PER_LEVEL.as_nano() * (level.value() as u64 + 1);
+    let serving = SERVING_REWARD.as_nano().saturating_mul(served);
+    Token::from_nano(storage.saturating_add(serving))
+}
+
+fn add(lhs: Token, rhs: Token) -> Token {
+    Token::from_nano(lhs.as_nano().saturating_add(rhs.as_nano()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Keypair;
+    use eyre::Result;
+    use tempfile::tempdir;
+
+    fn wallet() -> PublicKey {
+        Keypair::new_ed25519(&mut rand::thread_rng()).public_key()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rewards_are_credited_to_the_node_wallet() -> Result<()> {
+        let root_dir = tempdir()?;
+        let rewards = Rewards::new(root_dir.path()).await?;
+        let node = XorName::random();
+        let wallet = wallet();
+        rewards.set_wallet(node, wallet).await?;
+        rewards.record_served(node).await;
+        rewards.record_served(node).await;
+
+        let levels = BTreeMap::from([(node, StorageLevel::from(2)?)]);
+        let (epoch, served) = rewards.observed().await;
+        assert_eq!(epoch, 1);
+        let payout = rewards.next_payout(&levels, &served).await;
+        assert_eq!(payout.epoch, 1);
+        assert_eq!(payout.nodes[&node].wallet, Some(wallet));
+        assert_eq!(payout.nodes[&node].served, 2);
+
+        // nothing is credited until the payout is agreed and applied
+        let ledger = rewards.ledger(Prefix::default(), &levels).await;
+        assert_eq!(ledger.credited(&wallet), Token::zero());
+
+        let expected = Token::from_nano(3 * STORAGE_REWARD_PER_LEVEL.as_nano() + 20);
+        assert_eq!(rewards.apply_payout(&payout).await?, expected);
+
+        let ledger = rewards.ledger(Prefix::default(), &levels).await;
+        assert_eq!(ledger.epoch, 1);
+        assert_eq!(ledger.credited(&wallet), expected);
+        let node_rewards = &ledger.nodes[&node];
+        assert_eq!(node_rewards.served, 0);
+        assert_eq!(node_rewards.storage_level, StorageLevel::from(2)?);
+        assert_eq!(node_rewards.earned, expected);
+        assert_eq!(node_rewards.unpaid, Token::zero());
+
+        // a payout is applied only once
+        assert_eq!(rewards.apply_payout(&payout).await?, Token::zero());
+        let ledger = rewards.ledger(Prefix::default(), &levels).await;
+        assert_eq!(ledger.credited(&wallet), expected);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unpaid_rewards_are_credited_once_the_wallet_is_known() -> Result<()> {
+        let root_dir = tempdir()?;
+        let rewards = Rewards::new(root_dir.path()).await?;
+        let node = XorName::random();
+        let levels = BTreeMap::from([(node, StorageLevel::zero())]);
+
+        let payout = rewards.next_payout(&levels, &BTreeMap::new()).await;
+        let _ = rewards.apply_payout(&payout).await?;
+        let ledger = rewards.ledger(Prefix::default(), &levels).await;
+        assert_eq!(ledger.nodes[&node].unpaid, STORAGE_REWARD_PER_LEVEL);
+        assert!(ledger.credits.is_empty());
+
+        let wallet = wallet();
+        rewards.set_wallet(node, wallet).await?;
+        let payout = rewards.next_payout(&levels, &BTreeMap::new()).await;
+        let _ = rewards.apply_payout(&payout).await?;
+        let ledger = rewards.ledger(Prefix::default(), &levels).await;
+        assert_eq!(ledger.nodes[&node].unpaid, Token::zero());
+        let expected = Token::from_nano(2 * STORAGE_REWARD_PER_LEVEL.as_nano());
+        assert_eq!(ledger.credited(&wallet), expected);
+
+        // credits remain once the node has left
+        rewards.retain_members_only(&BTreeSet::new()).await?;
+        let ledger = rewards.ledger(Prefix::default(), &levels).await;
+        assert!(ledger.nodes.is_empty());
+        assert_eq!(ledger.credited(&wallet), expected);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ledger_is_restored_from_the_root_dir() -> Result<()> {
+        let root_dir = tempdir()?;
+        let rewards = Rewards::new(root_dir.path()).await?;
+        let node = XorName::random();
+        let wallet = wallet();
+        rewards.set_wallet(node, wallet).await?;
+
+        let levels = BTreeMap::from([(node, StorageLevel::from(1)?)]);
+        let payout = rewards.next_payout(&levels, &BTreeMap::new()).await;
+        let _ = rewards.apply_payout(&payout).await?;
+        let ledger = rewards.ledger(Prefix::default(), &levels).await;
+
+        let restored = Rewards::new(root_dir.path()).await?;
+        assert_eq!(restored.ledger(Prefix::default(), &levels).await, ledger);
+        assert_eq!(
+            restored.next_payout(&levels, &BTreeMap::new()).await.epoch,
+            2
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn payouts_inconsistent_with_our_records_are_invalid() -> Result<()> {
+        let root_dir = tempdir()?;
+        let rewards = Rewards::new(root_dir.path()).await?;
+        let node = XorName::random();
+        let wallet = wallet();
+        rewards.set_wallet(node, wallet).await?;
+
+        rewards.record_served(node).await;
+
+        let levels = BTreeMap::from([(node, StorageLevel::from(1)?)]);
+        let (_, served) = rewards.observed().await;
+        let payout = rewards.next_payout(&levels, &served).await;
+        assert!(rewards.is_valid_payout(&payout, &levels).await);
+
+        // rewarding a node we don't know of
+        let mut unknown_node = payout.clone();
+        let _ = unknown_node.nodes.insert(
+            XorName::random(),
+            NodePayout {
+                wallet: None,
+                storage_level: StorageLevel::zero(),
+                served: 0,
+            },
+        );
+        assert!(!rewards.is_valid_payout(&unknown_node, &levels).await);
+
+        // at a higher storage level than the node reported to us
+        let mut higher_level = payout.clone();
+        higher_level
+            .nodes
+            .get_mut(&node)
+            .ok_or_else(|| eyre::eyre!("node missing from payout"))?
+            .storage_level = StorageLevel::from(2)?;
+        assert!(!rewards.is_valid_payout(&higher_level, &levels).await);
+
+        // for more queries than we observed it serve
+        let mut more_served = payout.clone();
+        more_served
+            .nodes
+            .get_mut(&node)
+            .ok_or_else(|| eyre::eyre!("node missing from payout"))?
+            .served = 2;
+        assert!(!rewards.is_valid_payout(&more_served, &levels).await);
+
+        // crediting some other wallet
+        let mut other_wallet = payout.clone();
+        other_wallet
+            .nodes
+            .get_mut(&node)
+            .ok_or_else(|| eyre::eyre!("node missing from payout"))?
+            .wallet = Some(self::wallet());
+        assert!(!rewards.is_valid_payout(&other_wallet, &levels).await);
+
+        // or once it's been applied already
+        let _ = rewards.apply_payout(&payout).await?;
+        assert!(!rewards.is_valid_payout(&payout, &levels).await);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn nodes_are_rewarded_for_the_queries_a_supermajority_observed() -> Result<()> {
+        let root_dir = tempdir()?;
+        let rewards = Rewards::new(root_dir.path()).await?;
+        let node = XorName::random();
+        let elders: Vec<_> = (0..4).map(|_| XorName::random()).collect();
+        let elder_names: BTreeSet<_> = elders.iter().copied().collect();
+        let report = |count| BTreeMap::from([(node, count)]);
+
+        // reports of others than our Elders, or for another payout, don't count
+        assert!(rewards
+            .add_report(XorName::random(), 1, report(9), &elder_names)
+            .await
+            .is_none());
+        assert!(rewards
+            .add_report(elders[0], 2, report(9), &elder_names)
+            .await
+            .is_none());
+
+        // until a supermajority of the Elders reported
+        assert!(rewards
+            .add_report(elders[0], 1, report(7), &elder_names)
+            .await
+            .is_none());
+        assert!(rewards
+            .add_report(elders[1], 1, report(2), &elder_names)
+            .await
+            .is_none());
+        let served = rewards
+            .add_report(elders[2], 1, report(5), &elder_names)
+            .await;
+        assert_eq!(served, Some(report(2)));
+
+        // the queries observed since are left for the next payout
+        for _ in 0..3 {
+            rewards.record_served(node).await;
+        }
+        let levels = BTreeMap::from([(node, StorageLevel::zero())]);
+        let payout = rewards.next_payout(&levels, &report(2)).await;
+        let _ = rewards.apply_payout(&payout).await?;
+        assert_eq!(rewards.observed().await, (2, report(1)));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ledger_is_synced_with_payouts_we_missed() -> Result<()> {
+        let node = XorName::random();
+        let wallet = wallet();
+        let levels = BTreeMap::from([(node, StorageLevel::zero())]);
+
+        let root_dirs = [tempdir()?, tempdir()?, tempdir()?];
+        let elder = Rewards::new(root_dirs[0].path()).await?;
+        elder.set_wallet(node, wallet).await?;
+        let payout = elder.next_payout(&levels, &BTreeMap::new()).await;
+        let _ = elder.apply_payout(&payout).await?;
+        let handed_over = elder.ledger(Prefix::default(), &levels).await;
+
+        // a new Elder takes on the ledger, along with the wallets and credits
+        let promoted = Rewards::new(root_dirs[1].path()).await?;
+        promoted.record_served(node).await;
+        promoted.sync(handed_over.clone()).await?;
+        let ledger = promoted.ledger(Prefix::default(), &levels).await;
+        assert_eq!(ledger, handed_over);
+        assert_eq!(ledger.credited(&wallet), STORAGE_REWARD_PER_LEVEL);
+
+        // but not an earlier one
+        let earlier = Rewards::new(root_dirs[2].path()).await?;
+        promoted
+            .sync(earlier.ledger(Prefix::default(), &levels).await)
+            .await?;
+        assert_eq!(
+            promoted.ledger(Prefix::default(), &levels).await,
+            handed_over
+        );
+
+        Ok(())
+    }
+}
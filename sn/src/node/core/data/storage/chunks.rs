@@ -6,14 +6,15 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::dbs::{convert_to_error_msg, ChunkStore, Error, Result};
+use crate::dbs::{
+    convert_to_error_msg, ChunkStore, Compression, Error, Result, StorageBackendKind,
+};
 use crate::messaging::system::NodeQueryResponse;
 use crate::types::{log_markers::LogMarker, Chunk, ChunkAddress};
 use crate::UsedSpace;
 
 use std::{
     fmt::{self, Display, Formatter},
-    io::ErrorKind,
     path::Path,
 };
 use tracing::info;
@@ -25,17 +26,21 @@ pub(crate) struct ChunkStorage {
 }
 
 impl ChunkStorage {
-    pub(crate) fn new(path: &Path, used_space: UsedSpace) -> Result<Self> {
+    pub(crate) async fn new(
+        path: &Path,
+        used_space: UsedSpace,
+        backend: Option<StorageBackendKind>,
+        compression: Option<Compression>,
+    ) -> Result<Self> {
         Ok(Self {
-            db: ChunkStore::new(path, used_space)?,
+            db: ChunkStore::new(path, used_space, backend, compression).await?,
         })
     }
 
-    pub(crate) fn keys(&self) -> Result<Vec<ChunkAddress>> {
-        self.db.list_all_chunk_addresses()
+    pub(crate) async fn keys(&self) -> Result<Vec<ChunkAddress>> {
+        self.db.list_all_chunk_addresses().await
     }
 
-    #[allow(dead_code)]
     pub(crate) async fn remove_chunk(&self, address: &ChunkAddress) -> Result<()> {
         trace!("Removing chunk, {:?}", address);
         self.db.delete_chunk(address).await
@@ -44,15 +49,7 @@ impl ChunkStorage {
     pub(crate) async fn get_chunk(&self, address: &ChunkAddress) -> Result<Chunk> {
         debug!("Getting chunk {:?}", address);
 
-        match self.db.read_chunk(address).await {
-            Ok(res) => Ok(res),
-            Err(error) => match error {
-                Error::Io(io_error) if io_error.kind() == ErrorKind::NotFound => {
-                    Err(Error::ChunkNotFound(*address.name()))
-                }
-                something_else => Err(something_else),
-            },
-        }
+        self.db.read_chunk(address).await
     }
 
     // Read chunk from local store and return NodeQueryResponse
@@ -61,11 +58,35 @@ impl ChunkStorage {
         NodeQueryResponse::GetChunk(self.get_chunk(address).await.map_err(convert_to_error_msg))
     }
 
+    /// Checks the content of the chunk stored at the address still hashes to it, removing the
+    /// chunk if it doesn't. Returns whether the chunk was found corrupt.
+    pub(crate) async fn remove_if_corrupt(&self, address: &ChunkAddress) -> Result<bool> {
+        // reading the chunk recomputes its address from the content
+        match self.db.read_chunk(address).await {
+            Ok(chunk) if chunk.address() == address => return Ok(false),
+            Ok(chunk) => warn!(
+                "{:?} {:?}, found content for {:?} instead",
+                LogMarker::CorruptChunkFound,
+                address,
+                chunk.address()
+            ),
+            Err(error @ (Error::UnknownEncodingFormat(_) | Error::Decompression(_))) => warn!(
+                "{:?} {:?}, could not decode it: {:?}",
+                LogMarker::CorruptChunkFound,
+                address,
+                error
+            ),
+            Err(error) => return Err(error),
+        }
+        self.db.delete_chunk(address).await?;
+        Ok(true)
+    }
+
     /// Store a chunk in the local disk store
     /// If that chunk was already in the local store, just overwrites it
     #[instrument(skip_all)]
     pub(super) async fn store(&self, data: &Chunk) -> Result<()> {
-        if self.db.chunk_file_exists(data.address())? {
+        if self.db.chunk_exists(data.address()).await? {
             info!(
                 "{}: Chunk already exists, not storing: {:?}",
                 self,
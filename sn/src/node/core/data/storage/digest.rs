@@ -0,0 +1,238 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Digest exchange between the holders of some data, finding the data one of them is missing
+//! in messages proportional to what's missing, rather than to all the data they hold.
+//!
+//! Both sides digest only the data they hold which both of them should hold, so data only one
+//! of them should hold never makes their digests differ. The sender digests the addresses of
+//! that data within each sub-prefix, `DIGEST_BITS` bits longer, of the prefixes being synced.
+//! The receiver asks for the prefixes whose digests differ from its own, which are then synced
+//! the same way, until few enough addresses are left within them to be sent as is.
+
+use crate::{
+    dbs::Result,
+    messaging::{
+        data::DataDigest,
+        system::{NodeCmd, SystemMsg},
+        DstLocation,
+    },
+    node::core::{Cmd, Node},
+    types::ReplicatedDataAddress as DataAddress,
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+use tiny_keccak::{Hasher, Sha3};
+use xor_name::{Prefix, XorName};
+
+// Number of bits each round of the exchange descends by, i.e. a prefix is split into 16 digests
+const DIGEST_BITS: usize = 4;
+// Once the data held within a prefix is down to this many addresses, they're sent instead of digests
+const MAX_ADDRESSES_PER_LEAF: usize = 64;
+
+impl Node {
+    /// Syncs the data we and `holder` should both hold within the given prefixes, sending the
+    /// addresses of the data within the prefixes where there's little of it, and digests elsewhere.
+    pub(crate) async fn sync_data_with(
+        &self,
+        holder: XorName,
+        prefixes: Vec<Prefix>,
+        adults: &BTreeSet<XorName>,
+    ) -> Result<Vec<Cmd>, crate::node::Error> {
+        let held = self.data_shared_with(&holder, adults).await?;
+
+        let mut addresses = vec![];
+        let mut digest = BTreeMap::new();
+        for prefix in prefixes {
+            let within = held
+                .iter()
+                .filter(|address| prefix.matches(address.name()))
+                .copied()
+                .collect::<Vec<_>>();
+            if within.len() <= MAX_ADDRESSES_PER_LEAF || prefix.bit_count() + DIGEST_BITS > 256 {
+                addresses.extend(within);
+            } else {
+                digest.extend(bucket_digests(&prefix, &within));
+            }
+        }
+
+        let section_pk = self.network_knowledge.section_key().await;
+        let dst = DstLocation::Node {
+            name: holder,
+            section_pk,
+        };
+        let mut cmds = vec![];
+        if !addresses.is_empty() {
+            cmds.push(Cmd::SignOutgoingSystemMsg {
+                msg: SystemMsg::NodeCmd(NodeCmd::SendReplicateDataAddress(addresses)),
+                dst,
+            });
+        }
+        if !digest.is_empty() {
+            cmds.push(Cmd::SignOutgoingSystemMsg {
+                msg: SystemMsg::NodeCmd(NodeCmd::SyncDataDigest(DataDigest(digest))),
+                dst,
+            });
+        }
+
+        Ok(cmds)
+    }
+
+    /// Compares the digests of the data `sender` holds which we should both hold with the
+    /// same data we hold, asking it to sync the prefixes we're missing data within.
+    pub(crate) async fn handle_data_digest(
+        &self,
+        sender: XorName,
+        digest: DataDigest,
+    ) -> Result<Vec<Cmd>, crate::node::Error> {
+        let adults = self.adult_names().await;
+        let held = self.data_shared_with(&sender, &adults).await?;
+
+        let DataDigest(digests) = digest;
+        let differing = digests
+            .into_iter()
+            .filter(|(prefix, digest)| {
+                let within = held
+                    .iter()
+                    .filter(|address| prefix.matches(address.name()))
+                    .copied()
+                    .collect::<Vec<_>>();
+                digest_of(&within) != *digest
+            })
+            .map(|(prefix, _)| prefix)
+            .collect::<Vec<_>>();
+
+        if differing.is_empty() {
+            trace!("Data held for us by {} is in sync", sender);
+            return Ok(vec![]);
+        }
+
+        debug!(
+            "Data held for us by {} differs within {} prefixes",
+            sender,
+            differing.len()
+        );
+        Ok(vec![Cmd::SignOutgoingSystemMsg {
+            msg: SystemMsg::NodeCmd(NodeCmd::RequestDataDigest(differing)),
+            dst: DstLocation::Node {
+                name: sender,
+                section_pk: self.section_key_by_name(&sender).await,
+            },
+        }])
+    }
+
+    /// Syncs the prefixes `sender` is missing data within, one level further down.
+    pub(crate) async fn handle_data_digest_request(
+        &self,
+        sender: XorName,
+        prefixes: Vec<Prefix>,
+    ) -> Result<Vec<Cmd>, crate::node::Error> {
+        let adults = self.adult_names().await;
+        self.sync_data_with(sender, prefixes, &adults).await
+    }
+
+    // the addresses of the data we hold which both we and `other` should hold
+    async fn data_shared_with(
+        &self,
+        other: &XorName,
+        adults: &BTreeSet<XorName>,
+    ) -> Result<Vec<DataAddress>, crate::node::Error> {
+        let our_name = self.info.read().await.name();
+        let mut held = vec![];
+        for address in self.data_storage.keys().await? {
+            let holders = self.compute_holders(&address, adults).await?;
+            if holders.contains(&our_name) && holders.contains(other) {
+                held.push(address);
+            }
+        }
+        Ok(held)
+    }
+
+    async fn adult_names(&self) -> BTreeSet<XorName> {
+        self.network_knowledge
+            .adults()
+            .await
+            .iter()
+            .map(|peer| peer.name())
+            .collect()
+    }
+}
+
+// digests the addresses within each sub-prefix of `prefix` that any of them fall within
+fn bucket_digests(prefix: &Prefix, addresses: &[DataAddress]) -> BTreeMap<Prefix, [u8; 32]> {
+    let bit_count = prefix.bit_count() + DIGEST_BITS;
+    let mut buckets: BTreeMap<Prefix, Vec<DataAddress>> = BTreeMap::new();
+    for address in addresses {
+        buckets
+            .entry(Prefix::new(bit_count, *address.name()))
+            .or_default()
+            .push(*address);
+    }
+
+    buckets
+        .into_iter()
+        .map(|(prefix, addresses)| (prefix, digest_of(&addresses)))
+        .collect()
+}
+
+// digests the addresses regardless of their order
+fn digest_of(addresses: &[DataAddress]) -> [u8; 32] {
+    let sorted = addresses.iter().collect::<BTreeSet<_>>();
+    let mut hasher = Sha3::v256();
+    for address in sorted {
+        if let Ok(bytes) = bincode::serialize(address) {
+            hasher.update(&bytes);
+        }
+    }
+    let mut digest = [0; 32];
+    hasher.finalize(&mut digest);
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bucket_digests, digest_of, DIGEST_BITS};
+    use crate::types::{ChunkAddress, RegisterAddress, ReplicatedDataAddress as DataAddress};
+    use xor_name::{Prefix, XorName};
+
+    #[test]
+    fn digests_find_the_differing_prefixes() {
+        let prefix = Prefix::default();
+        let mut addresses = (0..100)
+            .map(|_| DataAddress::Chunk(ChunkAddress(XorName::random())))
+            .chain((0..100).map(|_| {
+                DataAddress::Register(RegisterAddress::Public {
+                    name: XorName::random(),
+                    tag: 1,
+                })
+            }))
+            .collect::<Vec<_>>();
+
+        let ours = bucket_digests(&prefix, &addresses);
+        assert!(ours
+            .keys()
+            .all(|bucket| bucket.bit_count() == prefix.bit_count() + DIGEST_BITS));
+
+        // the order addresses are held in doesn't matter
+        addresses.reverse();
+        assert_eq!(bucket_digests(&prefix, &addresses), ours);
+
+        // only the bucket of the missing address differs
+        let missing = addresses.remove(42);
+        let theirs = bucket_digests(&prefix, &addresses);
+        let differing = ours
+            .iter()
+            .filter(|(bucket, digest)| theirs.get(bucket) != Some(digest))
+            .map(|(bucket, _)| *bucket)
+            .collect::<Vec<_>>();
+        assert_eq!(differing.len(), 1);
+        assert!(differing[0].matches(missing.name()));
+
+        assert_ne!(digest_of(&[missing]), digest_of(&[]));
+    }
+}
@@ -7,34 +7,37 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 mod chunks;
+mod digest;
 mod registers;
 
 use crate::{
-    dbs::Result,
+    dbs::{Compression, Error, Result},
     messaging::{
         data::{DataQuery, RegisterStoreExport, StorageLevel},
         system::{NodeCmd, NodeQueryResponse, SystemMsg},
-        DstLocation,
     },
     node::core::{Cmd, Node},
-    types::{register::User, ReplicatedData, ReplicatedDataAddress as DataAddress},
-    UsedSpace,
+    types::{register::User, ChunkAddress, ReplicatedData, ReplicatedDataAddress as DataAddress},
+    StorageBackendKind, UsedSpace,
 };
 
 pub(crate) use chunks::ChunkStorage;
 pub(crate) use registers::RegisterStorage;
 
 use crate::types::ReplicatedDataAddress;
-use std::collections::btree_map::Entry;
 use std::{
     collections::{BTreeMap, BTreeSet},
     path::Path,
     sync::Arc,
+    time::Duration,
 };
 use tokio::sync::RwLock;
 use tracing::info;
 use xor_name::XorName;
 
+// Pause between checking two chunks when scrubbing
+const CHUNK_SCRUB_THROTTLE: Duration = Duration::from_millis(50);
+
 /// Operations on data.
 #[derive(Clone)]
 pub(crate) struct DataStorage {
@@ -45,10 +48,15 @@ pub(crate) struct DataStorage {
 }
 
 impl DataStorage {
-    pub(crate) fn new(path: &Path, used_space: UsedSpace) -> Result<Self> {
+    pub(crate) async fn new(
+        path: &Path,
+        used_space: UsedSpace,
+        backend: Option<StorageBackendKind>,
+        chunk_compression: Option<Compression>,
+    ) -> Result<Self> {
         Ok(Self {
-            chunks: ChunkStorage::new(path, used_space.clone())?,
-            registers: RegisterStorage::new(path, used_space.clone())?,
+            chunks: ChunkStorage::new(path, used_space.clone(), backend, chunk_compression).await?,
+            registers: RegisterStorage::new(path, used_space.clone(), backend).await?,
             used_space,
             last_recorded_level: Arc::new(RwLock::new(StorageLevel::zero())),
         })
@@ -85,6 +93,11 @@ impl DataStorage {
         Ok(None)
     }
 
+    /// Ratio of the used space to the max capacity of the local store
+    pub(crate) fn used_space_ratio(&self) -> f64 {
+        self.used_space.ratio()
+    }
+
     // Query the local store and return NodeQueryResponse
     pub(crate) async fn query(&self, query: &DataQuery, requester: User) -> NodeQueryResponse {
         match query {
@@ -112,7 +125,6 @@ impl DataStorage {
         }
     }
 
-    #[allow(dead_code)]
     pub(crate) async fn remove(&self, address: &DataAddress) -> Result<()> {
         match address {
             DataAddress::Chunk(addr) => self.chunks.remove_chunk(addr).await,
@@ -121,7 +133,12 @@ impl DataStorage {
     }
 
     async fn keys(&self) -> Result<Vec<DataAddress>> {
-        let chunk_keys = self.chunks.keys()?.into_iter().map(DataAddress::Chunk);
+        let chunk_keys = self
+            .chunks
+            .keys()
+            .await?
+            .into_iter()
+            .map(DataAddress::Chunk);
         let reg_keys = self
             .registers
             .keys()
@@ -131,6 +148,24 @@ impl DataStorage {
         Ok(reg_keys.chain(chunk_keys).collect())
     }
 
+    /// Checks every stored chunk against its address, pausing for `throttle` between chunks so
+    /// the scrub doesn't compete with serving data. Corrupt chunks are removed, and their
+    /// addresses returned.
+    pub(crate) async fn scrub_chunks(&self, throttle: Duration) -> Result<Vec<ChunkAddress>> {
+        let mut corrupt = vec![];
+        for address in self.chunks.keys().await? {
+            match self.chunks.remove_if_corrupt(&address).await {
+                Ok(true) => corrupt.push(address),
+                Ok(false) => {}
+                // the chunk may have been removed since we listed it
+                Err(Error::ChunkNotFound(_)) => {}
+                Err(error) => warn!("Could not scrub chunk {:?}: {:?}", address, error),
+            }
+            tokio::time::sleep(throttle).await;
+        }
+        Ok(corrupt)
+    }
+
     pub(crate) async fn get_for_replication(
         &self,
         data_address: ReplicatedDataAddress,
@@ -140,6 +175,70 @@ impl DataStorage {
 }
 
 impl Node {
+    /// Removes the data in our local store which doesn't belong to our section, e.g. data left
+    /// from a previous run of this node in another section. Returns the number of entries removed.
+    pub(crate) async fn remove_data_outside_section(&self) -> Result<usize, crate::node::Error> {
+        let prefix = self.network_knowledge.prefix().await;
+        let mut removed = 0;
+        for addr in self.data_storage.keys().await? {
+            if !prefix.matches(addr.name()) {
+                self.data_storage.remove(&addr).await?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Removes the extra replicas we were given of data which is no longer hot, keeping the
+    /// ones we are a regular holder of.
+    pub(crate) async fn drop_extra_replicas(
+        &self,
+        data_addresses: Vec<DataAddress>,
+    ) -> Result<(), crate::node::Error> {
+        let adults = self
+            .network_knowledge
+            .adults()
+            .await
+            .iter()
+            .map(|peer| peer.name())
+            .collect();
+        let our_name = self.info.read().await.name();
+
+        for address in data_addresses {
+            if self
+                .compute_holders(&address, &adults)
+                .await?
+                .contains(&our_name)
+            {
+                continue;
+            }
+            match self.data_storage.remove(&address).await {
+                Ok(()) | Err(Error::ChunkNotFound(_)) => {}
+                Err(error) => warn!("Could not drop extra replica {:?}: {:?}", address, error),
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the chunks we hold which have gone corrupt, and reports them to our Elders so we
+    /// get fresh replicas from another holder.
+    pub(crate) async fn scrub_chunks(&self) -> Result<Vec<Cmd>, crate::node::Error> {
+        // Elders don't hold data
+        if self.is_elder().await {
+            return Ok(vec![]);
+        }
+
+        let corrupt = self.data_storage.scrub_chunks(CHUNK_SCRUB_THROTTLE).await?;
+        if corrupt.is_empty() {
+            return Ok(vec![]);
+        }
+
+        info!("Removed {} corrupt chunks, reporting them", corrupt.len());
+        let data_addresses = corrupt.into_iter().map(DataAddress::Chunk).collect();
+        let msg = SystemMsg::NodeCmd(NodeCmd::ReportCorruptData(data_addresses));
+        Ok(vec![self.send_msg_to_our_elders(msg).await?])
+    }
+
     #[allow(clippy::mutable_key_type)]
     pub(crate) async fn reorganize_data(
         &self,
@@ -159,33 +258,18 @@ impl Node {
             }
         }
 
-        let mut cmds = vec![];
-        let section_pk = self.network_knowledge.section_key().await;
-        let mut send_list: BTreeMap<XorName, Vec<ReplicatedDataAddress>> = BTreeMap::new();
-
-        for (data_address, (_data, targets)) in data_for_replication {
-            for target in targets {
-                let entry = send_list.entry(target);
-                match entry {
-                    Entry::Occupied(mut present_entries) => {
-                        let addresses = present_entries.get_mut();
-                        addresses.push(data_address);
-                    }
-                    Entry::Vacant(e) => {
-                        let _ = e.insert(vec![data_address]);
-                    }
-                }
-            }
+        let mut targets = BTreeSet::new();
+        for (_data_address, (_data, holders)) in data_for_replication {
+            targets.extend(holders);
         }
 
-        for (target, data_addresses) in send_list.into_iter() {
-            cmds.push(Cmd::SignOutgoingSystemMsg {
-                msg: SystemMsg::NodeCmd(NodeCmd::SendReplicateDataAddress(data_addresses).clone()),
-                dst: DstLocation::Node {
-                    name: target,
-                    section_pk,
-                },
-            })
+        // rather than listing all the data each target should hold, we sync it through
+        // digests, so only what they're missing is sent
+        let prefix = self.network_knowledge.prefix().await;
+        let adults = remaining.union(&new_adults).copied().collect();
+        let mut cmds = vec![];
+        for target in targets {
+            cmds.extend(self.sync_data_with(target, vec![prefix], &adults).await?);
         }
 
         Ok(cmds)
@@ -203,10 +287,17 @@ impl Node {
 
         let old_adult_list = remaining.union(lost_adults).copied().collect();
         let new_adult_list = remaining.union(new_adults).copied().collect();
-        let new_holders = self.compute_holders(address, &new_adult_list);
-
+        let (new_holders, old_holders) = match (
+            self.compute_holders(address, &new_adult_list).await,
+            self.compute_holders(address, &old_adult_list).await,
+        ) {
+            (Ok(new_holders), Ok(old_holders)) => (new_holders, old_holders),
+            (Err(error), _) | (_, Err(error)) => {
+                warn!("Error computing the holders of {address:?}: {error:?}");
+                return None;
+            }
+        };
         debug!("New holders len: {:?}", new_holders.len());
-        let old_holders = self.compute_holders(address, &old_adult_list);
 
         let new_adult_is_holder = !new_holders.is_disjoint(new_adults);
         let lost_old_holder = !old_holders.is_disjoint(lost_adults);
@@ -246,11 +337,27 @@ mod tests {
     use crate::types::register::User;
     use crate::types::utils::random_bytes;
     use crate::types::{Chunk, ReplicatedData};
-    use crate::UsedSpace;
+    use crate::{StorageBackendKind, UsedSpace};
+    use std::time::Duration;
     use tempfile::tempdir;
+    use walkdir::WalkDir;
 
     #[tokio::test]
     async fn data_storage_basics() -> Result<(), Error> {
+        for backend in [
+            None,
+            Some(StorageBackendKind::FileTree),
+            Some(StorageBackendKind::Sled),
+            Some(StorageBackendKind::Redb),
+            Some(StorageBackendKind::Memory),
+        ] {
+            data_storage_basics_with(backend).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn data_storage_basics_with(backend: Option<StorageBackendKind>) -> Result<(), Error> {
         // Generate temp path for storage
         // Cleaned up automatically after test completes
         let tmp_dir = tempdir()?;
@@ -258,7 +365,7 @@ mod tests {
         let used_space = UsedSpace::new(usize::MAX);
 
         // Create instance
-        let storage = DataStorage::new(path, used_space)?;
+        let storage = DataStorage::new(path, used_space, backend, None).await?;
 
         // 5mb random data chunk
         let bytes = random_bytes(5 * 1024 * 1024);
@@ -297,4 +404,54 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn scrubbing_removes_corrupt_chunks() -> Result<(), Error> {
+        let tmp_dir = tempdir()?;
+        let used_space = UsedSpace::new(1024 * 1024);
+        let storage = DataStorage::new(
+            tmp_dir.path(),
+            used_space.clone(),
+            Some(StorageBackendKind::FileTree),
+            None,
+        )
+        .await?;
+
+        let intact = Chunk::new(random_bytes(1024));
+        let corrupt = Chunk::new(random_bytes(1024));
+        for chunk in [&intact, &corrupt] {
+            let _ = storage.store(&ReplicatedData::Chunk(chunk.clone())).await?;
+        }
+        let used_before = used_space.ratio();
+
+        // flip the content of the chunk's file on disk
+        let corrupt_file = WalkDir::new(tmp_dir.path())
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .find(|entry| entry.file_name() == hex::encode(corrupt.name()).as_str())
+            .expect("chunk file not found");
+        std::fs::write(corrupt_file.path(), random_bytes(1024))?;
+
+        let removed = storage.scrub_chunks(Duration::ZERO).await?;
+        assert_eq!(removed, vec![*corrupt.address()]);
+        assert!(used_space.ratio() < used_before);
+
+        assert_eq!(
+            storage
+                .get_from_local_store(&ReplicatedData::Chunk(intact.clone()).address())
+                .await?,
+            ReplicatedData::Chunk(intact)
+        );
+        assert!(matches!(
+            storage
+                .get_from_local_store(&ReplicatedData::Chunk(corrupt).address())
+                .await,
+            Err(Error::ChunkNotFound(_))
+        ));
+
+        // nothing left to remove on a second pass
+        assert!(storage.scrub_chunks(Duration::ZERO).await?.is_empty());
+
+        Ok(())
+    }
 }
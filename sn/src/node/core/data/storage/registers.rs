@@ -7,13 +7,15 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::dbs::{
-    convert_to_error_msg, Error, EventStore, LruCache, Result, UsedSpace, SLED_FLUSH_TIME_MS,
+    convert_to_error_msg, deserialise, serialise, Error, EventStore, LruCache, Result,
+    StorageBackend, StorageBackendKind, UsedSpace,
 };
 use crate::messaging::{
     data::{
         CreateRegister, DeleteRegister, EditRegister, ExtendRegister, OperationId, RegisterCmd,
-        RegisterQuery, RegisterStoreExport, ReplicatedRegisterLog, SignedRegisterCreate,
-        SignedRegisterDelete, SignedRegisterEdit, SignedRegisterExtend,
+        RegisterQuery, RegisterSnapshot, RegisterStoreExport, ReplicatedRegisterLog,
+        SetRegisterPolicy, SignedRegisterCreate, SignedRegisterDelete, SignedRegisterEdit,
+        SignedRegisterExtend, SignedRegisterSetPolicy,
     },
     system::NodeQueryResponse,
     SectionAuth, VerifyAuthority,
@@ -24,12 +26,14 @@ use crate::types::{
 };
 
 use bincode::serialize;
-use rayon::prelude::*;
-use sled::Db;
+use serde::{Deserialize, Serialize};
 use std::{
     fmt::{self, Display, Formatter},
     path::Path,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 use tokio::sync::RwLock;
 use tracing::info;
@@ -39,7 +43,10 @@ use xor_name::{XorName, XOR_NAME_LEN};
 
 const REG_DB_NAME: &str = "register";
 const KEY_DB_NAME: &str = "addresses";
+const SNAPSHOT_DB_NAME: &str = "register_snapshots";
 const CACHE_SIZE: u16 = 100;
+// Number of ops appended to the log of a register before a snapshot of it is taken.
+const OPS_PER_SNAPSHOT: u64 = 100;
 
 type RegOpStore = EventStore<RegisterCmd>;
 type Cache = LruCache<CacheEntry>;
@@ -48,8 +55,9 @@ type Cache = LruCache<CacheEntry>;
 // TODO: dont expose this
 #[derive(Clone, Debug)]
 pub(crate) struct RegisterStorage {
-    key_db: Db,
-    reg_db: Db,
+    key_db: Arc<dyn StorageBackend>,
+    reg_db: Arc<dyn StorageBackend>,
+    snapshot_db: Arc<dyn StorageBackend>,
     cache: Cache,
     used_space: UsedSpace,
 }
@@ -59,25 +67,38 @@ struct CacheEntry {
     state: Arc<RwLock<Register>>,
     store: RegOpStore,
     section_auth: SectionAuth,
+    create: SignedRegisterCreate,
+    // the signed op which set the current policy, shipped along with snapshots to verify them
+    policy_op: Arc<RwLock<Option<SignedRegisterSetPolicy>>>,
+    // index in the op log of the first op not covered by the latest snapshot
+    snapshot_index: Arc<AtomicU64>,
+}
+
+// A snapshot of a register as stored on disk, the op log carrying on from `next_index`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredSnapshot {
+    create: SignedRegisterCreate,
+    register: Register,
+    policy_op: Option<SignedRegisterSetPolicy>,
+    section_auth: SectionAuth,
+    next_index: u64,
 }
 
 impl RegisterStorage {
-    /// Create new RegisterStorage
-    pub(crate) fn new(path: &Path, used_space: UsedSpace) -> Result<Self> {
-        let create_path = |name: &str| path.join("db").join(name);
-        let create_db = |db_dir| {
-            sled::Config::default()
-                .path(&db_dir)
-                .flush_every_ms(SLED_FLUSH_TIME_MS)
-                .open()
-                .map_err(Error::from)
-        };
+    /// Create new RegisterStorage, using sled dbs unless another backend is given
+    pub(crate) async fn new(
+        path: &Path,
+        used_space: UsedSpace,
+        backend: Option<StorageBackendKind>,
+    ) -> Result<Self> {
+        let backend = backend.unwrap_or(StorageBackendKind::Sled);
 
         Ok(Self {
             used_space,
             cache: Cache::new(CACHE_SIZE),
-            key_db: create_db(&create_path(KEY_DB_NAME))?,
-            reg_db: create_db(&create_path(REG_DB_NAME))?,
+            key_db: backend.open(path, KEY_DB_NAME).await?,
+            reg_db: backend.open(path, REG_DB_NAME).await?,
+            snapshot_db: backend.open(path, SNAPSHOT_DB_NAME).await?,
         })
     }
 
@@ -90,39 +111,11 @@ impl RegisterStorage {
     }
 
     pub(crate) async fn keys(&self) -> Result<Vec<Address>> {
-        type KeyResults = Vec<Result<XorName>>;
         let mut the_data = vec![];
 
-        // parse keys in parallel
-        let (ok, err): (KeyResults, KeyResults) = self
-            .key_db
-            .export()
-            .into_iter()
-            .flat_map(|(_, _, pairs)| pairs)
-            .par_bridge()
-            .map(|pair| {
-                let src_key = &pair[0];
-                // we expect xornames as keys
-                if src_key.len() != XOR_NAME_LEN {
-                    return Err(Error::CouldNotParseDbKey(src_key.to_vec()));
-                }
-                let mut dst_key: [u8; 32] = Default::default();
-                dst_key.copy_from_slice(src_key);
-
-                Ok(XorName(dst_key))
-            })
-            .partition(|r| r.is_ok());
-
-        if !err.is_empty() {
-            for e in err {
-                error!("{:?}", e);
-            }
-            return Err(Error::CouldNotConvertDbKey);
-        }
-
         // TODO: make this concurrent
-        for key in ok.iter().flatten() {
-            match self.try_load_cache_entry(key).await {
+        for key in self.register_names().await? {
+            match self.try_load_cache_entry(&key).await {
                 Ok(entry) => {
                     the_data.push(*entry.state.read().await.address());
                 }
@@ -148,30 +141,57 @@ impl RegisterStorage {
             Err(e) => return Err(e),
         };
 
-        self.create_replica(key, entry)
+        self.create_replica(key, entry).await
     }
 
-    fn create_replica(
+    async fn create_replica(
         &self,
         key: XorName,
         entry: Arc<CacheEntry>,
     ) -> Result<ReplicatedRegisterLog> {
-        let mut address = None;
-        let op_log = entry
-            .store
-            .get_all()?
+        // the latest snapshot is shipped along with the signed ops to verify it against, i.e. the
+        // create op and the one which set its policy, followed by the ops it doesn't cover,
+        // while the full history of signed ops is shipped when no snapshot was taken yet
+        let state = entry.state.read().await; // no snapshot is taken meanwhile
+        let (snapshot, history) = match self.get_snapshot(&key).await? {
+            Some(StoredSnapshot {
+                create,
+                register,
+                policy_op,
+                section_auth,
+                next_index,
+            }) => {
+                let mut history = vec![RegisterCmd::Create {
+                    cmd: create,
+                    section_auth,
+                }];
+                history.extend(entry.store.get_from(next_index).await?);
+                (
+                    Some(RegisterSnapshot {
+                        register,
+                        policy_op,
+                    }),
+                    history,
+                )
+            }
+            None => (None, entry.store.get_from(0).await?),
+        };
+        drop(state);
+
+        let address = entry.create.dst_address();
+
+        let op_log = history
             .into_iter()
             .filter_map(|stored_cmd| {
                 // only spread signed data
                 match stored_cmd.clone() {
-                    RegisterCmd::Create { cmd, .. } => {
+                    RegisterCmd::Create { .. } => {
                         // TODO 1: in higher layers we must verify that the section_auth is from a proper section..!
                         // TODO 2: Enable this check once we have section signature over the container key.
                         // if section_auth.verify_authority(key).is_err() {
                         //     warn!("Invalid section auth on register container: {}", key);
                         //     return None;
                         // }
-                        address = Some(cmd.dst_address());
                     }
                     RegisterCmd::Edit(SignedRegisterEdit { op, auth }) => {
                         let verification = auth.verify_authority(serialize(&op).ok()?);
@@ -193,6 +213,16 @@ impl RegisterStorage {
                             return None;
                         }
                     }
+                    RegisterCmd::SetPolicy(SignedRegisterSetPolicy { op, auth }) => {
+                        let verification = auth.verify_authority(serialize(&op).ok()?);
+                        if verification.is_err() {
+                            error!(
+                                "Invalid signature found for cmd stored in db: {:?}",
+                                stored_cmd
+                            );
+                            return None;
+                        }
+                    }
                     RegisterCmd::Extend { section_auth, .. } => {
                         // TODO: in higher layers we must verify that the section_auth is from a proper section..!
                         if section_auth.verify_authority(key).is_err() {
@@ -206,8 +236,9 @@ impl RegisterStorage {
             .collect();
 
         Ok(ReplicatedRegisterLog {
-            address: address.ok_or(Error::InvalidStore)?,
+            address,
             section_auth: entry.section_auth.clone(),
+            snapshot,
             op_log,
         })
     }
@@ -215,44 +246,16 @@ impl RegisterStorage {
     /// Used for replication of data to new Adults.
     #[cfg(test)]
     pub(crate) async fn get_data_of(&self, prefix: Prefix) -> Result<RegisterStoreExport> {
-        type KeyResults = Vec<Result<XorName>>;
-
-        // parse keys in parallel
-        let (ok, err): (KeyResults, KeyResults) = self
-            .key_db
-            .export()
-            .into_iter()
-            .flat_map(|(_, _, pairs)| pairs)
-            .par_bridge()
-            .map(|pair| {
-                let src_key = &pair[0];
-                // we expect xornames as keys
-                if src_key.len() != XOR_NAME_LEN {
-                    return Err(Error::CouldNotParseDbKey(src_key.to_vec()));
-                }
-                let mut dst_key: [u8; 32] = Default::default();
-                dst_key.copy_from_slice(src_key);
-
-                Ok(XorName(dst_key))
-            })
-            .partition(|r| r.is_ok());
-
-        if !err.is_empty() {
-            for e in err {
-                error!("{:?}", e);
-            }
-            return Err(Error::CouldNotConvertDbKey);
-        }
-
         let mut the_data = vec![];
 
         // TODO: make this concurrent
-        for key in ok.into_iter().flatten() {
+        for key in self.register_names().await? {
             match self.try_load_cache_entry(&key).await {
                 Ok(entry) => {
-                    let read_only = entry.state.read().await;
-                    if prefix.matches(read_only.name()) {
-                        the_data.push(self.create_replica(key, entry.clone())?);
+                    // the lock is released first, as it's taken again for the replica
+                    let matches = prefix.matches(entry.state.read().await.name());
+                    if matches {
+                        the_data.push(self.create_replica(key, entry.clone()).await?);
                     }
                 }
                 Err(Error::KeyNotFound(_)) => return Err(Error::InvalidStore),
@@ -272,7 +275,31 @@ impl RegisterStorage {
         // nested loops, slow..
         for data in registers {
             let key = data.address.id()?;
-            for replicated_cmd in data.op_log {
+            let mut op_log = data.op_log.into_iter();
+            if let Some(snapshot) = data.snapshot {
+                // the create op comes first, for the snapshot to be verified against
+                let (create, section_auth) = match op_log.next() {
+                    Some(RegisterCmd::Create { cmd, section_auth }) => (cmd, section_auth),
+                    _ => {
+                        warn!(
+                            "Corrupt ReplicatedRegisterLog, snapshot shipped without the create op: {}",
+                            key
+                        );
+                        continue;
+                    }
+                };
+                let result = self
+                    .install_snapshot(key, data.address, create, section_auth, snapshot)
+                    .await;
+                if let Err(error) = result {
+                    warn!(
+                        "Could not install snapshot in ReplicatedRegisterLog of {}: {:?}",
+                        key, error
+                    );
+                    continue;
+                }
+            }
+            for replicated_cmd in op_log {
                 if replicated_cmd.dst_address() != data.address {
                     warn!(
                         "Corrupt ReplicatedRegisterLog, op log contains foreign ops: {}",
@@ -280,17 +307,75 @@ impl RegisterStorage {
                     );
                     continue;
                 }
-                let _ = self.apply(replicated_cmd).await?;
+                // each op is verified as it's applied, and the ones we hold already are skipped
+                match self.apply(replicated_cmd).await {
+                    Ok(()) | Err(Error::DataExists) => {}
+                    Err(error) => warn!(
+                        "Could not apply op in ReplicatedRegisterLog of {}: {:?}",
+                        key, error
+                    ),
+                }
             }
         }
 
         Ok(())
     }
 
+    // verifies a replicated snapshot, then merges it into the register we hold,
+    // or stores it as the register when we don't hold it yet
+    async fn install_snapshot(
+        &self,
+        key: XorName,
+        address: Address,
+        create: SignedRegisterCreate,
+        section_auth: SectionAuth,
+        snapshot: RegisterSnapshot,
+    ) -> Result<()> {
+        let RegisterSnapshot {
+            register,
+            policy_op,
+        } = snapshot;
+        verify_snapshot(&address, &create, &register, policy_op.as_ref())?;
+
+        match self.try_load_cache_entry(&key).await {
+            Ok(entry) => {
+                let mut state = entry.state.write().await;
+                let prev_policy_version = state.policy_version();
+                let prev_policy = state.policy().clone();
+                state.merge(register).map_err(Error::NetworkData)?;
+                if (state.policy_version(), state.policy()) != (prev_policy_version, &prev_policy) {
+                    *entry.policy_op.write().await = policy_op;
+                }
+                drop(state);
+
+                // the entries merged in are not in our log, so they are kept in a snapshot
+                self.take_snapshot(&key, &entry).await
+            }
+            Err(Error::KeyNotFound(_)) => {
+                // only inserts if no value existed
+                if !self.key_db.insert_if_absent(&key, &[]).await? {
+                    return Err(Error::DataExists);
+                }
+                trace!("Creating new register from a snapshot");
+
+                let next_index = self.get_or_create_store(&key).next_index().await?;
+                let stored = StoredSnapshot {
+                    create,
+                    register,
+                    policy_op,
+                    section_auth,
+                    next_index,
+                };
+                self.put_snapshot(&key, &stored).await
+            }
+            Err(error) => Err(error),
+        }
+    }
+
     /// --- Writing ---
 
     pub(crate) async fn write(&self, cmd: RegisterCmd) -> Result<()> {
-        // rough estimate ignoring the extra space used by sled
+        // rough estimate ignoring the extra space used by the db
         let required_space = std::mem::size_of::<RegisterCmd>();
         if !self.used_space.can_add(required_space) {
             return Err(Error::NotEnoughSpace);
@@ -299,7 +384,7 @@ impl RegisterStorage {
     }
 
     async fn apply(&self, cmd: RegisterCmd) -> Result<()> {
-        // rough estimate ignoring the extra space used by sled
+        // rough estimate ignoring the extra space used by the db
         let required_space = std::mem::size_of::<RegisterCmd>();
 
         let address = cmd.dst_address();
@@ -321,21 +406,17 @@ impl RegisterStorage {
                     .verify_authority(serialize(&op)?)
                     .or(Err(Error::InvalidSignature(public_key)))?;
 
-                let old_value = None::<Vec<u8>>;
-                let new_value = Some(vec![]); // inserts empty value
-
                 // init store first, to allow append to happen asap after key insert
-                // could be races, but edge case for later todos.
-                let store = self.get_or_create_store(&key)?;
+                let store = self.get_or_create_store(&key);
 
-                // only inserts if no value existed - which is denoted by passing in `None` as old_value
-                match self.key_db.compare_and_swap(key, old_value, new_value)? {
-                    Ok(()) => trace!("Creating new register"),
-                    Err(sled::CompareAndSwapError { .. }) => return Err(Error::DataExists),
+                // only inserts if no value existed
+                if !self.key_db.insert_if_absent(&key, &[]).await? {
+                    return Err(Error::DataExists);
                 }
+                trace!("Creating new register");
 
                 // insert the op to the event log
-                let _ = store.append(cmd)?;
+                let _ = store.append(cmd).await?;
                 self.used_space.increase(required_space);
 
                 Ok(())
@@ -364,9 +445,10 @@ impl RegisterStorage {
                     .map_err(Error::NetworkData);
 
                 if result.is_ok() {
-                    entry.store.append(cmd)?;
+                    let index = entry.store.append(cmd).await?;
                     self.used_space.increase(required_space);
                     trace!("Editing Register success!");
+                    self.snapshot_if_due(&key, &entry, index).await?;
                 } else {
                     trace!("Editing Register failed!");
                 }
@@ -422,11 +504,12 @@ impl RegisterStorage {
                 let ExtendRegister { extend_with, .. } = op;
 
                 let entry = self.try_load_cache_entry(&key).await?;
-                entry.store.append(cmd)?;
 
                 let mut write = entry.state.write().await;
+                let index = entry.store.append(cmd).await?;
                 let prev = write.cap();
                 write.increment_cap(extend_with);
+                drop(write);
 
                 info!(
                     "Extended Register size from {} to {}",
@@ -435,7 +518,42 @@ impl RegisterStorage {
                 );
 
                 self.used_space.increase(required_space);
-                Ok(())
+                self.snapshot_if_due(&key, &entry, index).await
+            }
+            SetPolicy(signed) => {
+                let SignedRegisterSetPolicy { op, auth } = signed.clone();
+                let public_key = auth.public_key;
+                let _ = auth
+                    .verify_authority(serialize(&op)?)
+                    .or(Err(Error::InvalidSignature(public_key)))?;
+
+                let SetRegisterPolicy {
+                    policy, version, ..
+                } = op;
+
+                let entry = self.try_load_cache_entry(&key).await?;
+
+                let mut write = entry.state.write().await;
+                if User::Key(public_key) != write.owner() {
+                    return Err(Error::InvalidOwner(public_key));
+                }
+
+                info!("Setting Register policy version {}", version);
+                if !write
+                    .set_policy(policy, version)
+                    .map_err(Error::NetworkData)?
+                {
+                    // the same policy, or a concurrent one of this version it's ordered after
+                    trace!("Register policy version {} already applied", version);
+                    return Ok(());
+                }
+                let index = entry.store.append(cmd).await?;
+                *entry.policy_op.write().await = Some(signed);
+                drop(write);
+                self.used_space.increase(required_space);
+                trace!("Setting Register policy success!");
+
+                self.snapshot_if_due(&key, &entry, index).await
             }
         }
     }
@@ -594,23 +712,59 @@ impl RegisterStorage {
     /// =========================== Helpers ====================================
     /// ========================================================================
 
+    // names of all the registers stored
+    async fn register_names(&self) -> Result<Vec<XorName>> {
+        type KeyResults = Vec<Result<XorName>>;
+
+        let (ok, err): (KeyResults, KeyResults) = self
+            .key_db
+            .list(&[])
+            .await?
+            .into_iter()
+            .map(|src_key| {
+                // we expect xornames as keys
+                if src_key.len() != XOR_NAME_LEN {
+                    return Err(Error::CouldNotParseDbKey(src_key));
+                }
+                let mut dst_key: [u8; 32] = Default::default();
+                dst_key.copy_from_slice(&src_key);
+
+                Ok(XorName(dst_key))
+            })
+            .partition(|r| r.is_ok());
+
+        if !err.is_empty() {
+            for e in err {
+                error!("{:?}", e);
+            }
+            return Err(Error::CouldNotConvertDbKey);
+        }
+
+        Ok(ok.into_iter().flatten().collect())
+    }
+
     // get or create a register op store
-    fn get_or_create_store(&self, id: &XorName) -> Result<RegOpStore> {
-        RegOpStore::new(id, self.reg_db.clone()).map_err(Error::from)
+    fn get_or_create_store(&self, id: &XorName) -> RegOpStore {
+        RegOpStore::new(id, self.reg_db.clone())
     }
 
-    // helper that drops the sled tree for a given register
+    // helper that drops the op log for a given register
     // decreases the used space by a rough estimate of the size before deletion
-    // as with addition this estimate ignores the extra space used by sled
+    // as with addition this estimate ignores the extra space used by the db
     // (that estimate can fall victim to a race condition if someone writes to a register that is being deleted)
     async fn drop_register_key(&self, key: XorName) -> Result<()> {
         let regcmd_size = std::mem::size_of::<RegisterCmd>();
-        let reg_tree = self.reg_db.open_tree(key)?;
-        let len = reg_tree.len();
-        let key_used_space = len * regcmd_size;
+        let store = self.get_or_create_store(&key);
+        let len = store.len().await?;
+        let snapshot_size = match self.snapshot_db.get(&key).await? {
+            Some(snapshot) => snapshot.len(),
+            None => 0,
+        };
+        let key_used_space = len * regcmd_size + snapshot_size;
 
-        let _removed = self.key_db.remove(key)?;
-        let _removed = self.reg_db.drop_tree(key)?;
+        self.key_db.delete(&key).await?;
+        store.clear().await?;
+        self.snapshot_db.delete(&key).await?;
 
         self.cache.remove(&key).await;
         self.used_space.decrease(key_used_space);
@@ -618,6 +772,71 @@ impl RegisterStorage {
         Ok(())
     }
 
+    // takes a snapshot of the register once enough ops were appended to its log since the latest one
+    async fn snapshot_if_due(
+        &self,
+        key: &XorName,
+        entry: &CacheEntry,
+        appended_at: u64,
+    ) -> Result<()> {
+        let ops_since_snapshot =
+            (appended_at + 1).saturating_sub(entry.snapshot_index.load(Ordering::SeqCst));
+        if ops_since_snapshot >= OPS_PER_SNAPSHOT {
+            self.take_snapshot(key, entry).await?;
+        }
+        Ok(())
+    }
+
+    // stores the current state of the register, and drops the ops it covers from its log
+    async fn take_snapshot(&self, key: &XorName, entry: &CacheEntry) -> Result<()> {
+        // no ops are applied, nor replicas created, while the snapshot is taken,
+        // so it covers all the ops before `next_index`
+        let state = entry.state.write().await;
+        let next_index = entry.store.next_index().await?;
+        let stored = StoredSnapshot {
+            create: entry.create.clone(),
+            register: state.clone(),
+            policy_op: entry.policy_op.read().await.clone(),
+            section_auth: entry.section_auth.clone(),
+            next_index,
+        };
+        self.put_snapshot(key, &stored).await?;
+        entry.snapshot_index.store(next_index, Ordering::SeqCst);
+
+        let dropped = entry.store.truncate(next_index).await?;
+        drop(state);
+        self.used_space
+            .decrease(dropped * std::mem::size_of::<RegisterCmd>());
+        debug!(
+            "Took a snapshot of register {}, dropping {} ops from its log",
+            key, dropped
+        );
+
+        Ok(())
+    }
+
+    async fn get_snapshot(&self, key: &XorName) -> Result<Option<StoredSnapshot>> {
+        match self.snapshot_db.get(key).await? {
+            Some(snapshot) => Ok(Some(deserialise(&snapshot)?)),
+            None => Ok(None),
+        }
+    }
+
+    // replaces the snapshot of the register, accounting for the change in its size
+    async fn put_snapshot(&self, key: &XorName, stored: &StoredSnapshot) -> Result<()> {
+        let snapshot = serialise(stored)?;
+        let prev_size = match self.snapshot_db.get(key).await? {
+            Some(prev) => prev.len(),
+            None => 0,
+        };
+
+        self.snapshot_db.put(key, &snapshot).await?;
+        self.used_space.decrease(prev_size);
+        self.used_space.increase(snapshot.len());
+
+        Ok(())
+    }
+
     // gets entry from the cache, or populates cache from disk if expired
     async fn try_load_cache_entry(&self, key: &XorName) -> Result<Arc<CacheEntry>> {
         let entry = self.cache.get(key).await;
@@ -627,31 +846,42 @@ impl RegisterStorage {
             return Ok(entry);
         }
 
-        // read from disk
-        let store = self.get_or_create_store(key)?;
-        let mut hydrated_register = None;
-        // apply all ops
+        // read from disk, starting from the latest snapshot if there's one
+        let store = self.get_or_create_store(key);
+        let (mut hydrated_register, mut policy_op, snapshot_index) =
+            match self.get_snapshot(key).await? {
+                Some(StoredSnapshot {
+                    create,
+                    register,
+                    policy_op,
+                    section_auth,
+                    next_index,
+                }) => (
+                    Some((register, create, section_auth)),
+                    policy_op,
+                    next_index,
+                ),
+                None => (None, None, 0),
+            };
+        // apply all ops not covered by the snapshot
         use RegisterCmd::*;
-        for stored_cmd in store.get_all()? {
+        for stored_cmd in store.get_from(snapshot_index).await? {
             match stored_cmd {
                 // first op would be create
-                Create {
-                    cmd: SignedRegisterCreate { op, .. },
-                    section_auth,
-                } => {
-                    hydrated_register = match op {
+                Create { cmd, section_auth } => {
+                    hydrated_register = match cmd.op.clone() {
                         CreateRegister::Empty {
                             name,
                             tag,
                             size,
                             policy,
-                        } => Some((Register::new(name, tag, policy, size), section_auth)),
+                        } => Some((Register::new(name, tag, policy, size), cmd, section_auth)),
                         CreateRegister::Populated(instance) => {
                             if instance.size() > (u16::MAX as u64) {
                                 // this would mean the instance has been modified on disk outside of the software
                                 warn!("Data corruption! Encountered stored register with {} entries, wich is larger than max size of {}", instance.size(), u16::MAX);
                             }
-                            Some((instance, section_auth))
+                            Some((instance, cmd, section_auth))
                         }
                     };
                 }
@@ -659,7 +889,7 @@ impl RegisterStorage {
                     op: EditRegister { edit, .. },
                     ..
                 }) => {
-                    if let Some((reg, _)) = &mut hydrated_register {
+                    if let Some((reg, ..)) = &mut hydrated_register {
                         reg.apply_op(edit).map_err(Error::NetworkData)?
                     }
                 }
@@ -675,20 +905,39 @@ impl RegisterStorage {
                         },
                     ..
                 } => {
-                    if let Some((reg, _)) = &mut hydrated_register {
+                    if let Some((reg, ..)) = &mut hydrated_register {
                         reg.increment_cap(extend_with);
                     }
                 }
+                SetPolicy(signed) => {
+                    if let Some((reg, ..)) = &mut hydrated_register {
+                        let SetRegisterPolicy {
+                            policy, version, ..
+                        } = signed.op.clone();
+                        // a stored op can be older than the policy the snapshot or a replica
+                        // merged in already holds, which must not keep the register from loading
+                        match reg.set_policy(policy, version) {
+                            Ok(true) => policy_op = Some(signed),
+                            Ok(false) => {}
+                            Err(error) => {
+                                warn!("Skipping stored Register policy op: {:?}", error)
+                            }
+                        }
+                    }
+                }
             }
         }
 
         match hydrated_register {
             None => Err(Error::KeyNotFound(key.to_string())), // nothing found on disk
-            Some((reg, section_auth)) => {
+            Some((reg, create, section_auth)) => {
                 let entry = Arc::new(CacheEntry {
                     state: Arc::new(RwLock::new(reg)),
                     store,
                     section_auth,
+                    create,
+                    policy_op: Arc::new(RwLock::new(policy_op)),
+                    snapshot_index: Arc::new(AtomicU64::new(snapshot_index)),
                 });
                 // populate cache
                 self.cache.insert(key, entry.clone()).await;
@@ -698,6 +947,59 @@ impl RegisterStorage {
     }
 }
 
+// verifies that the policy of a replicated snapshot was set by the owner of the register, through
+// the signed op shipped with it, or is the one the register was created with
+fn verify_snapshot(
+    address: &Address,
+    create: &SignedRegisterCreate,
+    register: &Register,
+    policy_op: Option<&SignedRegisterSetPolicy>,
+) -> Result<()> {
+    let public_key = create.auth.public_key;
+    let _ = create
+        .auth
+        .clone()
+        .verify_authority(serialize(&create.op)?)
+        .or(Err(Error::InvalidSignature(public_key)))?;
+    if create.op.address() != *address || register.address() != address {
+        return Err(Error::InvalidRegisterSnapshot(format!(
+            "not a snapshot of the register at {:?}",
+            address
+        )));
+    }
+
+    let (policy, version) = match policy_op {
+        Some(SignedRegisterSetPolicy { op, auth }) => {
+            let public_key = auth.public_key;
+            let _ = auth
+                .clone()
+                .verify_authority(serialize(op)?)
+                .or(Err(Error::InvalidSignature(public_key)))?;
+            if User::Key(public_key) != create.op.owner() {
+                return Err(Error::InvalidOwner(public_key));
+            }
+            if op.address != *address {
+                return Err(Error::InvalidRegisterSnapshot(format!(
+                    "policy op of another register: {:?}",
+                    op.address
+                )));
+            }
+            (&op.policy, op.version)
+        }
+        None => match &create.op {
+            CreateRegister::Empty { policy, .. } => (policy, 0),
+            CreateRegister::Populated(instance) => (instance.policy(), instance.policy_version()),
+        },
+    };
+    if (register.policy(), register.policy_version()) != (policy, version) {
+        return Err(Error::InvalidRegisterSnapshot(
+            "policy not set by the signed op shipped with it".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 impl Display for RegisterStorage {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         write!(formatter, "RegisterStorage")
@@ -706,17 +1008,22 @@ impl Display for RegisterStorage {
 
 #[cfg(test)]
 mod test {
-    use super::RegisterStorage;
+    use super::{RegisterStorage, KEY_DB_NAME, OPS_PER_SNAPSHOT, REG_DB_NAME};
+
+    use crate::dbs::serialise;
 
     use crate::messaging::SectionAuth;
     use crate::node::{Error, Result};
-    use crate::types::register::{EntryHash, PrivatePolicy};
+    use crate::types::register::{EntryHash, PrivatePermissions, PrivatePolicy};
     use crate::types::DataAddress;
     use crate::types::{register::User, Keypair};
     use crate::UsedSpace;
     use crate::{
         messaging::{
-            data::{CreateRegister, RegisterCmd, RegisterQuery, SignedRegisterCreate},
+            data::{
+                CreateRegister, RegisterCmd, RegisterQuery, RegisterStoreExport, SetRegisterPolicy,
+                SignedRegisterCreate, SignedRegisterSetPolicy,
+            },
             system::NodeQueryResponse,
             ServiceAuth,
         },
@@ -768,12 +1075,392 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_set_policy() -> Result<()> {
+        // setup store
+        let store = new_store().await?;
+
+        // create register
+        let (authority, keypair) = random_user();
+        let policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions: Default::default(),
+        });
+        let cmd = create_reg_w_policy(policy, keypair.clone())?;
+        let _ = store.write(cmd.clone()).await?;
+        let address = cmd.dst_address();
+
+        // grant a user permissions by setting a new policy
+        let (user, user_keypair) = random_user();
+        let mut permissions = std::collections::BTreeMap::new();
+        let _ = permissions.insert(user, PrivatePermissions::new(true, true));
+        let policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions,
+        });
+        let grant = set_policy_cmd(address, policy.clone(), 1, &keypair)?;
+        let _ = store.write(grant.clone()).await?;
+
+        let res = store
+            .read(
+                &RegisterQuery::GetUserPermissions { address, user },
+                authority,
+            )
+            .await;
+        match res {
+            NodeQueryResponse::GetRegisterUserPermissions((Ok(_), _)) => {}
+            e => panic!("User should have been granted permissions! {:?}", e),
+        }
+
+        // only the owner can set the policy
+        let res = store
+            .write(set_policy_cmd(address, policy, 2, &user_keypair)?)
+            .await;
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            Error::InvalidOwner(user_keypair.public_key()).to_string(),
+            "Only the owner should be able to set the policy!"
+        );
+
+        // the owner of the register can't be changed
+        let policy = Policy::Private(PrivatePolicy {
+            owner: user,
+            permissions: Default::default(),
+        });
+        let res = store
+            .write(set_policy_cmd(address, policy, 2, &keypair)?)
+            .await;
+        assert!(res.is_err(), "Should not be able to change the owner!");
+
+        // revoke the permissions with a newer version of the policy
+        let policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions: Default::default(),
+        });
+        let _ = store
+            .write(set_policy_cmd(address, policy, 2, &keypair)?)
+            .await?;
+
+        // the older policy granting them can't be replayed
+        let res = store.write(grant).await;
+        assert!(
+            matches!(
+                res,
+                Err(crate::dbs::Error::NetworkData(
+                    crate::types::Error::StalePolicyVersion { .. }
+                ))
+            ),
+            "Should not be able to replay an older policy! {:?}",
+            res
+        );
+        let res = store
+            .read(
+                &RegisterQuery::GetUserPermissions { address, user },
+                authority,
+            )
+            .await;
+        match res {
+            NodeQueryResponse::GetRegisterUserPermissions((Err(_), _)) => {}
+            e => panic!("User permissions should have been revoked! {:?}", e),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_concurrent_policies_converge() -> Result<()> {
+        let (authority, keypair) = random_user();
+        let policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions: Default::default(),
+        });
+        let cmd = create_reg_w_policy(policy, keypair.clone())?;
+        let address = cmd.dst_address();
+        let key = address.id()?;
+
+        // two concurrent policies of the same version, each granting another user permissions
+        let (first_user, second_user) = (random_user().0, random_user().0);
+        let policy_granting = |user: User| {
+            let mut permissions = std::collections::BTreeMap::new();
+            let _ = permissions.insert(user, PrivatePermissions::new(true, true));
+            Policy::Private(PrivatePolicy {
+                owner: authority,
+                permissions,
+            })
+        };
+        let first = set_policy_cmd(address, policy_granting(first_user), 1, &keypair)?;
+        let second = set_policy_cmd(address, policy_granting(second_user), 1, &keypair)?;
+
+        // the stores get them in different orders, and load the register from disk again
+        let mut granted = vec![];
+        for ops in [[&first, &second], [&second, &first]] {
+            let store = new_store().await?;
+            let _ = store.write(cmd.clone()).await?;
+            for op in ops {
+                let _ = store.write(op.clone()).await?;
+            }
+            store.cache.remove(&key).await;
+
+            let mut users = vec![];
+            for user in [first_user, second_user] {
+                let res = store
+                    .read(
+                        &RegisterQuery::GetUserPermissions { address, user },
+                        authority,
+                    )
+                    .await;
+                if let NodeQueryResponse::GetRegisterUserPermissions((Ok(_), _)) = res {
+                    users.push(user);
+                }
+            }
+            granted.push(users);
+        }
+
+        assert_eq!(granted[0].len(), 1);
+        assert_eq!(granted[0], granted[1]);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_snapshot_truncates_log() -> Result<()> {
+        // setup store
+        let store = new_store().await?;
+
+        // create register
+        let (authority, keypair) = random_user();
+        let policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions: Default::default(),
+        });
+        let cmd = create_reg_w_policy(policy, keypair.clone())?;
+        let _ = store.write(cmd.clone()).await?;
+        let address = cmd.dst_address();
+        let key = address.id()?;
+
+        // apply enough ops, along with the create, for a snapshot to be taken
+        let mut user = authority;
+        for version in 1..OPS_PER_SNAPSHOT {
+            user = random_user().0;
+            let mut permissions = std::collections::BTreeMap::new();
+            let _ = permissions.insert(user, PrivatePermissions::new(true, true));
+            let policy = Policy::Private(PrivatePolicy {
+                owner: authority,
+                permissions,
+            });
+            let _ = store
+                .write(set_policy_cmd(address, policy, version, &keypair)?)
+                .await?;
+        }
+
+        // only the latest op is kept in the log
+        assert_eq!(store.get_or_create_store(&key).len().await?, 1);
+
+        // the register is restored from the snapshot once evicted from the cache
+        store.cache.remove(&key).await;
+        let res = store
+            .read(
+                &RegisterQuery::GetUserPermissions { address, user },
+                authority,
+            )
+            .await;
+        match res {
+            NodeQueryResponse::GetRegisterUserPermissions((Ok(_), _)) => {}
+            e => panic!("Latest policy should have been restored! {:?}", e),
+        }
+
+        // the snapshot is shipped instead of the ops it covers, along with the create op
+        let for_update = store.get_data_of(Prefix::new(0, cmd.name())).await?;
+        let RegisterStoreExport(logs) = &for_update;
+        assert!(logs[0].snapshot.is_some());
+        assert_eq!(logs[0].op_log, vec![cmd.clone()]);
+
+        let new_store = new_store().await?;
+        let _ = new_store.update(for_update).await?;
+        let res = new_store
+            .read(
+                &RegisterQuery::GetUserPermissions { address, user },
+                authority,
+            )
+            .await;
+        match res {
+            NodeQueryResponse::GetRegisterUserPermissions((Ok(_), _)) => {}
+            e => panic!("Snapshot should have been replicated! {:?}", e),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_forged_snapshot_is_not_installed() -> Result<()> {
+        // setup store
+        let store = new_store().await?;
+
+        // create register, and apply enough ops for a snapshot to be taken
+        let (authority, keypair) = random_user();
+        let policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions: Default::default(),
+        });
+        let cmd = create_reg_w_policy(policy, keypair.clone())?;
+        let _ = store.write(cmd.clone()).await?;
+        let address = cmd.dst_address();
+        for version in 1..OPS_PER_SNAPSHOT {
+            let policy = Policy::Private(PrivatePolicy {
+                owner: authority,
+                permissions: Default::default(),
+            });
+            let _ = store
+                .write(set_policy_cmd(address, policy, version, &keypair)?)
+                .await?;
+        }
+
+        // a snapshot where a user was granted permissions by a policy the owner never signed
+        let mut for_update = store.get_data_of(Prefix::new(0, cmd.name())).await?;
+        let (user, _) = random_user();
+        let mut permissions = std::collections::BTreeMap::new();
+        let _ = permissions.insert(user, PrivatePermissions::new(true, true));
+        let policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions,
+        });
+        let snapshot = for_update.0[0]
+            .snapshot
+            .as_mut()
+            .expect("a snapshot should have been taken");
+        assert!(snapshot.register.set_policy(policy, OPS_PER_SNAPSHOT)?);
+
+        let new_store = new_store().await?;
+        let _ = new_store.update(for_update).await?;
+        let res = new_store
+            .read(
+                &RegisterQuery::GetUserPermissions { address, user },
+                authority,
+            )
+            .await;
+        match res {
+            NodeQueryResponse::GetRegisterUserPermissions((Err(_), _)) => {}
+            e => panic!("Forged snapshot should not have been installed! {:?}", e),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_replica_with_forged_op_is_not_applied() -> Result<()> {
+        // setup store
+        let store = new_store().await?;
+
+        // create register
+        let (authority, keypair) = random_user();
+        let policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions: Default::default(),
+        });
+        let cmd = create_reg_w_policy(policy, keypair.clone())?;
+        let _ = store.write(cmd.clone()).await?;
+        let address = cmd.dst_address();
+
+        // a replica where a user was granted permissions by a policy the owner never signed
+        let mut for_update = store.get_data_of(Prefix::new(0, cmd.name())).await?;
+        let (user, _) = random_user();
+        let mut permissions = std::collections::BTreeMap::new();
+        let _ = permissions.insert(user, PrivatePermissions::new(true, true));
+        let policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions,
+        });
+        let signed_policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions: Default::default(),
+        });
+        let forged = match set_policy_cmd(address, signed_policy, 1, &keypair)? {
+            RegisterCmd::SetPolicy(SignedRegisterSetPolicy { mut op, auth }) => {
+                // the owner's signature is kept over another policy
+                op.policy = policy;
+                RegisterCmd::SetPolicy(SignedRegisterSetPolicy { op, auth })
+            }
+            _ => unreachable!(),
+        };
+        for_update.0[0].op_log.push(forged);
+
+        let new_store = new_store().await?;
+        let _ = new_store.update(for_update).await?;
+        let res = new_store
+            .read(
+                &RegisterQuery::GetUserPermissions { address, user },
+                authority,
+            )
+            .await;
+        match res {
+            NodeQueryResponse::GetRegisterUserPermissions((Err(_), _)) => {}
+            e => panic!("Forged policy should not have been applied! {:?}", e),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_store_of_former_layout_is_read() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path();
+
+        let (authority, keypair) = random_user();
+        let policy = Policy::Private(PrivatePolicy {
+            owner: authority,
+            permissions: Default::default(),
+        });
+        let cmd = create_reg_w_policy(policy, keypair.clone())?;
+        let address = cmd.dst_address();
+        let key = address.id()?;
+
+        // a store from before the storage backends, with a sled tree per op log, keyed by
+        // the decimal index of each op, and enough ops for their string order to differ
+        let mut user = authority;
+        {
+            let key_db = sled::open(path.join("db").join(KEY_DB_NAME))?;
+            let _ = key_db.insert(key, &[])?;
+            let reg_db = sled::open(path.join("db").join(REG_DB_NAME))?;
+            let log = reg_db.open_tree(key)?;
+            let _ = log.insert("0", serialise(&cmd)?)?;
+            for version in 1..12 {
+                user = random_user().0;
+                let mut permissions = std::collections::BTreeMap::new();
+                let _ = permissions.insert(user, PrivatePermissions::new(true, true));
+                let policy = Policy::Private(PrivatePolicy {
+                    owner: authority,
+                    permissions,
+                });
+                let op = set_policy_cmd(address, policy, version, &keypair)?;
+                let _ = log.insert(version.to_string(), serialise(&op)?)?;
+            }
+            let _ = key_db.flush()?;
+            let _ = reg_db.flush()?;
+        }
+
+        let store = RegisterStorage::new(path, UsedSpace::new(usize::MAX), None).await?;
+        assert_eq!(store.keys().await?, vec![address]);
+        assert_eq!(store.get_or_create_store(&key).len().await?, 12);
+        let res = store
+            .read(
+                &RegisterQuery::GetUserPermissions { address, user },
+                authority,
+            )
+            .await;
+        match res {
+            NodeQueryResponse::GetRegisterUserPermissions((Ok(_), _)) => {}
+            e => panic!("Latest policy should have been read! {:?}", e),
+        }
+
+        Ok(())
+    }
+
     async fn register_write<F>(create_register: F) -> Result<()>
     where
         F: Fn() -> Result<(RegisterCmd, User)>,
     {
         // setup store
-        let store = new_store()?;
+        let store = new_store().await?;
 
         // create register
         let (cmd, authority) = create_register()?;
@@ -810,7 +1497,7 @@ mod test {
         F: Fn() -> Result<(RegisterCmd, User)>,
     {
         // setup store
-        let store = new_store()?;
+        let store = new_store().await?;
 
         // create register
         let (cmd, authority) = create_register()?;
@@ -842,7 +1529,7 @@ mod test {
         F: Fn() -> Result<(RegisterCmd, User)>,
     {
         // setup store
-        let store = new_store()?;
+        let store = new_store().await?;
 
         // create register
         let (cmd, authority) = create_register()?;
@@ -854,7 +1541,7 @@ mod test {
         let for_update = store.get_data_of(prefix).await?;
 
         // create new db and update it with the data from first db
-        let new_store = new_store()?;
+        let new_store = new_store().await?;
 
         let _ = new_store.update(for_update).await?;
         let address = cmd.dst_address();
@@ -890,7 +1577,7 @@ mod test {
         F: Fn() -> Result<(RegisterCmd, User)>,
     {
         // setup store
-        let store = new_store()?;
+        let store = new_store().await?;
 
         // create register
         let (cmd, authority) = create_register()?;
@@ -921,7 +1608,7 @@ mod test {
         F: Fn() -> Result<(RegisterCmd, User)>,
     {
         // setup store
-        let store = new_store()?;
+        let store = new_store().await?;
 
         // create register
         let (cmd, authority) = create_register()?;
@@ -951,11 +1638,11 @@ mod test {
         Ok(())
     }
 
-    fn new_store() -> Result<RegisterStorage> {
+    async fn new_store() -> Result<RegisterStorage> {
         let tmp_dir = tempdir()?;
         let path = tmp_dir.path();
         let used_space = UsedSpace::new(usize::MAX);
-        let store = RegisterStorage::new(path, used_space)?;
+        let store = RegisterStorage::new(path, used_space, None).await?;
         Ok(store)
     }
 
@@ -1004,6 +1691,27 @@ mod test {
         })
     }
 
+    fn set_policy_cmd(
+        address: crate::types::RegisterAddress,
+        policy: Policy,
+        version: u64,
+        keypair: &Keypair,
+    ) -> Result<RegisterCmd> {
+        let op = SetRegisterPolicy {
+            address,
+            policy,
+            version,
+        };
+        let signature = keypair.sign(&bincode::serialize(&op)?);
+
+        let auth = ServiceAuth {
+            public_key: keypair.public_key(),
+            signature,
+        };
+
+        Ok(RegisterCmd::SetPolicy(SignedRegisterSetPolicy { op, auth }))
+    }
+
     fn section_auth() -> SectionAuth {
         use crate::messaging::system::KeyedSig;
 
@@ -40,6 +40,10 @@ impl Node {
                 *self.joins_allowed.write().await = joins_allowed;
                 Ok(vec![])
             }
+            Proposal::RewardPayout(payout) => {
+                self.handle_reward_payout_agreement(payout).await;
+                Ok(vec![])
+            }
         }
     }
 
@@ -71,7 +75,7 @@ impl Node {
             if new_age >= MIN_ADULT_AGE {
                 // TODO: consider handling the relocation inside the bootstrap phase, to avoid
                 // having to send this `NodeApproval`.
-                cmds.extend(self.send_node_approval(old_info.clone()).await);
+                cmds.extend(self.send_node_approval(old_info.clone()).await?);
 
                 cmds.extend(
                     self.relocate_rejoining_peer(old_info.value, new_age)
@@ -133,7 +137,7 @@ impl Node {
         }
 
         cmds.extend(result);
-        cmds.extend(self.send_node_approval(new_info).await);
+        cmds.extend(self.send_node_approval(new_info).await?);
 
         info!("cmds in queue for Accepting node {:?}", cmds);
 
@@ -324,7 +328,7 @@ impl Node {
                     Ok(true) => {
                         info!("Updated our network knowledge for {:?}", prefix);
                         info!("Writing updated knowledge to disk");
-                        self.write_prefix_map().await
+                        self.write_network_knowledge().await
                     }
                     _ => {}
                 },
@@ -226,9 +226,9 @@ impl Node {
             )
             .await?
         {
-            self.write_prefix_map().await;
+            self.write_network_knowledge().await;
             info!(
-                "PrefixMap written to disk with update for prefix {:?}",
+                "Network knowledge written to disk with update for prefix {:?}",
                 prefix
             );
         }
@@ -469,7 +469,7 @@ mod tests {
         },
         NodeInfo, XorName, MIN_ADULT_AGE,
     };
-    use crate::UsedSpace;
+    use crate::{StorageBackendKind, UsedSpace};
 
     use assert_matches::assert_matches;
     use bls::SecretKey;
@@ -700,6 +700,8 @@ mod tests {
                 mpsc::channel(1).0,
                 UsedSpace::new(max_capacity),
                 root_storage_dir,
+                Some(StorageBackendKind::Memory),
+                None,
                 genesis_sk_set.clone(),
             )
             .await?;
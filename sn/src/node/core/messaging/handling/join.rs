@@ -41,6 +41,8 @@ impl Node {
         if let Some(response) = join_request.aggregated {
             if response.verify(&self.section_chain().await) {
                 info!("Handling Online agreement of {:?}", peer);
+                self.record_reward_wallet(peer.name(), join_request.reward_key)
+                    .await;
                 return Ok(vec![Cmd::HandleNewNodeOnline(response)]);
             }
         }
@@ -114,6 +116,25 @@ impl Node {
             ]);
         }
 
+        let storage_policy = self.network_knowledge.storage_policy().await?;
+        if let Some(requested) = join_request.storage_policy {
+            if requested != storage_policy {
+                debug!(
+                    "Rejecting JoinRequest from {} - storage policy {:?} differs from ours {:?}.",
+                    peer, requested, storage_policy
+                );
+                let node_msg = SystemMsg::JoinResponse(Box::new(JoinResponse::Rejected(
+                    JoinRejectionReason::StoragePolicyMismatch(storage_policy),
+                )));
+
+                trace!("Sending {:?} to {}", node_msg, peer);
+                return Ok(vec![
+                    self.send_direct_msg(peer, node_msg, our_section_key)
+                        .await?,
+                ]);
+            }
+        }
+
         if !*self.joins_allowed.read().await {
             debug!(
                 "Rejecting JoinRequest from {} - joins currently not allowed.",
@@ -13,6 +13,7 @@ mod join;
 mod proposals;
 mod relocation;
 mod resource_proof;
+mod rewards;
 mod service_msgs;
 mod update_section;
 
@@ -20,7 +21,7 @@ pub(crate) use proposals::handle_proposal;
 
 use crate::dbs::Error as DbError;
 use crate::messaging::{
-    data::{ServiceMsg, StorageLevel},
+    data::{MetadataExchange, ServiceMsg, StorageLevel},
     signature_aggregator::Error as AggregatorError,
     system::{
         JoinRequest, JoinResponse, NodeCmd, NodeEvent, NodeQuery, SectionAuth as SystemSectionAuth,
@@ -30,17 +31,17 @@ use crate::messaging::{
 };
 use crate::node::{
     api::cmds::Cmd,
-    core::{DkgSessionInfo, Node, DATA_QUERY_LIMIT},
+    core::{DkgSessionInfo, Node, Proposal, DATA_QUERY_LIMIT},
     messages::{NodeMsgAuthorityUtils, WireMsgUtils},
     network_knowledge::NetworkKnowledge,
     Error, Event, MessageReceived, Result, MIN_LEVEL_WHEN_FULL,
 };
-use crate::types::{log_markers::LogMarker, Peer, PublicKey};
+use crate::types::{log_markers::LogMarker, Peer, PublicKey, ReplicatedData};
 
 use bls::PublicKey as BlsPublicKey;
 use bytes::Bytes;
 use itertools::Itertools;
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, iter};
 use tokio::time::Duration;
 use xor_name::XorName;
 
@@ -84,6 +85,7 @@ impl Node {
                 return Ok(cmds);
             }
         };
+        self.comm.count_incoming_kind(message_type.kind());
 
         match message_type {
             MsgType::System {
@@ -443,6 +445,7 @@ impl Node {
                                     let new_name = new_node.name();
                                     let previous_name = self.info.read().await.name();
                                     let new_keypair = new_node.keypair.clone();
+                                    let reward_key = new_node.reward_key;
 
                                     info!(
                                         "Relocation: switching from {:?} to {:?}",
@@ -483,6 +486,14 @@ impl Node {
                                             .map(|member| member.into_authed_state())
                                             .collect(),
                                     );
+                                    // the storage policy is the same across the network
+                                    if let Some(storage_policy) =
+                                        self.network_knowledge.signed_storage_policy().await
+                                    {
+                                        new_network_knowledge
+                                            .set_storage_policy(storage_policy)
+                                            .await?;
+                                    }
 
                                     // TODO: confirm whether carry out the switch immediately here
                                     //       or still using the cmd pattern.
@@ -500,6 +511,8 @@ impl Node {
                                         section_key,
                                         resource_proof_response: None,
                                         aggregated: Some(auth),
+                                        reward_key,
+                                        storage_policy: None,
                                     };
 
                                     trace!(
@@ -585,15 +598,25 @@ impl Node {
 
                 trace!("Handling msg: Propose from {}: {:?}", sender, msg_id);
 
-                handle_proposal(
-                    msg_id,
-                    proposal.into_state(),
-                    sig_share,
-                    sender,
-                    &self.network_knowledge,
-                    &self.proposal_aggregator,
-                )
-                .await
+                let proposal = proposal.into_state();
+                let mut cmds = vec![];
+                // Reward payouts are proposed by one of us Elders, and signed by the rest
+                if let Proposal::RewardPayout(payout) = &proposal {
+                    cmds.extend(self.sign_reward_payout(payout, &sender).await?);
+                }
+
+                cmds.extend(
+                    handle_proposal(
+                        msg_id,
+                        proposal,
+                        sig_share,
+                        sender,
+                        &self.network_knowledge,
+                        &self.proposal_aggregator,
+                    )
+                    .await?,
+                );
+                Ok(cmds)
             }
             SystemMsg::DkgStart {
                 session_id,
@@ -664,11 +687,50 @@ impl Node {
                 }
                 Ok(vec![])
             }
+            SystemMsg::NodeCmd(NodeCmd::Leave) => {
+                trace!("Handling msg: Leave from {}: {:?}", sender, msg_id);
+                if self.is_not_elder().await {
+                    return Ok(vec![]);
+                }
+                // Only the leaving node itself can tell us it's leaving.
+                if !matches!(msg_authority, NodeMsgAuthority::Node(_)) {
+                    warn!(
+                        "Dropping Leave msg not signed by the leaving node: {:?}",
+                        msg_id
+                    );
+                    return Ok(vec![]);
+                }
+
+                self.handle_leaving_node(src_name).await
+            }
             SystemMsg::NodeCmd(NodeCmd::ReceiveMetadata { metadata }) => {
                 info!("Processing received MetadataExchange packet: {:?}", msg_id);
-                self.set_adult_levels(metadata).await;
+                let MetadataExchange {
+                    adult_levels,
+                    reward_ledger,
+                } = metadata;
+                self.set_adult_levels(adult_levels).await;
+                // rewards are only handed over among the Elders of the section
+                if self.is_from_our_elders(&msg_authority, &src_name).await {
+                    self.sync_reward_ledger(reward_ledger).await;
+                } else {
+                    warn!(
+                        "Ignoring reward ledger from {}, which isn't one of our Elders",
+                        src_name
+                    );
+                }
                 Ok(vec![])
             }
+            SystemMsg::NodeEvent(NodeEvent::QueriesServed { epoch, served }) => {
+                if !self.is_from_our_elders(&msg_authority, &src_name).await {
+                    warn!(
+                        "Dropping QueriesServed from {}, which isn't one of our Elders",
+                        src_name
+                    );
+                    return Ok(vec![]);
+                }
+                self.handle_queries_served(src_name, epoch, served).await
+            }
             SystemMsg::NodeEvent(NodeEvent::CouldNotStoreData {
                 node_id,
                 data,
@@ -702,6 +764,21 @@ impl Node {
 
                 return self.republish_data_for_deviant_nodes(deviants).await;
             }
+            SystemMsg::NodeEvent(NodeEvent::NodeLeaving(name)) => {
+                // Only our Elders can tell us a node is leaving
+                if self.is_elder().await {
+                    return Ok(vec![]);
+                } else if !self.is_from_our_elders(&msg_authority, &src_name).await {
+                    warn!(
+                        "Dropping NodeLeaving msg not sent by our Elders: {:?}",
+                        msg_id
+                    );
+                    return Ok(vec![]);
+                }
+
+                info!("Node {name} is leaving, replicating the data held along with it");
+                return self.republish_data_of_leaving_node(name).await;
+            }
             SystemMsg::NodeCmd(NodeCmd::ReplicateData(data_collection)) => {
                 info!("ReplicateData MsgId: {:?}", msg_id);
                 return if self.is_elder().await {
@@ -709,44 +786,50 @@ impl Node {
                     Ok(vec![])
                 } else {
                     let mut cmds = vec![];
-
                     for data in data_collection {
-                        // We are an adult here, so just store away!
-                        // This may return a DatabaseFull error... but we should have reported storage increase
-                        // well before this
-                        match self.data_storage.store(&data).await {
-                            Ok(level_report) => {
-                                info!("Storage level report: {:?}", level_report);
-                                cmds.extend(self.record_storage_level_if_any(level_report).await);
-                            }
-                            Err(error) => {
-                                match error {
-                                    DbError::NotEnoughSpace => {
-                                        // db full
-                                        error!("Not enough space to store more data");
-
-                                        let node_id =
-                                            PublicKey::from(self.info.read().await.keypair.public);
-                                        let msg =
-                                            SystemMsg::NodeEvent(NodeEvent::CouldNotStoreData {
-                                                node_id,
-                                                data,
-                                                full: true,
-                                            });
-
-                                        cmds.push(self.send_msg_to_our_elders(msg).await?)
-                                    }
-                                    _ => {
-                                        error!("Problem storing data, but it was ignored: {error}");
-                                    } // the rest seem to be non-problematic errors.. (?)
-                                }
-                            }
-                        }
+                        let (_stored, store_cmds) = self.store_replica(data).await?;
+                        cmds.extend(store_cmds);
                     }
 
                     Ok(cmds)
                 };
             }
+            SystemMsg::NodeCmd(NodeCmd::StoreData {
+                data,
+                correlation_id,
+            }) => {
+                info!("StoreData MsgId: {:?}", msg_id);
+                return if self.is_elder().await {
+                    error!("Received unexpected message while Elder");
+                    Ok(vec![])
+                } else {
+                    let (stored, mut cmds) = self.store_replica(data).await?;
+                    // the Elder acks the client's write once enough of us stored the data
+                    if stored {
+                        cmds.push(Cmd::SignOutgoingSystemMsg {
+                            msg: SystemMsg::NodeEvent(NodeEvent::DataStored { correlation_id }),
+                            dst: DstLocation::Node {
+                                name: src_name,
+                                section_pk: self.network_knowledge.section_key().await,
+                            },
+                        });
+                    }
+
+                    Ok(cmds)
+                };
+            }
+            SystemMsg::NodeEvent(NodeEvent::DataStored { correlation_id }) => {
+                info!(
+                    "DataStored of write {:?} by {} MsgId: {:?}",
+                    correlation_id, src_name, msg_id
+                );
+                return if self.is_elder().await {
+                    self.handle_data_stored(correlation_id, src_name).await
+                } else {
+                    error!("Received unexpected message while Adult");
+                    Ok(vec![])
+                };
+            }
             SystemMsg::NodeCmd(NodeCmd::SendReplicateDataAddress(data_addresses)) => {
                 info!("ReplicateData MsgId: {:?}", msg_id);
 
@@ -813,6 +896,25 @@ impl Node {
                     Ok(vec![cmd])
                 };
             }
+            SystemMsg::NodeCmd(NodeCmd::SyncDataDigest(digest)) => {
+                info!("SyncDataDigest MsgId: {:?}", msg_id);
+                return if self.is_elder().await {
+                    error!("Received unexpected message while Elder");
+                    Ok(vec![])
+                } else {
+                    self.handle_data_digest(sender.name(), digest).await
+                };
+            }
+            SystemMsg::NodeCmd(NodeCmd::RequestDataDigest(prefixes)) => {
+                info!("RequestDataDigest MsgId: {:?}", msg_id);
+                return if self.is_elder().await {
+                    error!("Received unexpected message while Elder");
+                    Ok(vec![])
+                } else {
+                    self.handle_data_digest_request(sender.name(), prefixes)
+                        .await
+                };
+            }
             SystemMsg::NodeCmd(NodeCmd::FetchReplicateData(data_addresses)) => {
                 let mut cmds = vec![];
                 info!("FetchReplicateData MsgId: {:?}", msg_id);
@@ -859,6 +961,61 @@ impl Node {
                     Ok(cmds)
                 };
             }
+            SystemMsg::NodeCmd(NodeCmd::ReportCorruptData(data_addresses)) => {
+                info!("ReportCorruptData MsgId: {:?}", msg_id);
+                return if self.is_elder().await {
+                    self.handle_corrupt_data_report(src_name, data_addresses)
+                        .await
+                } else {
+                    error!("Received unexpected message while Adult");
+                    Ok(vec![])
+                };
+            }
+            SystemMsg::NodeCmd(NodeCmd::FetchReplicateDataFrom {
+                holder,
+                data_addresses,
+            }) => {
+                info!("FetchReplicateDataFrom MsgId: {:?}", msg_id);
+                return if self.is_elder().await {
+                    error!("Received unexpected message while Elder");
+                    Ok(vec![])
+                } else {
+                    let section_pk = self.section_key_by_name(&holder).await;
+                    let cmds = data_addresses
+                        .into_iter()
+                        .chunks(REPLICATION_BATCH_SIZE)
+                        .into_iter()
+                        .map(|chunked_data_address| Cmd::SignOutgoingSystemMsg {
+                            msg: SystemMsg::NodeCmd(NodeCmd::FetchReplicateData(
+                                chunked_data_address.collect_vec(),
+                            )),
+                            dst: DstLocation::Node {
+                                name: holder,
+                                section_pk,
+                            },
+                        })
+                        .collect();
+
+                    Ok(cmds)
+                };
+            }
+            SystemMsg::NodeCmd(NodeCmd::DropExtraReplicas(data_addresses)) => {
+                info!("DropExtraReplicas MsgId: {:?}", msg_id);
+                // Only our Elders can have us drop data
+                return if self.is_elder().await {
+                    error!("Received unexpected message while Elder");
+                    Ok(vec![])
+                } else if !self.is_from_our_elders(&msg_authority, &src_name).await {
+                    warn!(
+                        "Dropping DropExtraReplicas msg not sent by our Elders: {:?}",
+                        msg_id
+                    );
+                    Ok(vec![])
+                } else {
+                    self.drop_extra_replicas(data_addresses).await?;
+                    Ok(vec![])
+                };
+            }
             SystemMsg::NodeCmd(node_cmd) => {
                 self.send_event(Event::MessageReceived {
                     msg_id,
@@ -1061,6 +1218,68 @@ impl Node {
             .map_err(crate::node::Error::from)
     }
 
+    async fn republish_data_of_leaving_node(&self, leaving: XorName) -> Result<Vec<Cmd>> {
+        let remaining = self
+            .network_knowledge
+            .adults()
+            .await
+            .iter()
+            .map(|peer| peer.name())
+            .filter(|adult| *adult != leaving)
+            .collect::<BTreeSet<XorName>>();
+
+        self.reorganize_data(BTreeSet::new(), iter::once(leaving).collect(), remaining)
+            .await
+    }
+
+    // Stores a replica of the data as an Adult, returning whether it was stored, along with the
+    // cmds reporting our storage level, or the Elders storing it elsewhere if we're full.
+    async fn store_replica(&self, data: ReplicatedData) -> Result<(bool, Vec<Cmd>)> {
+        // This may return a DatabaseFull error... but we should have reported storage increase
+        // well before this
+        match self.data_storage.store(&data).await {
+            Ok(level_report) => {
+                info!("Storage level report: {:?}", level_report);
+                Ok((true, self.record_storage_level_if_any(level_report).await))
+            }
+            Err(DbError::NotEnoughSpace) => {
+                // db full
+                error!("Not enough space to store more data");
+
+                let node_id = PublicKey::from(self.info.read().await.keypair.public);
+                let msg = SystemMsg::NodeEvent(NodeEvent::CouldNotStoreData {
+                    node_id,
+                    data,
+                    full: true,
+                });
+
+                Ok((false, vec![self.send_msg_to_our_elders(msg).await?]))
+            }
+            Err(error) => {
+                // the rest seem to be non-problematic errors.. (?)
+                error!("Problem storing data, but it was ignored: {error}");
+                Ok((false, vec![]))
+            }
+        }
+    }
+
+    // Whether the msg was sent by an Elder of our current section, or by our section itself
+    async fn is_from_our_elders(
+        &self,
+        msg_authority: &NodeMsgAuthority,
+        src_name: &XorName,
+    ) -> bool {
+        match msg_authority {
+            NodeMsgAuthority::Node(_) => self
+                .network_knowledge
+                .authority_provider()
+                .await
+                .contains_elder(src_name),
+            NodeMsgAuthority::Section(_) => self.network_knowledge.prefix().await.matches(src_name),
+            NodeMsgAuthority::BlsShare(_) => false,
+        }
+    }
+
     // Convert the provided NodeMsgAuthority to be a `Section` message
     // authority on successful accumulation. Also return 'true' if
     // current message shall not be processed any further.
@@ -0,0 +1,47 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::messaging::{
+    data::{Error as ErrorMsg, QueryResponse, RewardQuery, ServiceMsg},
+    DstLocation, EndUser, MsgId, WireMsg,
+};
+use crate::node::{api::cmds::Cmd, core::Node, Result};
+use crate::types::Peer;
+
+impl Node {
+    /// Handle a reward query from a client, replying with our reward ledger.
+    pub(crate) async fn handle_reward_query(
+        &self,
+        msg_id: MsgId,
+        query: RewardQuery,
+        origin: Peer,
+    ) -> Result<Vec<Cmd>> {
+        let operation_id = query.operation_id()?;
+        let response = match query {
+            RewardQuery::GetLedger(_) => {
+                let result = self.reward_ledger().await.map_err(|_| {
+                    ErrorMsg::InvalidOperation("Only Elders keep reward ledgers".to_string())
+                });
+                QueryResponse::GetRewardLedger((result, operation_id))
+            }
+        };
+
+        let msg = ServiceMsg::QueryResponse {
+            response,
+            correlation_id: msg_id,
+        };
+        let (msg_kind, payload) = self.ed_sign_client_msg(&msg).await?;
+        let dst = DstLocation::EndUser(EndUser(origin.name()));
+        let wire_msg = WireMsg::new_msg(MsgId::new(), payload, msg_kind, dst)?;
+
+        Ok(vec![Cmd::SendMsg {
+            recipients: vec![origin],
+            wire_msg,
+        }])
+    }
+}
@@ -6,9 +6,8 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::data_copy_count;
 use crate::messaging::{
-    data::{CmdError, DataCmd, DataQuery, Error as ErrorMsg, ServiceMsg},
+    data::{DataCmd, DataQuery, QueryResponse, ServiceMsg},
     system::{NodeQueryResponse, SystemMsg},
     AuthorityProof, DstLocation, EndUser, MsgId, ServiceAuth, WireMsg,
 };
@@ -158,6 +157,14 @@ impl Node {
             return Ok(cmds);
         }
 
+        if query_response.is_success() {
+            self.record_query_served(node_id).await;
+        }
+
+        if let QueryResponse::GetChunk(Ok(chunk)) = &query_response {
+            cmds.extend(self.record_chunk_served(chunk).await?);
+        }
+
         // Send response if one is warranted
         if query_response.failed_with_data_not_found()
             || (!query_response.is_success()
@@ -220,25 +227,17 @@ impl Node {
                     .read_data_from_adults(query, msg_id, auth, origin)
                     .await
             }
+            // ...while rewards are accounted for by us Elders
+            ServiceMsg::Rewards(query) => {
+                return self.handle_reward_query(msg_id, query, origin).await
+            }
             _ => {
                 warn!("!!!! Unexpected ServiceMsg received in routing. Was not sent to node layer: {:?}", msg);
                 return Ok(vec![]);
             }
         };
-        // build the replication cmds
-        let mut cmds = self.replicate_data(data).await?;
-        // make sure the expected replication factor is achieved
-        if data_copy_count() > cmds.len() {
-            error!("InsufficientAdults for storing data reliably");
-            let error = CmdError::Data(ErrorMsg::InsufficientAdults {
-                prefix: self.network_knowledge().prefix().await,
-                expected: data_copy_count() as u8,
-                found: cmds.len() as u8,
-            });
-            return self.send_cmd_error_response(error, origin, msg_id).await;
-        }
-        cmds.extend(self.send_cmd_ack(origin.clone(), msg_id).await?);
-        Ok(cmds)
+        // the write is acked once enough of the Adults storing the data acked it
+        self.store_data_of_client(data, msg_id, origin).await
     }
 
     /// Handle incoming data msgs.
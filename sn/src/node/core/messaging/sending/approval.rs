@@ -7,16 +7,26 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::messaging::system::{JoinResponse, SectionAuth, SystemMsg};
-use crate::node::{api::cmds::Cmd, core::Node, network_knowledge::NodeState};
+use crate::node::{api::cmds::Cmd, core::Node, network_knowledge::NodeState, Error, Result};
 use crate::types::log_markers::LogMarker;
 
 impl Node {
     // Send `NodeApproval` to a joining node which makes it a section member
-    pub(crate) async fn send_node_approval(&self, node_state: SectionAuth<NodeState>) -> Vec<Cmd> {
+    pub(crate) async fn send_node_approval(
+        &self,
+        node_state: SectionAuth<NodeState>,
+    ) -> Result<Vec<Cmd>> {
         let peer = node_state.peer().clone();
         let prefix = self.network_knowledge.prefix().await;
         info!("Our section with {:?} has approved peer {}.", prefix, peer,);
 
+        // it's what the joining node learns the storage policy of the network from
+        let storage_policy = self
+            .network_knowledge
+            .signed_storage_policy()
+            .await
+            .ok_or(Error::UnknownStoragePolicy)?;
+
         let node_msg = SystemMsg::JoinResponse(Box::new(JoinResponse::Approval {
             genesis_key: *self.network_knowledge.genesis_key(),
             section_auth: self
@@ -26,6 +36,7 @@ impl Node {
                 .into_authed_msg(),
             node_state: node_state.into_authed_msg(),
             section_chain: self.network_knowledge.section_chain().await,
+            storage_policy,
         }));
 
         let dst_section_pk = self.network_knowledge.section_key().await;
@@ -34,10 +45,10 @@ impl Node {
             .send_direct_msg(peer.clone(), node_msg, dst_section_pk)
             .await
         {
-            Ok(cmd) => vec![cmd],
+            Ok(cmd) => Ok(vec![cmd]),
             Err(err) => {
                 error!("Failed to send join approval to node {}: {:?}", peer, err);
-                vec![]
+                Ok(vec![])
             }
         }
     }
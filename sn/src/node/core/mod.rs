@@ -40,13 +40,11 @@ use crate::messaging::{
     AuthorityProof, SectionAuth,
 };
 use crate::node::error::Result;
-use crate::types::{
-    log_markers::LogMarker, utils::compare_and_write_prefix_map_to_disk, Cache, Peer,
-};
-use crate::UsedSpace;
+use crate::types::{log_markers::LogMarker, Cache, Peer};
+use crate::{Compression, StorageBackendKind, UsedSpace};
 
 use backoff::ExponentialBackoff;
-use data::{Capacity, Liveness};
+use data::{Capacity, HotChunks, Liveness, PendingWrites, Rewards};
 use itertools::Itertools;
 use resource_proof::ResourceProof;
 use std::{
@@ -100,6 +98,7 @@ pub(crate) struct Node {
     pub(crate) comm: Comm,
 
     pub(super) data_storage: DataStorage, // Adult only before cache
+    root_storage_dir: PathBuf,
 
     resource_proof: ResourceProof,
     // Network resources
@@ -119,7 +118,10 @@ pub(crate) struct Node {
     // Trackers
     capacity: Capacity,
     liveness: Liveness,
+    rewards: Rewards,
+    hot_chunks: HotChunks,
     pending_data_queries: Arc<Cache<OperationId, Vec<Peer>>>,
+    pending_writes: PendingWrites,
     // Caches
     ae_backoff_cache: AeBackoffCache,
 }
@@ -135,13 +137,23 @@ impl Node {
         event_tx: mpsc::Sender<Event>,
         used_space: UsedSpace,
         root_storage_dir: PathBuf,
+        storage_backend: Option<StorageBackendKind>,
+        chunk_compression: Option<Compression>,
     ) -> Result<Self> {
         let section_keys_provider = SectionKeysProvider::new(section_key_share).await;
 
         // make sure the Node has the correct local addr as Comm
         info.addr = comm.our_connection_info();
 
-        let data_storage = DataStorage::new(&root_storage_dir, used_space.clone())?;
+        let data_storage = DataStorage::new(
+            &root_storage_dir,
+            used_space.clone(),
+            storage_backend,
+            chunk_compression,
+        )
+        .await?;
+
+        let rewards = Rewards::new(&root_storage_dir).await?;
 
         info!("Creating Liveness checks");
         let adult_liveness = Liveness::new(
@@ -170,9 +182,13 @@ impl Node {
             current_joins_semaphore: Arc::new(Semaphore::new(CONCURRENT_JOINS)),
             resource_proof: ResourceProof::new(RESOURCE_PROOF_DATA_SIZE, RESOURCE_PROOF_DIFFICULTY),
             data_storage,
+            root_storage_dir,
             capacity: Capacity::default(),
             liveness: adult_liveness,
+            rewards,
+            hot_chunks: HotChunks::default(),
             pending_data_queries: Arc::new(Cache::with_expiry_duration(DATA_QUERY_TIMEOUT)),
+            pending_writes: PendingWrites::default(),
             ae_backoff_cache: AeBackoffCache::default(),
         })
     }
@@ -207,18 +223,15 @@ impl Node {
             .await
     }
 
-    pub(crate) async fn write_prefix_map(&self) {
-        info!("Writing our latest PrefixMap to disk");
-        // TODO: Make this serialization human readable
-
-        let prefix_map = self.network_knowledge.prefix_map().clone();
-
-        let _ = tokio::spawn(async move {
-            // Compare and write Prefix to `~/.safe/prefix_maps` dir
-            if let Err(e) = compare_and_write_prefix_map_to_disk(&prefix_map).await {
-                error!("Error writing PrefixMap to `~/.safe` dir: {:?}", e);
-            }
-        });
+    /// Writes our network knowledge to our root dir, to be restored if we restart.
+    pub(crate) async fn write_network_knowledge(&self) {
+        if let Err(e) = self
+            .network_knowledge
+            .write_to_disk(&self.root_storage_dir)
+            .await
+        {
+            error!("Error writing our network knowledge to disk: {:?}", e);
+        }
     }
 
     pub(super) async fn state_snapshot(&self) -> StateSnapshot {
@@ -6,7 +6,10 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::messaging::system::{Proposal as ProposalMsg, SectionAuth};
+use crate::messaging::{
+    data::RewardPayout,
+    system::{Proposal as ProposalMsg, SectionAuth},
+};
 use crate::node::{
     dkg::SigShare,
     network_knowledge::{NodeState, SectionAuthorityProvider},
@@ -20,6 +23,7 @@ pub(crate) enum Proposal {
     SectionInfo(SectionAuthorityProvider),
     NewElders(SectionAuth<SectionAuthorityProvider>),
     JoinsAllowed(bool),
+    RewardPayout(RewardPayout),
 }
 
 impl Proposal {
@@ -44,6 +48,7 @@ impl Proposal {
             Self::SectionInfo(info) => bincode::serialize(info),
             Self::NewElders(info) => bincode::serialize(&info.sig.public_key),
             Self::JoinsAllowed(joins_allowed) => bincode::serialize(&joins_allowed),
+            Self::RewardPayout(payout) => bincode::serialize(payout),
         }?)
     }
 }
@@ -58,6 +63,7 @@ impl Proposal {
             Self::SectionInfo(sap) => ProposalMsg::SectionInfo(sap.to_msg()),
             Self::NewElders(sap) => ProposalMsg::NewElders(sap.into_authed_msg()),
             Self::JoinsAllowed(allowed) => ProposalMsg::JoinsAllowed(allowed),
+            Self::RewardPayout(payout) => ProposalMsg::RewardPayout(payout),
         }
     }
 }
@@ -69,6 +75,7 @@ impl ProposalMsg {
             Self::SectionInfo(sap) => Proposal::SectionInfo(sap.into_state()),
             Self::NewElders(sap) => Proposal::NewElders(sap.into_authed_state()),
             Self::JoinsAllowed(allowed) => Proposal::JoinsAllowed(allowed),
+            Self::RewardPayout(payout) => Proposal::RewardPayout(payout),
         }
     }
 }
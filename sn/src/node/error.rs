@@ -82,6 +82,16 @@ pub enum Error {
     NoMatchingElder,
     #[error("Node cannot join the network since it is not externally reachable: {0}")]
     NodeNotReachable(SocketAddr),
+    #[error(
+        "Node cannot join the network since its storage policy differs from the network's: {0:?}"
+    )]
+    StoragePolicyMismatch(crate::messaging::system::StoragePolicy),
+    #[error("Storage policy not signed with the network genesis key")]
+    UntrustedStoragePolicy,
+    #[error("Invalid storage policy: {0:?}")]
+    InvalidStoragePolicy(crate::messaging::system::StoragePolicy),
+    #[error("Storage policy of the network not known")]
+    UnknownStoragePolicy,
     /// Timeout when trying to join the network
     #[error("Timeout when trying to join the network")]
     JoinTimeout,
@@ -109,6 +119,9 @@ pub enum Error {
     /// I/O error.
     #[error("I/O error: {0}")]
     Io(#[from] io::Error),
+
+    #[error("Admin endpoint error: {0}")]
+    AdminEndpoint(#[from] hyper::Error),
     /// JSON serialisation error.
     #[error("JSON serialisation error:: {0}")]
     JsonSerialisation(#[from] serde_json::Error),
@@ -12,6 +12,8 @@
 mod cfg;
 // Node public API
 mod api;
+// Admin endpoint for monitoring
+mod admin;
 
 mod core;
 mod dkg;
@@ -21,6 +23,7 @@ mod logging;
 mod messages;
 mod network_knowledge;
 
+use crate::messaging::system::StoragePolicy;
 use crate::types::{Peer, PublicKey};
 
 use ed25519_dalek::Keypair;
@@ -31,6 +34,7 @@ use std::{
 };
 
 pub use self::{
+    admin::NodeMetrics,
     api::{
         event::{Elders, Event, MessageReceived, NodeElderChange},
         event_stream::EventStream,
@@ -75,13 +79,31 @@ pub(crate) struct NodeInfo {
     #[debug(skip)]
     pub(crate) keypair: Arc<Keypair>,
     pub(crate) addr: SocketAddr,
+    // Key of the wallet to be credited with the rewards this node earns.
+    pub(crate) reward_key: PublicKey,
+    // Storage policy this node was configured with, which the network's has to match.
+    pub(crate) storage_policy: Option<StoragePolicy>,
 }
 
 impl NodeInfo {
     pub(crate) fn new(keypair: Keypair, addr: SocketAddr) -> Self {
+        let reward_key = PublicKey::from(keypair.public);
         Self {
             keypair: Arc::new(keypair),
             addr,
+            reward_key,
+            storage_policy: None,
+        }
+    }
+
+    pub(crate) fn with_reward_key(self, reward_key: PublicKey) -> Self {
+        Self { reward_key, ..self }
+    }
+
+    pub(crate) fn with_storage_policy(self, storage_policy: Option<StoragePolicy>) -> Self {
+        Self {
+            storage_policy,
+            ..self
         }
     }
 
@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 mod elder_candidates;
+mod persistence;
 mod section_peers;
 
 pub(super) mod node_state;
@@ -23,7 +24,9 @@ pub(crate) use node_state::NodeState;
 pub(crate) use section_authority_provider::SectionAuthorityProvider;
 
 use crate::elder_count;
-use crate::messaging::system::{KeyedSig, SectionAuth, SectionPeers as SectionPeersMsg};
+use crate::messaging::system::{
+    KeyedSig, SectionAuth, SectionPeers as SectionPeersMsg, StoragePolicy,
+};
 use crate::node::{dkg::SectionAuthUtils, recommended_section_size, Error, Result};
 use crate::types::{log_markers::LogMarker, prefix_map::NetworkPrefixMap, Peer};
 
@@ -50,6 +53,8 @@ pub(crate) struct NetworkKnowledge {
     prefix_map: NetworkPrefixMap,
     /// A DAG containing all section chains of the whole network that we are aware of
     all_sections_chains: Arc<RwLock<SecuredLinkedList>>,
+    /// Storage policy of the network, signed with the genesis key
+    storage_policy: Arc<RwLock<Option<SectionAuth<StoragePolicy>>>>,
 }
 
 impl NetworkKnowledge {
@@ -131,6 +136,7 @@ impl NetworkKnowledge {
             section_peers: SectionPeers::default(),
             prefix_map,
             all_sections_chains: Arc::new(RwLock::new(chain)),
+            storage_policy: Arc::new(RwLock::new(None)),
         })
     }
 
@@ -155,10 +161,12 @@ impl NetworkKnowledge {
         Ok(())
     }
 
-    /// Creates `NetworkKnowledge` for the first node in the network
+    /// Creates `NetworkKnowledge` for the first node in the network, which agrees on the
+    /// given storage policy for the whole network by signing it with the genesis key.
     pub(super) async fn first_node(
         peer: Peer,
         genesis_sk_set: bls::SecretKeySet,
+        storage_policy: StoragePolicy,
     ) -> Result<(NetworkKnowledge, SectionKeyShare)> {
         let public_key_set = genesis_sk_set.public_keys();
         let secret_key_share = genesis_sk_set.secret_key_share(0);
@@ -183,6 +191,11 @@ impl NetworkKnowledge {
             });
         }
 
+        let sig = create_first_sig(&public_key_set, &secret_key_share, &storage_policy)?;
+        network_knowledge
+            .set_storage_policy(SectionAuth::new(storage_policy, sig))
+            .await?;
+
         let section_key_share = SectionKeyShare {
             public_key_set,
             index: 0,
@@ -192,6 +205,38 @@ impl NetworkKnowledge {
         Ok((network_knowledge, section_key_share))
     }
 
+    /// Storage policy of the network, returning error if we haven't learnt it.
+    pub(crate) async fn storage_policy(&self) -> Result<StoragePolicy> {
+        self.storage_policy
+            .read()
+            .await
+            .as_ref()
+            .map(|signed| signed.value)
+            .ok_or(Error::UnknownStoragePolicy)
+    }
+
+    /// Storage policy of the network signed with the genesis key, if we know it.
+    pub(super) async fn signed_storage_policy(&self) -> Option<SectionAuth<StoragePolicy>> {
+        self.storage_policy.read().await.clone()
+    }
+
+    /// Sets the storage policy of the network, returning error if it's not signed
+    /// with the genesis key or it's not valid.
+    pub(super) async fn set_storage_policy(
+        &self,
+        storage_policy: SectionAuth<StoragePolicy>,
+    ) -> Result<()> {
+        if storage_policy.sig.public_key != self.genesis_key || !storage_policy.self_verify() {
+            return Err(Error::UntrustedStoragePolicy);
+        }
+        if !storage_policy.value.is_valid() {
+            return Err(Error::InvalidStoragePolicy(storage_policy.value));
+        }
+
+        *self.storage_policy.write().await = Some(storage_policy);
+        Ok(())
+    }
+
     /// If we already have the signed SAP and section chain for the provided key and prefix
     /// we make them the current SAP and section chain, and if so, this returns 'true'.
     /// Note this function assumes we already have the key share for the provided section key.
@@ -0,0 +1,204 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::NetworkKnowledge;
+use crate::messaging::{
+    system::{NodeState as NodeStateMsg, SectionAuth, StoragePolicy},
+    SectionAuthorityProvider as SectionAuthorityProviderMsg,
+};
+use crate::node::Result;
+use crate::types::prefix_map::NetworkPrefixMap;
+
+use bls::PublicKey as BlsPublicKey;
+use secured_linked_list::SecuredLinkedList;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, net::SocketAddr, path::Path};
+use tokio::fs;
+
+// Filename for storing the node's network knowledge, within its root dir
+const NETWORK_KNOWLEDGE_FILENAME: &str = "network_knowledge";
+
+// What's written to disk of our network knowledge. The genesis key is the root of the chain.
+#[derive(Serialize, Deserialize)]
+struct StoredNetworkKnowledge {
+    chain: SecuredLinkedList,
+    signed_sap: SectionAuth<SectionAuthorityProviderMsg>,
+    members: BTreeSet<SectionAuth<NodeStateMsg>>,
+    prefix_map: NetworkPrefixMap,
+    storage_policy: Option<SectionAuth<StoragePolicy>>,
+}
+
+impl NetworkKnowledge {
+    /// Writes our section chain, SAP, members, prefix map and the storage policy of the
+    /// network to the node's root dir,
+    /// so they can be restored when the node restarts.
+    pub(crate) async fn write_to_disk(&self, root_dir: &Path) -> Result<()> {
+        let stored = StoredNetworkKnowledge {
+            chain: self.section_chain().await,
+            signed_sap: self
+                .section_signed_authority_provider()
+                .await
+                .into_authed_msg(),
+            members: self
+                .section_signed_members()
+                .await
+                .into_iter()
+                .map(|node_state| node_state.into_authed_msg())
+                .collect(),
+            prefix_map: self.prefix_map.clone(),
+            storage_policy: self.signed_storage_policy().await,
+        };
+        let bytes = bincode::serialize(&stored)?;
+
+        // Write to a temporary file first, so a previous copy is not lost if we stop halfway.
+        let path = root_dir.join(NETWORK_KNOWLEDGE_FILENAME);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, bytes).await?;
+        fs::rename(tmp_path, path).await?;
+
+        Ok(())
+    }
+
+    /// Returns the network knowledge previously written to the node's root dir,
+    /// or None if there is none. It's verified in the same way as when received
+    /// from the network, and it must be rooted at the given genesis key.
+    pub(crate) async fn read_from_disk(
+        root_dir: &Path,
+        genesis_key: BlsPublicKey,
+    ) -> Result<Option<Self>> {
+        let path = root_dir.join(NETWORK_KNOWLEDGE_FILENAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(&path).await?;
+        let stored: StoredNetworkKnowledge = bincode::deserialize(&bytes)?;
+
+        let network_knowledge = Self::new(
+            genesis_key,
+            stored.chain,
+            stored.signed_sap.into_authed_state(),
+            Some(stored.prefix_map),
+        )?;
+
+        // Members which can't be verified with the section chain are left out.
+        let _updated = network_knowledge
+            .merge_members(
+                stored
+                    .members
+                    .into_iter()
+                    .map(|node_state| node_state.into_authed_state())
+                    .collect(),
+            )
+            .await?;
+
+        if let Some(storage_policy) = stored.storage_policy {
+            network_knowledge.set_storage_policy(storage_policy).await?;
+        }
+
+        Ok(Some(network_knowledge))
+    }
+
+    /// Addresses of all the peers we know of, i.e. the Elders of every known section
+    /// and the members of our own section, which can be used as bootstrap contacts.
+    pub(crate) async fn known_contacts(&self) -> BTreeSet<SocketAddr> {
+        let elders = self
+            .prefix_map
+            .all()
+            .into_iter()
+            .flat_map(|sap| sap.elders_vec())
+            .map(|peer| peer.addr());
+        let members = self
+            .section_members()
+            .await
+            .into_iter()
+            .map(|node_state| node_state.addr());
+
+        elders.chain(members).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NetworkKnowledge;
+    use crate::messaging::system::StoragePolicy;
+    use crate::node::{network_knowledge::test_utils::gen_addr, Error, Peer};
+    use eyre::{eyre, Result};
+    use tempfile::tempdir;
+    use xor_name::XorName;
+
+    async fn first_node_knowledge() -> Result<NetworkKnowledge> {
+        let peer = Peer::new(XorName::random(), gen_addr());
+        let genesis_sk_set = bls::SecretKeySet::random(0, &mut rand::thread_rng());
+        let (network_knowledge, _) =
+            NetworkKnowledge::first_node(peer, genesis_sk_set, StoragePolicy::default()).await?;
+        Ok(network_knowledge)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn network_knowledge_to_and_from_disk() -> Result<()> {
+        let root = tempdir().map_err(|e| eyre!("Failed to create temp dir: {}", e))?;
+        let root_dir = root.path();
+        let network_knowledge = first_node_knowledge().await?;
+        let genesis_key = *network_knowledge.genesis_key();
+
+        assert!(NetworkKnowledge::read_from_disk(root_dir, genesis_key)
+            .await?
+            .is_none());
+
+        network_knowledge.write_to_disk(root_dir).await?;
+        let restored = NetworkKnowledge::read_from_disk(root_dir, genesis_key)
+            .await?
+            .ok_or_else(|| eyre!("Network knowledge was not read from disk"))?;
+
+        assert_eq!(
+            restored.section_chain().await,
+            network_knowledge.section_chain().await
+        );
+        assert_eq!(
+            restored.authority_provider().await,
+            network_knowledge.authority_provider().await
+        );
+        assert_eq!(
+            restored.section_signed_members().await,
+            network_knowledge.section_signed_members().await
+        );
+        assert_eq!(
+            restored.storage_policy().await?,
+            network_knowledge.storage_policy().await?
+        );
+        assert_eq!(
+            restored.known_contacts().await,
+            network_knowledge
+                .authority_provider()
+                .await
+                .elders()
+                .map(Peer::addr)
+                .collect()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn network_knowledge_from_another_network_is_rejected() -> Result<()> {
+        let root = tempdir().map_err(|e| eyre!("Failed to create temp dir: {}", e))?;
+        let root_dir = root.path();
+        first_node_knowledge()
+            .await?
+            .write_to_disk(root_dir)
+            .await?;
+
+        let other_genesis_key = bls::SecretKey::random().public_key();
+        match NetworkKnowledge::read_from_disk(root_dir, other_genesis_key).await {
+            Err(Error::UntrustedProofChain(_)) => Ok(()),
+            Err(err) => Err(eyre!("Unexpected error: {:?}", err)),
+            Ok(_) => Err(eyre!("Network knowledge of another network was restored")),
+        }
+    }
+}
@@ -84,12 +84,23 @@ pub enum Error {
     /// Invalid Operation such as a POST on ImmutableData
     #[error("Invalid operation")]
     InvalidOperation,
+    /// A Register policy was set with a version older than that of its current policy.
+    #[error("Policy version {given} is older than the current one, {current}")]
+    StalePolicyVersion {
+        /// Version of the policy given
+        given: u64,
+        /// Version of the current policy
+        current: u64,
+    },
     /// Mismatch between key type and signature type.
     #[error("Sign key and signature type do not match")]
     SigningKeyTypeMismatch,
     /// Failed signature validation.
     #[error("Invalid signature")]
     InvalidSignature,
+    /// Data could not be decrypted with the keys provided.
+    #[error("Failed to decrypt the data, it may have been encrypted by a different owner")]
+    FailedToDecrypt,
     /// While parsing, precision would be lost.
     #[error("Lost precision on the number of coins during parsing")]
     LossOfPrecision,
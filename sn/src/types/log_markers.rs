@@ -36,6 +36,10 @@ pub enum LogMarker {
     ChunkQueryResponseReceviedFromAdult,
     ChunkQueryReceviedAtElder,
     ChunkQueryReceviedAtAdult,
+    CorruptChunkFound,
+    CorruptDataReportReceivedAtElder,
+    HotChunkServedAtElder,
+    HotChunkReplicated,
     // Data reorganisation
     DataReorganisationUnderway,
     // Register
@@ -93,4 +97,6 @@ pub enum LogMarker {
     // Relocation
     RelocateStart,
     RelocateEnd,
+    // Leaving
+    LeaveReceivedAtElder,
 }
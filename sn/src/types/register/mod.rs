@@ -39,6 +39,7 @@ pub struct Register {
     authority: User,
     crdt: RegisterCrdt,
     policy: Policy,
+    policy_version: u64,
     cap: u16,
 }
 
@@ -54,6 +55,7 @@ impl Register {
             authority: *policy.owner(),
             crdt: RegisterCrdt::new(address),
             policy,
+            policy_version: 0,
             cap,
         }
     }
@@ -79,6 +81,7 @@ impl Register {
             authority,
             crdt: RegisterCrdt::new(Address::Public { name, tag }),
             policy: policy.into(),
+            policy_version: 0,
             cap,
         }
     }
@@ -104,6 +107,7 @@ impl Register {
             authority,
             crdt: RegisterCrdt::new(Address::Private { name, tag }),
             policy: policy.into(),
+            policy_version: 0,
             cap,
         }
     }
@@ -168,6 +172,12 @@ impl Register {
         self.crdt.get(hash).ok_or(Error::NoSuchEntry)
     }
 
+    /// Return the hashes of the entries the one corresponding to the provided `hash` was written
+    /// on top of, i.e. the previous versions it replaced, if present.
+    pub fn children(&self, hash: EntryHash) -> Result<BTreeSet<EntryHash>> {
+        self.crdt.children(hash).ok_or(Error::NoSuchEntry)
+    }
+
     /// Read the last entry, or entries when there are branches, if the register is not empty.
     pub fn read(&self) -> BTreeSet<(EntryHash, Entry)> {
         self.crdt.read()
@@ -183,6 +193,43 @@ impl Register {
         &self.policy
     }
 
+    /// Return the version of the policy, starting at 0 for the one the Register was created with.
+    pub fn policy_version(&self) -> u64 {
+        self.policy_version
+    }
+
+    /// Replace the policy of the Register with the given version of it, returning whether it changed.
+    ///
+    /// The new policy must keep the same owner, and be of the same kind
+    /// (public or private), as the current one, since the Register's address depends on them.
+    ///
+    /// Policies are ordered by version, and by content for the same version, and only one ordered
+    /// after the current policy replaces it, so that replicas converge whatever order they are
+    /// set in. An older version is refused, while setting the current policy again, or a concurrent
+    /// one of the same version ordered before it, does nothing.
+    pub fn set_policy(&mut self, policy: Policy, version: u64) -> Result<bool> {
+        let same_kind = matches!(
+            (&self.policy, &policy),
+            (Policy::Public(_), Policy::Public(_)) | (Policy::Private(_), Policy::Private(_))
+        );
+        if !same_kind || policy.owner() != self.policy.owner() {
+            return Err(Error::InvalidOperation);
+        }
+        if version < self.policy_version {
+            return Err(Error::StalePolicyVersion {
+                given: version,
+                current: self.policy_version,
+            });
+        }
+        if (version, &policy) <= (self.policy_version, &self.policy) {
+            // the current policy, or a concurrent one of the same version which sorts before it
+            return Ok(false);
+        }
+        self.policy = policy;
+        self.policy_version = version;
+        Ok(true)
+    }
+
     /// Increment the size cap of the register, returning the previous value.
     pub fn increment_cap(&mut self, add: u16) {
         self.cap += add;
@@ -212,6 +259,21 @@ impl Register {
         self.crdt.apply_op(op)
     }
 
+    /// Merge the entries of another replica of this Register into this one.
+    ///
+    /// The size cap becomes the larger of the two, and the policy the latter of the two.
+    pub fn merge(&mut self, other: Register) -> Result<()> {
+        self.crdt.merge(other.crdt)?;
+        self.cap = self.cap.max(other.cap);
+        if other.policy.owner() == self.policy.owner()
+            && (other.policy_version, &other.policy) > (self.policy_version, &self.policy)
+        {
+            self.policy = other.policy;
+            self.policy_version = other.policy_version;
+        }
+        Ok(())
+    }
+
     /// Helper to check permissions for given `action`
     /// for the given requester's public key.
     ///
@@ -228,8 +290,8 @@ impl Register {
 mod tests {
     use super::super::{
         register::{
-            Entry, EntryHash, Permissions, PrivatePermissions, PrivatePolicy, PublicPermissions,
-            PublicPolicy, Register, RegisterOp, User,
+            Entry, EntryHash, Permissions, Policy, PrivatePermissions, PrivatePolicy,
+            PublicPermissions, PublicPolicy, Register, RegisterOp, User,
         },
         utils, Error, Keypair, Result,
     };
@@ -348,6 +410,83 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn register_merge_replicas() -> Result<()> {
+        let mut replicas = create_public_reg_replicas(2);
+        let (_, mut replica1) = replicas.remove(0);
+        let (_, mut replica2) = replicas.remove(0);
+
+        let _ = replica1.write(random_register_entry(), BTreeSet::new())?;
+        let _ = replica2.write(random_register_entry(), BTreeSet::new())?;
+
+        replica1.merge(replica2.clone())?;
+        replica2.merge(replica1.clone())?;
+
+        verify_data_convergence(vec![replica1.clone(), replica2], 2)?;
+
+        // replicas of other registers can't be merged
+        let (_, other) = &create_public_reg_replicas(1)[0];
+        assert!(replica1.merge(other.clone()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_policy_versions_converge() -> eyre::Result<()> {
+        let owner_keypair = Keypair::new_ed25519(&mut OsRng);
+        let owner = User::Key(owner_keypair.public_key());
+        let name = XorName::random();
+        let tag = 43_000;
+        let mut replica1 =
+            create_public_reg_replica_with(name, tag, Some(owner_keypair.clone()), None);
+        let mut replica2 = create_public_reg_replica_with(name, tag, Some(owner_keypair), None);
+
+        let policy_with = |user: User| {
+            let mut permissions = BTreeMap::new();
+            let _ = permissions.insert(user, PublicPermissions::new(true));
+            Policy::Public(PublicPolicy { owner, permissions })
+        };
+        let v1 = policy_with(User::Anyone);
+        let v2 = policy_with(User::Key(Keypair::new_ed25519(&mut OsRng).public_key()));
+        let v2_concurrent = policy_with(User::Key(Keypair::new_ed25519(&mut OsRng).public_key()));
+
+        // the replicas get the policies in different orders
+        assert!(replica1.set_policy(v1.clone(), 1)?);
+        assert!(replica1.set_policy(v2.clone(), 2)?);
+        let _ = replica1.set_policy(v2_concurrent.clone(), 2)?;
+        let _ = replica2.set_policy(v2_concurrent, 2)?;
+        let _ = replica2.set_policy(v2, 2)?;
+
+        // an older version is refused, replaying the current one does nothing
+        assert!(matches!(
+            replica2.set_policy(v1, 1),
+            Err(Error::StalePolicyVersion {
+                given: 1,
+                current: 2
+            })
+        ));
+        assert!(!replica1.set_policy(replica1.policy().clone(), 2)?);
+
+        assert_eq!(replica1.policy(), replica2.policy());
+        assert_eq!(replica1.policy_version(), 2);
+
+        // merging replicas keeps the latter policy
+        let mut replica3 = create_public_reg_replica_with(
+            name,
+            tag,
+            None,
+            Some(PublicPolicy {
+                owner,
+                permissions: BTreeMap::new(),
+            }),
+        );
+        replica3.merge(replica1.clone())?;
+        assert_eq!(replica3.policy(), replica1.policy());
+        assert_eq!(replica3.policy_version(), 2);
+
+        Ok(())
+    }
+
     #[test]
     fn register_get_by_hash() -> eyre::Result<()> {
         let (_, register) = &mut create_public_reg_replicas(1)[0];
@@ -385,6 +524,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn register_get_children() -> eyre::Result<()> {
+        let (_, register) = &mut create_public_reg_replicas(1)[0];
+
+        let (entry1_hash, _) = register.write(random_register_entry(), BTreeSet::new())?;
+        let (entry2_hash, _) = register.write(random_register_entry(), BTreeSet::new())?;
+        let children: BTreeSet<_> = vec![entry1_hash, entry2_hash].into_iter().collect();
+        let (entry3_hash, _) = register.write(random_register_entry(), children.clone())?;
+
+        assert_eq!(register.children(entry1_hash)?, BTreeSet::new());
+        assert_eq!(register.children(entry3_hash)?, children);
+        assert_eq!(
+            register.children(EntryHash::default()),
+            Err(Error::NoSuchEntry)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn register_query_public_policy() -> eyre::Result<()> {
         let name = XorName::random();
@@ -15,7 +15,7 @@ use super::{
 };
 use crdts::{
     merkle_reg::{MerkleReg, Node},
-    CmRDT,
+    CmRDT, CvRDT,
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -140,11 +140,30 @@ impl RegisterCrdt {
         Ok(())
     }
 
+    /// Merge the entries of another replica of this RegisterCrdt into this one.
+    pub(super) fn merge(&mut self, other: RegisterCrdt) -> Result<()> {
+        if self.address != other.address {
+            return Err(Error::CrdtWrongAddress(other.address));
+        }
+
+        self.data.merge(other.data);
+
+        Ok(())
+    }
+
     /// Get the entry corresponding to the provided `hash` if it exists.
     pub(super) fn get(&self, hash: EntryHash) -> Option<&Entry> {
         self.data.node(hash.0).map(|node| &node.value)
     }
 
+    /// Get the hashes of the entries the one corresponding to the provided `hash`
+    /// was written on top of, if it exists.
+    pub(super) fn children(&self, hash: EntryHash) -> Option<BTreeSet<EntryHash>> {
+        self.data
+            .node(hash.0)
+            .map(|node| node.children.iter().copied().map(EntryHash).collect())
+    }
+
     /// Read current entries (multiple entries occur on concurrent writes).
     pub(super) fn read(&self) -> BTreeSet<(EntryHash, Entry)> {
         self.data
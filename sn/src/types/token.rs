@@ -19,7 +19,9 @@ const TOKEN_TO_RAW_POWER_OF_10_CONVERSION: u32 = 9;
 /// The conversion from Token to raw value
 const TOKEN_TO_RAW_CONVERSION: u64 = 1_000_000_000;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 /// Structure representing a Token amount.
 pub struct Token(u64);
 
@@ -8,7 +8,7 @@
 
 use bytes::Buf;
 use color_eyre::{eyre::eyre, Result};
-use sn_api::{resolver::SafeData, PublicKey, Safe, SafeUrl};
+use sn_api::{resolver::SafeData, PublicKey, Safe, SafeUrl, Scope};
 use std::{
     collections::BTreeSet, env::temp_dir, fs::File, io::Write, net::SocketAddr, path::PathBuf,
 };
@@ -46,7 +46,7 @@ async fn main() -> Result<()> {
 
     println!("Uploading '{}' to Safe ...", file_path.display());
     let (xorurl, _, _) = safe
-        .files_container_create_from(&file_path, dst, recursive, follow_links)
+        .files_container_create_from(&file_path, dst, recursive, follow_links, Scope::Public)
         .await?;
 
     // The 'files_container_create_from' API returns (among other information) the
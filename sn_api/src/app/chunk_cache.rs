@@ -0,0 +1,42 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{ChunkCache, Safe};
+use crate::Result;
+use safe_network::client::{chunk_cache_size_from_env, default_root_dir};
+
+impl Safe {
+    /// # Get the cache of chunks kept on disk
+    /// Get the cache chunks retrieved from the network are kept in on disk, e.g. to inspect or clear it.
+    /// This is the one the connected client uses, if it has it enabled, or otherwise the one under
+    /// the default client root dir, with the size limit set with the `SN_CHUNK_CACHE_SIZE` env var.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let safe = Safe::dry_runner(None);
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    ///     let cache = safe.chunk_cache().await.unwrap();
+    ///     println!("{} chunks cached", cache.stats().await.chunks);
+    ///     cache.clear().await.unwrap();
+    /// # });
+    /// ```
+    pub async fn chunk_cache(&self) -> Result<ChunkCache> {
+        if let Some(cache) = self
+            .get_safe_client()
+            .ok()
+            .and_then(|client| client.chunk_cache())
+        {
+            return Ok(cache.clone());
+        }
+
+        let max_size = chunk_cache_size_from_env().unwrap_or(0);
+        Ok(ChunkCache::open(&default_root_dir(), max_size).await?)
+    }
+}
@@ -7,8 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::{metadata::get_metadata, FilesMapChange, ProcessedFiles};
-use crate::{Error, Result, Safe, XorUrl};
-use bytes::Bytes;
+use crate::{Error, Result, Safe, Scope, XorUrl};
 use log::info;
 use safe_network::client::Error as ClientError;
 use std::{
@@ -19,24 +18,20 @@ use walkdir::{DirEntry, WalkDir};
 
 const MAX_RECURSIVE_DEPTH: usize = 10_000;
 
-// Upload a file to the Network
-pub(crate) async fn upload_file_to_net(safe: &Safe, path: &Path) -> Result<XorUrl> {
-    let data = fs::read(path).map_err(|err| {
+// Upload a file to the Network, streaming its content from disk
+pub(crate) async fn upload_file_to_net(safe: &Safe, path: &Path, scope: Scope) -> Result<XorUrl> {
+    // Make sure the file can be read before attempting to upload it
+    let _ = fs::File::open(path).map_err(|err| {
         Error::InvalidInput(format!("Failed to read file from local location: {}", err))
     })?;
-    let data = Bytes::from(data);
 
     let mut mime_type_for_xorurl = mime_guess::from_path(&path).first_raw();
-    let result = match safe
-        .store_public_bytes(data.to_owned(), mime_type_for_xorurl)
-        .await
-    {
+    let result = match safe.store_file(path, mime_type_for_xorurl, scope).await {
         Ok(xorurl) => Ok(xorurl),
         Err(Error::InvalidMediaType(_)) => {
             // Let's then upload it and set media-type to be simply raw content
             mime_type_for_xorurl = None;
-            safe.store_public_bytes(data.clone(), mime_type_for_xorurl)
-                .await
+            safe.store_file(path, mime_type_for_xorurl, scope).await
         }
         other_err => other_err,
     };
@@ -45,10 +40,12 @@ pub(crate) async fn upload_file_to_net(safe: &Safe, path: &Path) -> Result<XorUr
     // thus let's report the error but providing the xorurl for the user to be aware of.
     if let Err(Error::ClientError(ClientError::NotEnoughChunksRetrieved { .. })) = result {
         // Let's obtain the xorurl with using dry-run mode.
-        // Use a dry runner only for this next operation
-        let dry_runner = Safe::dry_runner(Some(safe.xorurl_base));
+        // Use a dry runner only for this next operation, keeping the client
+        // since the address of private content depends on its keypair
+        let mut dry_runner = safe.clone();
+        dry_runner.dry_run_mode = true;
         let xorurl = dry_runner
-            .store_public_bytes(data, mime_type_for_xorurl)
+            .store_file(path, mime_type_for_xorurl, scope)
             .await?;
 
         Err(Error::ContentUploadVerificationFailed(xorurl))
@@ -70,6 +67,7 @@ pub(crate) async fn file_system_dir_walk(
     location: &Path,
     recursive: bool,
     follow_links: bool,
+    scope: Scope,
 ) -> Result<ProcessedFiles> {
     info!("Reading files from {}", location.display());
 
@@ -126,7 +124,7 @@ pub(crate) async fn file_system_dir_walk(
                     }
 
                     if metadata.file_type().is_file() {
-                        match upload_file_to_net(safe, current_file_path).await {
+                        match upload_file_to_net(safe, current_file_path, scope).await {
                             Ok(xorurl) => {
                                 processed_files
                                     .insert(normalised_path, FilesMapChange::Added(xorurl));
@@ -178,6 +176,7 @@ fn valid_depth(entry: &DirEntry, max_depth: usize) -> bool {
 pub(crate) async fn file_system_single_file(
     safe: &Safe,
     location: &Path,
+    scope: Scope,
 ) -> Result<ProcessedFiles> {
     info!("Reading file {}", location.display());
     let (metadata, _) = get_metadata(location, true)?; // follows symlinks.
@@ -191,7 +190,7 @@ pub(crate) async fn file_system_single_file(
             location.display()
         )))
     } else {
-        match upload_file_to_net(safe, location).await {
+        match upload_file_to_net(safe, location, scope).await {
             Ok(xorurl) => {
                 processed_files.insert(normalised_path, FilesMapChange::Added(xorurl));
             }
@@ -11,7 +11,7 @@ use super::{
     metadata::FileMeta,
     ProcessedFiles, RealPath,
 };
-use crate::{app::consts::*, Error, Result, Safe, XorUrl};
+use crate::{app::consts::*, Error, Result, Safe, Scope, XorUrl};
 use log::{debug, info};
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, fs, path::Path};
@@ -95,11 +95,12 @@ pub(crate) async fn add_or_update_file_item(
     file_meta: &FileMeta,
     file_link: Option<&str>,
     name_exists: bool,
+    scope: Scope,
     files_map: &mut FilesMap,
     processed_files: &mut ProcessedFiles,
 ) -> bool {
     // We need to add a new FileInfo, let's generate the FileInfo first
-    match gen_new_file_item(safe, file_path, file_meta, file_link).await {
+    match gen_new_file_item(safe, file_path, file_meta, file_link, scope).await {
         Ok(new_file_item) => {
             // note: files have link property, dirs and symlinks do not
             let xorurl = new_file_item
@@ -139,11 +140,12 @@ async fn gen_new_file_item(
     file_path: &Path,
     file_meta: &FileMeta,
     link: Option<&str>, // must be symlink target or None if FileMeta::is_symlink() is true.
+    scope: Scope,
 ) -> Result<FileInfo> {
     let mut file_item = file_meta.to_file_item();
     if file_meta.is_file() {
         let xorurl = match link {
-            None => upload_file_to_net(safe, file_path).await?,
+            None => upload_file_to_net(safe, file_path, scope).await?,
             Some(link) => link.to_string(),
         };
         file_item.insert(PREDICATE_LINK.to_string(), xorurl);
@@ -0,0 +1,343 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{FileInfo, FilesMap};
+use crate::{app::consts::*, app::nrs::VersionHash, ContentType, Error, Result, Safe, SafeUrl};
+use log::debug;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// A version of a FilesContainer, as listed in its history.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FilesContainerVersion {
+    /// The version, i.e. the hash of the entry of the FilesContainer linking to its FilesMap.
+    pub version: VersionHash,
+    /// The versions this one was made from, more than one if it merged concurrent changes.
+    pub parents: Vec<VersionHash>,
+    /// Time of the latest change to the files in this version, as seconds since the Unix epoch.
+    /// It's taken from the files' metadata, since the time entries are written isn't recorded.
+    /// `None` if there are no files in this version.
+    pub timestamp: Option<u64>,
+    /// Number of files, directories and symlinks in this version.
+    pub files: usize,
+    /// Number of paths added, removed or modified in comparison with the first of its parents.
+    pub changes: usize,
+}
+
+/// Paths which differ between two versions of a FilesContainer.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FilesMapDiff {
+    /// Paths only found in the newer version.
+    pub added: BTreeSet<String>,
+    /// Paths only found in the older version.
+    pub removed: BTreeSet<String>,
+    /// Paths found in both versions, with a different link or metadata.
+    pub modified: BTreeSet<String>,
+}
+
+impl FilesMapDiff {
+    /// Compares the FilesMap of an older version with the one of a newer version.
+    pub fn new(old: &FilesMap, new: &FilesMap) -> Self {
+        let mut diff = Self::default();
+        for (path, new_item) in new {
+            match old.get(path) {
+                None => {
+                    diff.added.insert(path.clone());
+                }
+                Some(old_item) if is_item_modified(old_item, new_item) => {
+                    diff.modified.insert(path.clone());
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .keys()
+            .filter(|path| !new.contains_key(*path))
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    /// Number of paths added, removed or modified.
+    pub fn len(&self) -> usize {
+        self.added.len() + self.removed.len() + self.modified.len()
+    }
+
+    /// Returns true if both versions have the same paths, with the same links and metadata.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Safe {
+    /// # List the versions of a FilesContainer.
+    ///
+    /// All the versions the FilesContainer went through are listed, the most recent first,
+    /// each of them along with the number of changes made to the files in it.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::{Safe, Scope};
+    /// # let mut safe = Safe::dry_runner(None);
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true, Scope::Public).await.unwrap();
+    ///     for version in safe.files_container_history(&xorurl).await.unwrap() {
+    ///         println!("Version {} has {} changes", version.version, version.changes);
+    ///     }
+    /// # });
+    /// ```
+    pub async fn files_container_history(&self, url: &str) -> Result<Vec<FilesContainerVersion>> {
+        debug!("Getting history of files container at: {:?}", url);
+        let mut safe_url = self.parse_and_resolve_url(url).await?;
+
+        // If the FilesContainer URL was resolved from an NRS name we need to remove
+        // the version from it so we list all of its versions
+        safe_url.set_content_version(None);
+
+        let entries = self.register_fetch_history(&safe_url).await?;
+        let mut files_maps = BTreeMap::new();
+        for (hash, entry, _) in &entries {
+            let files_map = self.fetch_files_map(entry).await?;
+            let _ = files_maps.insert(*hash, files_map);
+        }
+
+        let empty_files_map = FilesMap::default();
+        let history = entries
+            .iter()
+            .map(|(hash, _, parents)| {
+                let files_map = &files_maps[hash];
+                let parent_files_map = parents
+                    .iter()
+                    .next()
+                    .and_then(|parent| files_maps.get(parent))
+                    .unwrap_or(&empty_files_map);
+
+                FilesContainerVersion {
+                    version: hash.into(),
+                    parents: parents.iter().map(VersionHash::from).collect(),
+                    timestamp: latest_modified(files_map),
+                    files: files_map.len(),
+                    changes: FilesMapDiff::new(parent_files_map, files_map).len(),
+                }
+            })
+            .collect();
+
+        Ok(history)
+    }
+
+    /// # Compare two versions of a FilesContainer.
+    ///
+    /// Returns the paths added, removed and modified in version `to` in comparison with version `from`.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::{Safe, Scope};
+    /// # let mut safe = Safe::dry_runner(None);
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true, Scope::Public).await.unwrap();
+    ///     let (from, _) = safe.files_container_get(&xorurl).await.unwrap().unwrap();
+    ///     let (to, _, _) = safe.files_container_remove_path(&format!("{}/test.md", xorurl), false, false).await.unwrap();
+    ///     let diff = safe.files_container_diff(&xorurl, from, to).await.unwrap();
+    ///     assert!(diff.removed.contains("/test.md"));
+    /// # });
+    /// ```
+    pub async fn files_container_diff(
+        &self,
+        url: &str,
+        from: VersionHash,
+        to: VersionHash,
+    ) -> Result<FilesMapDiff> {
+        debug!(
+            "Comparing versions {} and {} of files container at: {:?}",
+            from, to, url
+        );
+        let safe_url = self.parse_and_resolve_url(url).await?;
+        let from_files_map = self.fetch_files_container_version(&safe_url, from).await?;
+        let to_files_map = self.fetch_files_container_version(&safe_url, to).await?;
+
+        Ok(FilesMapDiff::new(&from_files_map, &to_files_map))
+    }
+
+    /// # Roll a FilesContainer back to a previous version.
+    ///
+    /// A new version is appended to the FilesContainer, with the same files as the version
+    /// rolled back to, replacing the current version(s). Nothing is appended if the
+    /// FilesContainer is at that version already.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::{Safe, Scope};
+    /// # let mut safe = Safe::dry_runner(None);
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, files_map) = safe.files_container_create_from("./testdata", None, true, true, Scope::Public).await.unwrap();
+    ///     let (version, _) = safe.files_container_get(&xorurl).await.unwrap().unwrap();
+    ///     safe.files_container_remove_path(&format!("{}/test.md", xorurl), false, false).await.unwrap();
+    ///     let (new_version, new_files_map) = safe.files_container_rollback(&xorurl, version, false).await.unwrap();
+    ///     println!("FilesContainer is now at version: {}", new_version);
+    ///     assert_eq!(new_files_map, files_map);
+    /// # });
+    /// ```
+    pub async fn files_container_rollback(
+        &self,
+        url: &str,
+        version: VersionHash,
+        update_nrs: bool,
+    ) -> Result<(VersionHash, FilesMap)> {
+        let safe_url = SafeUrl::from_url(url)?;
+
+        // If NRS name shall be updated then the URL has to be an NRS-URL
+        if update_nrs && safe_url.content_type() != ContentType::NrsMapContainer {
+            return Err(Error::InvalidInput(
+                "'update-nrs' is not allowed since the URL provided is not an NRS URL".to_string(),
+            ));
+        }
+
+        let mut safe_url = self.parse_and_resolve_url(url).await?;
+        let files_map = self
+            .fetch_files_container_version(&safe_url, version)
+            .await?;
+
+        // If the FilesContainer URL was resolved from an NRS name we need to remove
+        // the version from it so we can fetch its current version(s) to replace
+        safe_url.set_content_version(None);
+        let current_versions: HashSet<VersionHash> = self
+            .register_fetch_entries(&safe_url)
+            .await?
+            .iter()
+            .map(|(hash, _)| hash.into())
+            .collect();
+
+        if current_versions.len() == 1 && current_versions.contains(&version) {
+            debug!(
+                "FilesContainer at {} is already at version {}",
+                url, version
+            );
+            return Ok((version, files_map));
+        }
+
+        let new_version = self
+            .append_version_to_files_container(
+                current_versions,
+                &files_map,
+                url,
+                safe_url,
+                update_nrs,
+            )
+            .await?;
+
+        Ok((new_version, files_map))
+    }
+
+    // Fetch the FilesMap of a specific version of the FilesContainer at a resolved URL
+    async fn fetch_files_container_version(
+        &self,
+        safe_url: &SafeUrl,
+        version: VersionHash,
+    ) -> Result<FilesMap> {
+        let mut safe_url = safe_url.clone();
+        safe_url.set_content_version(Some(version));
+        match self.fetch_files_container(&safe_url).await? {
+            Some((_, files_map)) => Ok(files_map),
+            None => Err(Error::VersionNotFound(format!(
+                "Version '{}' is invalid for FilesContainer found at \"{}\"",
+                version, safe_url
+            ))),
+        }
+    }
+}
+
+// The time the FileInfos are created at is bookkeeping of the FilesContainer,
+// thus it's not a change made to the item, e.g. when re-uploading the same file
+fn is_item_modified(old: &FileInfo, new: &FileInfo) -> bool {
+    let without_bookkeeping = |item: &FileInfo| -> FileInfo {
+        item.iter()
+            .filter(|(key, _)| *key != PREDICATE_CREATED && *key != PREDICATE_MODIFIED)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    };
+
+    without_bookkeeping(old) != without_bookkeeping(new)
+}
+
+// Time of the latest change to any of the items of the FilesMap
+fn latest_modified(files_map: &FilesMap) -> Option<u64> {
+    files_map
+        .values()
+        .filter_map(|item| item.get(PREDICATE_MODIFIED)?.parse().ok())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{latest_modified, FilesMapDiff};
+    use crate::{
+        app::consts::*,
+        files::{FileInfo, FilesMap},
+    };
+
+    fn file_item(link: &str, modified: &str) -> FileInfo {
+        vec![
+            (PREDICATE_TYPE.to_string(), "text/plain".to_string()),
+            (PREDICATE_LINK.to_string(), link.to_string()),
+            (PREDICATE_CREATED.to_string(), modified.to_string()),
+            (PREDICATE_MODIFIED.to_string(), modified.to_string()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn test_files_map_diff() {
+        let old: FilesMap = vec![
+            ("/kept.md".to_string(), file_item("safe://kept", "10")),
+            ("/reuploaded.md".to_string(), file_item("safe://same", "10")),
+            ("/changed.md".to_string(), file_item("safe://old", "10")),
+            ("/removed.md".to_string(), file_item("safe://removed", "10")),
+        ]
+        .into_iter()
+        .collect();
+        let new: FilesMap = vec![
+            ("/kept.md".to_string(), file_item("safe://kept", "10")),
+            ("/reuploaded.md".to_string(), file_item("safe://same", "20")),
+            ("/changed.md".to_string(), file_item("safe://new", "20")),
+            ("/added.md".to_string(), file_item("safe://added", "30")),
+        ]
+        .into_iter()
+        .collect();
+
+        let diff = FilesMapDiff::new(&old, &new);
+        assert_eq!(
+            diff.added.into_iter().collect::<Vec<_>>(),
+            vec!["/added.md"]
+        );
+        assert_eq!(
+            diff.removed.into_iter().collect::<Vec<_>>(),
+            vec!["/removed.md"]
+        );
+        assert_eq!(
+            diff.modified.into_iter().collect::<Vec<_>>(),
+            vec!["/changed.md"]
+        );
+
+        assert!(FilesMapDiff::new(&new, &new).is_empty());
+        assert_eq!(FilesMapDiff::new(&FilesMap::default(), &new).len(), 4);
+
+        assert_eq!(latest_modified(&new), Some(30));
+        assert_eq!(latest_modified(&FilesMap::default()), None);
+    }
+}
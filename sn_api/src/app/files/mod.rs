@@ -8,12 +8,13 @@
 
 mod file_system;
 mod files_map;
+mod history;
 mod metadata;
 mod realpath;
 
 use crate::{
-    app::consts::*, app::nrs::VersionHash, resolver::Range, ContentType, DataType, Error, Result,
-    Safe, SafeUrl, Scope, XorUrl,
+    app::consts::*, app::nrs::VersionHash, resolver::Range, ContentType, DataAddress, DataType,
+    Error, Result, Safe, SafeUrl, Scope, XorUrl,
 };
 use bytes::{Buf, Bytes};
 use file_system::{
@@ -22,19 +23,26 @@ use file_system::{
 use files_map::add_or_update_file_item;
 use log::{debug, info, warn};
 use relative_path::RelativePath;
-use safe_network::{client::Client, types::BytesAddress};
+use safe_network::{
+    client::{Client, Error as ClientError},
+    messaging::data::Error as ErrorMsg,
+    types::{BytesAddress, Error as TypesError},
+};
 use std::{
     collections::{BTreeMap, HashSet},
     iter::FromIterator,
     path::{Path, PathBuf},
     str,
 };
+use tokio::sync::broadcast::Receiver;
 
 pub(crate) use files_map::{file_map_for_path, get_file_link_and_metadata};
 pub(crate) use metadata::FileMeta;
 pub(crate) use realpath::RealPath;
 
 pub use files_map::{FileInfo, FilesMap, FilesMapChange, GetAttr};
+pub use history::{FilesContainerVersion, FilesMapDiff};
+pub use safe_network::client::{FileReader, Progress, ProgressEvent, TransferId};
 
 // List of files uploaded with details if they were added, updated or removed from FilesContainer
 pub type ProcessedFiles = BTreeMap<PathBuf, FilesMapChange>;
@@ -46,25 +54,28 @@ pub(crate) const FILES_CONTAINER_TYPE_TAG: u64 = 1_100;
 impl Safe {
     /// # Create an empty FilesContainer.
     ///
+    /// A private FilesContainer can only be read by its owner, as well as the files
+    /// later added to it, which are encrypted with the owner's keypair.
+    ///
     /// ## Example
     ///
     /// ```no_run
-    /// # use sn_api::Safe;
+    /// # use sn_api::{Safe, Scope};
     /// # let mut safe = Safe::default();
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     ///     safe.connect(None, None, None).await.unwrap();
-    ///     let xorurl = safe.files_container_create().await.unwrap();
+    ///     let xorurl = safe.files_container_create(Scope::Public).await.unwrap();
     ///     assert!(xorurl.contains("safe://"))
     /// # });
     /// ```
-    pub async fn files_container_create(&self) -> Result<XorUrl> {
+    pub async fn files_container_create(&self, scope: Scope) -> Result<XorUrl> {
         // Build a Register creation operation
         let xorurl = self
             .register_create(
                 None,
                 FILES_CONTAINER_TYPE_TAG,
-                false,
+                scope == Scope::Private,
                 ContentType::FilesContainer,
             )
             .await?;
@@ -74,15 +85,18 @@ impl Safe {
 
     /// # Create a FilesContainer containing files uploaded from a local folder.
     ///
+    /// With a private scope, the files are stored encrypted with the client's keypair,
+    /// in a private FilesContainer only its owner can read.
+    ///
     /// ## Example
     ///
     /// ```no_run
-    /// # use sn_api::Safe;
+    /// # use sn_api::{Safe, Scope};
     /// # let mut safe = Safe::default();
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     ///     safe.connect(None, None, None).await.unwrap();
-    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true, Scope::Public).await.unwrap();
     ///     assert!(xorurl.contains("safe://"))
     /// # });
     /// ```
@@ -92,10 +106,11 @@ impl Safe {
         dst: Option<&Path>,
         recursive: bool,
         follow_links: bool,
+        scope: Scope,
     ) -> Result<(XorUrl, ProcessedFiles, FilesMap)> {
         // Let's upload the files (if not dry_run) and generate the list of local files paths
         let mut processed_files =
-            file_system_dir_walk(self, location.as_ref(), recursive, follow_links).await?;
+            file_system_dir_walk(self, location.as_ref(), recursive, follow_links, scope).await?;
 
         // The FilesContainer is stored on a Register
         // and the link to the serialised FilesMap as the entry's value
@@ -109,13 +124,13 @@ impl Safe {
         .await?;
 
         // Create a Register
-        let xorurl = self.files_container_create().await?;
+        let xorurl = self.files_container_create(scope).await?;
 
         if self.dry_run_mode {
             Ok((xorurl.to_string(), processed_files, files_map))
         } else {
             // Store files map on network
-            let files_map_xorurl = self.store_files_map(&files_map).await?;
+            let files_map_xorurl = self.store_files_map(&files_map, scope).await?;
 
             let mut reg_url = SafeUrl::from_xorurl(&xorurl)?;
 
@@ -141,12 +156,12 @@ impl Safe {
     /// ## Example
     ///
     /// ```no_run
-    /// # use sn_api::Safe;
+    /// # use sn_api::{Safe, Scope};
     /// # let mut safe = Safe::default();
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   safe.connect(None, None, None).await.unwrap();
-    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true, Scope::Public).await.unwrap();
     ///     let (version, files_map) = safe.files_container_get(&xorurl).await.unwrap().unwrap();
     ///     println!("FilesContainer fetched is at version: {}", version);
     ///     println!("FilesMap of fetched version is: {:?}", files_map);
@@ -186,6 +201,10 @@ impl Safe {
                     },
                     safe_url
                 )),
+                Error::AccessDenied(_) => Error::AccessDenied(format!(
+                    "FilesContainer found at \"{}\" is private and it can't be read with the keypair configured, only its owner can read it",
+                    safe_url
+                )),
                 err => Error::NetDataError(format!("Failed to get current version: {}", err)),
             })?;
 
@@ -199,25 +218,30 @@ impl Safe {
             return Err(Error::NotImplementedError("Multiple file container entries not managed, this happends when 2 clients write concurrently to a file container".to_string()));
         }
         let first_entry = entries.iter().next();
-        let (version, files_map_xorurl) = if let Some((v, m)) = first_entry {
-            (v.into(), str::from_utf8(m)?)
+        let (version, entry) = if let Some((v, m)) = first_entry {
+            (v.into(), m)
         } else {
             warn!("FilesContainer found at \"{:?}\" was empty", safe_url);
             return Ok(None);
         };
 
+        let files_map = self.fetch_files_map(entry).await?;
+        debug!("Files map retrieved.... {:?}", &version);
+
+        Ok(Some((version, files_map)))
+    }
+
+    // Fetch the FilesMap linked from an entry of a FilesContainer
+    async fn fetch_files_map(&self, entry: &[u8]) -> Result<FilesMap> {
         // Using the FilesMap XOR-URL we can now fetch the FilesMap and deserialise it
-        let files_map_url = SafeUrl::from_xorurl(files_map_xorurl)?;
-        let serialised_files_map = self.fetch_public_data(&files_map_url, None).await?;
-        let files_map = serde_json::from_slice(serialised_files_map.chunk()).map_err(|err| {
+        let files_map_url = SafeUrl::from_xorurl(str::from_utf8(entry)?)?;
+        let serialised_files_map = self.fetch_data(&files_map_url, None).await?;
+        serde_json::from_slice(serialised_files_map.chunk()).map_err(|err| {
             Error::ContentError(format!(
                 "Couldn't deserialise the FilesMap stored in the FilesContainer: {:?}",
                 err
             ))
-        })?;
-        debug!("Files map retrieved.... {:?}", &version);
-
-        Ok(Some((version, files_map)))
+        })
     }
 
     /// # Sync up local folder with the content on a FilesContainer.
@@ -225,12 +249,12 @@ impl Safe {
     /// ## Example
     ///
     /// ```no_run
-    /// # use sn_api::Safe;
+    /// # use sn_api::{Safe, Scope};
     /// # let mut safe = Safe::default();
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   safe.connect(None, None, None).await.unwrap();
-    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, false).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, false, Scope::Public).await.unwrap();
     ///     let (version, new_processed_files, new_files_map) = safe.files_container_sync("./testdata", &xorurl, true, true, false, false).await.unwrap();
     ///     println!("FilesContainer synced up is at version: {}", version);
     ///     println!("The local files that were synced up are: {:?}", new_processed_files);
@@ -274,11 +298,20 @@ impl Safe {
                 None => (None, FilesMap::default()),
             };
 
+        // Files are stored with the same scope as the FilesContainer
+        let scope = safe_url.scope();
+
         // Let's generate the list of local files paths, without uploading any new file yet.
         // Use a dry runner only for this next operation
         let dry_runner = Safe::dry_runner(Some(self.xorurl_base));
-        let processed_files =
-            file_system_dir_walk(&dry_runner, location.as_ref(), recursive, follow_links).await?;
+        let processed_files = file_system_dir_walk(
+            &dry_runner,
+            location.as_ref(),
+            recursive,
+            follow_links,
+            scope,
+        )
+        .await?;
 
         let dst_path = Path::new(safe_url.path());
 
@@ -292,6 +325,7 @@ impl Safe {
             false,
             true,
             follow_links,
+            scope,
         )
         .await?;
 
@@ -312,12 +346,12 @@ impl Safe {
     /// ## Example
     ///
     /// ```no_run
-    /// # use sn_api::Safe;
+    /// # use sn_api::{Safe, Scope};
     /// # let mut safe = Safe::default();
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   safe.connect(None, None, None).await.unwrap();
-    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true, Scope::Public).await.unwrap();
     ///     let new_file_name = format!("{}/new_name_test.md", xorurl);
     ///     let (version, new_processed_files, new_files_map) = safe.files_container_add("./testdata/test.md", &new_file_name, false, false, true).await.unwrap();
     ///     println!("FilesContainer is now at version: {}", version);
@@ -346,11 +380,12 @@ impl Safe {
         } else {
             // We then assume source is a local path
             let source_path = Path::new(source_file);
+            let scope = safe_url.scope();
 
             // Let's generate the list of local files paths, without uploading any new file yet.
             // Use dry runner only for this next operation
             let dry_runner = Safe::dry_runner(Some(self.xorurl_base));
-            let processed_files = file_system_single_file(&dry_runner, source_path).await?;
+            let processed_files = file_system_single_file(&dry_runner, source_path, scope).await?;
 
             files_map_sync(
                 self,
@@ -362,6 +397,7 @@ impl Safe {
                 force,
                 false,
                 follow_links,
+                scope,
             )
             .await?
         };
@@ -383,12 +419,12 @@ impl Safe {
     /// ## Example
     ///
     /// ```no_run
-    /// # use sn_api::Safe;
+    /// # use sn_api::{Safe, Scope};
     /// # let mut safe = Safe::default();
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   safe.connect(None, None, None).await.unwrap();
-    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true, Scope::Public).await.unwrap();
     ///     let new_file_name = format!("{}/new_name_test.md", xorurl);
     ///     let (version, new_processed_files, new_files_map) = safe.files_container_add_from_raw(b"0123456789", &new_file_name, false, false).await.unwrap();
     ///     println!("FilesContainer is now at version: {}", version);
@@ -406,7 +442,7 @@ impl Safe {
         let (safe_url, current_version, current_files_map) =
             validate_files_add_params(self, "", url, update_nrs).await?;
 
-        let new_file_xorurl = self.store_public_bytes(data, None).await?;
+        let new_file_xorurl = self.store_bytes(data, None, safe_url.scope()).await?;
 
         let dst_path = Path::new(safe_url.path());
         let (processed_files, new_files_map, success_count) =
@@ -429,12 +465,12 @@ impl Safe {
     /// ## Example
     ///
     /// ```no_run
-    /// # use sn_api::Safe;
+    /// # use sn_api::{Safe, Scope};
     /// # let mut safe = Safe::default();
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   safe.connect(None, None, None).await.unwrap();
-    ///     let (xorurl, processed_files, files_map) = safe.files_container_create_from("./testdata/", None, true, true).await.unwrap();
+    ///     let (xorurl, processed_files, files_map) = safe.files_container_create_from("./testdata/", None, true, true, Scope::Public).await.unwrap();
     ///     let remote_file_path = format!("{}/test.md", xorurl);
     ///     let (version, new_processed_files, new_files_map) = safe.files_container_remove_path(&remote_file_path, false, false).await.unwrap();
     ///     println!("FilesContainer is now at version: {}", version);
@@ -559,7 +595,8 @@ impl Safe {
         // The FilesContainer is updated by adding an entry containing the link to
         // the file with the serialised new version of the FilesMap.
         let files_map_xorurl = if !self.dry_run_mode {
-            self.store_files_map(new_files_map).await?
+            self.store_files_map(new_files_map, safe_url.scope())
+                .await?
         } else {
             "".to_string()
         };
@@ -608,30 +645,94 @@ impl Safe {
         bytes: Bytes,
         media_type: Option<&str>,
     ) -> Result<XorUrl> {
-        let content_type = media_type.map_or_else(
-            || Ok(ContentType::Raw),
-            |media_type_str| {
-                if SafeUrl::is_media_type_supported(media_type_str) {
-                    Ok(ContentType::MediaType(media_type_str.to_string()))
-                } else {
-                    Err(Error::InvalidMediaType(format!(
-                        "Media-type '{}' not supported. You can pass 'None' as the 'media_type' for this content to be treated as raw",
-                        media_type_str
-                    )))
-                }
-            },
-        )?;
+        self.store_bytes(bytes, media_type, Scope::Public).await
+    }
+
+    // Store data with the given scope. Private data is encrypted with the client's keypair,
+    // which is also needed to calculate its address in dry-run mode when connected.
+    pub(crate) async fn store_bytes(
+        &self,
+        bytes: Bytes,
+        media_type: Option<&str>,
+        scope: Scope,
+    ) -> Result<XorUrl> {
+        let content_type = content_type_for(media_type)?;
 
         let address = if self.dry_run_mode {
             debug!(
                 "Calculating network address for {} bytes of data",
                 bytes.len()
             );
-            Client::calculate_address(bytes, Scope::Public)?
+            match self.get_safe_client() {
+                Ok(client) => client.calculate_own_address(bytes, scope)?,
+                Err(_) => Client::calculate_address(bytes, scope)?,
+            }
         } else {
             debug!("Storing {} bytes of data", bytes.len());
             let client = self.get_safe_client()?;
-            let (address, _) = client.upload_and_verify(bytes, Scope::Public).await?;
+            let (address, _) = client.upload_and_verify(bytes, scope).await?;
+            address
+        };
+        let xorurl = SafeUrl::encode_bytes(address, content_type, self.xorurl_base)?;
+
+        Ok(xorurl)
+    }
+
+    /// # Store the content of a local file onto the network
+    ///
+    /// Same as `store_public_bytes`, but the content is read from the file at `path` and sent to
+    /// the network as it is self-encrypted, without ever loading the whole file into memory.
+    /// Only the first chunk of the content is fetched back to verify the upload.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # use std::path::Path;
+    /// # let mut safe = Safe::default();
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let xorurl = safe.store_public_file(Path::new("./testdata/test.md"), Some("text/markdown")).await.unwrap();
+    ///     let received_data = safe.files_get_public(&xorurl, None).await.unwrap();
+    /// # });
+    /// ```
+    pub async fn store_public_file(&self, path: &Path, media_type: Option<&str>) -> Result<XorUrl> {
+        self.store_file(path, media_type, Scope::Public).await
+    }
+
+    // Store the content of a local file with the given scope, see `store_bytes`
+    pub(crate) async fn store_file(
+        &self,
+        path: &Path,
+        media_type: Option<&str>,
+        scope: Scope,
+    ) -> Result<XorUrl> {
+        let content_type = content_type_for(media_type)?;
+
+        let address = if self.dry_run_mode {
+            debug!("Calculating network address for file at {}", path.display());
+            match self.get_safe_client() {
+                Ok(client) => client.calculate_own_address_from_path(path, scope).await?,
+                Err(_) => Client::calculate_address_from_path(path, scope).await?,
+            }
+        } else {
+            let client = self.get_safe_client()?;
+            let pending_upload = if self.resume_uploads {
+                client.pending_upload(path, scope).await?
+            } else {
+                None
+            };
+            let address = match pending_upload {
+                Some(manifest) => {
+                    debug!("Resuming upload of content of file at {}", path.display());
+                    client.resume_upload(manifest).await?
+                }
+                None => {
+                    debug!("Storing content of file at {}", path.display());
+                    client.upload_from_path(path, scope).await?
+                }
+            };
+            let _ = client.read_from(address, 0, 1).await?;
             address
         };
         let xorurl = SafeUrl::encode_bytes(address, content_type, self.xorurl_base)?;
@@ -658,23 +759,73 @@ impl Safe {
     pub async fn files_get_public(&self, url: &str, range: Range) -> Result<Bytes> {
         // TODO: do we want ownership from other PKs yet?
         let safe_url = self.parse_and_resolve_url(url).await?;
-        self.fetch_public_data(&safe_url, range).await
+        self.fetch_data(&safe_url, range).await
     }
 
-    /// Fetch an file from a SafeUrl without performing any type of URL resolution
-    pub(crate) async fn fetch_public_data(
-        &self,
-        safe_url: &SafeUrl,
-        range: Range,
-    ) -> Result<Bytes> {
+    /// # Get a file as a stream
+    /// Get a reader over a file's content, which is fetched from the network as it's read,
+    /// rather than being retrieved in full before returning.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # use tokio::io::AsyncReadExt;
+    /// # let mut safe = Safe::default();
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let data = b"Something super good";
+    ///     let xorurl = safe.store_public_bytes(bytes::Bytes::from_static(data), None).await.unwrap();
+    ///     let mut reader = safe.files_get_public_stream(&xorurl).await.unwrap();
+    ///     let mut received_data = vec![];
+    ///     reader.read_to_end(&mut received_data).await.unwrap();
+    ///     assert_eq!(received_data, data);
+    /// # });
+    /// ```
+    pub async fn files_get_public_stream(&self, url: &str) -> Result<FileReader> {
+        let safe_url = self.parse_and_resolve_url(url).await?;
         match safe_url.data_type() {
             DataType::File => {
-                self.get_bytes(BytesAddress::Public(safe_url.xorname()), range)
-                    .await
-            }
-            other => {
-                return Err(Error::ContentError(format!("{}", other)));
+                let address = bytes_address(&safe_url)?;
+                debug!("Attempting to stream data from {:?}", address.name());
+                let client = self.get_safe_client()?;
+                client.read_stream(address).await.map_err(file_read_error)
             }
+            other => Err(Error::ContentError(format!("{}", other))),
+        }
+    }
+
+    /// # Get the progress of files being transferred
+    /// Get a receiver of the progress of the files uploaded and downloaded from now on,
+    /// e.g. to display progress bars while putting, syncing or getting files.
+    /// Every receiver obtained gets all the events, unless it falls too far behind.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use sn_api::{files::Progress, Safe};
+    /// # let mut safe = Safe::default();
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let mut progress = safe.files_progress_events().await.unwrap();
+    ///     let xorurl = safe.store_public_bytes(bytes::Bytes::from_static(b"Something super good"), None).await.unwrap();
+    ///     while let Ok(event) = progress.try_recv() {
+    ///         if let Progress::ChunkAcked { bytes } = event.progress {
+    ///             println!("{} bytes stored", bytes);
+    ///         }
+    ///     }
+    /// # });
+    /// ```
+    pub async fn files_progress_events(&self) -> Result<Receiver<ProgressEvent>> {
+        let client = self.get_safe_client()?;
+        Ok(client.progress_events().await)
+    }
+
+    /// Fetch an file from a SafeUrl without performing any type of URL resolution
+    pub(crate) async fn fetch_data(&self, safe_url: &SafeUrl, range: Range) -> Result<Bytes> {
+        match safe_url.data_type() {
+            DataType::File => self.get_bytes(bytes_address(safe_url)?, range).await,
+            other => Err(Error::ContentError(format!("{}", other))),
         }
     }
 
@@ -691,7 +842,7 @@ impl Safe {
         } else {
             client.read_bytes(address).await
         }
-        .map_err(|e| Error::NetDataError(format!("Failed to GET file: {:?}", e)))?;
+        .map_err(file_read_error)?;
 
         debug!(
             "{} bytes of data successfully retrieved from: {:?}",
@@ -702,8 +853,9 @@ impl Safe {
         Ok(data)
     }
 
-    // Private helper to serialise a FilesMap and store it in a file
-    async fn store_files_map(&self, files_map: &FilesMap) -> Result<String> {
+    // Private helper to serialise a FilesMap and store it in a file,
+    // with the same scope as the FilesContainer it belongs to
+    async fn store_files_map(&self, files_map: &FilesMap, scope: Scope) -> Result<String> {
         // The FilesMapContainer is a Register where each NRS Map version is
         // an entry containing the XOR-URL of the file that contains the serialised NrsMap.
         let serialised_files_map = serde_json::to_string(&files_map).map_err(|err| {
@@ -714,7 +866,7 @@ impl Safe {
         })?;
 
         let files_map_xorurl = self
-            .store_public_bytes(Bytes::from(serialised_files_map), None)
+            .store_bytes(Bytes::from(serialised_files_map), None, scope)
             .await?;
 
         Ok(files_map_xorurl)
@@ -723,6 +875,50 @@ impl Safe {
 
 // Helper functions
 
+// Get the address of the file a SafeUrl targets, either public or private
+fn bytes_address(safe_url: &SafeUrl) -> Result<BytesAddress> {
+    match safe_url.address() {
+        DataAddress::Bytes(address) => Ok(address),
+        other => Err(Error::ContentError(format!(
+            "The URL doesn't target a file but: {:?}",
+            other
+        ))),
+    }
+}
+
+// Private content which can't be decrypted wasn't stored by the configured keypair,
+// let's report it as such rather than as a generic network error
+fn file_read_error(err: ClientError) -> Error {
+    match err {
+        ClientError::NetworkDataError(TypesError::FailedToDecrypt)
+        | ClientError::ErrorMsg {
+            source: ErrorMsg::AccessDenied(_),
+            ..
+        } => Error::AccessDenied(
+            "The file is private and it can't be decrypted with the keypair configured, only its owner can read it"
+                .to_string(),
+        ),
+        err => Error::NetDataError(format!("Failed to GET file: {:?}", err)),
+    }
+}
+
+// Get the ContentType for the given media-type, if it's supported, or Raw if no media-type is provided
+fn content_type_for(media_type: Option<&str>) -> Result<ContentType> {
+    media_type.map_or_else(
+        || Ok(ContentType::Raw),
+        |media_type_str| {
+            if SafeUrl::is_media_type_supported(media_type_str) {
+                Ok(ContentType::MediaType(media_type_str.to_string()))
+            } else {
+                Err(Error::InvalidMediaType(format!(
+                    "Media-type '{}' not supported. You can pass 'None' as the 'media_type' for this content to be treated as raw",
+                    media_type_str
+                )))
+            }
+        },
+    )
+}
+
 // Make sure the input params are valid for a files_container_add operation
 async fn validate_files_add_params(
     safe: &Safe,
@@ -826,6 +1022,7 @@ async fn files_map_sync(
     force: bool,
     compare_file_content: bool,
     follow_links: bool,
+    scope: Scope,
 ) -> Result<(ProcessedFiles, FilesMap, u64)> {
     let (location_base_path, dst_base_path) = get_base_paths(location, dst_path);
     let mut updated_files_map = FilesMap::new();
@@ -864,6 +1061,7 @@ async fn files_map_sync(
                     &FileMeta::from_path(local_file_name, follow_links)?,
                     None, // no xorurl link
                     false,
+                    scope,
                     &mut updated_files_map,
                     &mut processed_files,
                 )
@@ -892,7 +1090,7 @@ async fn files_map_sync(
             }
             Some(file_item) => {
                 let is_modified =
-                    is_file_item_modified(safe, Path::new(local_file_name), file_item).await;
+                    is_file_item_modified(safe, Path::new(local_file_name), file_item, scope).await;
                 if force || (compare_file_content && is_modified) {
                     // We need to update the current FileInfo
                     if add_or_update_file_item(
@@ -903,6 +1101,7 @@ async fn files_map_sync(
                         &FileMeta::from_path(local_file_name.as_path(), follow_links)?,
                         None, // no xorurl link
                         true,
+                        scope,
                         &mut updated_files_map,
                         &mut processed_files,
                     )
@@ -979,11 +1178,18 @@ async fn files_map_sync(
     Ok((processed_files, updated_files_map, success_count))
 }
 
-async fn is_file_item_modified(safe: &Safe, local_filename: &Path, file_item: &FileInfo) -> bool {
+async fn is_file_item_modified(
+    safe: &Safe,
+    local_filename: &Path,
+    file_item: &FileInfo,
+    scope: Scope,
+) -> bool {
     if FileMeta::filetype_is_file(&file_item[PREDICATE_TYPE]) {
-        // Use a dry runner only for this next operation
-        let dry_runner = Safe::dry_runner(Some(safe.xorurl_base));
-        let is_uploaded = match upload_file_to_net(&dry_runner, local_filename).await {
+        // Use a dry runner only for this next operation. It keeps the client
+        // since the address of private content depends on the keypair it's stored with
+        let mut dry_runner = safe.clone();
+        dry_runner.dry_run_mode = true;
+        let is_uploaded = match upload_file_to_net(&dry_runner, local_filename, scope).await {
             Ok(local_xorurl) => file_item[PREDICATE_LINK] != local_xorurl,
             Err(_) => false,
         };
@@ -1054,6 +1260,7 @@ async fn files_map_add_link(
                         &file_meta,
                         Some(file_link),
                         true,
+                        Scope::Public, // nothing is uploaded when a link is provided
                         &mut files_map,
                         &mut processed_files,
                     )
@@ -1091,6 +1298,7 @@ async fn files_map_add_link(
                 &FileMeta::from_type_and_size(&file_type, file_size),
                 Some(file_link),
                 false,
+                Scope::Public, // nothing is uploaded when a link is provided
                 &mut files_map,
                 &mut processed_files,
             )
@@ -1209,6 +1417,7 @@ async fn files_map_create(
             &FileMeta::from_path(&file_name, follow_links)?,
             if link.is_empty() { None } else { Some(&link) },
             false,
+            Scope::Public, // files were already uploaded, only dirs and symlinks have no link
             &mut files_map,
             content,
         )
@@ -1222,7 +1431,9 @@ async fn files_map_create(
 mod tests {
     use super::*;
     use crate::{
-        app::test_helpers::{new_safe_instance, random_nrs_name},
+        app::test_helpers::{
+            new_safe_instance, new_safe_instance_with_random_keypair, random_nrs_name,
+        },
         register::EntryHash,
         retry_loop, retry_loop_for_pattern,
     };
@@ -1235,9 +1446,9 @@ mod tests {
 
     // make some constants for these, in case entries in the testdata folder change.
     const TESTDATA_PUT_FILEITEM_COUNT: usize = 11;
-    const TESTDATA_PUT_FILESMAP_COUNT: usize = 10; // TODO: review case of empty folder and empty file
+    const TESTDATA_PUT_FILESMAP_COUNT: usize = 11;
     const TESTDATA_NO_SLASH_PUT_FILEITEM_COUNT: usize = 12;
-    const TESTDATA_NO_SLASH_PUT_FILESMAP_COUNT: usize = 11; // TODO: review case of empty file
+    const TESTDATA_NO_SLASH_PUT_FILESMAP_COUNT: usize = 12;
     const SUBFOLDER_PUT_FILEITEM_COUNT: usize = 2;
     const SUBFOLDER_NO_SLASH_PUT_FILEITEM_COUNT: usize = 3;
 
@@ -1245,8 +1456,13 @@ mod tests {
     async fn new_files_container_from_testdata(
         safe: &Safe,
     ) -> Result<(String, ProcessedFiles, FilesMap)> {
-        let (xorurl, processed_files, files_map) =
-            retry_loop!(safe.files_container_create_from(TEST_DATA_FOLDER, None, true, true,));
+        let (xorurl, processed_files, files_map) = retry_loop!(safe.files_container_create_from(
+            TEST_DATA_FOLDER,
+            None,
+            true,
+            true,
+            Scope::Public,
+        ));
 
         assert!(xorurl.starts_with("safe://"));
         assert_eq!(processed_files.len(), TESTDATA_PUT_FILEITEM_COUNT);
@@ -1295,7 +1511,7 @@ mod tests {
     #[tokio::test]
     async fn test_files_container_create_empty() -> Result<()> {
         let safe = new_safe_instance().await?;
-        let xorurl = safe.files_container_create().await?;
+        let xorurl = safe.files_container_create(Scope::Public).await?;
 
         assert!(xorurl.starts_with("safe://"));
 
@@ -1345,7 +1561,13 @@ mod tests {
         let safe = new_safe_instance().await?;
         let filename = Path::new("./testdata/test.md");
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from(&filename.display().to_string(), None, false, false)
+            .files_container_create_from(
+                &filename.display().to_string(),
+                None,
+                false,
+                false,
+                Scope::Public,
+            )
             .await?;
 
         assert!(xorurl.starts_with("safe://"));
@@ -1360,12 +1582,52 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_files_container_create_from_private() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let (xorurl, processed_files, files_map) = retry_loop!(safe.files_container_create_from(
+            TEST_DATA_FOLDER,
+            None,
+            true,
+            true,
+            Scope::Private,
+        ));
+        assert_eq!(processed_files.len(), TESTDATA_PUT_FILEITEM_COUNT);
+
+        // the owner can read the container and the files it contains
+        let (_, fetched_files_map) = retry_loop!(safe.files_container_get(&xorurl))
+            .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+        assert_eq!(fetched_files_map, files_map);
+
+        let file_xorurl = &files_map["/test.md"][PREDICATE_LINK];
+        let file_url = SafeUrl::from_url(file_xorurl)?;
+        assert_eq!(file_url.scope(), Scope::Private);
+        let data = retry_loop!(safe.files_get_public(file_xorurl, None));
+        assert_eq!(
+            data.as_ref(),
+            std::fs::read("./testdata/test.md")?.as_slice()
+        );
+
+        // while anyone else is denied access to them
+        let other_safe = new_safe_instance_with_random_keypair().await?;
+        assert_matches!(
+            other_safe.files_container_get(&xorurl).await,
+            Err(Error::AccessDenied(_))
+        );
+        assert_matches!(
+            other_safe.files_get_public(file_xorurl, None).await,
+            Err(Error::AccessDenied(_))
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_files_container_create_from_dry_run() -> Result<()> {
         let mut safe = new_safe_instance().await?;
         safe.dry_run_mode = true;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from(TEST_DATA_FOLDER, None, true, false)
+            .files_container_create_from(TEST_DATA_FOLDER, None, true, false, Scope::Public)
             .await?;
 
         assert!(xorurl.starts_with("safe://"));
@@ -1415,6 +1677,7 @@ mod tests {
             None,
             true,
             true,
+            Scope::Public,
         ));
 
         assert!(xorurl.starts_with("safe://"));
@@ -1497,6 +1760,7 @@ mod tests {
                 Some(Path::new("/myroot")),
                 true,
                 true,
+                Scope::Public,
             )
             .await?;
 
@@ -1544,6 +1808,7 @@ mod tests {
                 Some(Path::new("/myroot/")),
                 true,
                 true,
+                Scope::Public,
             )
             .await?;
 
@@ -1718,7 +1983,7 @@ mod tests {
     async fn test_files_container_sync_same_size() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from("./testdata/test.md", None, false, false)
+            .files_container_create_from("./testdata/test.md", None, false, false, Scope::Public)
             .await?;
 
         assert_eq!(processed_files.len(), 1);
@@ -2284,12 +2549,37 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_files_container_create_from_get_empty_file() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let (xorurl, _, files_map) = new_files_container_from_testdata(&safe).await?;
+
+        let empty_file = &files_map["/emptyfolder/.gitkeep"];
+        assert_eq!(empty_file[PREDICATE_SIZE], "0");
+        let link = &empty_file[PREDICATE_LINK];
+        assert!(!link.is_empty());
+
+        let retrieved = retry_loop!(safe.files_get_public(link, None));
+        assert!(retrieved.is_empty());
+
+        let retrieved =
+            retry_loop!(safe.files_get_public(&format!("{}/emptyfolder/.gitkeep", xorurl), None));
+        assert!(retrieved.is_empty());
+
+        Ok(())
+    }
+
     #[tokio::test]
     #[ignore = "fix unknown issue"]
     async fn test_files_container_sync_with_nrs_url() -> Result<()> {
         let safe = new_safe_instance().await?;
-        let (xorurl, _, _) =
-            retry_loop!(safe.files_container_create_from("./testdata/test.md", None, false, true,));
+        let (xorurl, _, _) = retry_loop!(safe.files_container_create_from(
+            "./testdata/test.md",
+            None,
+            false,
+            true,
+            Scope::Public,
+        ));
         let _ = retry_loop!(safe.fetch(&xorurl, None));
         let (version0, _) = retry_loop!(safe.files_container_get(&xorurl))
             .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
@@ -2346,6 +2636,7 @@ mod tests {
             None,
             false,
             true,
+            Scope::Public,
         ));
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -2401,6 +2692,7 @@ mod tests {
             None,
             false,
             true,
+            Scope::Public,
         ));
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -2461,6 +2753,7 @@ mod tests {
             None,
             false,
             true,
+            Scope::Public,
         ));
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT); // root "/" + 2 files
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -2495,6 +2788,7 @@ mod tests {
             None,
             false,
             true,
+            Scope::Public,
         ));
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -2576,8 +2870,13 @@ mod tests {
     #[tokio::test]
     async fn test_files_container_fail_add_or_sync_invalid_path() -> Result<()> {
         let safe = new_safe_instance().await?;
-        let (xorurl, processed_files, files_map) =
-            retry_loop!(safe.files_container_create_from("./testdata/test.md", None, false, true,));
+        let (xorurl, processed_files, files_map) = retry_loop!(safe.files_container_create_from(
+            "./testdata/test.md",
+            None,
+            false,
+            true,
+            Scope::Public,
+        ));
         assert_eq!(processed_files.len(), 1);
         assert_eq!(files_map.len(), 1);
         let _ = retry_loop!(safe.fetch(&xorurl, None));
@@ -2634,6 +2933,7 @@ mod tests {
             None,
             false,
             true,
+            Scope::Public,
         ));
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -2724,6 +3024,7 @@ mod tests {
             None,
             false,
             true,
+            Scope::Public,
         ));
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -18,14 +18,18 @@ pub mod resolver;
 pub use crate::safeurl::*;
 pub use consts::DEFAULT_XORURL_BASE;
 pub use helpers::parse_tokens_amount;
+pub use safe_network::client::{ChunkCache, ChunkCacheStats};
+pub use safe_network::messaging::data::{NodeRewards, RewardLedger};
 pub use xor_name::{XorName, XOR_NAME_LEN};
 
 // --------------------------------------------------------------------
 
 mod auth;
+mod chunk_cache;
 mod consts;
 mod helpers;
 mod keys;
+mod rewards;
 
 #[cfg(test)]
 mod test_helpers;
@@ -49,6 +53,8 @@ pub struct Safe {
     client: Option<Client>,
     pub xorurl_base: XorUrlBase,
     pub dry_run_mode: bool,
+    /// Resume the interrupted uploads of files being stored, rather than uploading them anew
+    pub resume_uploads: bool,
 }
 
 impl Safe {
@@ -58,6 +64,7 @@ impl Safe {
             client: None,
             xorurl_base: xorurl_base.unwrap_or(DEFAULT_XORURL_BASE),
             dry_run_mode: true,
+            resume_uploads: false,
         }
     }
 
@@ -73,6 +80,7 @@ impl Safe {
             client: None,
             xorurl_base: xorurl_base.unwrap_or(DEFAULT_XORURL_BASE),
             dry_run_mode: false,
+            resume_uploads: false,
         };
 
         safe.connect(bootstrap_config, keypair, config_path, timeout)
@@ -37,6 +37,14 @@ impl Safe {
             .await
     }
 
+    /// Return the latest entries of a Multimap on the network
+    pub async fn multimap_read(&self, url: &str) -> Result<Multimap> {
+        debug!("Getting entries from Multimap at: {}", url);
+        let safeurl = self.parse_and_resolve_url(url).await?;
+
+        self.fetch_multimap(&safeurl).await
+    }
+
     /// Return the value of a Multimap on the network corresponding to the key provided
     pub async fn multimap_get_by_key(&self, url: &str, key: &[u8]) -> Result<Multimap> {
         debug!("Getting value by key from Multimap at: {}", url);
@@ -365,7 +365,7 @@ mod tests {
     use super::*;
     use crate::{
         app::test_helpers::{new_safe_instance, random_nrs_name, TestDataFilesContainer},
-        Error, SafeUrl,
+        Error, SafeUrl, Scope,
     };
     use anyhow::{anyhow, Result};
     use std::matches;
@@ -700,7 +700,7 @@ mod tests {
 
         // let's create an empty files container so we have a valid to link
         let (link, _, _) = safe
-            .files_container_create_from(TEST_DATA_FILE, None, false, false)
+            .files_container_create_from(TEST_DATA_FILE, None, false, false, Scope::Public)
             .await?;
         let (version0, _) = safe
             .files_container_get(&link)
@@ -6,7 +6,9 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-pub use safe_network::types::register::{Entry, EntryHash};
+pub use safe_network::types::register::{
+    Action, Entry, EntryHash, Policy, PrivatePermissions, PublicPermissions, User,
+};
 
 use crate::safeurl::{ContentType, SafeUrl, XorUrl};
 use crate::{Error, Result, Safe};
@@ -16,9 +18,7 @@ use rand::Rng;
 use safe_network::{
     client::Error as ClientError,
     types::{
-        register::{
-            Policy, PrivatePermissions, PrivatePolicy, PublicPermissions, PublicPolicy, User,
-        },
+        register::{PrivatePolicy, PublicPolicy},
         DataAddress, Error as SafeNdError, RegisterAddress, Scope,
     },
 };
@@ -125,6 +125,11 @@ impl Safe {
                 client.read_register(address).await.map_err(|err| {
                     if let ClientError::NetworkDataError(SafeNdError::NoSuchEntry) = err {
                         Error::EmptyContent(format!("Empty Register found at {:?}", address))
+                    } else if is_access_denied(&err) {
+                        Error::AccessDenied(format!(
+                            "Register found at {:?} is private and it can't be read with the keypair configured",
+                            address
+                        ))
                     } else {
                         Error::NetDataError(format!(
                             "Failed to read latest value from Register data: {:?}",
@@ -172,6 +177,11 @@ impl Safe {
                 } = err
                 {
                     Error::HashNotFound(hash)
+                } else if is_access_denied(&err) {
+                    Error::AccessDenied(format!(
+                        "Register found at {:?} is private and it can't be read with the keypair configured",
+                        address
+                    ))
                 } else {
                     Error::NetDataError(format!(
                         "Failed to retrieve entry with hash '{}' from Register data: {:?}",
@@ -182,6 +192,72 @@ impl Safe {
             })
     }
 
+    /// Fetch all the entries of a Register from a SafeUrl, without performing any type of URL
+    /// resolution, along with the hashes of the entries each of them was written on top of.
+    /// Entries are returned the most recent first, i.e. every entry comes before its parents.
+    pub(crate) async fn register_fetch_history(
+        &self,
+        url: &SafeUrl,
+    ) -> Result<Vec<(EntryHash, Entry, BTreeSet<EntryHash>)>> {
+        debug!("Fetching the history of Register at {}", url);
+        let address = self.get_register_address(url)?;
+        let client = self.get_safe_client()?;
+        let register = client.get_register(address).await.map_err(|err| {
+            if is_access_denied(&err) {
+                Error::AccessDenied(format!(
+                    "Register found at {:?} is private and it can't be read with the keypair configured",
+                    address
+                ))
+            } else {
+                Error::NetDataError(format!(
+                    "Failed to retrieve Register at \"{}\": {:?}",
+                    url, err
+                ))
+            }
+        })?;
+
+        // Depth-first walk of the entries from the latest ones, the reverse of the order
+        // the walk finishes with entries in being one where they all come before their parents.
+        let mut visited = BTreeSet::new();
+        let mut finished = Vec::new();
+        let mut stack: Vec<(EntryHash, bool)> = register
+            .read()
+            .into_iter()
+            .map(|(hash, _)| (hash, false))
+            .collect();
+        while let Some((hash, parents_walked)) = stack.pop() {
+            if parents_walked {
+                finished.push(hash);
+                continue;
+            }
+            if !visited.insert(hash) {
+                continue;
+            }
+            stack.push((hash, true));
+            let parents = register.children(hash).map_err(|err| {
+                Error::NetDataError(format!("Register at \"{}\" is corrupted: {:?}", url, err))
+            })?;
+            stack.extend(
+                parents
+                    .into_iter()
+                    .filter(|parent| !visited.contains(parent))
+                    .map(|parent| (parent, false)),
+            );
+        }
+
+        finished
+            .into_iter()
+            .rev()
+            .map(|hash| {
+                let entry = register.get(hash).map_err(|_| Error::HashNotFound(hash))?;
+                let parents = register
+                    .children(hash)
+                    .map_err(|_| Error::HashNotFound(hash))?;
+                Ok((hash, entry.clone(), parents))
+            })
+            .collect()
+    }
+
     /// Write value to a Register on the network
     pub async fn register_write(
         &self,
@@ -203,6 +279,21 @@ impl Safe {
         Ok(entry_hash)
     }
 
+    /// Fetch the Policy of a Register on the network, i.e. its owner and permissions
+    pub async fn register_policy(&self, url: &str) -> Result<Policy> {
+        debug!("Getting Register Policy from: {:?}", url);
+        let safeurl = self.parse_and_resolve_url(url).await?;
+        let address = self.get_register_address(&safeurl)?;
+
+        let client = self.get_safe_client()?;
+        client.get_register_policy(address).await.map_err(|err| {
+            Error::NetDataError(format!(
+                "Failed to retrieve the Policy of Register at \"{}\": {:?}",
+                safeurl, err
+            ))
+        })
+    }
+
     pub(crate) fn get_register_address(&self, url: &SafeUrl) -> Result<RegisterAddress> {
         let address = match url.address() {
             DataAddress::Register(reg_address) => reg_address,
@@ -230,8 +321,20 @@ fn public_policy(owner: User) -> Policy {
     Policy::Public(PublicPolicy { owner, permissions })
 }
 
+// Reading a private Register is denied to anyone without read permissions
+fn is_access_denied(err: &ClientError) -> bool {
+    matches!(
+        err,
+        ClientError::ErrorMsg {
+            source: safe_network::messaging::data::Error::AccessDenied(_),
+            ..
+        }
+    )
+}
+
 #[cfg(test)]
 mod tests {
+    use super::Policy;
     use crate::{app::test_helpers::new_safe_instance, ContentType};
     use anyhow::Result;
 
@@ -266,6 +369,13 @@ mod tests {
         assert_eq!(received_entry, initial_data.clone());
         assert_eq!(received_entry_priv, initial_data);
 
+        let policy = safe.register_policy(&xorurl).await?;
+        let policy_priv = safe.register_policy(&xorurl_priv).await?;
+
+        assert!(matches!(policy, Policy::Public(_)));
+        assert!(matches!(policy_priv, Policy::Private(_)));
+        assert_eq!(policy.owner(), policy_priv.owner());
+
         Ok(())
     }
 }
@@ -52,13 +52,13 @@ impl Safe {
     ///
     /// ### Fetch FilesContainer relative path file
     /// ```no_run
-    /// # use sn_api::{Safe, resolver::SafeData};
+    /// # use sn_api::{Safe, Scope, resolver::SafeData};
     /// # use std::collections::BTreeMap;
     /// # let mut safe = Safe::default();
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   safe.connect(None, None, None).await.unwrap();
-    ///     let (xorurl, _, _) = safe.files_container_create_from("./testdata/", None, true, false).await.unwrap();
+    ///     let (xorurl, _, _) = safe.files_container_create_from("./testdata/", None, true, false, Scope::Public).await.unwrap();
     ///
     ///     let safe_data = safe.fetch( &format!( "{}/test.md", &xorurl.replace("?v=0", "") ), None ).await.unwrap();
     ///     let data_string = match safe_data {
@@ -101,13 +101,13 @@ impl Safe {
     ///
     /// ### Inspect FilesContainer relative path file
     /// ```no_run
-    /// # use sn_api::{Safe, resolver::SafeData};
+    /// # use sn_api::{Safe, Scope, resolver::SafeData};
     /// # use std::collections::BTreeMap;
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   let mut safe = Safe::default();
     /// #   safe.connect(None, None, None).await.unwrap();
-    ///     let (container_xorurl, _, _) = safe.files_container_create_from("./testdata/", None, true, false).await.unwrap();
+    ///     let (container_xorurl, _, _) = safe.files_container_create_from("./testdata/", None, true, false, Scope::Public).await.unwrap();
     ///
     ///     let inspected_content = safe.inspect( &format!( "{}/test.md", &container_xorurl.replace("?v=0", "") ) ).await.unwrap();
     ///     match &inspected_content[0] {
@@ -257,7 +257,7 @@ mod tests {
     async fn test_fetch_files_container() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (fc_xorurl, _, original_files_map) = safe
-            .files_container_create_from("./testdata/", None, true, false)
+            .files_container_create_from("./testdata/", None, true, false, Scope::Public)
             .await?;
 
         let safe_url = SafeUrl::from_url(&fc_xorurl)?;
@@ -310,7 +310,7 @@ mod tests {
 
         // create file container
         let (xorurl, _, the_files_map) = safe
-            .files_container_create_from("./testdata/", None, true, false)
+            .files_container_create_from("./testdata/", None, true, false, Scope::Public)
             .await?;
         let _ = retry_loop!(safe.fetch(&xorurl, None));
         let (version0, _) = retry_loop!(safe.files_container_get(&xorurl))
@@ -370,7 +370,7 @@ mod tests {
 
         // create file container
         let (xorurl, _, _the_files_map) = safe
-            .files_container_create_from("./testdata/", None, true, false)
+            .files_container_create_from("./testdata/", None, true, false, Scope::Public)
             .await?;
         let _ = retry_loop!(safe.fetch(&xorurl, None));
         let (version0, _) = retry_loop!(safe.files_container_get(&xorurl))
@@ -554,7 +554,7 @@ mod tests {
 
         // create file container
         let (xorurl, _, _files_map) = safe
-            .files_container_create_from("./testdata/", None, true, false)
+            .files_container_create_from("./testdata/", None, true, false, Scope::Public)
             .await?;
         let _ = retry_loop!(safe.fetch(&xorurl, None));
         let (version0, _) = retry_loop!(safe.files_container_get(&xorurl))
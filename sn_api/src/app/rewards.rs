@@ -0,0 +1,21 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Safe;
+use crate::Result;
+use safe_network::messaging::data::RewardLedger;
+
+impl Safe {
+    /// Fetch the reward ledgers of all the sections known to us, each accounting for
+    /// the work done by the section's nodes and the rewards credited to their wallets.
+    pub async fn reward_ledgers(&self) -> Result<Vec<RewardLedger>> {
+        let client = self.get_safe_client()?;
+        let ledgers = client.get_reward_ledgers().await?;
+        Ok(ledgers)
+    }
+}
@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::{ipc::NodeConfig, Safe, SafeUrl};
+use crate::{ipc::NodeConfig, Safe, SafeUrl, Scope};
 use anyhow::{anyhow, bail, Context, Result};
 use rand::{distributions::Alphanumeric, rngs::OsRng, thread_rng, Rng};
 use safe_network::types::{Keypair, PublicKey};
@@ -57,7 +57,7 @@ impl TestDataFilesContainer {
         let mut map: HashMap<String, SafeUrl> = HashMap::new();
         let safe = new_safe_instance().await?;
         let (container_xorurl, _, files_map) = safe
-            .files_container_create_from("./testdata", None, false, false)
+            .files_container_create_from("./testdata", None, false, false, Scope::Public)
             .await?;
         let container_url = SafeUrl::from_url(&container_xorurl)?;
         for file in files {
@@ -110,6 +110,17 @@ pub async fn new_safe_instance() -> Result<Safe> {
     Ok(safe)
 }
 
+// Instantiate a Safe connected with a new random keypair, regardless of
+// the credentials set for the tests, e.g. to act as a different user
+pub async fn new_safe_instance_with_random_keypair() -> Result<Safe> {
+    init_logger();
+    let keypair = Keypair::new_ed25519(&mut OsRng);
+    let bootstrap_contacts = get_bootstrap_contacts()?;
+    let safe = Safe::connected(bootstrap_contacts, Some(keypair), None, None, None).await?;
+
+    Ok(safe)
+}
+
 // Create a random NRS name
 pub fn random_nrs_name() -> String {
     thread_rng().sample_iter(&Alphanumeric).take(15).collect()
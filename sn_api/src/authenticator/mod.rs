@@ -7,18 +7,33 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
-    ipc::{req::AuthReq, resp::AuthGranted},
-    Result, SafeAuthReq,
+    ipc::{
+        req::{AuthReq, IpcReq},
+        resp::{AuthGranted, IpcResp},
+        IpcMsg, NodeConfig,
+    },
+    AuthedApp, AuthedAppsList, Error, Result, SafeAuthReq,
 };
 use hmac::Hmac;
-use rand::rngs::StdRng;
+use log::{debug, info, trace};
+use rand::{
+    rngs::{OsRng, StdRng},
+    Rng,
+};
 use rand_core::SeedableRng;
-use safe_network::client::client_api::Client;
-use safe_network::types::{Keypair, RegisterAddress};
+use safe_network::client::{
+    client_api::Client, ClientConfig, Error as ClientError, ErrorMsg, DEFAULT_OPERATION_TIMEOUT,
+};
+use safe_network::types::{
+    register::{
+        Entry, EntryHash, Policy, PrivatePermissions, PrivatePolicy, PublicPermissions, User,
+    },
+    Keypair, RegisterAddress,
+};
+use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 use std::{
-    collections::HashSet,
-    net::SocketAddr,
+    collections::{BTreeMap, BTreeSet},
     path::{Path, PathBuf},
 };
 use tiny_keccak::{Hasher, Sha3};
@@ -26,10 +41,13 @@ use xor_name::{XorName, XOR_NAME_LEN};
 
 const SHA3_512_HASH_LEN: usize = 64;
 
-// Type tag value used for the Map which holds the Safe's content on the network.
-#[allow(dead_code)]
+// Type tag value used for the Register which holds the Safe's content on the network.
 const SAFE_TYPE_TAG: u64 = 1_300;
 
+// Entry written to the Safe's Register to remove the entries it replaces,
+// in the same way Multimap entries are removed.
+const SAFE_ENTRY_REMOVED_MARK: &[u8] = b"";
+
 /// Derive Passphrase, Password and Salt (in order).
 pub fn derive_secrets(acc_passphrase: &[u8], acc_password: &[u8]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
     let mut passphrase_hasher = Sha3::v512();
@@ -56,12 +74,22 @@ pub fn derive_secrets(acc_passphrase: &[u8], acc_password: &[u8]) -> (Vec<u8>, V
 
 /// Create a new Ed25519 keypair from seed
 fn create_ed25519_keypair_from_seed(seeder: &[u8]) -> Keypair {
+    let mut rng = rng_from_seed(seeder);
+    Keypair::new_ed25519(&mut rng)
+}
+
+/// Create a new BLS secret key, to encrypt the Safe's content with, from seed
+fn create_encryption_key_from_seed(seeder: &[u8]) -> bls::SecretKey {
+    let mut rng = rng_from_seed(seeder);
+    rng.gen()
+}
+
+fn rng_from_seed(seeder: &[u8]) -> StdRng {
     let mut hasher = Sha3::v256();
     let mut seed = [0; 32];
     hasher.update(seeder);
     hasher.finalize(&mut seed);
-    let mut rng = StdRng::from_seed(seed);
-    Keypair::new_ed25519(&mut rng)
+    StdRng::from_seed(seed)
 }
 
 /// Perform all derivations and seeding to deterministically obtain location and Keypair from input
@@ -77,6 +105,17 @@ pub fn derive_location_and_keypair(passphrase: &str, password: &str) -> Result<(
     Ok((map_data_location, keypair))
 }
 
+/// Deterministically derive the key used to encrypt the Safe's content from the same input
+/// used to derive its location and Keypair.
+fn derive_encryption_key(passphrase: &str, password: &str) -> bls::SecretKey {
+    let (passphrase, password, salt) = derive_secrets(passphrase.as_bytes(), password.as_bytes());
+
+    let mut seed = passphrase;
+    seed.extend(password.iter());
+    seed.extend(salt.iter());
+    create_encryption_key_from_seed(&seed)
+}
+
 /// Generates User's Identity for the network using supplied credentials in
 /// a deterministic way.  This is similar to the username in various places.
 pub fn generate_network_address(passphrase: &[u8], salt: &[u8]) -> Result<XorName> {
@@ -89,32 +128,175 @@ pub fn generate_network_address(passphrase: &[u8], salt: &[u8]) -> Result<XorNam
     Ok(id)
 }
 
+// Information kept in the Safe for each of the authorised applications.
+#[derive(Debug, Serialize, Deserialize)]
+struct AppEntry {
+    id: String,
+    name: String,
+    vendor: String,
+    keypair: Keypair,
+    // Registers owned by the Safe which the app was granted write access to
+    #[serde(default)]
+    registers: BTreeSet<RegisterAddress>,
+}
+
+// A Safe currently unlocked
+struct UnlockedSafe {
+    // Client instantiated with the keypair derived from the Safe's credentials
+    client: Client,
+    // Address of the Register which holds the Safe's content on the network
+    address: RegisterAddress,
+    // Key the content of the Safe is encrypted with
+    encryption_key: bls::SecretKey,
+}
+
+impl UnlockedSafe {
+    // Read all the apps entries currently in the Safe, along with the hash of their Register entry
+    async fn read_apps(&self) -> Result<Vec<(EntryHash, AppEntry)>> {
+        let entries = self
+            .client
+            .read_register(self.address)
+            .await
+            .map_err(|err| {
+                Error::AuthenticatorError(format!("Failed to read the Safe's content: {}", err))
+            })?;
+
+        let mut apps = vec![];
+        for (hash, entry) in entries {
+            if entry == SAFE_ENTRY_REMOVED_MARK {
+                continue;
+            }
+            apps.push((hash, self.decrypt_entry(&entry)?));
+        }
+
+        Ok(apps)
+    }
+
+    // Read the entry of an app from the Safe, along with the hashes of all its Register entries
+    async fn read_app(&self, app_id: &str) -> Result<Option<(BTreeSet<EntryHash>, AppEntry)>> {
+        let mut hashes = BTreeSet::new();
+        let mut found = None;
+        for (hash, app) in self.read_apps().await? {
+            if app.id == app_id {
+                let _ = hashes.insert(hash);
+                found = Some(app);
+            }
+        }
+
+        Ok(found.map(|app| (hashes, app)))
+    }
+
+    // Set the Policy of a Register, changing the permissions the given app has on it
+    async fn set_app_permissions(
+        &self,
+        address: RegisterAddress,
+        app: &AppEntry,
+        grant: bool,
+    ) -> Result<()> {
+        let user = User::Key(app.keypair.public_key());
+        let mut policy = self
+            .client
+            .get_register_policy(address)
+            .await
+            .map_err(|err| {
+                Error::AuthenticatorError(format!(
+                    "Failed to retrieve the Policy of Register at {:?}: {}",
+                    address, err
+                ))
+            })?;
+
+        match &mut policy {
+            Policy::Private(policy) if grant => {
+                let _ = policy
+                    .permissions
+                    .insert(user, PrivatePermissions::new(true, true));
+            }
+            Policy::Public(policy) if grant => {
+                let _ = policy
+                    .permissions
+                    .insert(user, PublicPermissions::new(true));
+            }
+            Policy::Private(policy) => {
+                let _ = policy.permissions.remove(&user);
+            }
+            Policy::Public(policy) => {
+                let _ = policy.permissions.remove(&user);
+            }
+        }
+
+        let op_batch = self
+            .client
+            .set_register_policy(address, policy)
+            .await
+            .map_err(|err| {
+                Error::AuthenticatorError(format!(
+                    "Failed to set the Policy of Register at {:?}: {}",
+                    address, err
+                ))
+            })?;
+        self.client.publish_register_ops(op_batch).await?;
+
+        Ok(())
+    }
+
+    // Write an entry to the Safe, replacing the entries with the given hashes
+    async fn write_entry(&self, entry: Entry, replace: BTreeSet<EntryHash>) -> Result<EntryHash> {
+        let (hash, op_batch) = self
+            .client
+            .write_to_register(self.address, entry, replace)
+            .await?;
+        self.client.publish_register_ops(op_batch).await?;
+
+        Ok(hash)
+    }
+
+    fn encrypt_entry(&self, app: &AppEntry) -> Result<Entry> {
+        let serialised = rmp_serde::to_vec_named(app).map_err(|err| {
+            Error::Serialisation(format!("Failed to serialise app entry: {:?}", err))
+        })?;
+        let ciphertext = self.encryption_key.public_key().encrypt(&serialised);
+
+        bincode::serialize(&ciphertext).map_err(|err| {
+            Error::Serialisation(format!(
+                "Failed to serialise encrypted app entry: {:?}",
+                err
+            ))
+        })
+    }
+
+    fn decrypt_entry(&self, entry: &[u8]) -> Result<AppEntry> {
+        let ciphertext: bls::Ciphertext = bincode::deserialize(entry).map_err(|err| {
+            Error::AuthenticatorError(format!("The Safe contains an invalid entry: {:?}", err))
+        })?;
+        let serialised = self.encryption_key.decrypt(&ciphertext).ok_or_else(|| {
+            Error::AuthenticatorError("Failed to decrypt an entry of the Safe".to_string())
+        })?;
+
+        rmp_serde::from_slice(&serialised).map_err(|err| {
+            Error::AuthenticatorError(format!("The Safe contains an invalid app entry: {:?}", err))
+        })
+    }
+}
+
 // Authenticator API
 #[derive(Default)]
 pub struct SafeAuthenticator {
     // We keep the client instantiated with the derived keypair, along
-    // with the address of the Map which holds its Safe on the network.
-    #[allow(dead_code)]
-    safe: Option<(Client, RegisterAddress)>,
-    #[allow(dead_code)]
+    // with the address of the Register which holds its Safe on the network.
+    safe: Option<UnlockedSafe>,
     config_path: Option<PathBuf>,
-    #[allow(dead_code)]
-    bootstrap_contacts: Option<HashSet<SocketAddr>>,
+    bootstrap_config: Option<NodeConfig>,
 }
 
 impl SafeAuthenticator {
-    pub fn new(
-        _config_dir_path: Option<&Path>,
-        _bootstrap_contacts: Option<HashSet<SocketAddr>>,
-    ) -> Self {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-        // let config_path = config_dir_path.map(|p| p.to_path_buf());
-
-        // Self {
-        //     safe: None,
-        //     config_path,
-        //     bootstrap_contacts,
-        // }
+    pub fn new(config_dir_path: Option<&Path>, bootstrap_config: Option<NodeConfig>) -> Self {
+        let config_path = config_dir_path.map(|p| p.to_path_buf());
+
+        Self {
+            safe: None,
+            config_path,
+            bootstrap_config,
+        }
     }
 
     /// # Create Safe
@@ -129,15 +311,14 @@ impl SafeAuthenticator {
     /// ## Example
     /// ```ignore
     /// use sn_api::SafeAuthenticator;
-    /// let mut safe_auth = SafeAuthenticator::new(None);
+    /// let mut safe_auth = SafeAuthenticator::new(None, None);
     /// # fn random_str() -> String { (0..4).map(|_| rand::random::<char>()).collect() }
     /// let my_secret = "mysecretstring";
     /// let my_password = "mypassword";
     /// # let my_secret = &(random_str());
     /// # let my_password = &(random_str());
-    /// # let sk = "83c055c5efdc483bd967adba5c1769daee0a17bc5fa2b6e129cd6b596c217617";
     /// # async_std::task::block_on(async {
-    /// let acc_created = safe_auth.create(sk, my_secret, my_password).await;
+    /// let acc_created = safe_auth.create(my_secret, my_password).await;
     /// match acc_created {
     ///    Ok(()) => assert!(true), // This should pass
     ///    Err(_) => assert!(false)
@@ -150,17 +331,16 @@ impl SafeAuthenticator {
     /// the function will return an error:
     /// ```ignore
     /// use sn_api::{SafeAuthenticator, Error};
-    /// let mut safe_auth = SafeAuthenticator::new(None);
+    /// let mut safe_auth = SafeAuthenticator::new(None, None);
     /// # fn random_str() -> String { (0..4).map(|_| rand::random::<char>()).collect() }
     /// /// Using an already existing Safe's passphrase and password:
     /// let my_secret = "mysecretstring";
     /// let my_password = "mypassword";
     /// # let my_secret = &(random_str());
     /// # let my_password = &(random_str());
-    /// # let sk = "83c055c5efdc483bd967adba5c1769daee0a17bc5fa2b6e129cd6b596c217617";
     /// # async_std::task::block_on(async {
-    /// # safe_auth.create(sk, my_secret, my_password).await.unwrap();
-    /// let acc_not_created = safe_auth.create(sk, my_secret, my_password).await;
+    /// # safe_auth.create(my_secret, my_password).await.unwrap();
+    /// let acc_not_created = safe_auth.create(my_secret, my_password).await;
     /// match acc_not_created {
     ///    Ok(_) => assert!(false), // This should not pass
     ///    Err(Error::AuthError(message)) => {
@@ -170,53 +350,66 @@ impl SafeAuthenticator {
     /// }
     /// # });
     ///```
-    pub async fn create(&mut self, _passphrase: &str, _password: &str) -> Result<()> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-        // debug!("Attempting to create a Safe from provided passphrase and password.");
-
-        // let (location, keypair) = derive_location_and_keypair(passphrase, password)?;
-        // let data_owner = keypair.public_key();
-
-        // debug!("Creating Safe to be owned by PublicKey: {:?}", data_owner);
-
-        // let client = Client::new(
-        //     Some(keypair),
-        //     self.config_path.as_deref(),
-        //     self.bootstrap_contacts.clone(),
-        //     DEFAULT_OPERATION_TIMEOUT,
-        // )
-        // .await?;
-        // trace!("Client instantiated properly!");
-
-        // // Create Map data to store the list of keypairs generated for
-        // // each of the user's applications.
-        // let permission_set = MapPermissionSet::new()
-        //     .allow(MapAction::Read)
-        //     .allow(MapAction::Insert)
-        //     .allow(MapAction::Update)
-        //     .allow(MapAction::Delete)
-        //     .allow(MapAction::ManagePermissions);
-
-        // let mut permission_map = BTreeMap::new();
-        // permission_map.insert(data_owner, permission_set);
-
-        // // TODO: encrypt content
-        // let map_address = client
-        //     .store_seq_map(
-        //         location,
-        //         SAFE_TYPE_TAG,
-        //         data_owner,
-        //         None,
-        //         Some(permission_map),
-        //     )
-        //     .await
-        //     .map_err(|err| {
-        //         Error::AuthenticatorError(format!("Failed to store Safe on a Map: {}", err))
-        //     })?;
-        // debug!("Map stored successfully for new Safe!");
-
-        // self.safe = Some((client, map_address));
-        // Ok(())
+    pub async fn create(&mut self, passphrase: &str, password: &str) -> Result<()> {
+        debug!("Attempting to create a Safe from provided passphrase and password.");
+
+        let (location, keypair) = derive_location_and_keypair(passphrase, password)?;
+        let encryption_key = derive_encryption_key(passphrase, password);
+        let data_owner = User::Key(keypair.public_key());
+
+        debug!("Creating Safe to be owned by PublicKey: {:?}", data_owner);
+
+        let client = self.connect(keypair).await?;
+        let address = RegisterAddress::Private {
+            name: location,
+            tag: SAFE_TYPE_TAG,
+        };
+
+        match client.get_register(address).await {
+            Ok(_) => {
+                return Err(Error::AuthError(
+                    "Failed to create a Safe: a Safe already exists with the provided passphrase"
+                        .to_string(),
+                ))
+            }
+            Err(ClientError::ErrorMsg {
+                source: ErrorMsg::DataNotFound(_),
+                ..
+            }) => {}
+            Err(err) => {
+                return Err(Error::AuthError(format!(
+                    "Failed to create a Safe: couldn't check if it already exists: {}",
+                    err
+                )))
+            }
+        }
+
+        // Create a private Register to store the list of keypairs generated for
+        // each of the user's applications.
+        let mut permissions = BTreeMap::new();
+        let _ = permissions.insert(data_owner, PrivatePermissions::new(true, true));
+        let policy = Policy::Private(PrivatePolicy {
+            owner: data_owner,
+            permissions,
+        });
+
+        let (_, op_batch) = client
+            .create_register(location, SAFE_TYPE_TAG, policy)
+            .await
+            .map_err(|err| {
+                Error::AuthError(format!("Failed to create a Safe on a Register: {}", err))
+            })?;
+        client.publish_register_ops(op_batch).await.map_err(|err| {
+            Error::AuthError(format!("Failed to create a Safe on a Register: {}", err))
+        })?;
+        debug!("Register stored successfully for new Safe!");
+
+        self.safe = Some(UnlockedSafe {
+            client,
+            address,
+            encryption_key,
+        });
+        Ok(())
     }
 
     /// # Unlock
@@ -226,16 +419,15 @@ impl SafeAuthenticator {
     /// ## Example
     /// ```ignore
     /// use sn_api::SafeAuthenticator;
-    /// let mut safe_auth = SafeAuthenticator::new(None);
+    /// let mut safe_auth = SafeAuthenticator::new(None, None);
     /// # fn random_str() -> String { (0..4).map(|_| rand::random::<char>()).collect() }
     /// /// Using an already existing Safe's passphrase and password:
     /// let my_secret = "mysecretstring";
     /// let my_password = "mypassword";
     /// # let my_secret = &(random_str());
     /// # let my_password = &(random_str());
-    /// # let sk = "83c055c5efdc483bd967adba5c1769daee0a17bc5fa2b6e129cd6b596c217617";
     /// # async_std::task::block_on(async {
-    /// # safe_auth.create(sk, my_secret, my_password).await.unwrap();
+    /// # safe_auth.create(my_secret, my_password).await.unwrap();
     /// let logged_in = safe_auth.unlock(my_secret, my_password).await;
     /// match logged_in {
     ///    Ok(()) => assert!(true), // This should pass
@@ -248,7 +440,7 @@ impl SafeAuthenticator {
     /// If the Safe does not exist, the function will return an appropriate error:
     ///```ignore
     /// use sn_api::{SafeAuthenticator, Error};
-    /// let mut safe_auth = SafeAuthenticator::new(None);
+    /// let mut safe_auth = SafeAuthenticator::new(None, None);
     /// # async_std::task::block_on(async {
     /// let not_logged_in = safe_auth.unlock("non", "existant").await;
     /// match not_logged_in {
@@ -260,109 +452,176 @@ impl SafeAuthenticator {
     /// }
     /// # });
     ///```
-    pub async fn unlock(&mut self, _passphrase: &str, _password: &str) -> Result<()> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // debug!("Attempting to unlock a Safe...");
-
-        // let (location, keypair) = derive_location_and_keypair(passphrase, password)?;
-
-        // debug!(
-        //     "Unlocking Safe owned by PublicKey: {:?}",
-        //     keypair.public_key()
-        // );
-
-        // let client = Client::new(
-        //     Some(keypair),
-        //     self.config_path.as_deref(),
-        //     self.bootstrap_contacts.clone(),
-        //     DEFAULT_OPERATION_TIMEOUT,
-        // )
-        // .await?;
-        // trace!("Client instantiated properly!");
-
-        // let map_address = MapAddress::Seq {
-        //     name: location,
-        //     tag: SAFE_TYPE_TAG,
-        // };
-
-        // // Attempt to retrieve Map to make sure it actually exists
-        // let _ = client.get_map(map_address).await?;
-        // debug!("Safe unlocked successfully!");
-
-        // self.safe = Some((client, map_address));
-        // Ok(())
+    pub async fn unlock(&mut self, passphrase: &str, password: &str) -> Result<()> {
+        debug!("Attempting to unlock a Safe...");
+
+        let (location, keypair) = derive_location_and_keypair(passphrase, password)?;
+        let encryption_key = derive_encryption_key(passphrase, password);
+
+        debug!(
+            "Unlocking Safe owned by PublicKey: {:?}",
+            keypair.public_key()
+        );
+
+        let client = self.connect(keypair).await?;
+        let address = RegisterAddress::Private {
+            name: location,
+            tag: SAFE_TYPE_TAG,
+        };
+
+        // Attempt to retrieve the Register to make sure it actually exists
+        let _ = client.get_register(address).await.map_err(|err| {
+            Error::AuthError(format!("Failed to log in: no Safe could be read: {}", err))
+        })?;
+        debug!("Safe unlocked successfully!");
+
+        self.safe = Some(UnlockedSafe {
+            client,
+            address,
+            encryption_key,
+        });
+        Ok(())
     }
 
     pub fn lock(&mut self) -> Result<()> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // debug!("Locking Safe...");
-        // self.safe = None;
-        // Ok(())
+        debug!("Locking Safe...");
+        self.safe = None;
+        Ok(())
     }
 
     pub fn is_a_safe_unlocked(&self) -> bool {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // let is_a_safe_unlocked = self.safe.is_some();
-        // debug!(
-        //     "Is there a Safe currently unlocked?: {}",
-        //     is_a_safe_unlocked
-        // );
-        // is_a_safe_unlocked
+        let is_a_safe_unlocked = self.safe.is_some();
+        debug!(
+            "Is there a Safe currently unlocked?: {}",
+            is_a_safe_unlocked
+        );
+        is_a_safe_unlocked
+    }
+
+    pub async fn decode_req(&self, req: &str) -> Result<SafeAuthReq> {
+        match IpcMsg::from_string(req) {
+            Ok(IpcMsg::Req(IpcReq::Auth(app_auth_req))) => {
+                debug!("Auth request string decoded: {:?}", app_auth_req);
+                Ok(SafeAuthReq::Auth(app_auth_req))
+            }
+            Ok(other) => Err(Error::AuthError(format!(
+                "Failed to decode string as an authorisation request, it's a: '{:?}'",
+                other
+            ))),
+            Err(error) => Err(Error::AuthenticatorError(format!(
+                "Failed to decode request: {:?}",
+                error
+            ))),
+        }
+    }
+
+    /// List the applications currently authorised in the Safe
+    pub async fn authed_apps(&self) -> Result<AuthedAppsList> {
+        let safe = self.unlocked_safe()?;
+
+        let apps: BTreeMap<String, AuthedApp> = safe
+            .read_apps()
+            .await?
+            .into_iter()
+            .map(|(_, app)| {
+                let authed_app = AuthedApp {
+                    id: app.id.clone(),
+                    name: app.name,
+                    vendor: app.vendor,
+                };
+                (app.id, authed_app)
+            })
+            .collect();
+
+        Ok(apps.into_values().collect())
     }
 
-    pub async fn decode_req(&self, _req: &str) -> Result<SafeAuthReq> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // match IpcMsg::from_string(req) {
-        //     Ok(IpcMsg::Req(IpcReq::Auth(app_auth_req))) => {
-        //         debug!("Auth request string decoded: {:?}", app_auth_req);
-        //         Ok(SafeAuthReq::Auth(app_auth_req))
-        //     }
-        //     Ok(other) => Err(Error::AuthError(format!(
-        //         "Failed to decode string as an authorisation request, it's a: '{:?}'",
-        //         other
-        //     ))),
-        //     Err(error) => Err(Error::AuthenticatorError(format!(
-        //         "Failed to decode request: {:?}",
-        //         error
-        //     ))),
-        // }
+    /// Grant an authorised application write access to a Register owned by the Safe,
+    /// adding the app's key to the Register's Policy.
+    /// The access is removed from the Register's Policy if the app is revoked.
+    pub async fn grant_register_access(
+        &self,
+        app_id: &str,
+        address: RegisterAddress,
+    ) -> Result<()> {
+        debug!(
+            "Granting application '{}' write access to Register at {:?}",
+            app_id, address
+        );
+        let safe = self.unlocked_safe()?;
+
+        let (hashes, mut app) = safe.read_app(app_id).await?.ok_or_else(|| {
+            Error::AuthenticatorError(format!(
+                "Application '{}' is not authorised in the Safe",
+                app_id
+            ))
+        })?;
+
+        safe.set_app_permissions(address, &app, true).await?;
+
+        // Record the Register in the app's entry so its access can be revoked later on
+        if app.registers.insert(address) {
+            let entry = safe.encrypt_entry(&app)?;
+            let _ = safe.write_entry(entry, hashes).await?;
+        }
+
+        Ok(())
     }
 
-    // TODO: update terminology around apps auth here
-    pub async fn revoke_app(&self, _y: &str) -> Result<()> {
-        unimplemented!()
+    /// Revoke an application's access, removing the key it was assigned from the Policy of
+    /// the Registers it was granted write access to, and removing the key from the Safe.
+    /// If the application is authorised again, a new keypair will be generated for it.
+    pub async fn revoke_app(&self, app_id: &str) -> Result<()> {
+        debug!("Revoking application: {}", app_id);
+        let safe = self.unlocked_safe()?;
+
+        let (hashes, app) = safe.read_app(app_id).await?.ok_or_else(|| {
+            Error::AuthenticatorError(format!(
+                "Application '{}' is not authorised in the Safe",
+                app_id
+            ))
+        })?;
+
+        for address in &app.registers {
+            safe.set_app_permissions(*address, &app, false).await?;
+            debug!(
+                "Application '{}' access to Register at {:?} revoked",
+                app_id, address
+            );
+        }
+
+        let _ = safe
+            .write_entry(SAFE_ENTRY_REMOVED_MARK.to_vec(), hashes)
+            .await?;
+        debug!("Application '{}' revoked", app_id);
+
+        Ok(())
     }
 
     /// Decode requests and trigger application authorisation against the current client
-    pub async fn authorise_app(&self, _req: &str) -> Result<String> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // let ipc_req = IpcMsg::from_string(req).map_err(|err| {
-        //     Error::AuthenticatorError(format!("Failed to decode authorisation request: {:?}", err))
-        // })?;
-
-        // debug!("Auth request string decoded: {:?}", ipc_req);
-
-        // match ipc_req {
-        //     IpcMsg::Req(IpcReq::Auth(app_auth_req)) => {
-        //         info!("Request was recognised as an application auth request");
-        //         debug!("Decoded request: {:?}", app_auth_req);
-        //         self.gen_auth_response(app_auth_req).await
-        //     }
-        //     IpcMsg::Req(IpcReq::Unregistered(user_data)) => {
-        //         info!("Request was recognised as an unregistered auth request");
-        //         debug!("Decoded request: {:?}", user_data);
-
-        //         self.gen_unreg_auth_response()
-        //     }
-        //     IpcMsg::Resp { .. } | IpcMsg::Err(..) => Err(Error::AuthError(
-        //         "The request was not recognised as a valid auth request".to_string(),
-        //     )),
-        // }
+    pub async fn authorise_app(&self, req: &str) -> Result<String> {
+        let ipc_req = IpcMsg::from_string(req).map_err(|err| {
+            Error::AuthenticatorError(format!("Failed to decode authorisation request: {:?}", err))
+        })?;
+
+        debug!("Auth request string decoded: {:?}", ipc_req);
+
+        match ipc_req {
+            IpcMsg::Req(IpcReq::Auth(app_auth_req)) => {
+                info!("Request was recognised as an application auth request");
+                debug!("Decoded request: {:?}", app_auth_req);
+                self.gen_auth_response(app_auth_req).await
+            }
+            IpcMsg::Req(IpcReq::Unregistered(user_data)) => {
+                info!("Request was recognised as an unregistered auth request");
+                debug!("Decoded request: {:?}", user_data);
+
+                self.gen_unreg_auth_response()
+            }
+            IpcMsg::Resp { .. } | IpcMsg::Err(..) => Err(Error::AuthError(
+                "The request was not recognised as a valid auth request".to_string(),
+            )),
+        }
     }
 
     /// Authenticate an app request.
@@ -370,141 +629,129 @@ impl SafeAuthenticator {
     /// First, this function searches for an app info in the Safe.
     /// If the app is found, then the `AuthGranted` struct is returned based on that information.
     /// If the app is not found in the Safe, then it will be authenticated.
-    pub async fn authenticate(&self, _auth_req: AuthReq) -> Result<AuthGranted> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // debug!(
-        //     "Retrieving/generating keypair for an application: {:?}",
-        //     auth_req
-        // );
-        // if let Some((client, map_address)) = &self.safe {
-        //     let app_id = auth_req.app_id.as_bytes().to_vec();
-        //     let keypair = match client.get_map_value(*map_address, app_id.clone()).await {
-        //         Ok(value) => {
-        //             // This app already has its own keypair
-        //             trace!(
-        //                 "The app ('{}') already has a Keypair in the Safe",
-        //                 auth_req.app_id
-        //             );
-
-        //             // TODO: support for scenario when app was previously revoked,
-        //             // in which case we should generate a new keypair
-
-        //             let keypair_bytes = match value {
-        //                 MapValue::Seq(seq_value) => seq_value.data,
-        //                 MapValue::Unseq(data) => data,
-        //             };
-        //             let keypair_str = String::from_utf8(keypair_bytes).map_err(|_err| {
-        //                 Error::AuthError(
-        //                     "The Safe contains an invalid keypair associated to this app"
-        //                         .to_string(),
-        //                 )
-        //             })?;
-        //             let keypair: Keypair = serde_json::from_str(&keypair_str).map_err(|_err| {
-        //                 Error::AuthError(
-        //                     "The Safe contains an invalid keypair associated to this app"
-        //                         .to_string(),
-        //                 )
-        //             })?;
-
-        //             debug!(
-        //                 "Keypair for the app being authorised ('{}') retrieved from the Safe: {}",
-        //                 auth_req.app_id,
-        //                 keypair.public_key()
-        //             );
-
-        //             keypair
-        //         }
-        //         Err(ClientError::ErrorMsg {
-        //             source: NoSuchEntry,
-        //             ..
-        //         }) => {
-        //             // This is the first time this app is being authorised,
-        //             // thus let's generate a keypair for it
-        //             trace!(
-        //                 "The app ('{}') was not assigned a Keypair yet in the Safe. Generating one for it...",
-        //                 auth_req.app_id
-        //             );
-        //             let mut rng = OsRng;
-        //             let keypair = Keypair::new_ed25519(&mut rng);
-
-        //             let keypair_str = serde_json::to_string(&keypair).map_err(|err| {
-        //                 Error::AuthError(format!(
-        //                     "Failed to serialised keypair to store it in the Safe: {}",
-        //                     err
-        //                 ))
-        //             })?;
-
-        //             debug!(
-        //                 "New keypair generated for app ('{}') being authorised: {}",
-        //                 auth_req.app_id,
-        //                 keypair.public_key()
-        //             );
-
-        //             // Store the keypair in the Safe, mapped to the app id
-        //             let map_actions =
-        //                 MapSeqEntryActions::new().ins(app_id, keypair_str.as_bytes().to_vec(), 0);
-
-        //             client
-        //                 .edit_map_entries(*map_address, MapEntryActions::Seq(map_actions))
-        //                 .await?;
-
-        //             keypair
-        //         }
-        //         Err(err) => {
-        //             return Err(Error::AuthError(format!(
-        //                 "Failed to retrieve keypair from the Safe: {}",
-        //                 err
-        //             )))
-        //         }
-        //     };
-
-        //     Ok(AuthGranted {
-        //         app_keypair: keypair,
-        //         bootstrap_config: self.bootstrap_contacts.clone(),
-        //     })
-        // } else {
-        //     Err(Error::AuthenticatorError(
-        //         "No Safe is currently unlocked".to_string(),
-        //     ))
-        // }
+    pub async fn authenticate(&self, auth_req: AuthReq) -> Result<AuthGranted> {
+        debug!(
+            "Retrieving/generating keypair for an application: {:?}",
+            auth_req
+        );
+        let safe = self.unlocked_safe()?;
+
+        let keypair = match safe.read_app(&auth_req.app_id).await? {
+            Some((_, app)) => {
+                // This app already has its own keypair
+                debug!(
+                    "Keypair for the app being authorised ('{}') retrieved from the Safe: {}",
+                    auth_req.app_id,
+                    app.keypair.public_key()
+                );
+                app.keypair
+            }
+            None => {
+                // This is the first time this app is being authorised,
+                // thus let's generate a keypair for it
+                trace!(
+                    "The app ('{}') was not assigned a Keypair yet in the Safe. Generating one for it...",
+                    auth_req.app_id
+                );
+                let mut rng = OsRng;
+                let keypair = Keypair::new_ed25519(&mut rng);
+
+                debug!(
+                    "New keypair generated for app ('{}') being authorised: {}",
+                    auth_req.app_id,
+                    keypair.public_key()
+                );
+
+                // Store the keypair in the Safe, along with the app's information
+                let app = AppEntry {
+                    id: auth_req.app_id,
+                    name: auth_req.app_name,
+                    vendor: auth_req.app_vendor,
+                    keypair,
+                    registers: BTreeSet::new(),
+                };
+                let entry = safe.encrypt_entry(&app)?;
+                let _ = safe.write_entry(entry, BTreeSet::new()).await?;
+
+                app.keypair
+            }
+        };
+
+        Ok(AuthGranted {
+            app_keypair: keypair,
+            bootstrap_config: self.bootstrap_config.clone(),
+        })
     }
 
-    // Helper function to generate an app authorisation response
-    // async fn gen_auth_response(&self, auth_req: AuthReq) -> Result<String> {
-    //     let auth_granted = self.authenticate(auth_req).await.map_err(|err| {
-    //         Error::AuthenticatorError(format!(
-    //             "Failed to authorise application on the network: {}",
-    //             err
-    //         ))
-    //     })?;
-
-    //     debug!("Encoding response with auth credentials auth granted...");
-    //     let resp = serde_json::to_string(&IpcMsg::Resp(IpcResp::Auth(Ok(auth_granted)))).map_err(
-    //         |err| Error::AuthenticatorError(format!("Failed to encode response: {:?}", err)),
-    //     )?;
+    // Helper function to instantiate a client with the keypair derived from the Safe's credentials
+    async fn connect(&self, keypair: Keypair) -> Result<Client> {
+        let (genesis_key, bootstrap_nodes) = self.bootstrap_config.clone().ok_or_else(|| {
+            Error::AuthenticatorError("Bootstrap contacts information not available".to_string())
+        })?;
+
+        let config = ClientConfig::new(
+            None,
+            None,
+            genesis_key,
+            self.config_path.as_deref(),
+            Some(DEFAULT_OPERATION_TIMEOUT),
+            Some(DEFAULT_OPERATION_TIMEOUT),
+            None,
+        )
+        .await;
+
+        let client = Client::new(config, bootstrap_nodes, Some(keypair))
+            .await
+            .map_err(|err| {
+                Error::ConnectionError(format!("Failed to connect to the SAFE Network: {:?}", err))
+            })?;
+        trace!("Client instantiated properly!");
+
+        Ok(client)
+    }
 
-    //     debug!("Returning auth response generated");
+    // Helper function to get the Safe currently unlocked
+    fn unlocked_safe(&self) -> Result<&UnlockedSafe> {
+        self.safe
+            .as_ref()
+            .ok_or_else(|| Error::AuthenticatorError("No Safe is currently unlocked".to_string()))
+    }
 
-    //     Ok(resp)
-    // }
+    // Helper function to generate an app authorisation response
+    async fn gen_auth_response(&self, auth_req: AuthReq) -> Result<String> {
+        let auth_granted = self.authenticate(auth_req).await.map_err(|err| {
+            Error::AuthenticatorError(format!(
+                "Failed to authorise application on the network: {}",
+                err
+            ))
+        })?;
+
+        debug!("Encoding response with auth credentials auth granted...");
+        let resp = serde_json::to_string(&IpcMsg::Resp(IpcResp::Auth(Ok(auth_granted)))).map_err(
+            |err| Error::AuthenticatorError(format!("Failed to encode response: {:?}", err)),
+        )?;
+
+        debug!("Returning auth response generated");
+
+        Ok(resp)
+    }
 
     // Helper function to generate an unregistered authorisation response
-    // fn gen_unreg_auth_response(&self) -> Result<String> {
-    //     let bootstrap_contacts = self.bootstrap_contacts.clone().ok_or_else(|| {
-    //         Error::AuthenticatorError("Bootstrap contacts information not available".to_string())
-    //     })?;
-
-    //     debug!("Encoding response... {:?}", bootstrap_contacts);
-    //     let resp =
-    //         serde_json::to_string(&IpcMsg::Resp(IpcResp::Unregistered(Ok(bootstrap_contacts))))
-    //             .map_err(|err| {
-    //                 Error::AuthenticatorError(format!("Failed to encode response: {:?}", err))
-    //             })?;
-
-    //     debug!("Returning unregistered auth response generated: {:?}", resp);
-    //     Ok(resp)
-    // }
+    fn gen_unreg_auth_response(&self) -> Result<String> {
+        let bootstrap_config = self.bootstrap_config.clone().ok_or_else(|| {
+            Error::AuthenticatorError("Bootstrap contacts information not available".to_string())
+        })?;
+
+        debug!("Encoding response... {:?}", bootstrap_config);
+        let resp =
+            serde_json::to_string(&IpcMsg::Resp(IpcResp::Unregistered(Ok(bootstrap_config))))
+                .map_err(|err| {
+                    Error::AuthenticatorError(format!("Failed to encode response: {:?}", err))
+                })?;
+
+        debug!("Returning unregistered auth response generated: {:?}", resp);
+        Ok(resp)
+    }
 }
 
 #[cfg(test)]
@@ -541,5 +788,12 @@ mod tests {
             prop_assert_eq!(location, location_again);
             prop_assert_eq!(keypair, keypair_again);
         }
+
+        #[test]
+        fn proptest_always_get_same_encryption_key_from_phrase_and_pw(s in "\\PC*", p in "\\PC*") {
+            let key = derive_encryption_key(&s, &p);
+            let key_again = derive_encryption_key(&s, &p);
+            prop_assert_eq!(key, key_again);
+        }
     }
 }
@@ -11,14 +11,17 @@ use crate::{
     operations::config::{Config, SnLaunchToolNetworkLauncher},
     shell,
     subcommands::{
+        cache::cache_commander,
         cat::cat_commander,
         config::config_commander,
         dog::dog_commander,
         files::files_commander,
         keys::key_commander,
+        multimap::multimap_commander,
         networks::networks_commander,
-        node::node_commander,
+        node::{node_commander, NodeSubCommands},
         nrs::nrs_commander,
+        register::register_commander,
         setup::setup_commander,
         update::update_commander,
         xorurl::{xorurl_commander, xorurl_of_files},
@@ -112,10 +115,22 @@ pub async fn run_with(cmd_args: Option<&[&str]>, safe: &mut Safe) -> Result<()>
         }
         Some(SubCommands::Setup(cmd)) => setup_commander(cmd, output_fmt),
         Some(SubCommands::Node { cmd }) => {
+            // Querying the rewards is the only node command which needs a connection
+            if matches!(cmd, Some(NodeSubCommands::Rewards { .. })) {
+                connect_if_needed(safe).await?;
+            }
             let mut launcher = Box::new(SnLaunchToolNetworkLauncher::default());
-            node_commander(cmd, &mut get_config().await?, &mut launcher).await
+            node_commander(
+                cmd,
+                output_fmt,
+                &mut get_config().await?,
+                &mut launcher,
+                safe,
+            )
+            .await
         }
         Some(SubCommands::Keys(cmd)) => key_commander(cmd, output_fmt, safe).await,
+        Some(SubCommands::Cache { cmd }) => cache_commander(cmd, output_fmt, safe).await,
         Some(SubCommands::Xorurl {
             cmd,
             location,
@@ -140,26 +155,15 @@ pub async fn run_with(cmd_args: Option<&[&str]>, safe: &mut Safe) -> Result<()>
             // available to connect to the network with them (unless dry-run was set),
             // otherwise the connection created will be with read-only access and some
             // of these commands will fail if they require write access.
-            if !safe.dry_run_mode && !safe.is_connected() {
-                let timeout_secs: u64 = match env::var(SN_CLI_QUERY_TIMEOUT) {
-                    Ok(timeout) => timeout.parse::<u64>().map_err(|_| {
-                        eyre!(
-                            "Could not parse {} env var value: {}",
-                            SN_CLI_QUERY_TIMEOUT,
-                            timeout
-                        )
-                    })?,
-                    Err(_) => DEFAULT_OPERATION_TIMEOUT_SECS,
-                };
-
-                connect(safe, get_config().await?, Duration::from_secs(timeout_secs)).await?;
-            }
+            connect_if_needed(safe).await?;
 
             match other {
                 SubCommands::Cat(cmd) => cat_commander(cmd, output_fmt, safe).await,
                 SubCommands::Dog(cmd) => dog_commander(cmd, output_fmt, safe).await,
                 SubCommands::Files(cmd) => files_commander(cmd, output_fmt, safe).await,
                 SubCommands::Nrs(cmd) => nrs_commander(cmd, output_fmt, safe).await,
+                SubCommands::Register(cmd) => register_commander(cmd, output_fmt, safe).await,
+                SubCommands::Multimap(cmd) => multimap_commander(cmd, output_fmt, safe).await,
                 _ => Err(eyre!("Unknown safe subcommand")),
             }
         }
@@ -170,6 +174,27 @@ pub async fn run_with(cmd_args: Option<&[&str]>, safe: &mut Safe) -> Result<()>
     result
 }
 
+// Connects to the network, with the credentials if they are available, unless
+// we are already connected or dry-run was set.
+async fn connect_if_needed(safe: &mut Safe) -> Result<()> {
+    if !safe.dry_run_mode && !safe.is_connected() {
+        let timeout_secs: u64 = match env::var(SN_CLI_QUERY_TIMEOUT) {
+            Ok(timeout) => timeout.parse::<u64>().map_err(|_| {
+                eyre!(
+                    "Could not parse {} env var value: {}",
+                    SN_CLI_QUERY_TIMEOUT,
+                    timeout
+                )
+            })?,
+            Err(_) => DEFAULT_OPERATION_TIMEOUT_SECS,
+        };
+
+        connect(safe, get_config().await?, Duration::from_secs(timeout_secs)).await?;
+    }
+
+    Ok(())
+}
+
 /// Gets the configuration, which is used by various parts of the application.
 ///
 /// The SN_CLI_CONFIG_PATH allows the user to define a custom location as an alternative to
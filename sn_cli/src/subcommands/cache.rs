@@ -0,0 +1,61 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{helpers::serialise_output, OutputFmt};
+use color_eyre::Result;
+use sn_api::Safe;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub enum CacheSubCommands {
+    #[structopt(name = "info")]
+    /// Show where the chunks retrieved are cached on disk, and how many of them there are
+    Info {},
+    #[structopt(name = "clear")]
+    /// Remove all the chunks cached on disk
+    Clear {},
+}
+
+pub async fn cache_commander(
+    cmd: Option<CacheSubCommands>,
+    output_fmt: OutputFmt,
+    safe: &Safe,
+) -> Result<()> {
+    let cache = safe.chunk_cache().await?;
+    match cmd {
+        Some(CacheSubCommands::Clear {}) => {
+            let stats = cache.stats().await;
+            cache.clear().await?;
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "Removed {} chunks ({} bytes) from the cache at \"{}\"",
+                    stats.chunks,
+                    stats.size,
+                    cache.dir().display()
+                );
+            }
+            Ok(())
+        }
+        Some(CacheSubCommands::Info {}) | None => {
+            let stats = cache.stats().await;
+            if OutputFmt::Pretty == output_fmt {
+                println!("Chunk cache: \"{}\"", cache.dir().display());
+                println!("Chunks: {}", stats.chunks);
+                println!("Size: {} bytes", stats.size);
+                if stats.max_size > 0 {
+                    println!("Size limit: {} bytes", stats.max_size);
+                } else {
+                    println!("Size limit: none, set it with the SN_CHUNK_CACHE_SIZE env var to enable the cache");
+                }
+            } else {
+                println!("{}", serialise_output(&stats, output_fmt));
+            }
+            Ok(())
+        }
+    }
+}
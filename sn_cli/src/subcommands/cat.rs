@@ -10,12 +10,14 @@ use super::{
     helpers::{get_from_arg_or_stdin, get_target_url, print_nrs_map, serialise_output},
     OutputFmt,
 };
-use color_eyre::{eyre::WrapErr, Result};
+use color_eyre::{eyre::eyre, eyre::WrapErr, Result};
 use comfy_table::Table;
 use sn_api::{resolver::SafeData, Safe};
-use std::io::{self, Write};
 use structopt::StructOpt;
-use tokio::time::{sleep, Duration};
+use tokio::{
+    io::{self, AsyncReadExt, AsyncWriteExt},
+    time::{sleep, Duration},
+};
 use tracing::{debug, trace};
 
 const MAX_RETRY_ATTEMPTS: usize = 5;
@@ -36,17 +38,20 @@ pub async fn cat_commander(cmd: CatCommands, output_fmt: OutputFmt, safe: &Safe)
 
     let mut attempts = 0;
 
-    let mut content = safe.fetch(&url.to_string(), None).await;
+    // Files' content is not retrieved when resolving the URL, we stream it below instead
+    let mut resolution_chain = safe.inspect(&url.to_string()).await;
 
-    while content.is_err() && attempts < MAX_RETRY_ATTEMPTS {
+    while resolution_chain.is_err() && attempts < MAX_RETRY_ATTEMPTS {
         trace!("cat attempt #{:?}", attempts);
         sleep(Duration::from_secs(1)).await;
-        content = safe.fetch(&url.to_string(), None).await;
+        resolution_chain = safe.inspect(&url.to_string()).await;
 
         attempts += 1;
     }
 
-    let content = content?;
+    let content = resolution_chain?
+        .pop()
+        .ok_or_else(|| eyre!("Failed to resolve {}", url))?;
 
     match &content {
         SafeData::FilesContainer {
@@ -79,14 +84,25 @@ pub async fn cat_commander(cmd: CatCommands, output_fmt: OutputFmt, safe: &Safe)
                 );
             }
         }
-        SafeData::PublicFile { data, .. } => {
+        SafeData::PublicFile { xorurl, .. } => {
+            let mut reader = safe.files_get_public_stream(xorurl).await?;
             if cmd.hexdump {
                 // Render hex representation of file
-                println!("{}", pretty_hex::pretty_hex(data));
+                let mut data = vec![];
+                let _ = reader
+                    .read_to_end(&mut data)
+                    .await
+                    .context("Failed to fetch the content of the file")?;
+                println!("{}", pretty_hex::pretty_hex(&data));
             } else {
-                // Render file
-                io::stdout()
-                    .write_all(data)
+                // Render file, as its content is fetched
+                let mut stdout = io::stdout();
+                let _ = io::copy(&mut reader, &mut stdout)
+                    .await
+                    .context("Failed to print out the content of the file")?;
+                stdout
+                    .flush()
+                    .await
                     .context("Failed to print out the content of the file")?;
             }
         }
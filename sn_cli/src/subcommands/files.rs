@@ -8,6 +8,7 @@
 
 use super::{
     files_get::{process_get_command, FileExistsAction, ProgressIndicator},
+    files_progress::with_progress_bars,
     helpers::{
         gen_processed_files_table, get_from_arg_or_stdin, get_from_stdin, get_target_url, if_tty,
         notice_dry_run, parse_stdin_arg, pluralize, serialise_output,
@@ -20,10 +21,10 @@ use color_eyre::{eyre::bail, eyre::eyre, Result};
 use comfy_table::Table;
 use serde::Serialize;
 use sn_api::{
-    files::{FilesMap, ProcessedFiles},
+    files::{FilesContainerVersion, FilesMap, ProcessedFiles},
     nrs::VersionHash,
     resolver::SafeData,
-    Safe, SafeUrl, XorUrl,
+    Safe, SafeUrl, Scope, XorUrl,
 };
 use std::{
     collections::{BTreeMap, HashMap},
@@ -110,6 +111,17 @@ pub enum FilesSubCommands {
         /// Follow symlinks
         #[structopt(short = "l", long = "follow-links")]
         follow_links: bool,
+        /// Create a private FilesContainer, with the files encrypted with the configured keypair,
+        /// so only its owner can read them. Files later added or synced are kept private too
+        #[structopt(long = "private")]
+        private: bool,
+        /// Resume the interrupted uploads of the files, only sending the chunks which weren't
+        /// stored yet. Files with no interrupted upload are uploaded as usual
+        #[structopt(long = "resume")]
+        resume: bool,
+        /// How to display the progress of the files being uploaded.
+        #[structopt(short = "i", long = "progress", possible_values = &["bars", "none"], default_value="bars")]
+        progress: ProgressIndicator,
     },
     /// Get a file or folder from the SAFE Network
     Get {
@@ -121,7 +133,7 @@ pub enum FilesSubCommands {
         #[structopt(short = "e", long = "exists", possible_values = &["ask", "preserve", "overwrite"], default_value="ask")]
         exists: FileExistsAction,
         /// How to display progress.
-        #[structopt(short = "i", long = "progress", possible_values = &["text", "none"], default_value="text")]
+        #[structopt(short = "i", long = "progress", possible_values = &["bars", "text", "none"], default_value="bars")]
         progress: ProgressIndicator,
         /// Preserves modification times, access times, and modes from the original file
         #[structopt(short = "p", long = "preserve")]
@@ -146,6 +158,9 @@ pub enum FilesSubCommands {
         /// Automatically update the NRS name to link to the new version of the FilesContainer. This is only allowed if an NRS URL was provided, and if the NRS name is currently linked to a specific version of the FilesContainer
         #[structopt(short = "u", long = "update-nrs")]
         update_nrs: bool,
+        /// How to display the progress of the files being uploaded.
+        #[structopt(short = "i", long = "progress", possible_values = &["bars", "none"], default_value="bars")]
+        progress: ProgressIndicator,
     },
     #[structopt(name = "add")]
     /// Add a file to an existing FilesContainer on the network
@@ -197,12 +212,39 @@ pub enum FilesSubCommands {
         #[structopt(short = "d", long = "details")]
         details: bool,
     },
+    #[structopt(name = "history")]
+    /// List the versions of an existing FilesContainer on the network, the most recent first
+    History {
+        /// The target FilesContainer to list the versions of
+        target: Option<String>,
+    },
+    #[structopt(name = "diff")]
+    /// Show the files added, removed and modified between two versions of a FilesContainer
+    Diff {
+        /// The target FilesContainer
+        target: String,
+        /// The version to compare from
+        from: VersionHash,
+        /// The version to compare with (default is the version the target URL resolves to, i.e. the latest one if it's not versioned)
+        to: Option<VersionHash>,
+    },
+    #[structopt(name = "rollback")]
+    /// Roll an existing FilesContainer back to a previous version, by adding a new version with the same files as it
+    Rollback {
+        /// The target FilesContainer to roll back
+        target: String,
+        /// The version to roll back to
+        version: VersionHash,
+        /// Automatically update the NRS name to link to the new version of the FilesContainer. This is only allowed if an NRS URL was provided, and if the NRS name is currently linked to a specific version of the FilesContainer
+        #[structopt(short = "u", long = "update-nrs")]
+        update_nrs: bool,
+    },
 }
 
 pub async fn files_commander(
     cmd: FilesSubCommands,
     output_fmt: OutputFmt,
-    safe: &Safe,
+    safe: &mut Safe,
 ) -> Result<()> {
     match cmd {
         FilesSubCommands::Put {
@@ -210,21 +252,43 @@ pub async fn files_commander(
             dst,
             recursive,
             follow_links,
+            private,
+            resume,
+            progress,
         } => {
+            safe.resume_uploads = resume;
+            let scope = if private {
+                Scope::Private
+            } else {
+                Scope::Public
+            };
             // create FilesContainer from a given path to local files/folders
             if safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
                 notice_dry_run();
             }
-            let (files_container_xorurl, processed_files, _) = safe
-                .files_container_create_from(&location, dst.as_deref(), recursive, follow_links)
-                .await?;
+            let (files_container_xorurl, processed_files, _) = with_progress_bars(
+                safe,
+                &progress,
+                safe.files_container_create_from(
+                    &location,
+                    dst.as_deref(),
+                    recursive,
+                    follow_links,
+                    scope,
+                ),
+            )
+            .await?;
 
             // Now let's just print out a list of the files uploaded/processed
             if OutputFmt::Pretty == output_fmt {
                 if safe.dry_run_mode {
                     println!("FilesContainer not created since running in dry-run mode");
                 } else {
-                    println!("FilesContainer created at: \"{}\"", files_container_xorurl);
+                    println!(
+                        "{}FilesContainer created at: \"{}\"",
+                        if private { "Private " } else { "" },
+                        files_container_xorurl
+                    );
                 }
 
                 let (table, _) = gen_processed_files_table(&processed_files, true);
@@ -242,6 +306,7 @@ pub async fn files_commander(
             follow_links,
             delete,
             update_nrs,
+            progress,
         } => {
             let target = get_from_arg_or_stdin(target, None)?;
             let mut target_url = get_target_url(&target)?;
@@ -249,16 +314,19 @@ pub async fn files_commander(
                 notice_dry_run();
             }
             // Update the FilesContainer on the Network
-            let (content, processed_files) = safe
-                .files_container_sync(
+            let (content, processed_files) = with_progress_bars(
+                safe,
+                &progress,
+                safe.files_container_sync(
                     &location,
                     &target_url.to_string(),
                     recursive,
                     follow_links,
                     delete,
                     update_nrs,
-                )
-                .await?;
+                ),
+            )
+            .await?;
             let version = content.map(|(version, _)| version);
 
             // Now let's just print out a list of the files synced/processed
@@ -404,6 +472,98 @@ pub async fn files_commander(
         FilesSubCommands::Tree { target, details } => {
             process_tree_command(safe, target, details, output_fmt).await
         }
+        FilesSubCommands::History { target } => {
+            let target_url =
+                get_from_arg_or_stdin(target, Some("...awaiting target URl from STDIN"))?;
+
+            let history = safe.files_container_history(&target_url).await?;
+            if OutputFmt::Pretty == output_fmt {
+                print_files_container_history(&history, &target_url);
+            } else {
+                let versions: Vec<_> = history.iter().map(VersionDetails::from).collect();
+                println!("{}", serialise_output(&(target_url, versions), output_fmt));
+            }
+
+            Ok(())
+        }
+        FilesSubCommands::Diff { target, from, to } => {
+            let to = match to {
+                Some(version) => version,
+                None => match safe.files_container_get(&target).await? {
+                    Some((version, _)) => version,
+                    None => bail!("FilesContainer found at \"{}\" is empty", target),
+                },
+            };
+
+            let diff = safe.files_container_diff(&target, from, to).await?;
+            if OutputFmt::Pretty == output_fmt {
+                if diff.is_empty() {
+                    println!(
+                        "No changes between version {} and version {} of FilesContainer at \"{}\"",
+                        from, to, target
+                    );
+                } else {
+                    println!(
+                        "Changes between version {} and version {} of FilesContainer at \"{}\":",
+                        from, to, target
+                    );
+                    let mut table = Table::new();
+                    for (change_sign, paths) in [
+                        ("+", &diff.added),
+                        ("-", &diff.removed),
+                        ("*", &diff.modified),
+                    ] {
+                        for path in paths {
+                            table.add_row(&vec![change_sign, path]);
+                        }
+                    }
+                    println!("{table}");
+                }
+            } else {
+                println!("{}", serialise_output(&(target, diff), output_fmt));
+            }
+
+            Ok(())
+        }
+        FilesSubCommands::Rollback {
+            target,
+            version,
+            update_nrs,
+        } => {
+            if safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
+                notice_dry_run();
+            }
+
+            let (new_version, files_map) = safe
+                .files_container_rollback(&target, version, update_nrs)
+                .await?;
+
+            let url = match SafeUrl::from_url(&target) {
+                Ok(mut safeurl) => {
+                    safeurl.set_content_version(Some(new_version));
+                    safeurl.set_path("");
+                    safeurl.to_string()
+                }
+                Err(_) => target,
+            };
+            if OutputFmt::Pretty == output_fmt {
+                if new_version == version {
+                    println!(
+                        "FilesContainer is already at version {}: \"{}\"",
+                        version, url
+                    );
+                } else {
+                    println!(
+                        "FilesContainer rolled back to the files of version {} (version {}): \"{}\"",
+                        version, new_version, url
+                    );
+                }
+            } else {
+                println!("{}", serialise_output(&(url, files_map), output_fmt));
+            }
+
+            Ok(())
+        }
         FilesSubCommands::Get {
             source,
             dst,
@@ -463,6 +623,51 @@ async fn process_tree_command(
     Ok(())
 }
 
+// Details of a version of a FilesContainer, as serialised by `safe files history`
+#[derive(Serialize)]
+struct VersionDetails {
+    version: String,
+    parents: Vec<String>,
+    timestamp: Option<u64>,
+    files: usize,
+    changes: usize,
+}
+
+impl From<&FilesContainerVersion> for VersionDetails {
+    fn from(version: &FilesContainerVersion) -> Self {
+        Self {
+            version: version.version.to_string(),
+            parents: version.parents.iter().map(|v| v.to_string()).collect(),
+            timestamp: version.timestamp,
+            files: version.files,
+            changes: version.changes,
+        }
+    }
+}
+
+fn print_files_container_history(history: &[FilesContainerVersion], target_url: &str) {
+    println!(
+        "FilesContainer at \"{}\" has {} {}:",
+        target_url,
+        history.len(),
+        pluralize("version", "versions", history.len() as u64)
+    );
+
+    let mut table = Table::new();
+    table.add_row(&vec!["VERSION", "MODIFIED", "FILES", "CHANGES"]);
+    for version in history {
+        table.add_row(&vec![
+            version.version.to_string(),
+            version
+                .timestamp
+                .map_or_else(String::default, |t| t.to_string()),
+            version.files.to_string(),
+            version.changes.to_string(),
+        ]);
+    }
+    println!("{table}");
+}
+
 fn print_serialized_output(
     xorurl: XorUrl,
     change_version: Option<VersionHash>,
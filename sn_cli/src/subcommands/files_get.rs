@@ -7,15 +7,14 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::{
+    files_progress::with_progress_bars,
     helpers::{div_or, pluralize, processed_files_err_report, prompt_user},
     OutputFmt,
 };
-use bytes::Buf;
 use color_eyre::{eyre::bail, eyre::eyre, eyre::WrapErr, Result};
 use console::Term;
 use sn_api::{
-    files::{FilesMap, GetAttr},
-    resolver::Range,
+    files::{FileReader, FilesMap, GetAttr},
     resolver::SafeData,
     DataType, Result as ApiResult, Safe, SafeUrl, XorUrl,
 };
@@ -25,8 +24,12 @@ use std::{
     io::{BufWriter, Write},
     path::Path,
 };
+use tokio::io::AsyncReadExt;
 use tracing::{debug, info, trace, warn};
 
+// Size of the buffer used to write downloaded files to disk, i.e. 16 times a common disk block size.
+const DOWNLOAD_BUFFER_SIZE: usize = 16 * 4096;
+
 /// # Retrieval/write status for current file and overall transfer.
 #[derive(Debug, Clone)]
 pub struct FilesGetStatus<'a, 'b> {
@@ -75,15 +78,17 @@ impl std::str::FromStr for FileExistsAction {
 // What type of Progress Indicator to display.
 #[derive(Debug)]
 pub enum ProgressIndicator {
+    Bars,
     Text,
     None,
 }
 
-// implement FromStr for parsing "--exists" arg.
+// implement FromStr for parsing "--progress" arg.
 impl std::str::FromStr for ProgressIndicator {
     type Err = String;
     fn from_str(str: &str) -> Result<Self, String> {
         match str {
+            "bars" => Ok(Self::Bars),
             "text" => Ok(Self::Text),
             "none" => Ok(Self::None),
             other => Err(format!(
@@ -96,7 +101,7 @@ impl std::str::FromStr for ProgressIndicator {
 
 impl Default for ProgressIndicator {
     fn default() -> Self {
-        ProgressIndicator::Text
+        ProgressIndicator::Bars
     }
 }
 
@@ -132,76 +137,76 @@ pub async fn process_get_command(
     let mut overwrites: u64 = 0;
     let mut preserves: u64 = 0;
 
-    let (_version, processed_files) =
-        files_container_get_files(safe, &source, &str_path, |status| {
-            let mut overwrite = true;
-            let mut mystatus = status.clone();
-
-            if status.file_bytes_written == 0 {
-                // It is an error/warning if the dst path attempts to use
-                // an existing file as a directory. But other files should
-                // still be written.  eg:
-                // $ mkdir -p /tmp/a/b/c && touch /tmp/a/file.txt
-                // $ mkdir /tmp/target && touch /tmp/target/b   (b is a file)
-                // $ cp -r /tmp/a/* /tmp/target
-                //    cp: cannot overwrite non-directory '/tmp/target/b' with directory '/tmp/a/b'
-                // $ ls -l /tmp/target/
-                //      total 0
-                //      -rw-rw-r-- 1 user user 0 Mar 31 14:38 b         (b still a file)
-                //      -rw-rw-r-- 1 user user 0 Mar 31 14:38 file.txt  (other file written)
-                //
-                // TBD: Should FileExistsAction apply to this case?
-                //      unix cp does not provide any flag/option/prompt to permit this
-                //      and it always emits a warning.  So I am satisfied with this
-                //      working the same way, at least for now.
-                let dirpath = if status.file_type == "inode/directory" {
-                    Some(status.path_local)
-                } else {
-                    status.path_local.parent()
-                };
-                if let Some(parent) = dirpath {
-                    if let Some(filepath) = path_contains_file(parent) {
-                        let msg = format!(
-                            "cannot overwrite non-directory '{}' with directory in '{}'",
-                            filepath.display(),
-                            status.path_local.display()
-                        );
-
-                        warn!("Skipping file \"{}\". {}", status.path_local.display(), msg);
-                        if isatty::stderr_isatty() {
-                            eprintln!("Warning: {}", msg);
-                        }
-                        overwrite = false;
+    let get_files = files_container_get_files(safe, &source, &str_path, |status| {
+        let mut overwrite = true;
+        let mut mystatus = status.clone();
+
+        if status.file_bytes_written == 0 {
+            // It is an error/warning if the dst path attempts to use
+            // an existing file as a directory. But other files should
+            // still be written.  eg:
+            // $ mkdir -p /tmp/a/b/c && touch /tmp/a/file.txt
+            // $ mkdir /tmp/target && touch /tmp/target/b   (b is a file)
+            // $ cp -r /tmp/a/* /tmp/target
+            //    cp: cannot overwrite non-directory '/tmp/target/b' with directory '/tmp/a/b'
+            // $ ls -l /tmp/target/
+            //      total 0
+            //      -rw-rw-r-- 1 user user 0 Mar 31 14:38 b         (b still a file)
+            //      -rw-rw-r-- 1 user user 0 Mar 31 14:38 file.txt  (other file written)
+            //
+            // TBD: Should FileExistsAction apply to this case?
+            //      unix cp does not provide any flag/option/prompt to permit this
+            //      and it always emits a warning.  So I am satisfied with this
+            //      working the same way, at least for now.
+            let dirpath = if status.file_type == "inode/directory" {
+                Some(status.path_local)
+            } else {
+                status.path_local.parent()
+            };
+            if let Some(parent) = dirpath {
+                if let Some(filepath) = path_contains_file(parent) {
+                    let msg = format!(
+                        "cannot overwrite non-directory '{}' with directory in '{}'",
+                        filepath.display(),
+                        status.path_local.display()
+                    );
+
+                    warn!("Skipping file \"{}\". {}", status.path_local.display(), msg);
+                    if isatty::stderr_isatty() {
+                        eprintln!("Warning: {}", msg);
                     }
+                    overwrite = false;
                 }
-                if status.path_local.exists() && overwrite {
-                    overwrite = match exists {
-                        FileExistsAction::Overwrite => true,
-                        FileExistsAction::Preserve => false,
-                        FileExistsAction::Ask => {
-                            let prompt = format!("overwrite '{}'? ", status.path_local.display());
-                            prompt_yes_no(&prompt, "Y")
-                        }
-                    };
-                    if overwrite {
-                        overwrites += 1;
-                    } else {
-                        preserves += 1;
-                        mystatus.total_transfer_bytes -= mystatus.file_size;
+            }
+            if status.path_local.exists() && overwrite {
+                overwrite = match exists {
+                    FileExistsAction::Overwrite => true,
+                    FileExistsAction::Preserve => false,
+                    FileExistsAction::Ask => {
+                        let prompt = format!("overwrite '{}'? ", status.path_local.display());
+                        prompt_yes_no(&prompt, "Y")
                     }
+                };
+                if overwrite {
+                    overwrites += 1;
+                } else {
+                    preserves += 1;
+                    mystatus.total_transfer_bytes -= mystatus.file_size;
                 }
             }
-            if overwrite {
-                match progress {
-                    ProgressIndicator::Text => {
-                        print_status(status);
-                    }
-                    ProgressIndicator::None => {}
+        }
+        if overwrite {
+            match progress {
+                ProgressIndicator::Text => {
+                    print_status(status);
                 }
+                // the bars are drawn from the progress of the downloads themselves
+                ProgressIndicator::Bars | ProgressIndicator::None => {}
             }
-            overwrite
-        })
-        .await?;
+        }
+        overwrite
+    });
+    let (_version, processed_files) = with_progress_bars(safe, &progress, get_files).await?;
 
     if processed_files.is_empty() && preserves == 0 {
         bail!("Path '{}' not found", path.display());
@@ -620,28 +625,33 @@ fn denormalize_slashes(p: &str) -> String {
 async fn download_file_from_net(safe: &Safe, xorurl: &str, path: &Path, size: u64) -> Result<u64> {
     debug!("downloading file {} to {}", xorurl, path.display());
 
-    // TODO: download the file by concurrently (spawning tasks/threads) pulling chunks.
-    // The chunk_size can be based on https://stackoverflow.com/questions/8803515/optimal-buffer-size-for-write2
-    // which can be 4096 to match common disk block size, but that seems a bit small for the
-    // network, so I multiplied by 16. Perhaps should make it a param so caller can decide.
-    let mut rcvd: u64 = 0;
     let mut bytes_written: u64 = 0;
 
     let fh = file_create(path)?;
     let mut stream = BufWriter::new(fh);
 
-    // gets public or private, based on xorurl type
-    let filedata = files_get(safe, xorurl, None).await?;
-    bytes_written += stream_write(&mut stream, &filedata, path)? as u64;
-    rcvd += filedata.len() as u64;
-    trace!("received {} bytes of {}", rcvd, size,);
+    // The file's chunks are fetched as we read them, so we write them to disk as they arrive
+    // rather than holding the whole file in memory.
+    let mut reader = files_get_stream(safe, xorurl).await?;
+    let mut buf = vec![0; DOWNLOAD_BUFFER_SIZE];
+    loop {
+        let rcvd = reader
+            .read(&mut buf)
+            .await
+            .with_context(|| format!("Error reading file from the network: \"{}\"", xorurl))?;
+        if rcvd == 0 {
+            break;
+        }
+        bytes_written += stream_write(&mut stream, &buf[..rcvd], path)? as u64;
+        trace!("received {} bytes of {}", bytes_written, size);
+    }
 
     // Close may generate an error, so we do a flush/sync first to detect such.
     // see https://github.com/rust-lang/rust/pull/63410#issuecomment-519965351
     let fh = bufwriter_into_inner(stream, path)?;
     file_sync_all(&fh, path)?;
 
-    Ok(bytes_written as u64)
+    Ok(bytes_written)
 }
 
 // syncs file to filesystem.
@@ -661,8 +671,9 @@ fn bufwriter_into_inner<W: Write>(w: BufWriter<W>, path: &Path) -> Result<W> {
 // Writes data to a file/stream.
 fn stream_write(writer: &mut dyn Write, data: &[u8], path: &Path) -> Result<usize> {
     writer
-        .write(data)
-        .with_context(|| format!("Error writing to file: \"{}\"", path.display(),))
+        .write_all(data)
+        .with_context(|| format!("Error writing to file: \"{}\"", path.display(),))?;
+    Ok(data.len())
 }
 
 // Creates a file, ready for writing.
@@ -682,14 +693,11 @@ fn create_dir_all(dir_path: &Path) -> Result<()> {
         .with_context(|| format!("Couldn't create path: \"{}\"", dir_path.display(),))
 }
 
-/// # Get Public or Private file
-/// Get immutable files from the network.
-pub async fn files_get(safe: &Safe, url: &str, range: Range) -> Result<Vec<u8>> {
+/// # Get Public or Private file as a stream
+/// Get a reader over immutable files from the network, which fetches the content as it's read.
+pub async fn files_get_stream(safe: &Safe, url: &str) -> Result<FileReader> {
     match SafeUrl::from_url(url)?.data_type() {
-        DataType::File => {
-            let bytes = safe.files_get_public(url, range).await?;
-            Ok(bytes.chunk().to_vec())
-        }
+        DataType::File => Ok(safe.files_get_public_stream(url).await?),
         _ => Err(eyre!("URL target is not immutable data")),
     }
 }
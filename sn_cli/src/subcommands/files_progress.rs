@@ -0,0 +1,179 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::files_get::ProgressIndicator;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use sn_api::{
+    files::{Progress, ProgressEvent, TransferId},
+    Safe,
+};
+use std::{collections::BTreeMap, future::Future, sync::Arc, thread};
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tracing::warn;
+
+const FILE_BAR_TEMPLATE: &str = "{msg:30} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})";
+const OVERALL_BAR_TEMPLATE: &str =
+    "{msg:30} [{bar:40.green/white}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})";
+const PROGRESS_CHARS: &str = "=> ";
+
+// Awaits the given transfer of files, drawing a progress bar for each file being uploaded or
+// downloaded, and one for the overall transfer, if bars were chosen as the progress indicator.
+pub async fn with_progress_bars<F, T>(safe: &Safe, progress: &ProgressIndicator, transfer: F) -> T
+where
+    F: Future<Output = T>,
+{
+    // there is nothing being transferred when not connected, i.e. in dry-run mode
+    let mut events = match progress {
+        ProgressIndicator::Bars if safe.is_connected() => {
+            match safe.files_progress_events().await {
+                Ok(events) => events,
+                Err(err) => {
+                    warn!("Cannot display progress bars: {}", err);
+                    return transfer.await;
+                }
+            }
+        }
+        _ => return transfer.await,
+    };
+
+    let mut bars = ProgressBars::new();
+    tokio::pin!(transfer);
+    let output = loop {
+        tokio::select! {
+            output = &mut transfer => break output,
+            event = events.recv() => match event {
+                Ok(event) => bars.update(event),
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("Progress bars missed {} events, falling behind", missed)
+                }
+                Err(RecvError::Closed) => break transfer.await,
+            },
+        }
+    };
+
+    // draw the events of the last chunks too, before finishing
+    loop {
+        match events.try_recv() {
+            Ok(event) => bars.update(event),
+            Err(TryRecvError::Lagged(_)) => continue,
+            Err(_) => break,
+        }
+    }
+    bars.finish();
+
+    output
+}
+
+// The bars of the files being transferred and of the overall transfer, drawn on stderr.
+struct ProgressBars {
+    multi: Arc<MultiProgress>,
+    overall: ProgressBar,
+    files: BTreeMap<TransferId, ProgressBar>,
+    files_started: u64,
+    files_completed: u64,
+    drawing: thread::JoinHandle<()>,
+}
+
+impl ProgressBars {
+    fn new() -> Self {
+        let multi = Arc::new(MultiProgress::new());
+        let overall = multi.add(
+            ProgressBar::new(0).with_style(
+                ProgressStyle::default_bar()
+                    .template(OVERALL_BAR_TEMPLATE)
+                    .progress_chars(PROGRESS_CHARS),
+            ),
+        );
+
+        // the bars are only drawn while joined, until all of them are finished
+        let joined = multi.clone();
+        let drawing = thread::spawn(move || {
+            if let Err(err) = joined.join() {
+                warn!("Failed to draw progress bars: {}", err);
+            }
+        });
+
+        let bars = Self {
+            multi,
+            overall,
+            files: BTreeMap::new(),
+            files_started: 0,
+            files_completed: 0,
+            drawing,
+        };
+        bars.update_overall_message();
+        bars
+    }
+
+    fn update(&mut self, event: ProgressEvent) {
+        match event.progress {
+            Progress::UploadStarted { path, bytes } => {
+                let name = path
+                    .as_ref()
+                    .and_then(|path| path.file_name())
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "<bytes>".to_string());
+                self.start_file(event.transfer, name, bytes);
+            }
+            Progress::DownloadStarted { address, bytes } => {
+                self.start_file(event.transfer, format!("{:?}", address.name()), bytes);
+            }
+            Progress::ChunkAcked { bytes } | Progress::ChunkFetched { bytes } => {
+                if let Some(bar) = self.files.get(&event.transfer) {
+                    bar.inc(bytes as u64);
+                    self.overall.inc(bytes as u64);
+                }
+            }
+            Progress::UploadCompleted { .. } | Progress::DownloadCompleted => {
+                if let Some(bar) = self.files.remove(&event.transfer) {
+                    // count whatever was left, e.g. if the content was stored already
+                    self.overall
+                        .inc(bar.length().saturating_sub(bar.position()));
+                    bar.finish_and_clear();
+                    self.files_completed += 1;
+                    self.update_overall_message();
+                }
+            }
+            Progress::ChunkEncrypted { .. } | Progress::ChunkSent { .. } => {}
+        }
+    }
+
+    fn start_file(&mut self, transfer: TransferId, name: String, bytes: u64) {
+        let bar = self.multi.add(
+            ProgressBar::new(bytes)
+                .with_style(
+                    ProgressStyle::default_bar()
+                        .template(FILE_BAR_TEMPLATE)
+                        .progress_chars(PROGRESS_CHARS),
+                )
+                .with_message(name),
+        );
+        let _ = self.files.insert(transfer, bar);
+        self.overall.inc_length(bytes);
+        self.files_started += 1;
+        self.update_overall_message();
+    }
+
+    fn update_overall_message(&self) {
+        self.overall.set_message(format!(
+            "Files: {} of {}",
+            self.files_completed, self.files_started
+        ));
+    }
+
+    // Finishes all the bars, leaving those of files which were not completed as they are.
+    fn finish(self) {
+        for bar in self.files.values() {
+            bar.abandon();
+        }
+        self.overall.finish();
+        if self.drawing.join().is_err() {
+            warn!("Failed to finish drawing progress bars");
+        }
+    }
+}
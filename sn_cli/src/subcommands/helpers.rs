@@ -15,6 +15,7 @@ use serde::ser::Serialize;
 use sn_api::{
     files::{FilesMapChange, ProcessedFiles},
     nrs::NrsMap,
+    register::EntryHash,
     SafeUrl,
 };
 use std::io::{stdin, stdout, Read, Write};
@@ -31,6 +32,15 @@ pub fn xorname_to_hex(xorname: &XorName) -> String {
     xorname.0.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
+// Parses a hex encoded Register entry hash
+pub fn parse_entry_hash(hash: &str) -> Result<EntryHash> {
+    let bytes = hex::decode(hash).map_err(|err| eyre!("Invalid entry hash '{}': {}", hash, err))?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| eyre!("Invalid entry hash '{}': it must be 32 bytes long", hash))?;
+    Ok(EntryHash(bytes))
+}
+
 // Read the argument string from the STDIN if is not an arg provided
 pub fn get_from_arg_or_stdin(arg: Option<String>, message: Option<&str>) -> Result<String> {
     match arg {
@@ -6,16 +6,20 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+pub mod cache;
 pub mod cat;
 pub mod config;
 pub mod dog;
 pub mod files;
 mod files_get;
+mod files_progress;
 mod helpers;
 pub mod keys;
+pub mod multimap;
 pub mod networks;
 pub mod node;
 pub mod nrs;
+pub mod register;
 pub mod safe_id;
 pub mod setup;
 pub mod update;
@@ -79,6 +83,17 @@ pub enum SubCommands {
     )]
     /// Read data on the SAFE Network
     Cat(cat::CatCommands),
+    #[structopt(
+        name = "cache",
+        no_version,
+        global_settings(&[AppSettings::DisableVersion]),
+    )]
+    /// Inspect or clear the chunks retrieved from the network which are cached on disk
+    Cache {
+        /// subcommands
+        #[structopt(subcommand)]
+        cmd: Option<cache::CacheSubCommands>,
+    },
     #[structopt(
         name = "dog",
         no_version,
@@ -114,6 +129,20 @@ pub enum SubCommands {
     )]
     /// Manage keys on the SAFE Network
     Keys(keys::KeysSubCommands),
+    #[structopt(
+        name = "register",
+        no_version,
+        global_settings(&[AppSettings::DisableVersion]),
+    )]
+    /// Manage Registers on the SAFE Network
+    Register(register::RegisterSubCommands),
+    #[structopt(
+        name = "multimap",
+        no_version,
+        global_settings(&[AppSettings::DisableVersion]),
+    )]
+    /// Manage Multimaps on the SAFE Network
+    Multimap(multimap::MultimapSubCommands),
     /// Obtain the XOR-URL of data without uploading it to the network, or decode XOR-URLs
     Xorurl {
         /// subcommands
@@ -0,0 +1,283 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{
+    helpers::{parse_entry_hash, serialise_output},
+    OutputFmt,
+};
+use color_eyre::{eyre::bail, eyre::eyre, Result};
+use comfy_table::Table;
+use sn_api::{multimap::Multimap, Error, Safe};
+use std::collections::BTreeSet;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub enum MultimapSubCommands {
+    #[structopt(name = "create")]
+    /// Create a new empty Multimap
+    Create {
+        /// Create a private Multimap, only readable by its owner and the users granted permissions
+        #[structopt(long = "private")]
+        private: bool,
+        /// The type tag of the Multimap
+        #[structopt(long = "tag", default_value = "15000")]
+        tag: u64,
+    },
+    #[structopt(name = "insert")]
+    /// Insert a key-value pair into a Multimap
+    Insert {
+        /// The URL of the Multimap
+        url: String,
+        /// The key of the entry
+        key: String,
+        /// The value of the entry
+        value: String,
+        /// The hex encoded hash of an entry to be replaced by the new one. Can be provided several times
+        #[structopt(long = "replace", number_of_values = 1)]
+        replace: Vec<String>,
+        #[structopt(flatten)]
+        encoding: EncodingArgs,
+    },
+    #[structopt(name = "get")]
+    /// Get the entries of a Multimap with the given key, or the entry with the given hash
+    Get {
+        /// The URL of the Multimap
+        url: String,
+        /// The key of the entries
+        key: Option<String>,
+        /// The hex encoded hash of the entry, to get instead of the entries with a key
+        #[structopt(long = "hash")]
+        hash: Option<String>,
+        #[structopt(flatten)]
+        encoding: EncodingArgs,
+    },
+    #[structopt(name = "remove")]
+    /// Remove entries from a Multimap
+    Remove {
+        /// The URL of the Multimap
+        url: String,
+        /// The hex encoded hashes of the entries to remove
+        hashes: Vec<String>,
+        /// Remove all the entries with this key, instead of those with the given hashes
+        #[structopt(long = "key")]
+        key: Option<String>,
+        #[structopt(flatten)]
+        encoding: EncodingArgs,
+    },
+    #[structopt(name = "ls")]
+    /// List the latest entries of a Multimap
+    Ls {
+        /// The URL of the Multimap
+        url: String,
+        #[structopt(flatten)]
+        encoding: EncodingArgs,
+    },
+}
+
+#[derive(StructOpt, Debug)]
+pub struct EncodingArgs {
+    /// Encoding of the keys provided and output: utf8, hex or base64
+    #[structopt(long = "key-encoding", default_value = "utf8")]
+    key_encoding: Encoding,
+    /// Encoding of the values provided and output: utf8, hex or base64
+    #[structopt(long = "value-encoding", default_value = "utf8")]
+    value_encoding: Encoding,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Utf8,
+    Hex,
+    Base64,
+}
+
+impl std::str::FromStr for Encoding {
+    type Err = String;
+    fn from_str(str: &str) -> Result<Self, String> {
+        match str {
+            "utf8" => Ok(Self::Utf8),
+            "hex" => Ok(Self::Hex),
+            "base64" => Ok(Self::Base64),
+            other => Err(format!(
+                "Encoding '{}' not supported. Supported values are utf8, hex, and base64",
+                other
+            )),
+        }
+    }
+}
+
+impl Encoding {
+    fn decode(self, input: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::Utf8 => Ok(input.as_bytes().to_vec()),
+            Self::Hex => {
+                hex::decode(input).map_err(|err| eyre!("Invalid hex string '{}': {}", input, err))
+            }
+            Self::Base64 => base64::decode(input)
+                .map_err(|err| eyre!("Invalid base64 string '{}': {}", input, err)),
+        }
+    }
+
+    // Bytes which aren't valid UTF-8 are shown hex encoded when using the utf8 encoding
+    fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => match std::str::from_utf8(bytes) {
+                Ok(str) => str.to_string(),
+                Err(_) => format!("<binary> 0x{}", hex::encode(bytes)),
+            },
+            Self::Hex => hex::encode(bytes),
+            Self::Base64 => base64::encode(bytes),
+        }
+    }
+}
+
+pub async fn multimap_commander(
+    cmd: MultimapSubCommands,
+    output_fmt: OutputFmt,
+    safe: &Safe,
+) -> Result<()> {
+    match cmd {
+        MultimapSubCommands::Create { private, tag } => {
+            let xorurl = safe.multimap_create(None, tag, private).await?;
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "{} Multimap created at: \"{}\"",
+                    if private { "Private" } else { "Public" },
+                    xorurl
+                );
+            } else {
+                println!("{}", serialise_output(&xorurl, output_fmt));
+            }
+            Ok(())
+        }
+        MultimapSubCommands::Insert {
+            url,
+            key,
+            value,
+            replace,
+            encoding,
+        } => {
+            let key = encoding.key_encoding.decode(&key)?;
+            let value = encoding.value_encoding.decode(&value)?;
+            let replace = replace
+                .iter()
+                .map(|hash| parse_entry_hash(hash))
+                .collect::<Result<BTreeSet<_>>>()?;
+
+            let hash = safe.multimap_insert(&url, (key, value), replace).await?;
+            let hash = hex::encode(hash.0);
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "Entry inserted into Multimap at \"{}\" with hash: {}",
+                    url, hash
+                );
+            } else {
+                println!("{}", serialise_output(&(url, hash), output_fmt));
+            }
+            Ok(())
+        }
+        MultimapSubCommands::Get {
+            url,
+            key,
+            hash,
+            encoding,
+        } => {
+            let entries = match (key, hash) {
+                (Some(key), None) => {
+                    let key = encoding.key_encoding.decode(&key)?;
+                    match safe.multimap_get_by_key(&url, &key).await {
+                        Err(Error::EmptyContent(_)) => Multimap::new(),
+                        other => other?,
+                    }
+                }
+                (None, Some(hash)) => {
+                    let hash = parse_entry_hash(&hash)?;
+                    let key_val = safe.multimap_get_by_hash(&url, hash).await?;
+                    BTreeSet::from([(hash, key_val)])
+                }
+                _ => bail!("Either a key or the '--hash' argument must be provided"),
+            };
+            print_entries(&url, &entries, &encoding, output_fmt);
+            Ok(())
+        }
+        MultimapSubCommands::Remove {
+            url,
+            hashes,
+            key,
+            encoding,
+        } => {
+            let to_remove = match key {
+                Some(key) if hashes.is_empty() => {
+                    let key = encoding.key_encoding.decode(&key)?;
+                    match safe.multimap_get_by_key(&url, &key).await {
+                        Err(Error::EmptyContent(_)) => BTreeSet::new(),
+                        other => other?.into_iter().map(|(hash, _)| hash).collect(),
+                    }
+                }
+                None if !hashes.is_empty() => hashes
+                    .iter()
+                    .map(|hash| parse_entry_hash(hash))
+                    .collect::<Result<BTreeSet<_>>>()?,
+                _ => bail!(
+                    "Either the hashes of the entries or the '--key' argument must be provided"
+                ),
+            };
+            if to_remove.is_empty() {
+                bail!("No entries found to remove from Multimap at \"{}\"", url);
+            }
+
+            let removed = to_remove.len();
+            let hash = safe.multimap_remove(&url, to_remove).await?;
+            let hash = hex::encode(hash.0);
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "{} entry/ies removed from Multimap at \"{}\", with removal mark hash: {}",
+                    removed, url, hash
+                );
+            } else {
+                println!("{}", serialise_output(&(url, hash), output_fmt));
+            }
+            Ok(())
+        }
+        MultimapSubCommands::Ls { url, encoding } => {
+            let entries = match safe.multimap_read(&url).await {
+                Err(Error::EmptyContent(_)) => Multimap::new(),
+                other => other?,
+            };
+            print_entries(&url, &entries, &encoding, output_fmt);
+            Ok(())
+        }
+    }
+}
+
+fn print_entries(url: &str, entries: &Multimap, encoding: &EncodingArgs, output_fmt: OutputFmt) {
+    let rows = entries.iter().map(|(hash, (key, value))| {
+        (
+            hex::encode(hash.0),
+            encoding.key_encoding.encode(key),
+            encoding.value_encoding.encode(value),
+        )
+    });
+
+    if OutputFmt::Pretty == output_fmt {
+        if entries.is_empty() {
+            println!("No entries found in Multimap at \"{}\"", url);
+            return;
+        }
+        let mut table = Table::new();
+        table.add_row(&vec!["Hash", "Key", "Value"]);
+        for (hash, key, value) in rows {
+            table.add_row(&vec![hash, key, value]);
+        }
+        println!("Entries of Multimap at \"{}\":", url);
+        println!("{table}");
+    } else {
+        let rows: Vec<_> = rows.collect();
+        println!("{}", serialise_output(&(url, rows), output_fmt));
+    }
+}
@@ -6,9 +6,11 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use super::{helpers::serialise_output, OutputFmt};
 use crate::operations::{config::Config, config::NetworkLauncher, node::*};
 use color_eyre::{eyre::eyre, Help, Result};
-use sn_api::PublicKey;
+use comfy_table::Table;
+use sn_api::{PublicKey, Safe, Token};
 use std::{collections::BTreeSet, net::SocketAddr, path::PathBuf};
 use structopt::StructOpt;
 use tracing::debug;
@@ -118,13 +120,22 @@ pub enum NodeSubCommands {
         #[structopt(long = "ip")]
         ip: Option<String>,
     },
-    /// Shutdown all running nodes processes
+    /// Shutdown all running nodes processes. Except on Windows, the nodes first tell their
+    /// Elders they are leaving, so their data is handed off to other nodes
     #[structopt(name = "killall")]
     Killall {
         /// Path of the sn_node executable used to launch the processes with (default ~/.safe/node/sn_node). The SN_NODE_PATH env var can be also used to set this path
         #[structopt(long = "node-path", env = "SN_NODE_PATH")]
         node_path: Option<PathBuf>,
     },
+    #[structopt(name = "rewards")]
+    /// Show the rewards the network's nodes have earned for the work they do
+    Rewards {
+        /// Only show the total credited to this wallet, i.e. the hex encoded key set as
+        /// `wallet-id` when launching a node.
+        #[structopt(long = "wallet")]
+        wallet: Option<String>,
+    },
     #[structopt(name = "update")]
     /// Update to latest sn_node released version
     Update {
@@ -137,8 +148,10 @@ pub enum NodeSubCommands {
 
 pub async fn node_commander(
     cmd: Option<NodeSubCommands>,
+    output_fmt: OutputFmt,
     config: &mut Config,
     network_launcher: &mut Box<impl NetworkLauncher>,
+    safe: &Safe,
 ) -> Result<()> {
     match cmd {
         Some(NodeSubCommands::BinVersion { node_path }) => node_version(node_path),
@@ -248,10 +261,93 @@ pub async fn node_commander(
         }
         Some(NodeSubCommands::Killall { node_path }) => node_shutdown(node_path),
         Some(NodeSubCommands::Update { node_path }) => node_update(node_path),
+        Some(NodeSubCommands::Rewards { wallet }) => {
+            node_rewards_commander(wallet, output_fmt, safe).await
+        }
         None => Err(eyre!("Missing node subcommand")),
     }
 }
 
+async fn node_rewards_commander(
+    wallet: Option<String>,
+    output_fmt: OutputFmt,
+    safe: &Safe,
+) -> Result<()> {
+    let ledgers = safe.reward_ledgers().await?;
+
+    if let Some(wallet) = wallet {
+        let wallet_key = PublicKey::bls_from_hex(&wallet)
+            .or_else(|_| PublicKey::ed25519_from_hex(&wallet))
+            .map_err(|_| eyre!("Invalid wallet key: {}", wallet))
+            .with_suggestion(|| "The wallet must be a hex encoded BLS or Ed25519 public key")?;
+        let credited: u64 = ledgers
+            .iter()
+            .map(|ledger| ledger.credited(&wallet_key).as_nano())
+            .sum();
+        let credited = Token::from_nano(credited);
+
+        if OutputFmt::Pretty == output_fmt {
+            println!(
+                "Wallet {} has been credited {} in rewards",
+                wallet, credited
+            );
+        } else {
+            println!(
+                "{}",
+                serialise_output(&(wallet, credited.to_string()), output_fmt)
+            );
+        }
+        return Ok(());
+    }
+
+    let rows: Vec<_> = ledgers
+        .iter()
+        .flat_map(|ledger| {
+            ledger.nodes.iter().map(move |(name, rewards)| {
+                (
+                    format!("{:?}", ledger.prefix),
+                    format!("{:x}", name),
+                    rewards.wallet.map(|wallet| format!("{:x}", wallet)),
+                    rewards.served,
+                    rewards.storage_level.value(),
+                    rewards.earned.to_string(),
+                    rewards.unpaid.to_string(),
+                )
+            })
+        })
+        .collect();
+
+    if OutputFmt::Pretty == output_fmt {
+        let mut table = Table::new();
+        table.add_row(&vec![
+            "Section",
+            "Node",
+            "Wallet",
+            "Served",
+            "Storage level",
+            "Earned",
+            "Unpaid",
+        ]);
+        for (prefix, name, wallet, served, level, earned, unpaid) in rows {
+            table.add_row(&vec![
+                prefix,
+                name,
+                wallet.unwrap_or_else(|| "<unknown>".to_string()),
+                served.to_string(),
+                level.to_string(),
+                earned,
+                unpaid,
+            ]);
+        }
+        println!("Rewards accounted for by {} section/s:", ledgers.len());
+        println!("{table}");
+    } else {
+        println!("{}", serialise_output(&rows, output_fmt));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use crate::operations::config::NetworkLauncher;
@@ -278,11 +374,12 @@ mod test {
 #[cfg(test)]
 mod run_command {
     use super::test::FakeNetworkLauncher;
-    use super::{node_commander, NodeSubCommands, NODES_DATA_DIR_NAME};
+    use super::{node_commander, NodeSubCommands, OutputFmt, NODES_DATA_DIR_NAME};
     use crate::operations::config::{Config, NetworkInfo};
     use crate::operations::node::SN_NODE_EXECUTABLE;
     use assert_fs::prelude::*;
     use color_eyre::{eyre::eyre, Result};
+    use sn_api::Safe;
     use std::path::{Path, PathBuf};
 
     // Each of these tests will assume the launch tool runs successfully and a node config is
@@ -330,7 +427,14 @@ mod run_command {
             ip: None,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
 
@@ -371,7 +475,14 @@ mod run_command {
             ip: None,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--node-path"));
@@ -411,7 +522,14 @@ mod run_command {
             ip: None,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--nodes-dir"));
@@ -449,7 +567,14 @@ mod run_command {
             ip: None,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--nodes-dir"));
@@ -490,7 +615,14 @@ mod run_command {
             ip: None,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         node_data_dir.assert(predicates::path::is_dir());
@@ -524,7 +656,14 @@ mod run_command {
             ip: None,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--interval"));
@@ -559,7 +698,14 @@ mod run_command {
             ip: None,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--num-nodes"));
@@ -594,7 +740,14 @@ mod run_command {
             ip: Some("10.10.0.1".to_string()),
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--ip"));
@@ -629,7 +782,14 @@ mod run_command {
             ip: None,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--local"));
@@ -665,7 +825,14 @@ mod run_command {
             ip: None,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert_eq!(config.networks_iter().count(), 1);
@@ -693,11 +860,12 @@ mod run_command {
 #[cfg(test)]
 mod join_command {
     use super::test::FakeNetworkLauncher;
-    use super::{node_commander, NodeSubCommands, LOCAL_NODE_DIR_NAME};
+    use super::{node_commander, NodeSubCommands, OutputFmt, LOCAL_NODE_DIR_NAME};
     use crate::operations::config::Config;
     use crate::operations::node::SN_NODE_EXECUTABLE;
     use assert_fs::prelude::*;
     use color_eyre::Result;
+    use sn_api::Safe;
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
     use std::path::PathBuf;
 
@@ -752,7 +920,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher
@@ -807,7 +982,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--node-path"));
@@ -853,7 +1035,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--nodes-dir"));
@@ -900,7 +1089,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--nodes-dir"));
@@ -946,7 +1142,14 @@ mod join_command {
             skip_auto_port_forwarding: true,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher
@@ -989,7 +1192,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--local-addr"));
@@ -1033,7 +1243,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--public-addr"));
@@ -1074,7 +1291,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--clear-data"));
@@ -1114,7 +1338,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "-yyy"));
@@ -1164,7 +1395,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher
@@ -1227,7 +1465,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_err());
         let error = result.unwrap_err();
@@ -1272,7 +1517,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         node_data_dir.assert(predicates::path::is_dir());
@@ -1323,7 +1575,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(launcher.launch_args.iter().any(|x| x == "--genesis-key"));
@@ -1375,7 +1634,14 @@ mod join_command {
             skip_auto_port_forwarding: false,
         };
 
-        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+        let result = node_commander(
+            Some(cmd),
+            OutputFmt::Pretty,
+            &mut config,
+            &mut launcher,
+            &Safe::dry_runner(None),
+        )
+        .await;
 
         assert!(result.is_err());
         let error = result.unwrap_err();
@@ -0,0 +1,243 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{
+    helpers::{get_from_arg_or_stdin, parse_entry_hash, serialise_output, xorname_to_hex},
+    OutputFmt,
+};
+use color_eyre::Result;
+use comfy_table::Table;
+use sn_api::{
+    register::{Action, Entry, EntryHash, Policy, User},
+    ContentType, Safe,
+};
+use std::collections::BTreeSet;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub enum RegisterSubCommands {
+    #[structopt(name = "create")]
+    /// Create a new empty Register
+    Create {
+        /// Create a private Register, only readable by its owner and the users granted permissions
+        #[structopt(long = "private")]
+        private: bool,
+        /// The type tag of the Register
+        #[structopt(long = "tag", default_value = "15000")]
+        tag: u64,
+    },
+    #[structopt(name = "read")]
+    /// Read the latest entries of a Register
+    Read {
+        /// The URL of the Register
+        url: String,
+    },
+    #[structopt(name = "read-entry")]
+    /// Read an entry of a Register by its hash
+    ReadEntry {
+        /// The URL of the Register
+        url: String,
+        /// The hex encoded hash of the entry
+        hash: String,
+    },
+    #[structopt(name = "write")]
+    /// Write an entry to a Register
+    Write {
+        /// The URL of the Register
+        url: String,
+        /// The value of the entry. If not provided, it's read from stdin
+        value: Option<String>,
+        /// The hex encoded hash of an entry the new one is to follow. Can be provided
+        /// several times. Defaults to the Register's latest entries
+        #[structopt(long = "parent", number_of_values = 1)]
+        parents: Vec<String>,
+    },
+    #[structopt(name = "dog")]
+    /// Inspect a Register, showing its owner and the permissions of its users
+    Dog {
+        /// The URL of the Register
+        url: String,
+    },
+}
+
+pub async fn register_commander(
+    cmd: RegisterSubCommands,
+    output_fmt: OutputFmt,
+    safe: &Safe,
+) -> Result<()> {
+    match cmd {
+        RegisterSubCommands::Create { private, tag } => {
+            let xorurl = safe
+                .register_create(None, tag, private, ContentType::Raw)
+                .await?;
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "{} Register created at: \"{}\"",
+                    if private { "Private" } else { "Public" },
+                    xorurl
+                );
+            } else {
+                println!("{}", serialise_output(&xorurl, output_fmt));
+            }
+            Ok(())
+        }
+        RegisterSubCommands::Read { url } => {
+            let entries = match safe.register_read(&url).await {
+                Ok(entries) => entries,
+                Err(sn_api::Error::EmptyContent(_)) => BTreeSet::new(),
+                Err(err) => return Err(err.into()),
+            };
+            print_entries(&url, &entries, output_fmt);
+            Ok(())
+        }
+        RegisterSubCommands::ReadEntry { url, hash } => {
+            let hash = parse_entry_hash(&hash)?;
+            let entry = safe.register_read_entry(&url, hash).await?;
+            print_entries(&url, &BTreeSet::from([(hash, entry)]), output_fmt);
+            Ok(())
+        }
+        RegisterSubCommands::Write {
+            url,
+            value,
+            parents,
+        } => {
+            let value = get_from_arg_or_stdin(value, Some("...awaiting entry value from stdin"))?;
+            let parents = if parents.is_empty() {
+                match safe.register_read(&url).await {
+                    Ok(entries) => entries.into_iter().map(|(hash, _)| hash).collect(),
+                    Err(sn_api::Error::EmptyContent(_)) => BTreeSet::new(),
+                    Err(err) => return Err(err.into()),
+                }
+            } else {
+                parents
+                    .iter()
+                    .map(|hash| parse_entry_hash(hash))
+                    .collect::<Result<BTreeSet<_>>>()?
+            };
+
+            let hash = safe
+                .register_write(&url, value.into_bytes(), parents)
+                .await?;
+            let hash = hex::encode(hash.0);
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "Entry written to Register at \"{}\" with hash: {}",
+                    url, hash
+                );
+            } else {
+                println!("{}", serialise_output(&(url, hash), output_fmt));
+            }
+            Ok(())
+        }
+        RegisterSubCommands::Dog { url } => {
+            let safeurl = safe.parse_and_resolve_url(&url).await?;
+            let policy = safe.register_policy(&url).await?;
+            let scope = match policy {
+                Policy::Public(_) => "Public",
+                Policy::Private(_) => "Private",
+            };
+            let owner = user_to_string(policy.owner());
+            let permissions = permissions_rows(&policy);
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("= Register =");
+                println!("XOR-URL: {}", safeurl);
+                println!("Type tag: {}", safeurl.type_tag());
+                println!("XOR name: 0x{}", xorname_to_hex(&safeurl.xorname()));
+                println!("Scope: {}", scope);
+                println!("Owner: {}", owner);
+
+                let mut table = Table::new();
+                table.add_row(&vec!["User", "Read", "Write"]);
+                for (user, read, write) in permissions {
+                    table.add_row(&vec![user, read, write]);
+                }
+                println!("Permissions:");
+                println!("{table}");
+            } else {
+                println!(
+                    "{}",
+                    serialise_output(
+                        &(
+                            safeurl.to_string(),
+                            safeurl.type_tag(),
+                            scope,
+                            owner,
+                            permissions
+                        ),
+                        output_fmt
+                    )
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_entries(url: &str, entries: &BTreeSet<(EntryHash, Entry)>, output_fmt: OutputFmt) {
+    if OutputFmt::Pretty == output_fmt {
+        if entries.is_empty() {
+            println!("Register at \"{}\" has no entries", url);
+            return;
+        }
+        let mut table = Table::new();
+        table.add_row(&vec!["Hash", "Entry"]);
+        for (hash, entry) in entries {
+            let entry = match std::str::from_utf8(entry) {
+                Ok(entry) => entry.to_string(),
+                Err(_) => format!("<binary> 0x{}", hex::encode(entry)),
+            };
+            table.add_row(&vec![hex::encode(hash.0), entry]);
+        }
+        println!("Entries of Register at \"{}\":", url);
+        println!("{table}");
+    } else {
+        // entries are hex encoded as they may not be valid UTF-8
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|(hash, entry)| (hex::encode(hash.0), hex::encode(entry)))
+            .collect();
+        println!("{}", serialise_output(&(url, entries), output_fmt));
+    }
+}
+
+// Each user with its read and write permissions, which are unset in
+// public Registers if the owner hasn't explicitly allowed or denied them.
+fn permissions_rows(policy: &Policy) -> Vec<(String, String, String)> {
+    let allowed = |allowed: bool| if allowed { "allowed" } else { "denied" }.to_string();
+    match policy {
+        Policy::Public(policy) => policy
+            .permissions
+            .iter()
+            .map(|(user, perms)| {
+                let write = perms
+                    .is_allowed(Action::Write)
+                    .map_or_else(|| "unset".to_string(), allowed);
+                (user_to_string(user), allowed(true), write)
+            })
+            .collect(),
+        Policy::Private(policy) => policy
+            .permissions
+            .iter()
+            .map(|(user, perms)| {
+                (
+                    user_to_string(user),
+                    allowed(perms.is_allowed(Action::Read)),
+                    allowed(perms.is_allowed(Action::Write)),
+                )
+            })
+            .collect(),
+    }
+}
+
+fn user_to_string(user: &User) -> String {
+    match user {
+        User::Anyone => "Anyone".to_string(),
+        User::Key(key) => format!("{:x}", key),
+    }
+}
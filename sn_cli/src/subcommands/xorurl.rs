@@ -11,7 +11,7 @@ use super::{
     OutputFmt,
 };
 use color_eyre::{eyre::eyre, Result};
-use sn_api::{files::FilesMapChange, PublicKey, Safe, SafeUrl, XorName, XorUrlBase};
+use sn_api::{files::FilesMapChange, PublicKey, Safe, SafeUrl, Scope, XorName, XorUrlBase};
 use structopt::StructOpt;
 
 // Defines subcommands of 'xorurl'
@@ -101,7 +101,7 @@ pub async fn xorurl_of_files(
 
     let location = get_from_arg_or_stdin(location, Some("...awaiting location path from stdin"))?;
     let (_, processed_files, _) = safe
-        .files_container_create_from(&location, None, recursive, follow_symlinks)
+        .files_container_create_from(&location, None, recursive, follow_symlinks, Scope::Public)
         .await?;
 
     // Now let's just print out a list of the xorurls
@@ -123,6 +123,32 @@ fn calling_safe_files_put_recursive_and_set_dst_path() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn calling_safe_files_put_private_and_cat() -> Result<()> {
+    let output = safe_cmd_stdout(
+        [
+            "files",
+            "put",
+            TEST_FOLDER,
+            "--recursive",
+            "--private",
+            "--json",
+        ],
+        Some(0),
+    )?;
+    let (container_xorurl, _) = parse_files_put_or_sync_output(&output)?;
+
+    let mut safeurl = safeurl_from(&container_xorurl)?;
+    assert_eq!(safeurl.scope(), sn_api::Scope::Private);
+
+    // the owner, i.e. the keypair the CLI is configured with, can read the files
+    safeurl.set_path("/test.md");
+    let file_cat = safe_cmd_stdout(["cat", &safeurl.to_string()], Some(0))?;
+    let contents = std::fs::read_to_string(TEST_FILE)?;
+    assert_eq!(file_cat, contents);
+    Ok(())
+}
+
 #[test]
 fn calling_safe_files_put_recursive_subfolder() -> Result<()> {
     let mut cmd = Command::cargo_bin(CLI).map_err(|e| eyre!(e.to_string()))?;
@@ -0,0 +1,95 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use assert_cmd::prelude::*;
+use color_eyre::{eyre::eyre, Result};
+use predicates::prelude::*;
+use sn_cmd_test_utilities::util::{safe_cmd, safe_cmd_stdout};
+
+fn create_multimap() -> Result<String> {
+    let output = safe_cmd_stdout(["multimap", "create", "--json"], Some(0))?;
+    serde_json::from_str(&output).map_err(|err| eyre!("Failed to parse output: {}", err))
+}
+
+fn insert_entry(url: &str, key: &str, value: &str, extra_args: &[&str]) -> Result<String> {
+    let mut args = vec!["multimap", "insert", url, key, value, "--json"];
+    args.extend(extra_args);
+    let output = safe_cmd_stdout(args, Some(0))?;
+    let (_, hash): (String, String) =
+        serde_json::from_str(&output).map_err(|err| eyre!("Failed to parse output: {}", err))?;
+    Ok(hash)
+}
+
+#[test]
+fn calling_safe_multimap_insert_and_replace() -> Result<()> {
+    let url = create_multimap()?;
+    let hash = insert_entry(&url, "color", "blue", &[])?;
+    let _ = insert_entry(&url, "shape", "circle", &[])?;
+
+    safe_cmd(["multimap", "get", &url, "color"], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains(&hash))
+        .stdout(predicate::str::contains("blue"))
+        .stdout(predicate::str::contains("circle").count(0));
+
+    let new_hash = insert_entry(&url, "color", "red", &["--replace", &hash])?;
+    safe_cmd(["multimap", "ls", &url], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains(&new_hash))
+        .stdout(predicate::str::contains("red"))
+        .stdout(predicate::str::contains("circle"))
+        .stdout(predicate::str::contains("blue").count(0));
+
+    safe_cmd(["multimap", "get", &url, "--hash", &new_hash], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("red"));
+
+    Ok(())
+}
+
+#[test]
+fn calling_safe_multimap_remove_by_key() -> Result<()> {
+    let url = create_multimap()?;
+    let _ = insert_entry(&url, "color", "blue", &[])?;
+    let _ = insert_entry(&url, "shape", "circle", &[])?;
+
+    safe_cmd(["multimap", "remove", &url, "--key", "color"], Some(0))?;
+    safe_cmd(["multimap", "ls", &url], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("circle"))
+        .stdout(predicate::str::contains("blue").count(0));
+
+    Ok(())
+}
+
+#[test]
+fn calling_safe_multimap_with_binary_encodings() -> Result<()> {
+    let url = create_multimap()?;
+    let _ = insert_entry(
+        &url,
+        "00ff",
+        "aGVsbG8=",
+        &["--key-encoding", "hex", "--value-encoding", "base64"],
+    )?;
+
+    safe_cmd(
+        ["multimap", "get", &url, "00ff", "--key-encoding", "hex"],
+        Some(0),
+    )?
+    .assert()
+    .stdout(predicate::str::contains("hello"));
+
+    safe_cmd(
+        ["multimap", "ls", &url, "--key-encoding", "base64"],
+        Some(0),
+    )?
+    .assert()
+    .stdout(predicate::str::contains("AP8="));
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use assert_cmd::prelude::*;
+use color_eyre::{eyre::eyre, Result};
+use predicates::prelude::*;
+use sn_cmd_test_utilities::util::{safe_cmd, safe_cmd_stdout};
+
+fn create_register(private: bool) -> Result<String> {
+    let mut args = vec!["register", "create", "--json"];
+    if private {
+        args.push("--private");
+    }
+    let output = safe_cmd_stdout(args, Some(0))?;
+    serde_json::from_str(&output).map_err(|err| eyre!("Failed to parse output: {}", err))
+}
+
+fn write_entry(url: &str, value: &str, parents: &[&str]) -> Result<String> {
+    let mut args = vec!["register", "write", url, value, "--json"];
+    for parent in parents {
+        args.extend(["--parent", parent]);
+    }
+    let output = safe_cmd_stdout(args, Some(0))?;
+    let (_, hash): (String, String) =
+        serde_json::from_str(&output).map_err(|err| eyre!("Failed to parse output: {}", err))?;
+    Ok(hash)
+}
+
+#[test]
+fn calling_safe_register_write_and_read() -> Result<()> {
+    let url = create_register(false)?;
+    safe_cmd(["register", "read", &url], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("has no entries"));
+
+    let first = write_entry(&url, "first entry", &[])?;
+    let second = write_entry(&url, "second entry", &[])?;
+
+    // the second entry follows the first one, thus it's the only latest entry
+    safe_cmd(["register", "read", &url], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains(&second))
+        .stdout(predicate::str::contains("second entry"))
+        .stdout(predicate::str::contains("first entry").count(0));
+
+    safe_cmd(["register", "read-entry", &url, &first], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("first entry"));
+
+    // a branch from the first entry makes two latest entries
+    let branch = write_entry(&url, "branch entry", &[&first])?;
+    safe_cmd(["register", "read", &url], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains(&second))
+        .stdout(predicate::str::contains(&branch));
+
+    Ok(())
+}
+
+#[test]
+fn calling_safe_register_dog() -> Result<()> {
+    let url = create_register(true)?;
+    safe_cmd(["register", "dog", &url], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("Type tag: 15000"))
+        .stdout(predicate::str::contains("Scope: Private"))
+        .stdout(predicate::str::contains("allowed"));
+    Ok(())
+}
+
+#[test]
+fn calling_safe_register_read_entry_with_invalid_hash() -> Result<()> {
+    let url = create_register(false)?;
+    safe_cmd(["register", "read-entry", &url, "not-a-hash"], Some(1))?
+        .assert()
+        .stderr(predicate::str::contains("Invalid entry hash"));
+    Ok(())
+}
@@ -0,0 +1,7 @@
+
+    #![allow(dead_code)]
+
+    #[track_caller]
+    fn foo() {
+        let _location = std::panic::Location::caller();
+    }